@@ -230,6 +230,35 @@ impl<K: CacheKey, V: CacheValue> Cache<K, V> {
         Ok(())
     }
 
+    /// Insert an item to the cache, replacing the value of an existing key.
+    ///
+    /// The new value is appended to the file, so it takes precedence over the previous one when
+    /// the cache is loaded again.
+    pub fn update(&mut self, key: K, value: V) {
+        if let Some(mut reader) = self.file.lock() {
+            let mut buffer = Vec::new();
+            reader.read_to_end(&mut buffer).unwrap();
+            self.sync_content(&buffer, None).ok();
+        }
+
+        if self.in_memory_cache.get(&key) != Some(&value) {
+            self.insert_unchecked(key, value);
+        }
+
+        self.file.unlock();
+    }
+
+    /// Remove all items from the cache, including the ones stored on disk.
+    ///
+    /// Other processes sharing the cache keep the items they already loaded.
+    pub fn clear(&mut self) {
+        // The lock is taken to clear the file, its remaining content is discarded.
+        let _ = self.file.lock();
+        self.file.clear();
+        self.file.unlock();
+        self.in_memory_cache.clear();
+    }
+
     fn sync_content(
         &mut self,
         bytes: &[u8],
@@ -382,4 +411,48 @@ mod tests {
         let value2_actual = cache.get(&key2()).unwrap();
         assert_eq!(value2_actual, &value2());
     }
+
+    #[test_log::test]
+    #[cfg_attr(miri, ignore)]
+    fn test_cache_clear() {
+        let root = tempfile::tempdir().unwrap();
+        let cache =
+            || Cache::<String, String>::new("test", CacheOption::default().root(root.path()));
+
+        let mut cleared = cache();
+        cleared
+            .insert("key".to_string(), "value".to_string())
+            .unwrap();
+        assert_eq!(cache().len(), 1);
+
+        cleared.clear();
+        assert!(cleared.is_empty());
+        assert!(cache().is_empty());
+
+        // The cache is still usable, with a different value for the removed key.
+        cleared
+            .insert("key".to_string(), "other".to_string())
+            .unwrap();
+        assert_eq!(cache().get(&"key".to_string()).unwrap(), "other");
+    }
+
+    #[test_log::test]
+    #[cfg_attr(miri, ignore)]
+    fn test_cache_update() {
+        let root = tempfile::tempdir().unwrap();
+        let cache =
+            || Cache::<String, String>::new("test", CacheOption::default().root(root.path()));
+
+        let mut updated = cache();
+        updated
+            .insert("key".to_string(), "value".to_string())
+            .unwrap();
+        updated.update("key".to_string(), "other".to_string());
+        assert_eq!(updated.get(&"key".to_string()).unwrap(), "other");
+
+        // The latest value is the one loaded from disk.
+        let reloaded = cache();
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded.get(&"key".to_string()).unwrap(), "other");
+    }
 }
@@ -155,6 +155,10 @@ impl CacheFile {
                 return None;
             }
         };
+        // The file was cleared by another process, its content is read again from the start.
+        if self.cursor > end {
+            self.cursor = 0;
+        }
         if let Err(err) = file.seek(SeekFrom::Start(self.cursor)) {
             log::error!(
                 "cubecl cache: seek({:?}, cursor={}) failed: kind={:?} raw_os_error={:?} err={}",
@@ -183,6 +187,31 @@ impl CacheFile {
         self.lock.unlock();
     }
 
+    /// Remove the content of the file.
+    ///
+    /// Like [`write`](Self::write), the file should be locked first.
+    pub fn clear(&mut self) {
+        if !self.valid {
+            return;
+        }
+        if !self.lock.is_lock {
+            panic!("The cache file should be locked before clearing it.")
+        }
+
+        if let Err(err) = File::create(&self.path) {
+            log::error!(
+                "cubecl cache: truncate({:?}) failed: kind={:?} raw_os_error={:?} err={}",
+                self.path,
+                err.kind(),
+                err.raw_os_error(),
+                err
+            );
+            self.valid = false;
+            return;
+        }
+        self.cursor = 0;
+    }
+
     /// Write the content to the file.
     ///
     /// The `valid` check comes BEFORE the `is_lock` check: `lock()`'s
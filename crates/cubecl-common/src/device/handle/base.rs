@@ -127,6 +127,9 @@ pub(crate) trait DeviceHandleSpec<S: DeviceService>: Sized {
     /// If a runner thread for this `device_id` does not exist, it will be spawned.
     fn new(device_id: DeviceId) -> Self;
 
+    /// Whether a service of type `S` is already registered for the given device ID.
+    fn is_registered(device_id: DeviceId) -> bool;
+
     /// Retrieves the device ID for this handle.
     fn device_id(&self) -> DeviceId;
 
@@ -62,6 +62,18 @@ impl<S: DeviceService + 'static> DeviceHandleSpec<S> for ChannelDeviceHandle<S>
         }
     }
 
+    fn is_registered(device_id: DeviceId) -> bool {
+        let runner_id = RunnerId {
+            device: device_id,
+            stage: S::stage(),
+        };
+
+        CHANNELS
+            .lock()
+            .as_ref()
+            .is_some_and(|channels| channels.contains_key(&(runner_id, TypeId::of::<S>())))
+    }
+
     fn device_id(&self) -> DeviceId {
         self.state.client.runner_id().device
     }
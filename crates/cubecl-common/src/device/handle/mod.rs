@@ -54,6 +54,10 @@ impl<S: DeviceService> DeviceHandle<S> {
         }
     }
 
+    pub fn is_registered(device_id: super::DeviceId) -> bool {
+        <Inner<S> as DeviceHandleSpec<S>>::is_registered(device_id)
+    }
+
     pub fn device_id(&self) -> DeviceId {
         self.handle.device_id()
     }
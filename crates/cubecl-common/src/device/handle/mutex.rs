@@ -72,6 +72,14 @@ impl<S: DeviceService + 'static> DeviceHandleSpec<S> for MutexDeviceHandle<S> {
         }
     }
 
+    fn is_registered(device_id: DeviceId) -> bool {
+        DEVICE_REGISTRY
+            .lock()
+            .as_ref()
+            .and_then(|registry| registry.get(&device_id))
+            .is_some_and(|device_map| device_map.contains_key(&TypeId::of::<S>()))
+    }
+
     fn device_id(&self) -> DeviceId {
         self.device_id
     }
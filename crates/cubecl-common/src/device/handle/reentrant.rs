@@ -31,6 +31,10 @@ impl<S: DeviceService> DeviceHandleSpec<S> for ReentrantMutexDeviceHandle<S> {
         Self::locate(device_id)
     }
 
+    fn is_registered(device_id: DeviceId) -> bool {
+        DeviceStateLock::registered::<S>(device_id)
+    }
+
     fn device_id(&self) -> DeviceId {
         self.device_id
     }
@@ -253,6 +257,19 @@ impl DeviceStateLock {
             _phantom: PhantomData,
         }
     }
+
+    fn registered<S: DeviceService>(device: DeviceId) -> bool {
+        // Clone the lock out so the global lock isn't held while waiting on the device.
+        let lock = match &GLOBAL.lock().state {
+            Some(state) => state.states.get(&device).cloned(),
+            None => None,
+        };
+
+        lock.is_some_and(|lock| {
+            let state = lock.lock.lock();
+            state.map.borrow().contains_key(&TypeId::of::<S>())
+        })
+    }
 }
 
 impl DeviceStateMap {
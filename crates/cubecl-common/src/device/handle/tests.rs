@@ -42,6 +42,25 @@ fn test_recursive_execution_different_state() {
     });
 }
 
+#[test]
+fn test_is_registered() {
+    let device_id = DeviceId {
+        type_id: 0,
+        index_id: 6,
+    };
+    assert!(!DeviceHandle::<TestDeviceState<3>>::is_registered(
+        device_id
+    ));
+
+    let context = DeviceHandle::<TestDeviceState<3>>::new(device_id);
+    context.submit_blocking(|state| state.counter).unwrap();
+
+    assert!(DeviceHandle::<TestDeviceState<3>>::is_registered(device_id));
+    assert!(!DeviceHandle::<TestDeviceState<4>>::is_registered(
+        device_id
+    ));
+}
+
 #[derive(Debug, Clone, Default, new)]
 /// Type is only to create different type ids.
 pub struct TestDevice<const TYPE: u8> {
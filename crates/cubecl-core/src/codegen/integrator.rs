@@ -1,6 +1,7 @@
 use alloc::{string::ToString, vec::Vec};
 use cubecl_ir::{Id, Scope, StorageType, Value};
 use cubecl_runtime::{
+    id::Instrumentation,
     kernel::{KernelArg, KernelDefinition, KernelOptions, ScalarKernelArg},
     server::CubeDim,
 };
@@ -15,6 +16,7 @@ pub struct KernelIntegrator {
     buffer_bindings: Vec<KernelArg>,
     scalar_bindings: Vec<ScalarKernelArg>,
     tensor_maps: Vec<KernelArg>,
+    textures: Vec<KernelArg>,
 }
 
 /// The information necessary to compile a [kernel definition](KernelDefinition).
@@ -23,6 +25,7 @@ pub struct KernelExpansion {
     pub buffers: Vec<BufferInfo>,
     pub scalars: Vec<ScalarInfo>,
     pub tensor_maps: Vec<BufferInfo>,
+    pub textures: Vec<BufferInfo>,
     pub scope: Scope,
 }
 
@@ -31,6 +34,8 @@ pub struct KernelSettings {
     pub cube_dim: CubeDim,
     pub address_type: AddressType,
     pub options: KernelOptions,
+    /// The debugging instrumentation of the kernel, from the config by default.
+    pub instrumentation: Instrumentation,
 }
 
 impl Default for KernelSettings {
@@ -39,6 +44,7 @@ impl Default for KernelSettings {
             cube_dim: CubeDim::new_1d(1),
             address_type: AddressType::U32,
             options: Default::default(),
+            instrumentation: Instrumentation::from_config(),
         }
     }
 }
@@ -73,6 +79,12 @@ impl KernelSettings {
         self.options.cluster_dim = Some(cluster_dim);
         self
     }
+
+    /// Set the debugging instrumentation, overriding the config.
+    pub fn instrumentation(mut self, instrumentation: Instrumentation) -> Self {
+        self.instrumentation = instrumentation;
+        self
+    }
 }
 
 /// Information related to a buffer binding.
@@ -99,6 +111,7 @@ impl KernelIntegrator {
             buffer_bindings: Default::default(),
             scalar_bindings: Default::default(),
             tensor_maps: Default::default(),
+            textures: Default::default(),
         }
     }
 
@@ -108,12 +121,14 @@ impl KernelIntegrator {
         self.register_buffers();
         self.register_scalars();
         self.register_tensor_maps();
+        self.register_textures();
 
         self.scalar_bindings.sort_by_key(|binding| binding.ty);
 
         KernelDefinition {
             buffers: self.buffer_bindings,
             tensor_maps: self.tensor_maps,
+            textures: self.textures,
             scalars: self.scalar_bindings,
             cube_dim: settings.cube_dim,
             body: self.expansion.scope,
@@ -149,4 +164,14 @@ impl KernelIntegrator {
             });
         }
     }
+
+    fn register_textures(&mut self) {
+        for texture in self.expansion.textures.drain(..) {
+            self.textures.push(KernelArg {
+                id: texture.id,
+                value: texture.value,
+                has_extended_meta: texture.has_extended_meta,
+            });
+        }
+    }
 }
@@ -0,0 +1,185 @@
+use alloc::vec::Vec;
+use core::fmt::Display;
+use cubecl_ir::{Id, Type};
+use cubecl_runtime::{
+    kernel::{CubeKernel, KernelArg, KernelDefinition, ScalarKernelArg, Visibility},
+    server::KernelArguments,
+};
+
+use crate::post_processing::analysis_helper::{BufferVisibility, GlobalAnalyses};
+
+/// The bindings a kernel expects when it's launched, in launch order.
+///
+/// Useful for frameworks building generic launch adapters on top of `CubeCL` kernels, and to
+/// [validate](KernelBindingLayout::validate) the arguments of a launch with clear errors instead
+/// of a failure in the driver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelBindingLayout {
+    /// The buffer bindings, including tensors.
+    pub buffers: Vec<BufferLayout>,
+    /// The tensor map bindings.
+    pub tensor_maps: Vec<BufferLayout>,
+    /// The texture bindings.
+    pub textures: Vec<BufferLayout>,
+    /// The scalars, grouped and sorted by type.
+    pub scalars: Vec<ScalarKernelArg>,
+}
+
+/// A buffer binding of a [kernel layout](KernelBindingLayout).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferLayout {
+    /// The id of the binding.
+    pub id: Id,
+    /// The type of the elements, including their vector size.
+    pub ty: Type,
+    /// The size of one element in bytes.
+    pub elem_size: usize,
+    /// Whether the kernel writes to the binding.
+    pub visibility: Visibility,
+    /// Whether the binding has a shape and strides, i.e. is a tensor.
+    pub is_tensor: bool,
+}
+
+/// A mismatch between the arguments of a launch and the [layout](KernelBindingLayout) of the
+/// kernel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BindingMismatch {
+    /// The number of buffers doesn't match.
+    BufferCount {
+        /// The number of buffers of the kernel.
+        expected: usize,
+        /// The number of buffers of the launch.
+        actual: usize,
+    },
+    /// The number of tensor maps doesn't match.
+    TensorMapCount {
+        /// The number of tensor maps of the kernel.
+        expected: usize,
+        /// The number of tensor maps of the launch.
+        actual: usize,
+    },
+    /// The number of textures doesn't match.
+    TextureCount {
+        /// The number of textures of the kernel.
+        expected: usize,
+        /// The number of textures of the launch.
+        actual: usize,
+    },
+    /// A buffer doesn't hold a whole number of elements.
+    BufferSize {
+        /// The position of the buffer.
+        index: usize,
+        /// The size of the buffer in bytes.
+        size: u64,
+        /// The size of one element in bytes.
+        elem_size: usize,
+    },
+}
+
+impl Display for BindingMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BindingMismatch::BufferCount { expected, actual } => {
+                write!(f, "Expected {expected} buffers, got {actual}")
+            }
+            BindingMismatch::TensorMapCount { expected, actual } => {
+                write!(f, "Expected {expected} tensor maps, got {actual}")
+            }
+            BindingMismatch::TextureCount { expected, actual } => {
+                write!(f, "Expected {expected} textures, got {actual}")
+            }
+            BindingMismatch::BufferSize {
+                index,
+                size,
+                elem_size,
+            } => write!(
+                f,
+                "Buffer {index} has {size} bytes, which isn't a multiple of its element size \
+                 ({elem_size} bytes)"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for BindingMismatch {}
+
+impl KernelBindingLayout {
+    /// The layout of the bindings of `kernel`, which is defined but not compiled.
+    ///
+    /// The buffers of the [instrumentation](cubecl_runtime::id::Instrumentation) of the kernel are
+    /// left out, since they're bound by the launcher after the arguments.
+    pub fn new<K: CubeKernel>(kernel: &K) -> Self {
+        let mut layout = Self::from_definition(&kernel.define());
+        let instrumentation = kernel.id().instrumentation;
+        let instrumented =
+            instrumentation.coverage as usize + instrumentation.arithmetic_traps as usize;
+        layout
+            .buffers
+            .truncate(layout.buffers.len().saturating_sub(instrumented));
+        layout
+    }
+
+    /// The layout of the bindings of a kernel definition, including the buffers added by its
+    /// instrumentation.
+    pub fn from_definition(definition: &KernelDefinition) -> Self {
+        let analyses = GlobalAnalyses::default();
+        analyses.recalculate_pointer_source(&definition.body);
+        let visibility = BufferVisibility::new(&definition.body, &analyses);
+
+        let layout = |arg: &KernelArg| {
+            let ty = arg.value.ty.value_type();
+            BufferLayout {
+                id: arg.id,
+                ty,
+                elem_size: ty.size(),
+                visibility: visibility
+                    .get(arg.id as usize)
+                    .copied()
+                    .unwrap_or(Visibility::Read),
+                is_tensor: arg.has_extended_meta,
+            }
+        };
+
+        Self {
+            buffers: definition.buffers.iter().map(layout).collect(),
+            tensor_maps: definition.tensor_maps.iter().map(layout).collect(),
+            textures: definition.textures.iter().map(layout).collect(),
+            scalars: definition.scalars.clone(),
+        }
+    }
+
+    /// Check that `arguments` can be used to launch the kernel.
+    pub fn validate(&self, arguments: &KernelArguments) -> Result<(), BindingMismatch> {
+        if arguments.buffers.len() != self.buffers.len() {
+            return Err(BindingMismatch::BufferCount {
+                expected: self.buffers.len(),
+                actual: arguments.buffers.len(),
+            });
+        }
+        if arguments.tensor_maps.len() != self.tensor_maps.len() {
+            return Err(BindingMismatch::TensorMapCount {
+                expected: self.tensor_maps.len(),
+                actual: arguments.tensor_maps.len(),
+            });
+        }
+        if arguments.textures.len() != self.textures.len() {
+            return Err(BindingMismatch::TextureCount {
+                expected: self.textures.len(),
+                actual: arguments.textures.len(),
+            });
+        }
+
+        for (index, (buffer, layout)) in arguments.buffers.iter().zip(&self.buffers).enumerate() {
+            let size = buffer.size_in_used();
+            if layout.elem_size != 0 && !size.is_multiple_of(layout.elem_size as u64) {
+                return Err(BindingMismatch::BufferSize {
+                    index,
+                    size,
+                    elem_size: layout.elem_size,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
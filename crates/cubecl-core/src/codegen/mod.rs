@@ -1,5 +1,6 @@
 mod info;
 mod integrator;
+mod layout;
 mod metadata;
 mod scalars;
 
@@ -8,5 +9,6 @@ mod compiler;
 pub use compiler::*;
 pub use info::*;
 pub use integrator::*;
+pub use layout::*;
 pub use metadata::*;
 pub use scalars::*;
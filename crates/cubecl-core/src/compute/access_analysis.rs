@@ -0,0 +1,1200 @@
+//! Analysis of the memory accesses of a kernel, to validate loading strategies without a profiler.
+//!
+//! The kernel is interpreted for every unit of a cube, tracking the integer values used to compute
+//! addresses. The accesses of the units of each plane to global and shared memory are then grouped
+//! per instruction, giving the coalescing efficiency of global accesses and the bank conflicts of
+//! shared accesses.
+//!
+//! Values that depend on memory or floating-point math are unknown: an access with an unknown
+//! address is counted as unresolved, a branch with an unknown condition takes its first block and
+//! a loop with unknown bounds runs for the configured number of iterations.
+
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Display;
+use cubecl_ir::{
+    AddressSpace, Arithmetic, AtomicOp, Bitwise, Branch, Builtin, Comparison, ConstantValue,
+    ElemType, Id, Instruction, Memory, Metadata, Operation, Operator, Scope, StorageType, Type,
+    Value, ValueKind,
+};
+use hashbrown::HashMap;
+
+use super::instrumentation::source_location;
+use crate::prelude::{CubeDim, KernelDefinition};
+
+/// The byte offset of an access in its memory, and its width in bytes.
+type Address = (i128, usize);
+
+/// The size in bytes of a global memory sector.
+const SECTOR_SIZE: i128 = 32;
+/// The number of shared memory banks.
+const BANKS: i128 = 32;
+/// The size in bytes of a shared memory bank.
+const BANK_SIZE: i128 = 4;
+
+/// Simulate the memory accesses of a kernel for a launch shape.
+///
+/// ```ignore
+/// let report = AccessAnalysis::new(CubeDim::new_2d(32, 4))
+///     .tensor(0, &[64, 64], &[64, 1])
+///     .analyze(&kernel.define());
+/// println!("{report}");
+/// ```
+#[derive(Clone, Debug)]
+pub struct AccessAnalysis {
+    cube_dim: CubeDim,
+    cube_pos: [u32; 3],
+    cube_count: [u32; 3],
+    plane_size: u32,
+    iterations: u32,
+    scalars: HashMap<(StorageType, Id), i64>,
+    tensors: HashMap<Id, TensorInfo>,
+    block: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct TensorInfo {
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+    len: usize,
+}
+
+impl AccessAnalysis {
+    /// Analyze the units of a cube with the given dimensions.
+    pub fn new(cube_dim: CubeDim) -> Self {
+        Self {
+            cube_dim,
+            cube_pos: [0; 3],
+            cube_count: [1; 3],
+            plane_size: 32,
+            iterations: 2,
+            scalars: HashMap::new(),
+            tensors: HashMap::new(),
+            block: None,
+        }
+    }
+
+    /// Analyze the cube at the given position, `(0, 0, 0)` by default.
+    pub fn cube_pos(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.cube_pos = [x, y, z];
+        self
+    }
+
+    /// The number of cubes of the launch, `(1, 1, 1)` by default.
+    pub fn cube_count(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.cube_count = [x, y, z];
+        self
+    }
+
+    /// The number of units per plane, 32 by default.
+    pub fn plane_size(mut self, plane_size: u32) -> Self {
+        self.plane_size = plane_size;
+        self
+    }
+
+    /// The maximum number of iterations simulated for each loop, 2 by default.
+    pub fn iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// The value of the scalar argument of the given type at `index`.
+    pub fn scalar(mut self, ty: StorageType, index: Id, value: i64) -> Self {
+        self.scalars.insert((ty, index), value);
+        self
+    }
+
+    /// The shape and strides of the tensor bound to the buffer `id`, in elements.
+    pub fn tensor(mut self, id: Id, shape: &[usize], strides: &[usize]) -> Self {
+        let len = shape
+            .iter()
+            .zip(strides)
+            .map(|(shape, stride)| shape.saturating_sub(1) * stride)
+            .sum::<usize>()
+            + 1;
+        self.tensors.insert(
+            id,
+            TensorInfo {
+                shape: shape.to_vec(),
+                strides: strides.to_vec(),
+                len,
+            },
+        );
+        self
+    }
+
+    /// Only report the accesses of the given block and its nested blocks, with the path of a
+    /// [block coverage](super::BlockCoverage), e.g. `body/3:for` for a loop.
+    pub fn block(mut self, path: impl Into<String>) -> Self {
+        self.block = Some(path.into());
+        self
+    }
+
+    /// Simulate the memory accesses of every unit of the cube.
+    pub fn analyze(&self, definition: &KernelDefinition) -> AccessReport {
+        let mut sites = Sites::default();
+        sites.collect(&definition.body, "body", &mut 0);
+
+        let mut buffers = HashMap::new();
+        for buffer in &definition.buffers {
+            if let ValueKind::Value { id } = buffer.value.kind {
+                buffers.insert(id, Val::Ptr(Space::Global(buffer.id), 0));
+            }
+        }
+
+        let CubeDim { x, y, z } = self.cube_dim;
+        let num_units = (x * y * z) as usize;
+        let mut events = Vec::with_capacity(num_units);
+        let mut unknown_branches = 0;
+        for unit_pos in 0..num_units as u32 {
+            let mut unit = Unit {
+                analysis: self,
+                sites: &sites,
+                position: [unit_pos % x, unit_pos / x % y, unit_pos / (x * y)],
+                values: buffers.clone(),
+                memory: HashMap::new(),
+                occurrences: HashMap::new(),
+                events: Vec::new(),
+                unknown_branches: 0,
+            };
+            unit.run_scope(&definition.body, 0);
+            unknown_branches += unit.unknown_branches;
+            events.push(unit.events);
+        }
+
+        let mut report = AccessReport {
+            sites: sites
+                .sites
+                .iter()
+                .map(|site| AccessSite {
+                    memory: site.memory,
+                    write: site.write,
+                    instruction: site.instruction.clone(),
+                    block: site.block.clone(),
+                    location: site.location.clone(),
+                    requests: 0,
+                    unresolved: 0,
+                    transactions: 0,
+                    ideal_transactions: 0,
+                })
+                .collect(),
+            unknown_branches,
+        };
+
+        let plane_size = self.plane_size.max(1) as usize;
+        for plane in events.chunks(plane_size) {
+            let mut requests: HashMap<(usize, usize), Vec<Option<Address>>> = HashMap::new();
+            for event in plane.iter().flatten() {
+                requests
+                    .entry((event.site, event.occurrence))
+                    .or_default()
+                    .push(event.address);
+            }
+
+            for ((site, _), addresses) in requests {
+                let site = &mut report.sites[site];
+                let resolved = addresses.iter().flatten().copied().collect::<Vec<_>>();
+                site.unresolved += addresses.len() - resolved.len();
+                if resolved.is_empty() {
+                    continue;
+                }
+
+                let (transactions, ideal) = match site.memory {
+                    MemoryKind::Global(_) => sectors(&resolved),
+                    MemoryKind::Shared => wavefronts(&resolved),
+                };
+                site.requests += 1;
+                site.transactions += transactions;
+                site.ideal_transactions += ideal;
+            }
+        }
+
+        if let Some(block) = &self.block {
+            report.sites.retain(|site| site.block.starts_with(block));
+        }
+
+        report
+    }
+}
+
+/// The sectors touched by the accesses of a plane, and the minimum needed for their bytes.
+fn sectors(accesses: &[Address]) -> (usize, usize) {
+    let bytes = bytes(accesses);
+    let sectors = bytes
+        .iter()
+        .map(|byte| byte.div_euclid(SECTOR_SIZE))
+        .collect::<BTreeSet<_>>();
+
+    (sectors.len(), bytes.len().div_ceil(SECTOR_SIZE as usize))
+}
+
+/// The wavefronts needed to serve the accesses of a plane, one per distinct word in the most
+/// loaded bank, and the minimum needed for their words. Units reading the same word are served
+/// by a broadcast.
+fn wavefronts(accesses: &[Address]) -> (usize, usize) {
+    let words = bytes(accesses)
+        .iter()
+        .map(|byte| byte.div_euclid(BANK_SIZE))
+        .collect::<BTreeSet<_>>();
+
+    let mut banks: HashMap<i128, usize> = HashMap::new();
+    for word in &words {
+        *banks.entry(word.rem_euclid(BANKS)).or_default() += 1;
+    }
+    let wavefronts = banks.values().copied().max().unwrap_or(0);
+
+    (wavefronts, words.len().div_ceil(BANKS as usize))
+}
+
+fn bytes(accesses: &[Address]) -> BTreeSet<i128> {
+    accesses
+        .iter()
+        .flat_map(|(address, width)| *address..*address + (*width).max(1) as i128)
+        .collect()
+}
+
+/// The memory accesses of a kernel, simulated by an [`AccessAnalysis`].
+#[derive(Clone, Debug, Default)]
+pub struct AccessReport {
+    /// The instructions accessing global or shared memory, in the order of the kernel.
+    pub sites: Vec<AccessSite>,
+    /// The number of branches taken with an unknown condition, over every unit. The report is
+    /// approximate when it isn't zero.
+    pub unknown_branches: usize,
+}
+
+/// The memory kind of an access.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MemoryKind {
+    /// The global buffer with the given id.
+    Global(Id),
+    /// Shared memory.
+    Shared,
+}
+
+/// The accesses of an instruction.
+#[derive(Clone, Debug)]
+pub struct AccessSite {
+    /// The accessed memory.
+    pub memory: MemoryKind,
+    /// Whether the instruction writes to memory.
+    pub write: bool,
+    /// The accessing instruction.
+    pub instruction: String,
+    /// The path of the block of the instruction, as in a [block coverage](super::BlockCoverage).
+    pub block: String,
+    /// The source location of the instruction, when debug symbols are enabled.
+    pub location: Option<String>,
+    /// The number of plane-wide requests with at least one resolved address.
+    pub requests: usize,
+    /// The number of accesses of a unit with an unknown address.
+    pub unresolved: usize,
+    /// The number of transactions of every request: sectors of 32 bytes for global memory and
+    /// wavefronts for shared memory.
+    pub transactions: usize,
+    /// The minimum number of transactions that could serve the same bytes.
+    pub ideal_transactions: usize,
+}
+
+impl AccessSite {
+    /// The ratio of the ideal number of transactions to the actual one, `1.0` when the accesses
+    /// are fully coalesced or free of bank conflicts.
+    pub fn efficiency(&self) -> f64 {
+        match self.transactions {
+            0 => 1.0,
+            transactions => self.ideal_transactions as f64 / transactions as f64,
+        }
+    }
+
+    /// The average number of transactions per request.
+    pub fn transactions_per_request(&self) -> f64 {
+        match self.requests {
+            0 => 0.0,
+            requests => self.transactions as f64 / requests as f64,
+        }
+    }
+}
+
+impl Display for AccessReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for site in &self.sites {
+            let memory = match site.memory {
+                MemoryKind::Global(id) => format!("global {id}"),
+                MemoryKind::Shared => "shared".to_string(),
+            };
+            let access = if site.write { "store" } else { "load" };
+            write!(
+                f,
+                "{:>6.1}%  {:>6.2} per request  {memory} {access}  {}  {}",
+                site.efficiency() * 100.0,
+                site.transactions_per_request(),
+                site.block,
+                site.instruction,
+            )?;
+            if let Some(location) = &site.location {
+                write!(f, " ({location})")?;
+            }
+            if site.unresolved > 0 {
+                write!(f, " [{} unresolved]", site.unresolved)?;
+            }
+            writeln!(f)?;
+        }
+
+        if self.unknown_branches > 0 {
+            writeln!(
+                f,
+                "{} branches taken with an unknown condition",
+                self.unknown_branches
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct SiteInfo {
+    memory: MemoryKind,
+    write: bool,
+    instruction: String,
+    block: String,
+    location: Option<String>,
+}
+
+/// The memory accesses of a kernel, keyed by instruction index and operand, and the offset of every
+/// shared memory.
+///
+/// Instructions are indexed in the order of the kernel, each branch followed by the instructions
+/// of its scopes, so the index doesn't depend on where the scopes are stored.
+#[derive(Default)]
+struct Sites {
+    sites: Vec<SiteInfo>,
+    ids: HashMap<(usize, usize), usize>,
+    branches: HashMap<usize, BranchScopes>,
+    shared: HashMap<Id, i128>,
+    shared_size: i128,
+}
+
+/// The index of the first instruction of every scope of a branch, and the index following its last
+/// nested instruction.
+#[derive(Debug)]
+struct BranchScopes {
+    starts: Vec<usize>,
+    end: usize,
+}
+
+impl Sites {
+    fn collect(&mut self, scope: &Scope, path: &str, index: &mut usize) {
+        for (position, instruction) in scope.instructions.borrow().iter().enumerate() {
+            let current = *index;
+            *index += 1;
+
+            for (operand, (ptr, write)) in accesses(&instruction.operation).enumerate() {
+                let memory = match ptr.address_space() {
+                    AddressSpace::Global(id) => MemoryKind::Global(id),
+                    AddressSpace::Shared => MemoryKind::Shared,
+                    AddressSpace::Local => continue,
+                };
+                self.ids.insert((current, operand), self.sites.len());
+                self.sites.push(SiteInfo {
+                    memory,
+                    write,
+                    instruction: instruction.to_string(),
+                    block: path.to_string(),
+                    location: instruction.source_loc.as_ref().map(source_location),
+                });
+            }
+
+            match &instruction.operation {
+                Operation::DeclareVariable {
+                    value_ty,
+                    addr_space: AddressSpace::Shared,
+                    alignment,
+                } => {
+                    let alignment = (*alignment).max(1) as i128;
+                    let offset = (self.shared_size + alignment - 1) / alignment * alignment;
+                    if let Some(out) = instruction.out.and_then(id) {
+                        self.shared.insert(out, offset);
+                    }
+                    self.shared_size = offset + size(*value_ty).unwrap_or(0) as i128;
+                }
+                Operation::Branch(branch) => {
+                    let mut starts = Vec::new();
+                    for (scope, kind) in branch_scopes(branch) {
+                        starts.push(*index);
+                        self.collect(scope, &format!("{path}/{position}:{kind}"), index);
+                    }
+                    self.branches.insert(
+                        current,
+                        BranchScopes {
+                            starts,
+                            end: *index,
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The scopes of a branch in the order of the kernel, with their kind in the block path.
+fn branch_scopes(branch: &Branch) -> Vec<(&Scope, String)> {
+    match branch {
+        Branch::If(op) => vec![(&op.scope, "if".to_string())],
+        Branch::IfElse(op) => vec![
+            (&op.scope_if, "if".to_string()),
+            (&op.scope_else, "else".to_string()),
+        ],
+        Branch::Switch(op) => op
+            .cases
+            .iter()
+            .map(|(value, case)| (case, format!("case {value}")))
+            .chain([(&op.scope_default, "default".to_string())])
+            .collect(),
+        Branch::RangeLoop(op) => vec![(&op.scope, "for".to_string())],
+        Branch::Loop(op) => vec![(&op.scope, "loop".to_string())],
+        Branch::Return | Branch::Break | Branch::Unreachable => vec![],
+    }
+}
+
+/// The pointers accessed by an operation, and whether each is written.
+fn accesses(operation: &Operation) -> impl Iterator<Item = (Value, bool)> {
+    let accesses = match operation {
+        Operation::Memory(Memory::Load(ptr))
+        | Operation::Atomic(AtomicOp::Load(ptr))
+        | Operation::WorkgroupUniformLoad(ptr) => vec![(*ptr, false)],
+        Operation::Memory(Memory::Store(op)) | Operation::Atomic(AtomicOp::Store(op)) => {
+            vec![(op.ptr, true)]
+        }
+        Operation::Memory(Memory::CopyMemory(op)) => vec![(op.source, false), (op.target, true)],
+        Operation::Atomic(
+            AtomicOp::Swap(op)
+            | AtomicOp::Add(op)
+            | AtomicOp::Sub(op)
+            | AtomicOp::Max(op)
+            | AtomicOp::Min(op)
+            | AtomicOp::And(op)
+            | AtomicOp::Or(op)
+            | AtomicOp::Xor(op),
+        ) => vec![(op.ptr, true)],
+        Operation::Atomic(AtomicOp::CompareAndSwap(op)) => vec![(op.ptr, true)],
+        _ => vec![],
+    };
+    accesses.into_iter()
+}
+
+fn id(value: Value) -> Option<Id> {
+    match value.kind {
+        ValueKind::Value { id } => Some(id),
+        ValueKind::Constant(_) => None,
+    }
+}
+
+/// The size of a type stored in memory, if it has one.
+fn size(ty: Type) -> Option<usize> {
+    match ty {
+        Type::Scalar(_) | Type::Vector(..) | Type::Atomic(_) | Type::Opaque(_) => Some(ty.size()),
+        Type::Array(inner, len) => size(*inner).map(|size| size * len),
+        _ => None,
+    }
+}
+
+/// The size of the value pointed to by a pointer type.
+fn pointee_size(ty: Type) -> Option<usize> {
+    match ty {
+        Type::Pointer(inner, _) => match *inner {
+            Type::DynamicArray(inner) => size(*inner),
+            inner => size(inner),
+        },
+        _ => None,
+    }
+}
+
+/// The number of bits of an integer type and whether it's signed.
+fn int_type(ty: Type) -> Option<(u32, bool)> {
+    match ty {
+        Type::Scalar(StorageType::Scalar(elem)) => match elem {
+            ElemType::Int(_) => Some((elem.size_bits() as u32, true)),
+            ElemType::UInt(_) => Some((elem.size_bits() as u32, false)),
+            ElemType::Bool => Some((1, false)),
+            ElemType::Float(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Wrap an integer to the range of a type.
+fn wrap(value: i128, (bits, signed): (u32, bool)) -> i128 {
+    let modulus = 1i128 << bits;
+    let value = value.rem_euclid(modulus);
+    if signed && value >= modulus / 2 {
+        value - modulus
+    } else {
+        value
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Space {
+    Global(Id),
+    Shared,
+    /// The local variable declared by the instruction with the given output.
+    Local(Id),
+}
+
+/// A simulated value.
+#[derive(Clone, Debug, PartialEq)]
+enum Val {
+    Int(i128),
+    /// A pointer to the given byte of a memory.
+    Ptr(Space, i128),
+    Aggregate(Vec<Val>),
+    Unknown,
+}
+
+impl Val {
+    fn int(&self) -> Option<i128> {
+        match self {
+            Val::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Event {
+    site: usize,
+    /// The number of previous executions of the site by the unit.
+    occurrence: usize,
+    address: Option<Address>,
+}
+
+#[derive(PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Break,
+    Return,
+}
+
+/// The state of a simulated unit.
+struct Unit<'a> {
+    analysis: &'a AccessAnalysis,
+    sites: &'a Sites,
+    position: [u32; 3],
+    values: HashMap<Id, Val>,
+    memory: HashMap<(Id, i128), Val>,
+    occurrences: HashMap<usize, usize>,
+    events: Vec<Event>,
+    unknown_branches: usize,
+}
+
+impl Unit<'_> {
+    /// Run the instructions of a scope, the first one having the given index.
+    fn run_scope(&mut self, scope: &Scope, start: usize) -> Flow {
+        let mut index = start;
+        for instruction in scope.instructions.borrow().iter() {
+            let flow = match &instruction.operation {
+                Operation::Branch(branch) => {
+                    let scopes = &self.sites.branches[&index];
+                    index = scopes.end;
+                    self.run_branch(branch, &scopes.starts)
+                }
+                _ => {
+                    self.record(instruction, index);
+                    index += 1;
+                    let value = self.run_instruction(instruction);
+                    if let Some(out) = instruction.out.and_then(id) {
+                        self.values.insert(out, value);
+                    }
+                    Flow::Continue
+                }
+            };
+            if flow != Flow::Continue {
+                return flow;
+            }
+        }
+
+        Flow::Continue
+    }
+
+    /// Run a branch, given the index of the first instruction of each of its scopes.
+    fn run_branch(&mut self, branch: &Branch, starts: &[usize]) -> Flow {
+        match branch {
+            Branch::If(op) => match self.condition(op.cond) {
+                true => self.run_scope(&op.scope, starts[0]),
+                false => Flow::Continue,
+            },
+            Branch::IfElse(op) => match self.condition(op.cond) {
+                true => self.run_scope(&op.scope_if, starts[0]),
+                false => self.run_scope(&op.scope_else, starts[1]),
+            },
+            Branch::Switch(op) => {
+                let value = self.eval(op.value);
+                if value == Val::Unknown {
+                    self.unknown_branches += 1;
+                }
+                let case = op
+                    .cases
+                    .iter()
+                    .position(|(case, _)| value != Val::Unknown && self.eval(*case) == value);
+                match case {
+                    Some(case) => self.run_scope(&op.cases[case].1, starts[case]),
+                    None => self.run_scope(&op.scope_default, starts[op.cases.len()]),
+                }
+            }
+            Branch::RangeLoop(op) => {
+                let start = self.eval(op.start).int();
+                let end = self.eval(op.end).int();
+                let step = op.step.map_or(Some(1), |step| self.eval(step).int());
+
+                for iteration in 0..self.analysis.iterations as i128 {
+                    let i = start
+                        .zip(step)
+                        .map(|(start, step)| start + iteration * step);
+                    let in_range = match (i, end) {
+                        (Some(i), Some(end)) if op.inclusive => i <= end,
+                        (Some(i), Some(end)) => i < end,
+                        _ => true,
+                    };
+                    if !in_range {
+                        break;
+                    }
+
+                    // The counter is either a value or a local variable.
+                    let i = i.map_or(Val::Unknown, Val::Int);
+                    match (self.eval(op.i), id(op.i)) {
+                        (Val::Ptr(Space::Local(local), offset), _) => {
+                            self.memory.insert((local, offset), i);
+                        }
+                        (_, Some(id)) => {
+                            self.values.insert(id, i);
+                        }
+                        _ => {}
+                    }
+                    match self.run_scope(&op.scope, starts[0]) {
+                        Flow::Continue => {}
+                        Flow::Break => break,
+                        Flow::Return => return Flow::Return,
+                    }
+                }
+                Flow::Continue
+            }
+            Branch::Loop(op) => {
+                for _ in 0..self.analysis.iterations {
+                    match self.run_scope(&op.scope, starts[0]) {
+                        Flow::Continue => {}
+                        Flow::Break => break,
+                        Flow::Return => return Flow::Return,
+                    }
+                }
+                Flow::Continue
+            }
+            Branch::Break => Flow::Break,
+            Branch::Return | Branch::Unreachable => Flow::Return,
+        }
+    }
+
+    /// Evaluate a branch condition, taking the branch when it's unknown.
+    fn condition(&mut self, cond: Value) -> bool {
+        match self.eval(cond) {
+            Val::Int(value) => value != 0,
+            _ => {
+                self.unknown_branches += 1;
+                true
+            }
+        }
+    }
+
+    /// Record the memory accesses of the instruction with the given index.
+    fn record(&mut self, instruction: &Instruction, index: usize) {
+        for (operand, (ptr, _)) in accesses(&instruction.operation).enumerate() {
+            let Some(&site) = self.sites.ids.get(&(index, operand)) else {
+                continue;
+            };
+            let width = match &instruction.operation {
+                Operation::Memory(Memory::CopyMemory(op)) => {
+                    pointee_size(ptr.ty).map(|size| size * op.len)
+                }
+                _ => pointee_size(ptr.ty),
+            };
+            let address = match (self.eval(ptr), width) {
+                (Val::Ptr(Space::Global(_) | Space::Shared, address), Some(width)) => {
+                    Some((address, width))
+                }
+                _ => None,
+            };
+
+            let occurrence = self.occurrences.entry(site).or_default();
+            self.events.push(Event {
+                site,
+                occurrence: *occurrence,
+                address,
+            });
+            *occurrence += 1;
+        }
+    }
+
+    fn eval(&self, value: Value) -> Val {
+        match value.kind {
+            ValueKind::Value { id } => self.values.get(&id).cloned().unwrap_or(Val::Unknown),
+            ValueKind::Constant(constant) => match constant {
+                ConstantValue::Int(value) => Val::Int(value as i128),
+                ConstantValue::UInt(value) => Val::Int(value as i128),
+                ConstantValue::Bool(value) => Val::Int(value as i128),
+                ConstantValue::Float(_) => Val::Unknown,
+            },
+        }
+    }
+
+    fn run_instruction(&mut self, instruction: &Instruction) -> Val {
+        let Some(out) = instruction.out else {
+            if let Operation::Memory(Memory::Store(op)) = &instruction.operation
+                && let Val::Ptr(Space::Local(local), offset) = self.eval(op.ptr)
+            {
+                let value = self.eval(op.value);
+                self.memory.insert((local, offset), value);
+            }
+            return Val::Unknown;
+        };
+
+        match &instruction.operation {
+            Operation::Copy(value) => self.eval(*value),
+            Operation::ConstructAggregate(fields) => {
+                Val::Aggregate(fields.iter().map(|field| self.eval(*field)).collect())
+            }
+            Operation::ExtractAggregateField(op) => match self.eval(op.aggregate) {
+                Val::Aggregate(mut fields) if op.field < fields.len() => {
+                    fields.swap_remove(op.field)
+                }
+                _ => Val::Unknown,
+            },
+            Operation::DeclareVariable { addr_space, .. } => match (addr_space, id(out)) {
+                (AddressSpace::Local, Some(id)) => Val::Ptr(Space::Local(id), 0),
+                (AddressSpace::Shared, Some(id)) => match self.sites.shared.get(&id) {
+                    Some(offset) => Val::Ptr(Space::Shared, *offset),
+                    None => Val::Unknown,
+                },
+                _ => Val::Unknown,
+            },
+            Operation::Memory(Memory::Index(op)) => {
+                match (
+                    self.eval(op.list),
+                    self.eval(op.index),
+                    pointee_size(out.ty),
+                ) {
+                    (Val::Ptr(space, offset), Val::Int(index), Some(size)) => {
+                        Val::Ptr(space, offset + index * size as i128)
+                    }
+                    _ => Val::Unknown,
+                }
+            }
+            Operation::Memory(Memory::Load(ptr)) => match self.eval(*ptr) {
+                Val::Ptr(Space::Local(local), offset) => self
+                    .memory
+                    .get(&(local, offset))
+                    .cloned()
+                    .unwrap_or(Val::Unknown),
+                _ => Val::Unknown,
+            },
+            Operation::Metadata(metadata) => self.metadata(metadata),
+            Operation::Operator(Operator::ReadBuiltin(builtin)) => self
+                .builtin(*builtin)
+                .map_or(Val::Unknown, |value| Val::Int(value as i128)),
+            Operation::Operator(Operator::ReadScalar(index)) => {
+                let StorageType::Scalar(_) = out.storage_type() else {
+                    return Val::Unknown;
+                };
+                self.analysis
+                    .scalars
+                    .get(&(out.storage_type(), *index))
+                    .map_or(Val::Unknown, |value| Val::Int(*value as i128))
+            }
+            Operation::Operator(Operator::ReadScalarArray(op)) => {
+                let (StorageType::Scalar(_), Val::Int(index)) =
+                    (out.storage_type(), self.eval(op.index))
+                else {
+                    return Val::Unknown;
+                };
+                Id::try_from(index)
+                    .ok()
+                    .and_then(|index| op.id.checked_add(index))
+                    .and_then(|id| self.analysis.scalars.get(&(out.storage_type(), id)))
+                    .map_or(Val::Unknown, |value| Val::Int(*value as i128))
+            }
+            Operation::Operator(Operator::Select(op)) => match self.eval(op.cond) {
+                Val::Int(0) => self.eval(op.or_else),
+                Val::Int(_) => self.eval(op.then),
+                _ => Val::Unknown,
+            },
+            operation => {
+                let Some(ty) = int_type(out.ty) else {
+                    return Val::Unknown;
+                };
+                self.integer(operation)
+                    .map_or(Val::Unknown, |value| Val::Int(wrap(value, ty)))
+            }
+        }
+    }
+
+    /// Evaluate an integer operation.
+    fn integer(&self, operation: &Operation) -> Option<i128> {
+        let int = |value: Value| self.eval(value).int();
+        let bits = |value: Value| int_type(value.ty).map(|(bits, _)| bits);
+        let bool = |value: bool| Some(value as i128);
+
+        match operation {
+            Operation::Arithmetic(arithmetic) => match arithmetic {
+                Arithmetic::Add(op) | Arithmetic::SaturatingAdd(op) => {
+                    Some(int(op.lhs)? + int(op.rhs)?)
+                }
+                Arithmetic::Sub(op) | Arithmetic::SaturatingSub(op) => {
+                    Some(int(op.lhs)? - int(op.rhs)?)
+                }
+                Arithmetic::Mul(op) => int(op.lhs)?.checked_mul(int(op.rhs)?),
+                Arithmetic::Fma(op) => int(op.a)?.checked_mul(int(op.b)?)?.checked_add(int(op.c)?),
+                Arithmetic::Div(op) => int(op.lhs)?.checked_div(int(op.rhs)?),
+                Arithmetic::Rem(op) => int(op.lhs)?.checked_rem(int(op.rhs)?),
+                Arithmetic::ModFloor(op) => {
+                    let (lhs, rhs) = (int(op.lhs)?, int(op.rhs)?);
+                    Some((lhs.checked_rem(rhs)? + rhs) % rhs)
+                }
+                Arithmetic::MulHi(op) => {
+                    Some(int(op.lhs)?.checked_mul(int(op.rhs)?)? >> bits(op.lhs)?)
+                }
+                Arithmetic::Min(op) => Some(int(op.lhs)?.min(int(op.rhs)?)),
+                Arithmetic::Max(op) => Some(int(op.lhs)?.max(int(op.rhs)?)),
+                Arithmetic::Clamp(op) => {
+                    Some(int(op.input)?.clamp(int(op.min_value)?, int(op.max_value)?))
+                }
+                Arithmetic::Abs(op) => Some(int(op.input)?.abs()),
+                Arithmetic::Neg(op) => Some(-int(op.input)?),
+                _ => None,
+            },
+            Operation::Bitwise(bitwise) => match bitwise {
+                Bitwise::BitwiseAnd(op) => Some(int(op.lhs)? & int(op.rhs)?),
+                Bitwise::BitwiseOr(op) => Some(int(op.lhs)? | int(op.rhs)?),
+                Bitwise::BitwiseXor(op) => Some(int(op.lhs)? ^ int(op.rhs)?),
+                Bitwise::BitwiseNot(op) => Some(!int(op.input)?),
+                Bitwise::ShiftLeft(op) => int(op.lhs)?.checked_shl(int(op.rhs)?.try_into().ok()?),
+                Bitwise::ShiftRight(op) => int(op.lhs)?.checked_shr(int(op.rhs)?.try_into().ok()?),
+                Bitwise::CountOnes(op) => {
+                    let bits = bits(op.input)?;
+                    Some(wrap(int(op.input)?, (bits, false)).count_ones() as i128)
+                }
+                Bitwise::LeadingZeros(op) => {
+                    let bits = bits(op.input)?;
+                    let value = wrap(int(op.input)?, (bits, false));
+                    Some(bits as i128 - (128 - value.leading_zeros()) as i128)
+                }
+                Bitwise::TrailingZeros(op) => {
+                    let bits = bits(op.input)?;
+                    let value = wrap(int(op.input)?, (bits, false));
+                    Some(value.trailing_zeros().min(bits) as i128)
+                }
+                Bitwise::FindFirstSet(op) => {
+                    let value = int(op.input)?;
+                    Some(if value == 0 {
+                        0
+                    } else {
+                        value.trailing_zeros() as i128 + 1
+                    })
+                }
+                _ => None,
+            },
+            Operation::Comparison(comparison) => match comparison {
+                Comparison::Lower(op) => bool(int(op.lhs)? < int(op.rhs)?),
+                Comparison::LowerEqual(op) => bool(int(op.lhs)? <= int(op.rhs)?),
+                Comparison::Equal(op) => bool(int(op.lhs)? == int(op.rhs)?),
+                Comparison::NotEqual(op) => bool(int(op.lhs)? != int(op.rhs)?),
+                Comparison::GreaterEqual(op) => bool(int(op.lhs)? >= int(op.rhs)?),
+                Comparison::Greater(op) => bool(int(op.lhs)? > int(op.rhs)?),
+                Comparison::IsNan(_) | Comparison::IsInf(_) => None,
+            },
+            Operation::Operator(operator) => match operator {
+                Operator::And(op) => bool(int(op.lhs)? != 0 && int(op.rhs)? != 0),
+                Operator::Or(op) => bool(int(op.lhs)? != 0 || int(op.rhs)? != 0),
+                Operator::Not(op) => bool(int(op.input)? == 0),
+                Operator::Cast(op) | Operator::Reinterpret(op) => int(op.input),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn metadata(&self, metadata: &Metadata) -> Val {
+        let tensor = |list: Value| match self.eval(list) {
+            Val::Ptr(Space::Global(id), _) => self.analysis.tensors.get(&id),
+            _ => None,
+        };
+        let value = match metadata {
+            Metadata::Shape { dim, list } => self
+                .eval(*dim)
+                .int()
+                .and_then(|dim| tensor(*list)?.shape.get(dim as usize).copied()),
+            Metadata::Stride { dim, list } => self
+                .eval(*dim)
+                .int()
+                .and_then(|dim| tensor(*list)?.strides.get(dim as usize).copied()),
+            Metadata::BufferLength { list } => {
+                tensor(*list).map(|tensor| tensor.len.div_ceil(list.ty.vector_size().max(1)))
+            }
+        };
+
+        value.map_or(Val::Unknown, |value| Val::Int(value as i128))
+    }
+
+    fn builtin(&self, builtin: Builtin) -> Option<u32> {
+        let analysis = self.analysis;
+        let [x, y, z] = self.position;
+        let CubeDim {
+            x: dim_x,
+            y: dim_y,
+            z: dim_z,
+        } = analysis.cube_dim;
+        let [cube_x, cube_y, cube_z] = analysis.cube_pos;
+        let [count_x, count_y, count_z] = analysis.cube_count;
+        let unit_pos = x + y * dim_x + z * dim_x * dim_y;
+        let absolute = [cube_x * dim_x + x, cube_y * dim_y + y, cube_z * dim_z + z];
+
+        let value = match builtin {
+            Builtin::UnitPos => unit_pos,
+            Builtin::UnitPosX => x,
+            Builtin::UnitPosY => y,
+            Builtin::UnitPosZ => z,
+            Builtin::CubePos => cube_x + cube_y * count_x + cube_z * count_x * count_y,
+            Builtin::CubePosX => cube_x,
+            Builtin::CubePosY => cube_y,
+            Builtin::CubePosZ => cube_z,
+            Builtin::CubeDim => dim_x * dim_y * dim_z,
+            Builtin::CubeDimX => dim_x,
+            Builtin::CubeDimY => dim_y,
+            Builtin::CubeDimZ => dim_z,
+            Builtin::CubeCount => count_x * count_y * count_z,
+            Builtin::CubeCountX => count_x,
+            Builtin::CubeCountY => count_y,
+            Builtin::CubeCountZ => count_z,
+            Builtin::PlaneDim => analysis.plane_size,
+            Builtin::PlanePos => unit_pos / analysis.plane_size.max(1),
+            Builtin::UnitPosPlane => unit_pos % analysis.plane_size.max(1),
+            Builtin::AbsolutePos => {
+                let (width, height) = (count_x * dim_x, count_y * dim_y);
+                absolute[0] + absolute[1] * width + absolute[2] * width * height
+            }
+            Builtin::AbsolutePosX => absolute[0],
+            Builtin::AbsolutePosY => absolute[1],
+            Builtin::AbsolutePosZ => absolute[2],
+            _ => return None,
+        };
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as cubecl, prelude::*};
+
+    type Buffer = <[u32] as CubeType>::ExpandType;
+
+    #[cube]
+    fn coalesced(input: &[u32], output: &mut [u32]) {
+        output[UNIT_POS as usize] = input[UNIT_POS as usize];
+    }
+
+    #[cube]
+    fn strided(input: &[u32], output: &mut [u32]) {
+        output[UNIT_POS as usize] = input[UNIT_POS as usize * 8];
+    }
+
+    #[cube]
+    fn shared(input: &[u32], output: &mut [u32], #[comptime] stride: usize) {
+        let mut shared = Shared::<[u32]>::new_slice(1024usize);
+        shared[UNIT_POS as usize * stride] = input[UNIT_POS as usize];
+        output[UNIT_POS as usize] = shared[0];
+    }
+
+    #[cube]
+    fn indirect(input: &[u32], output: &mut [u32]) {
+        output[input[UNIT_POS as usize] as usize] = 1;
+    }
+
+    #[cube]
+    fn branches(input: &[u32], output: &mut [u32]) {
+        if UNIT_POS < 16 {
+            output[UNIT_POS as usize] = 1;
+        } else {
+            output[UNIT_POS as usize * 2] = 2;
+        }
+        if input[0] == 0 {
+            output[0] = 3;
+        }
+    }
+
+    #[cube]
+    fn range_loop(_input: &[u32], output: &mut [u32]) {
+        for i in 0..4u32 {
+            output[(i * 32 + UNIT_POS) as usize] = i;
+        }
+    }
+
+    fn define(
+        cube_dim: CubeDim,
+        kernel: impl FnOnce(&mut Scope, &Buffer, &mut Buffer),
+    ) -> KernelDefinition {
+        let settings = KernelSettings::default().cube_dim(cube_dim);
+        let mut builder = KernelBuilder::default();
+        settings.address_type.register(&builder.scope);
+        let arg = BufferCompilationArg { inplace: None };
+        let input = builder.read_only(|builder| <[u32] as LaunchArg>::expand(&arg, builder));
+        let mut output = <[u32] as LaunchArg>::expand(&arg, &mut builder);
+        kernel(&mut builder.scope, &input, &mut output);
+        builder.build(settings)
+    }
+
+    /// The single site with the given memory and access.
+    fn site(report: &AccessReport, memory: MemoryKind, write: bool) -> &AccessSite {
+        let mut sites = report
+            .sites
+            .iter()
+            .filter(|site| site.memory == memory && site.write == write);
+        let site = sites.next().expect("The site should be reported");
+        assert!(sites.next().is_none(), "The site should be unique");
+        site
+    }
+
+    #[test]
+    fn coalesced_global_accesses() {
+        let definition = define(CubeDim::new_1d(64), |scope, input, output| {
+            coalesced::expand(scope, input, output)
+        });
+        let report = AccessAnalysis::new(CubeDim::new_1d(64)).analyze(&definition);
+
+        for write in [false, true] {
+            let site = site(&report, MemoryKind::Global(write as Id), write);
+            // Two planes of 32 units, each reading 128 contiguous bytes.
+            assert_eq!(site.requests, 2);
+            assert_eq!(site.transactions, 8);
+            assert_eq!(site.efficiency(), 1.0);
+            assert_eq!(site.transactions_per_request(), 4.0);
+        }
+        assert_eq!(report.unknown_branches, 0);
+    }
+
+    #[test]
+    fn strided_global_accesses() {
+        let definition = define(CubeDim::new_1d(32), |scope, input, output| {
+            strided::expand(scope, input, output)
+        });
+        let report = AccessAnalysis::new(CubeDim::new_1d(32)).analyze(&definition);
+
+        // Every unit reads its own sector.
+        let load = site(&report, MemoryKind::Global(0), false);
+        assert_eq!(load.transactions, 32);
+        assert_eq!(load.ideal_transactions, 4);
+        assert_eq!(load.efficiency(), 0.125);
+        assert_eq!(site(&report, MemoryKind::Global(1), true).efficiency(), 1.0);
+    }
+
+    #[test]
+    fn shared_bank_conflicts() {
+        let analysis = AccessAnalysis::new(CubeDim::new_1d(32));
+
+        let definition = define(CubeDim::new_1d(32), |scope, input, output| {
+            shared::expand(scope, input, output, 1)
+        });
+        let report = analysis.analyze(&definition);
+        let store = site(&report, MemoryKind::Shared, true);
+        assert_eq!((store.transactions, store.ideal_transactions), (1, 1));
+        // Every unit reads the same word, served by a broadcast.
+        let load = site(&report, MemoryKind::Shared, false);
+        assert_eq!((load.transactions, load.ideal_transactions), (1, 1));
+
+        // A stride of 32 words maps every unit to the same bank.
+        let definition = define(CubeDim::new_1d(32), |scope, input, output| {
+            shared::expand(scope, input, output, 32)
+        });
+        let report = analysis.analyze(&definition);
+        let store = site(&report, MemoryKind::Shared, true);
+        assert_eq!((store.transactions, store.ideal_transactions), (32, 1));
+        assert_eq!(store.efficiency(), 1.0 / 32.0);
+    }
+
+    #[test]
+    fn unresolved_addresses() {
+        let definition = define(CubeDim::new_1d(32), |scope, input, output| {
+            indirect::expand(scope, input, output)
+        });
+        let report = AccessAnalysis::new(CubeDim::new_1d(32)).analyze(&definition);
+
+        assert_eq!(site(&report, MemoryKind::Global(0), false).unresolved, 0);
+        // The address depends on the loaded value.
+        let store = site(&report, MemoryKind::Global(1), true);
+        assert_eq!(store.unresolved, 32);
+        assert_eq!(store.requests, 0);
+    }
+
+    #[test]
+    fn branches_split_the_accesses() {
+        let definition = define(CubeDim::new_1d(32), |scope, input, output| {
+            branches::expand(scope, input, output)
+        });
+        let report = AccessAnalysis::new(CubeDim::new_1d(32)).analyze(&definition);
+
+        let stores = report
+            .sites
+            .iter()
+            .filter(|site| site.write)
+            .collect::<Vec<_>>();
+        assert_eq!(stores.len(), 3);
+        assert!(stores[0].block.ends_with(":if"));
+        assert!(stores[1].block.ends_with(":else"));
+        // Half of the plane writes 64 contiguous bytes, the other half every other word.
+        assert_eq!(
+            (stores[0].transactions, stores[0].ideal_transactions),
+            (2, 2)
+        );
+        assert_eq!(
+            (stores[1].transactions, stores[1].ideal_transactions),
+            (4, 2)
+        );
+        // The condition depends on a load, so the branch is taken by every unit.
+        assert_eq!(stores[2].requests, 1);
+        assert_eq!(report.unknown_branches, 32);
+
+        let block = stores[1].block.clone();
+        let report = AccessAnalysis::new(CubeDim::new_1d(32))
+            .block(block.clone())
+            .analyze(&definition);
+        assert_eq!(report.sites.len(), 1);
+        assert_eq!(report.sites[0].block, block);
+    }
+
+    #[test]
+    fn loops_are_bounded() {
+        let definition = define(CubeDim::new_1d(32), |scope, input, output| {
+            range_loop::expand(scope, input, output)
+        });
+
+        // Every iteration is a separate request of the same site.
+        let report = AccessAnalysis::new(CubeDim::new_1d(32)).analyze(&definition);
+        let store = site(&report, MemoryKind::Global(1), true);
+        assert!(store.block.ends_with(":for"));
+        assert_eq!(store.requests, 2);
+        assert_eq!(store.efficiency(), 1.0);
+
+        // The known trip count bounds the simulated iterations.
+        let report = AccessAnalysis::new(CubeDim::new_1d(32))
+            .iterations(8)
+            .analyze(&definition);
+        let store = site(&report, MemoryKind::Global(1), true);
+        assert_eq!(store.requests, 4);
+        assert_eq!(store.transactions, 16);
+    }
+}
@@ -0,0 +1,317 @@
+//! Trapping of integer arithmetic exceptions, which otherwise show up as driver hangs or garbage.
+//!
+//! Enabled with `compilation.arithmetic_traps` in the config, the `CUBECL_ARITHMETIC_TRAPS`
+//! environment variable, or per kernel with [`KernelSettings::instrumentation`]. Instrumented
+//! kernels get checks around integer divisions and remainders, shifts and narrowing casts. The first failing check of a kernel is
+//! written to a buffer bound after the arguments of the kernel, and is read back with
+//! [`arithmetic_traps`].
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+use cubecl_ir::{
+    Arithmetic, Bitwise, ElemType, GlobalState, Instruction, Operation, Operator, Scope,
+    StorageType, Value,
+};
+use cubecl_runtime::{id::KernelId, server::ServerError};
+
+use super::instrumentation::{Instrumentation, source_location, with_slice};
+use crate::{
+    self as cubecl,
+    post_processing::{
+        analysis_helper::GlobalAnalyses, util::AtomicCounter, visitor::InstructionVisitor,
+    },
+    prelude::*,
+};
+
+define_scalar!(ElemA);
+define_scalar!(ElemB);
+define_size!(SizeA);
+
+/// The checks of every instrumented kernel, and the first one that failed.
+static TRAPS: Instrumentation<Vec<TrapSite>> = Instrumentation::new();
+
+/// The kind of an arithmetic exception.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrapKind {
+    /// An integer division or remainder by zero.
+    DivisionByZero,
+    /// A signed integer division or remainder of the minimum value by `-1`.
+    DivisionOverflow,
+    /// A shift by a negative amount, or by at least the number of bits of the shifted value.
+    ShiftOverflow,
+    /// An integer cast to a type that can't represent the value.
+    NarrowingCast,
+}
+
+/// A check inserted in a kernel.
+#[derive(Clone, Debug)]
+pub struct TrapSite {
+    /// The exception that is checked.
+    pub kind: TrapKind,
+    /// The checked instruction.
+    pub instruction: String,
+    /// The source location of the checked instruction, when debug symbols are enabled.
+    pub location: Option<String>,
+}
+
+/// The first arithmetic exception of a kernel.
+#[derive(Clone, Debug)]
+pub struct ArithmeticTrap {
+    /// The name of the kernel.
+    pub kernel: String,
+    /// The id of the kernel, distinguishing the variants of a kernel with the same name.
+    pub id: KernelId,
+    /// The check that failed.
+    pub site: TrapSite,
+}
+
+/// Insert the arithmetic checks of the kernel, writing to the given buffer.
+pub(crate) fn instrument(scope: &Scope, kernel_name: &str, id: KernelId, traps: Value) {
+    let mut sites = Vec::new();
+    with_slice(scope, traps, 1, |traps| {
+        let mut visitor = TrapVisitor {
+            traps: traps.expand,
+            sites: Vec::new(),
+        };
+        let changes = AtomicCounter::new(0);
+        visitor.visit_scope(scope, &GlobalAnalyses::default(), &changes);
+        sites = visitor.sites;
+    });
+
+    TRAPS.register(id, kernel_name, sites);
+}
+
+/// Whether the checks of a kernel are registered, see [`Instrumentation::is_registered`].
+pub(crate) fn is_registered(id: &KernelId) -> bool {
+    TRAPS.is_registered(id)
+}
+
+/// Forget the checks of a kernel, as in a new process.
+#[cfg(feature = "export_tests")]
+pub(crate) fn forget(id: &KernelId) {
+    TRAPS.forget(id);
+}
+
+/// The trap buffer of a kernel on the device of the client, created on its first launch.
+pub(crate) fn buffer<R: Runtime>(client: &ComputeClient<R>, id: KernelId) -> BufferArg<R> {
+    TRAPS.buffer(client, id, 1)
+}
+
+/// Read the first arithmetic exception of every kernel launched on the device of the client.
+pub fn arithmetic_traps<R: Runtime>(
+    client: &ComputeClient<R>,
+) -> Result<Vec<ArithmeticTrap>, ServerError> {
+    let traps = TRAPS
+        .read(client)?
+        .into_iter()
+        .filter_map(|kernel| {
+            // Sites are written with an offset of one, zero meaning no exception.
+            let site = u32::from_bytes(&kernel.buffer)[0].checked_sub(1)?;
+            let site = kernel.info.get(site as usize)?.clone();
+
+            Some(ArithmeticTrap {
+                kernel: kernel.name,
+                id: kernel.id,
+                site,
+            })
+        })
+        .collect();
+
+    Ok(traps)
+}
+
+/// Forget the arithmetic exceptions of every kernel on the device of the client.
+pub fn reset_arithmetic_traps<R: Runtime>(client: &ComputeClient<R>) {
+    TRAPS.reset(client);
+}
+
+#[derive(Debug)]
+struct TrapVisitor {
+    traps: Value,
+    sites: Vec<TrapSite>,
+}
+
+impl InstructionVisitor for TrapVisitor {
+    fn visit_instruction(
+        &mut self,
+        instruction: Instruction,
+        global_state: &GlobalState,
+        _analyses: &GlobalAnalyses,
+        _changes: &AtomicCounter,
+    ) -> Vec<Instruction> {
+        let scope = Scope::root(false).with_global_state(global_state.clone());
+        let traps: &SliceExpand<Atomic<u32>> = &self.traps.into();
+
+        match &instruction.operation {
+            Operation::Arithmetic(
+                Arithmetic::Div(op) | Arithmetic::Rem(op) | Arithmetic::ModFloor(op),
+            ) if is_integer(op.rhs.storage_type()) => {
+                let site = self.site(TrapKind::DivisionByZero, &instruction);
+                scope.register_type::<ElemA>(op.rhs.storage_type());
+                scope.register_size::<SizeA>(op.rhs.vector_size());
+                check_divisor::expand::<ElemA, SizeA>(&scope, traps, op.rhs.into(), site);
+
+                if op.lhs.storage_type().is_signed_int() && op.lhs.ty == op.rhs.ty {
+                    let site = self.site(TrapKind::DivisionOverflow, &instruction);
+                    check_division::expand::<ElemA, SizeA>(
+                        &scope,
+                        traps,
+                        op.lhs.into(),
+                        op.rhs.into(),
+                        site,
+                    );
+                }
+            }
+            Operation::Bitwise(Bitwise::ShiftLeft(op) | Bitwise::ShiftRight(op))
+                if is_integer(op.rhs.storage_type()) =>
+            {
+                let site = self.site(TrapKind::ShiftOverflow, &instruction);
+                let bits = op.lhs.storage_type().size_bits() as u32;
+                scope.register_type::<ElemA>(op.rhs.storage_type());
+                scope.register_size::<SizeA>(op.rhs.vector_size());
+                check_shift::expand::<ElemA, SizeA>(&scope, traps, op.rhs.into(), bits, site);
+            }
+            Operation::Operator(Operator::Cast(op))
+                if is_narrowing(op.input.storage_type(), instruction.out().storage_type()) =>
+            {
+                let site = self.site(TrapKind::NarrowingCast, &instruction);
+                scope.register_type::<ElemA>(op.input.storage_type());
+                scope.register_type::<ElemB>(instruction.out().storage_type());
+                scope.register_size::<SizeA>(op.input.vector_size());
+                check_cast::expand::<ElemA, ElemB, SizeA>(&scope, traps, op.input.into(), site);
+            }
+            _ => {}
+        }
+
+        let mut instructions = scope.take_instructions();
+        instructions.push(instruction);
+        instructions
+    }
+}
+
+impl TrapVisitor {
+    fn site(&mut self, kind: TrapKind, instruction: &Instruction) -> u32 {
+        self.sites.push(TrapSite {
+            kind,
+            instruction: instruction.to_string(),
+            location: instruction.source_loc.as_ref().map(source_location),
+        });
+        self.sites.len() as u32 - 1
+    }
+}
+
+fn is_integer(ty: StorageType) -> bool {
+    matches!(
+        ty,
+        StorageType::Scalar(ElemType::Int(_) | ElemType::UInt(_))
+    )
+}
+
+/// Whether casting between the given integer types can lose information.
+fn is_narrowing(from: StorageType, to: StorageType) -> bool {
+    if !is_integer(from) || !is_integer(to) {
+        return false;
+    }
+
+    let (from_signed, to_signed) = (from.is_signed_int(), to.is_signed_int());
+    match to.size_bits().cmp(&from.size_bits()) {
+        core::cmp::Ordering::Greater => from_signed && !to_signed,
+        core::cmp::Ordering::Equal => from_signed != to_signed,
+        core::cmp::Ordering::Less => true,
+    }
+}
+
+#[cube]
+fn check_divisor<I: Int, N: Size>(
+    traps: &[Atomic<u32>],
+    divisor: Vector<I, N>,
+    #[comptime] site: u32,
+) {
+    let zero = Vector::new(I::new(0));
+    trap_if::<N>(traps, divisor.equal(&zero), site);
+}
+
+#[cube]
+fn check_division<I: Int, N: Size>(
+    traps: &[Atomic<u32>],
+    dividend: Vector<I, N>,
+    divisor: Vector<I, N>,
+    #[comptime] site: u32,
+) {
+    let min = Vector::new(I::min_value());
+    let minus_one = Vector::new(I::new(-1));
+    let overflow = dividend.equal(&min).vec_and(divisor.equal(&minus_one));
+    trap_if::<N>(traps, overflow, site);
+}
+
+#[cube]
+fn check_shift<I: Int, N: Size>(
+    traps: &[Atomic<u32>],
+    shift: Vector<I, N>,
+    #[comptime] bits: u32,
+    #[comptime] site: u32,
+) {
+    let zero = Vector::new(I::new(0));
+    let bits = Vector::new(I::new(bits as i64));
+    trap_if::<N>(
+        traps,
+        shift.less_than(&zero).or(shift.greater_equal(&bits)),
+        site,
+    );
+}
+
+#[cube]
+fn check_cast<I: Int, O: Int, N: Size>(
+    traps: &[Atomic<u32>],
+    value: Vector<I, N>,
+    #[comptime] site: u32,
+) {
+    let cast = Vector::<O, N>::cast_from(value);
+    let truncated = Vector::<I, N>::cast_from(cast).not_equal(&value);
+    // Casting between signed and unsigned types of the same size round-trips, but flips the sign.
+    let negative = value.less_than(&Vector::new(I::new(0)));
+    let sign_changed = negative.not_equal(&cast.less_than(&Vector::new(O::new(0))));
+    trap_if::<N>(traps, truncated.or(sign_changed), site);
+}
+
+#[cube]
+fn trap_if<N: Size>(traps: &[Atomic<u32>], condition: Vector<bool, N>, #[comptime] site: u32) {
+    let mut trapped = false;
+    #[unroll]
+    for i in 0..N::value() {
+        trapped |= condition.extract(i);
+    }
+
+    if trapped {
+        // Only the first exception is kept, the next ones are often consequences of it.
+        traps[0].compare_exchange_weak(0u32, comptime![site + 1]);
+    }
+}
+
+impl Display for TrapKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TrapKind::DivisionByZero => f.write_str("Division by zero"),
+            TrapKind::DivisionOverflow => f.write_str("Division overflow"),
+            TrapKind::ShiftOverflow => f.write_str("Shift overflow"),
+            TrapKind::NarrowingCast => f.write_str("Narrowing cast"),
+        }
+    }
+}
+
+impl Display for ArithmeticTrap {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} in kernel {}: {}",
+            self.site.kind, self.kernel, self.site.instruction
+        )?;
+        match &self.site.location {
+            Some(location) => write!(f, " ({location})"),
+            None => Ok(()),
+        }
+    }
+}
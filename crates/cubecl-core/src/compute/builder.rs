@@ -2,15 +2,18 @@ use alloc::vec::Vec;
 use core::sync::atomic::{AtomicI8, Ordering};
 use derive_more::Deref;
 
+use super::{arithmetic_traps, coverage};
 use crate::{
     BufferInfo, KernelExpansion, KernelIntegrator, KernelSettings, ScalarInfo,
     ir::{Id, Type},
-    prelude::KernelDefinition,
+    post_processing::analysis_helper::{BufferVisibility, GlobalAnalyses},
+    prelude::{CubePrimitive, KernelDefinition, Visibility},
 };
 use alloc::collections::BTreeMap;
 use cubecl_ir::{DeviceProperties, Scope, StorageType, TargetProperties, Value};
-use cubecl_runtime::config::{
-    CubeClRuntimeConfig, RuntimeConfig, compilation::CompilationLogLevel,
+use cubecl_runtime::{
+    config::{CubeClRuntimeConfig, RuntimeConfig, compilation::CompilationLogLevel},
+    id::KernelId,
 };
 
 /// Prepare a kernel to create a [`KernelDefinition`].
@@ -22,6 +25,10 @@ pub struct KernelBuilder {
     buffers: Vec<BufferInfo>,
     scalars: BTreeMap<StorageType, usize>,
     tensor_maps: Vec<BufferInfo>,
+    textures: Vec<BufferInfo>,
+    /// Buffers of arguments declared behind a shared reference.
+    read_only: Vec<Id>,
+    kernel_id: Option<KernelId>,
 }
 
 static DEBUG: AtomicI8 = AtomicI8::new(-1);
@@ -35,8 +42,16 @@ impl KernelBuilder {
         id as Id
     }
 
+    /// Register `len` consecutive scalars and return the id of the first one.
+    pub fn scalar_array(&mut self, storage: StorageType, len: usize) -> Id {
+        let current_id = self.scalars.entry(storage).or_default();
+        let id = *current_id;
+        *current_id += len;
+        id as Id
+    }
+
     fn buffer_id(&self) -> Id {
-        self.buffers.len() as Id + self.tensor_maps.len() as Id
+        self.buffers.len() as Id + self.tensor_maps.len() as Id + self.textures.len() as Id
     }
 
     /// Register a buffer and return the [element](Value) to be used for kernel expansion.
@@ -75,10 +90,36 @@ impl KernelBuilder {
         value
     }
 
+    /// Register a texture and return the [element](Value) to be used for kernel expansion.
+    pub fn texture(&mut self) -> Value {
+        let id = self.buffer_id();
+        let value = self.scope.texture(id);
+        self.textures.push(BufferInfo {
+            id,
+            value,
+            has_extended_meta: false,
+        });
+        value
+    }
+
     /// Register an output that uses the same resource as the input as the given position.
     pub fn inplace(&mut self, position: Id) -> Value {
         let input = self.buffers.get_mut(position as usize);
-        input.expect("Position valid").value
+        let input = input.expect("Position valid");
+        // The output aliases the input, so the buffer is written to even if the input isn't.
+        self.read_only.retain(|id| *id != input.id);
+        input.value
+    }
+
+    /// Expand an argument declared behind a shared reference, whose buffers must never be written
+    /// to. Writes are reported when the kernel is built with the `read-only-checks` feature, since
+    /// the buffers may be inputs of other kernels.
+    pub fn read_only<T>(&mut self, expand: impl FnOnce(&mut Self) -> T) -> T {
+        let start = self.buffers.len();
+        let expanded = expand(self);
+        let ids = self.buffers[start..].iter().map(|buffer| buffer.id);
+        self.read_only.extend(ids);
+        expanded
     }
 
     pub fn runtime_properties(&mut self, properties: TargetProperties) {
@@ -89,8 +130,35 @@ impl KernelBuilder {
         self.scope.device_properties(properties);
     }
 
+    /// Set the id of the kernel, which is instrumented with it when [coverage](super::coverage_report)
+    /// or [arithmetic traps](super::arithmetic_traps) are enabled in its
+    /// [instrumentation](KernelId::instrumentation). Kernels with an instrumented id must set it,
+    /// since the launcher binds the instrumentation buffers of the id.
+    pub fn kernel_id(&mut self, id: KernelId) {
+        self.kernel_id = Some(id);
+    }
+
     /// Build the [kernel definition](KernelDefinition).
-    pub fn build(self, settings: KernelSettings) -> KernelDefinition {
+    pub fn build(mut self, settings: KernelSettings) -> KernelDefinition {
+        if cfg!(feature = "read-only-checks") {
+            self.assert_read_only(&settings.options.kernel_name);
+        }
+
+        // The instrumentation of the id is also used by the launcher to bind the buffers.
+        if let Some(id) = self.kernel_id.take() {
+            let name = &settings.options.kernel_name;
+            let instrumentation = id.instrumentation;
+            if instrumentation.coverage {
+                let counters = self.buffer(Type::atomic(u32::as_type_native_unchecked()));
+                coverage::instrument(&self.scope, name, id.clone(), counters);
+            }
+            // Inserted after the counters, so the checks aren't counted as blocks.
+            if instrumentation.arithmetic_traps {
+                let traps = self.buffer(Type::atomic(u32::as_type_native_unchecked()));
+                arithmetic_traps::instrument(&self.scope, name, id, traps);
+            }
+        }
+
         let scalars = self
             .scalars
             .into_iter()
@@ -101,10 +169,37 @@ impl KernelBuilder {
             buffers: self.buffers,
             scalars,
             tensor_maps: self.tensor_maps,
+            textures: self.textures,
         })
         .integrate(settings)
     }
 
+    /// Panics if the kernel writes to a read-only argument, for example after casting it with
+    /// `as_mut_unchecked`. Only checked with the `read-only-checks` feature.
+    fn assert_read_only(&self, kernel_name: &str) {
+        if self.read_only.is_empty() {
+            return;
+        }
+
+        let analyses = GlobalAnalyses::default();
+        analyses.recalculate_pointer_source(&self.scope);
+        let visibility = BufferVisibility::new(&self.scope, &analyses);
+
+        let buffers = self
+            .buffers
+            .iter()
+            // Atomics are written to through shared references.
+            .filter(|buffer| self.read_only.contains(&buffer.id) && !buffer.value.ty.is_atomic());
+        for buffer in buffers {
+            let written = visibility.get(buffer.id as usize) == Some(&Visibility::ReadWrite);
+            assert!(
+                !written,
+                "Kernel `{kernel_name}` writes to buffer {}, which is declared as read-only",
+                buffer.id
+            );
+        }
+    }
+
     pub fn new() -> Self {
         let debug = DEBUG.load(Ordering::Relaxed);
         let debug = if debug == -1 {
@@ -124,6 +219,9 @@ impl KernelBuilder {
             buffers: Default::default(),
             scalars: Default::default(),
             tensor_maps: Default::default(),
+            textures: Default::default(),
+            read_only: Default::default(),
+            kernel_id: None,
         }
     }
 }
@@ -0,0 +1,580 @@
+//! Static estimate of the cost of a kernel, for scheduling layers that make fusion and placement
+//! decisions without measuring the kernel.
+//!
+//! The floating-point operations and the global memory traffic of a unit are counted from the
+//! instructions of the kernel, then scaled by the number of units of the launch. Branches are
+//! counted for their most expensive path, and loops with constant bounds for their trip count.
+//! Loops and copies whose length isn't constant, including TMA stores, are counted for a
+//! configured number of iterations, so the estimate is only an upper bound for kernels where
+//! every loop and copy has a constant length.
+//!
+//! Copies shared by a whole cube, i.e. cooperative async copies, tensor memory accelerator (TMA)
+//! copies and cube-scoped matrices, are split between the units of the cube. TMA loads are
+//! counted from the transaction bytes expected by their barrier, since the size of their tile is
+//! only known by the tensor map.
+
+use core::{ops::Add, time::Duration};
+use cubecl_ir::{
+    AddressSpace, Arithmetic, AtomicOp, BarrierOps, Branch, CoopMma, DeviceProperties, MatrixScope,
+    MatrixType, Memory, Operation, Plane, RangeLoop, Scope, TextureOps, TmaOps, Type, Value,
+};
+
+use crate::prelude::{CubeDim, KernelDefinition};
+
+/// Estimate the cost of a kernel for a launch shape.
+///
+/// ```ignore
+/// let cost = CostModel::new().cube_count(64, 64, 1).estimate(&kernel.define());
+/// let duration = cost.duration(&DevicePeaks::new(50e12, 1e12));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CostModel {
+    cube_count: [u32; 3],
+    plane_size: u32,
+    iterations: u64,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CostModel {
+    /// Estimate the cost of a single cube.
+    pub fn new() -> Self {
+        Self {
+            cube_count: [1; 3],
+            plane_size: 32,
+            iterations: 16,
+        }
+    }
+
+    /// The number of cubes of the launch, `(1, 1, 1)` by default.
+    pub fn cube_count(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.cube_count = [x, y, z];
+        self
+    }
+
+    /// The number of units per plane, which share the work of plane-wide matrix operations. 32 by
+    /// default.
+    pub fn plane_size(mut self, plane_size: u32) -> Self {
+        self.plane_size = plane_size;
+        self
+    }
+
+    /// The number of iterations counted for loops without constant bounds, and the number of
+    /// elements counted for async copies without a constant length. 16 by default.
+    pub fn iterations(mut self, iterations: u64) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Estimate the cost of launching the kernel.
+    pub fn estimate(&self, definition: &KernelDefinition) -> KernelCost {
+        let counter = Counter {
+            model: self,
+            cube_dim: definition.cube_dim,
+        };
+        let unit = counter.scope(&definition.body);
+
+        let [x, y, z] = self.cube_count;
+        let units = definition.cube_dim.num_elems() as f64 * x as f64 * y as f64 * z as f64;
+        KernelCost {
+            flops: unit.flops * units,
+            bytes_read: unit.bytes_read * units,
+            bytes_written: unit.bytes_written * units,
+        }
+    }
+}
+
+/// The estimated cost of a kernel launch, see [`CostModel`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KernelCost {
+    /// The number of floating-point operations, counting a fused multiply-add as two.
+    pub flops: f64,
+    /// The number of bytes read from global memory.
+    pub bytes_read: f64,
+    /// The number of bytes written to global memory.
+    pub bytes_written: f64,
+}
+
+impl KernelCost {
+    /// The number of bytes moved to or from global memory.
+    pub fn bytes(&self) -> f64 {
+        self.bytes_read + self.bytes_written
+    }
+
+    /// The number of floating-point operations per byte of global memory traffic.
+    pub fn arithmetic_intensity(&self) -> f64 {
+        let bytes = self.bytes();
+        if bytes == 0.0 {
+            return f64::INFINITY;
+        }
+        self.flops / bytes
+    }
+
+    /// The expected duration of the launch on a device with the given peaks, bound by either its
+    /// compute throughput or its memory bandwidth.
+    pub fn duration(&self, peaks: &DevicePeaks) -> Duration {
+        let compute = self.flops / peaks.flops_per_second;
+        let memory = self.bytes() / peaks.bytes_per_second;
+        Duration::from_secs_f64(compute.max(memory))
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            flops: self.flops.max(other.flops),
+            bytes_read: self.bytes_read.max(other.bytes_read),
+            bytes_written: self.bytes_written.max(other.bytes_written),
+        }
+    }
+
+    fn scale(self, factor: f64) -> Self {
+        Self {
+            flops: self.flops * factor,
+            bytes_read: self.bytes_read * factor,
+            bytes_written: self.bytes_written * factor,
+        }
+    }
+}
+
+impl Add for KernelCost {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            flops: self.flops + rhs.flops,
+            bytes_read: self.bytes_read + rhs.bytes_read,
+            bytes_written: self.bytes_written + rhs.bytes_written,
+        }
+    }
+}
+
+/// The peak throughputs of a device, used to turn a [cost](KernelCost) into a duration.
+#[derive(new, Clone, Copy, Debug, PartialEq)]
+pub struct DevicePeaks {
+    /// Floating-point operations per second.
+    pub flops_per_second: f64,
+    /// Bytes per second between global memory and the cores.
+    pub bytes_per_second: f64,
+}
+
+impl DevicePeaks {
+    /// The clock assumed by [`from_properties`](Self::from_properties), in hertz.
+    pub const ASSUMED_CLOCK_HZ: f64 = 1.5e9;
+    /// The floating-point operations per byte of global memory traffic assumed by
+    /// [`from_properties`](Self::from_properties), typical of GPUs without tensor cores.
+    pub const ASSUMED_MACHINE_BALANCE: f64 = 10.0;
+
+    /// Rough peaks of a device from its properties, when measured or published peaks aren't
+    /// available, or `None` if the properties don't report the number of cores.
+    ///
+    /// Every streaming multiprocessor is assumed to run four planes of
+    /// [`plane_size_max`](cubecl_ir::HardwareProperties::plane_size_max) units, and every CPU
+    /// core a single one. Each unit retires a fused multiply-add per cycle at
+    /// [`ASSUMED_CLOCK_HZ`](Self::ASSUMED_CLOCK_HZ), and the memory bandwidth follows from
+    /// [`ASSUMED_MACHINE_BALANCE`](Self::ASSUMED_MACHINE_BALANCE).
+    pub fn from_properties(properties: &DeviceProperties) -> Option<Self> {
+        let hardware = &properties.hardware;
+        let planes = match (
+            hardware.num_streaming_multiprocessors,
+            hardware.num_cpu_cores,
+        ) {
+            (Some(sms), _) => sms as f64 * 4.0,
+            (None, Some(cores)) => cores as f64,
+            (None, None) => return None,
+        };
+        let units = planes * hardware.plane_size_max.max(1) as f64;
+        let flops_per_second = units * 2.0 * Self::ASSUMED_CLOCK_HZ;
+
+        Some(Self {
+            flops_per_second,
+            bytes_per_second: flops_per_second / Self::ASSUMED_MACHINE_BALANCE,
+        })
+    }
+}
+
+struct Counter<'a> {
+    model: &'a CostModel,
+    cube_dim: CubeDim,
+}
+
+impl Counter<'_> {
+    /// The cost of a unit running the scope.
+    fn scope(&self, scope: &Scope) -> KernelCost {
+        scope
+            .instructions
+            .borrow()
+            .iter()
+            .map(|instruction| match &instruction.operation {
+                Operation::Branch(branch) => self.branch(branch),
+                operation => self.operation(operation, instruction.out),
+            })
+            .fold(KernelCost::default(), Add::add)
+    }
+
+    fn branch(&self, branch: &Branch) -> KernelCost {
+        match branch {
+            Branch::If(op) => self.scope(&op.scope),
+            Branch::IfElse(op) => self.scope(&op.scope_if).max(self.scope(&op.scope_else)),
+            Branch::Switch(op) => op
+                .cases
+                .iter()
+                .map(|(_, case)| self.scope(case))
+                .fold(self.scope(&op.scope_default), KernelCost::max),
+            Branch::RangeLoop(op) => self.scope(&op.scope).scale(self.trip_count(op) as f64),
+            Branch::Loop(op) => self.scope(&op.scope).scale(self.model.iterations as f64),
+            Branch::Return | Branch::Break | Branch::Unreachable => KernelCost::default(),
+        }
+    }
+
+    fn trip_count(&self, op: &RangeLoop) -> u64 {
+        let constant = |value: Value| value.as_const().map(|value| value.as_i64());
+        let step = op.step.map_or(Some(1), constant);
+        match (constant(op.start), constant(op.end), step) {
+            (Some(start), Some(end), Some(step)) if step > 0 => {
+                let len = (end - start + op.inclusive as i64).max(0) as u64;
+                len.div_ceil(step as u64)
+            }
+            _ => self.model.iterations,
+        }
+    }
+
+    fn operation(&self, operation: &Operation, out: Option<Value>) -> KernelCost {
+        match operation {
+            Operation::Arithmetic(op) => {
+                let Some(out) = out.filter(|out| out.ty.is_float()) else {
+                    return KernelCost::default();
+                };
+                let ops = match op {
+                    Arithmetic::Fma(_) => 2,
+                    _ => 1,
+                };
+                KernelCost {
+                    flops: (ops * out.ty.vector_size()) as f64,
+                    ..Default::default()
+                }
+            }
+            Operation::Memory(Memory::Load(ptr)) | Operation::Atomic(AtomicOp::Load(ptr)) => {
+                KernelCost {
+                    bytes_read: global_bytes(*ptr),
+                    ..Default::default()
+                }
+            }
+            Operation::Memory(Memory::Store(op)) | Operation::Atomic(AtomicOp::Store(op)) => {
+                KernelCost {
+                    bytes_written: global_bytes(op.ptr),
+                    ..Default::default()
+                }
+            }
+            Operation::Memory(Memory::CopyMemory(op)) => KernelCost {
+                bytes_read: global_bytes(op.source),
+                bytes_written: global_bytes(op.target),
+                ..Default::default()
+            },
+            Operation::Atomic(
+                AtomicOp::Swap(op)
+                | AtomicOp::Add(op)
+                | AtomicOp::Sub(op)
+                | AtomicOp::Max(op)
+                | AtomicOp::Min(op)
+                | AtomicOp::And(op)
+                | AtomicOp::Or(op)
+                | AtomicOp::Xor(op),
+            ) => KernelCost {
+                bytes_read: global_bytes(op.ptr),
+                bytes_written: global_bytes(op.ptr),
+                ..Default::default()
+            },
+            Operation::Atomic(AtomicOp::CompareAndSwap(op)) => KernelCost {
+                bytes_read: global_bytes(op.ptr),
+                bytes_written: global_bytes(op.ptr),
+                ..Default::default()
+            },
+            Operation::CoopMma(op) => self.matrix(op, out),
+            Operation::Plane(op) => self.plane(op, out),
+            Operation::Barrier(op) => self.barrier(op),
+            // The source is in shared memory, and the tile is written to global memory.
+            Operation::Tma(TmaOps::TmaStore { source, .. }) => KernelCost {
+                bytes_written: pointee_bytes(*source) * self.model.iterations as f64
+                    / self.cube_units(),
+                ..Default::default()
+            },
+            Operation::Texture(TextureOps::Sample2d { .. }) => KernelCost {
+                bytes_read: out.map_or(0.0, |out| out.ty.size() as f64),
+                ..Default::default()
+            },
+            _ => KernelCost::default(),
+        }
+    }
+
+    /// Plane reductions and scans combine the values of the plane in `log2(plane_size)` steps.
+    fn plane(&self, op: &Plane, out: Option<Value>) -> KernelCost {
+        let Some(out) = out.filter(|out| out.ty.is_float()) else {
+            return KernelCost::default();
+        };
+        match op {
+            Plane::Sum(_)
+            | Plane::InclusiveSum(_)
+            | Plane::ExclusiveSum(_)
+            | Plane::Prod(_)
+            | Plane::InclusiveProd(_)
+            | Plane::ExclusiveProd(_)
+            | Plane::Min(_)
+            | Plane::Max(_) => {
+                let steps = self.model.plane_size.max(1).next_power_of_two().ilog2();
+                KernelCost {
+                    flops: (steps as usize * out.ty.vector_size()) as f64,
+                    ..Default::default()
+                }
+            }
+            _ => KernelCost::default(),
+        }
+    }
+
+    /// Async copies from global memory. Copies with a transaction count are counted from the
+    /// bytes expected by their barrier, like TMA loads.
+    fn barrier(&self, op: &BarrierOps) -> KernelCost {
+        let bytes_read = match op {
+            BarrierOps::MemCopyAsync {
+                source,
+                source_length,
+                ..
+            } => self.copy_bytes(*source, Some(*source_length)),
+            BarrierOps::MemCopyAsyncCooperative {
+                source,
+                source_length,
+                ..
+            } => self.copy_bytes(*source, Some(*source_length)) / self.cube_units(),
+            BarrierOps::CopyAsync {
+                source,
+                copy_length,
+                ..
+            } if is_global(*source) => *copy_length as f64,
+            BarrierOps::ExpectTx {
+                transaction_count_update,
+                ..
+            }
+            | BarrierOps::ArriveTx {
+                transaction_count_update,
+                ..
+            } => match transaction_count_update.as_const() {
+                Some(bytes) => bytes.as_u64() as f64 / self.cube_units(),
+                None => 0.0,
+            },
+            _ => 0.0,
+        };
+
+        KernelCost {
+            bytes_read,
+            ..Default::default()
+        }
+    }
+
+    /// The bytes of global memory copied from the elements at `ptr`, counting the configured
+    /// number of iterations when the length isn't constant.
+    fn copy_bytes(&self, ptr: Value, length: Option<Value>) -> f64 {
+        let length = length
+            .and_then(|length| length.as_const())
+            .map_or(self.model.iterations, |length| length.as_u64());
+        global_bytes(ptr) * length as f64
+    }
+
+    /// The share of a unit in a matrix operation, which is executed by a whole plane or cube.
+    fn matrix(&self, op: &CoopMma, out: Option<Value>) -> KernelCost {
+        let matrix_bytes = |matrix: &MatrixType, ptr: Value| match is_global(ptr) {
+            true => (matrix.num_elems() * matrix.storage.size()) as f64 / self.units(matrix),
+            false => 0.0,
+        };
+
+        match op {
+            CoopMma::Execute { mat_a, .. } => match mat_a.ty {
+                Type::Matrix(matrix) => KernelCost {
+                    flops: (2 * matrix.m * matrix.n * matrix.k) as f64 / self.units(&matrix),
+                    ..Default::default()
+                },
+                _ => KernelCost::default(),
+            },
+            CoopMma::Load { ptr, .. } | CoopMma::LoadTensor { buffer: ptr, .. } => {
+                match out.map(|out| out.ty) {
+                    Some(Type::Matrix(matrix)) => KernelCost {
+                        bytes_read: matrix_bytes(&matrix, *ptr),
+                        ..Default::default()
+                    },
+                    _ => KernelCost::default(),
+                }
+            }
+            CoopMma::Store {
+                mat, destination, ..
+            } => match mat.ty {
+                Type::Matrix(matrix) => KernelCost {
+                    bytes_written: matrix_bytes(&matrix, *destination),
+                    ..Default::default()
+                },
+                _ => KernelCost::default(),
+            },
+            _ => KernelCost::default(),
+        }
+    }
+
+    /// The number of units sharing a matrix.
+    fn units(&self, matrix: &MatrixType) -> f64 {
+        match matrix.scope {
+            MatrixScope::Plane => self.model.plane_size.max(1) as f64,
+            MatrixScope::Cube => self.cube_units(),
+        }
+    }
+
+    /// The number of units sharing a copy of the whole cube.
+    fn cube_units(&self) -> f64 {
+        self.cube_dim.num_elems().max(1) as f64
+    }
+}
+
+fn is_global(ptr: Value) -> bool {
+    matches!(ptr.address_space(), AddressSpace::Global(_))
+}
+
+/// The number of bytes accessed through a pointer to global memory, zero for other memories.
+fn global_bytes(ptr: Value) -> f64 {
+    match is_global(ptr) {
+        true => pointee_bytes(ptr),
+        false => 0.0,
+    }
+}
+
+/// The number of bytes accessed through a pointer.
+fn pointee_bytes(ptr: Value) -> f64 {
+    match ptr.ty {
+        Type::Pointer(inner, _) => match *inner {
+            Type::DynamicArray(inner) => inner.size() as f64,
+            inner => inner.size() as f64,
+        },
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::barrier::Barrier;
+    use crate::{self as cubecl, prelude::*};
+    use cubecl_runtime::dry_run::DryRunServer;
+
+    type Buffer = <[f32] as CubeType>::ExpandType;
+
+    #[cube]
+    fn accumulate(input: &[f32], output: &mut [f32]) {
+        let mut acc = 0.0;
+        for i in 0..4usize {
+            acc += input[UNIT_POS as usize + i] * 2.0;
+        }
+        output[UNIT_POS as usize] = acc;
+    }
+
+    #[cube]
+    fn unbounded(input: &[f32], output: &mut [f32]) {
+        let mut acc = 0.0;
+        for i in 1..input.len() {
+            acc += input[i - 1];
+        }
+        output[UNIT_POS as usize] = acc;
+    }
+
+    #[cube]
+    fn plane_reduce(input: &[f32], output: &mut [f32]) {
+        output[UNIT_POS as usize] = plane_sum(input[UNIT_POS as usize]);
+    }
+
+    #[cube]
+    fn async_copy(input: &[f32], output: &mut [f32]) {
+        let mut shared = Shared::<[f32]>::new_slice(256usize);
+        let barrier = Barrier::local();
+        barrier.memcpy_async(input.slice(0, 8), shared.slice_mut(0, 8));
+        barrier.arrive_and_wait();
+        output[UNIT_POS as usize] = shared[UNIT_POS as usize];
+    }
+
+    fn define(
+        cube_dim: CubeDim,
+        kernel: impl FnOnce(&mut Scope, &Buffer, &mut Buffer),
+    ) -> KernelDefinition {
+        let settings = KernelSettings::default().cube_dim(cube_dim);
+        let mut builder = KernelBuilder::default();
+        settings.address_type.register(&builder.scope);
+        let arg = BufferCompilationArg { inplace: None };
+        let input = builder.read_only(|builder| <[f32] as LaunchArg>::expand(&arg, builder));
+        let mut output = <[f32] as LaunchArg>::expand(&arg, &mut builder);
+        kernel(&mut builder.scope, &input, &mut output);
+        builder.build(settings)
+    }
+
+    #[test]
+    fn constant_loops_are_counted_for_their_trip_count() {
+        let definition = define(CubeDim::new_1d(8), |scope, input, output| {
+            accumulate::expand(scope, input, output)
+        });
+        let cost = CostModel::new().cube_count(2, 1, 1).estimate(&definition);
+
+        // Each of the 16 units reads and doubles 4 values, then writes their sum.
+        assert_eq!(
+            cost,
+            KernelCost {
+                flops: 128.0,
+                bytes_read: 256.0,
+                bytes_written: 64.0,
+            }
+        );
+    }
+
+    #[test]
+    fn other_loops_are_counted_for_the_configured_iterations() {
+        let definition = define(CubeDim::new_1d(1), |scope, input, output| {
+            unbounded::expand(scope, input, output)
+        });
+        let cost = CostModel::new().iterations(10).estimate(&definition);
+
+        assert_eq!(cost.flops, 10.0);
+        assert_eq!(cost.bytes_read, 40.0);
+    }
+
+    #[test]
+    fn plane_reductions_count_their_steps() {
+        let definition = define(CubeDim::new_1d(32), |scope, input, output| {
+            plane_reduce::expand(scope, input, output)
+        });
+        let cost = CostModel::new().plane_size(32).estimate(&definition);
+
+        // Each of the 32 units adds the values of the plane in 5 steps.
+        assert_eq!(cost.flops, 160.0);
+    }
+
+    #[test]
+    fn async_copies_read_global_memory() {
+        let definition = define(CubeDim::new_1d(4), |scope, input, output| {
+            async_copy::expand(scope, input, output)
+        });
+        let cost = CostModel::new().iterations(8).estimate(&definition);
+
+        // Each of the 4 units copies 8 values to shared memory.
+        assert_eq!(cost.bytes_read, 128.0);
+        assert_eq!(cost.bytes_written, 16.0);
+    }
+
+    #[test]
+    fn device_peaks_from_properties() {
+        let mut properties = DryRunServer::default_properties();
+        properties.hardware.num_streaming_multiprocessors = Some(10);
+
+        let peaks = DevicePeaks::from_properties(&properties).unwrap();
+        assert_eq!(peaks.flops_per_second, 10.0 * 4.0 * 32.0 * 2.0 * 1.5e9);
+        assert_eq!(
+            peaks.bytes_per_second,
+            peaks.flops_per_second / DevicePeaks::ASSUMED_MACHINE_BALANCE
+        );
+
+        properties.hardware.num_streaming_multiprocessors = None;
+        assert_eq!(DevicePeaks::from_properties(&properties), None);
+    }
+}
@@ -0,0 +1,217 @@
+//! Code coverage of kernels, to see which blocks of complex components are actually exercised.
+//!
+//! Enabled with `compilation.coverage` in the config, the `CUBECL_COVERAGE` environment
+//! variable, or per kernel with [`KernelSettings::instrumentation`]. Instrumented kernels get a
+//! counter per block: the body of the kernel and every branch of an `if`, a `switch` or a loop.
+//! The counters are stored in a buffer bound after the arguments of the kernel, and are read back
+//! with [`coverage_report`].
+//!
+//! Comptime branches are resolved during expansion, so a comptime branch that is never taken
+//! doesn't appear in the report, while a runtime branch that is never taken has no hit.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+use cubecl_ir::{Branch, Operation, Scope, Value};
+use cubecl_runtime::{id::KernelId, server::ServerError};
+
+use super::instrumentation::{Instrumentation, source_location, with_slice};
+use crate::{self as cubecl, prelude::*};
+
+/// The maximum number of instrumented blocks of a kernel, the next ones aren't counted.
+pub const MAX_COVERAGE_BLOCKS: usize = 4096;
+
+/// The blocks of every instrumented kernel, and their counters.
+static COVERAGE: Instrumentation<Vec<Block>> = Instrumentation::new();
+
+#[derive(Clone, Debug)]
+struct Block {
+    path: String,
+    location: Option<String>,
+}
+
+/// Insert a counter at the start of every block of the kernel, incrementing the given buffer.
+pub(crate) fn instrument(scope: &Scope, kernel_name: &str, id: KernelId, counters: Value) {
+    let mut blocks = Vec::new();
+    with_slice(scope, counters, MAX_COVERAGE_BLOCKS, |counters| {
+        instrument_block(scope, "body".to_string(), counters, &mut blocks);
+    });
+
+    if blocks.len() == MAX_COVERAGE_BLOCKS {
+        log::warn!("Kernel {kernel_name} has too many blocks, coverage is truncated");
+    }
+
+    COVERAGE.register(id, kernel_name, blocks);
+}
+
+fn instrument_block(
+    scope: &Scope,
+    path: String,
+    counters: &SliceExpand<Atomic<u32>>,
+    blocks: &mut Vec<Block>,
+) {
+    let index = blocks.len();
+    if index == MAX_COVERAGE_BLOCKS {
+        return;
+    }
+
+    let instructions = scope.take_instructions();
+    let location = instructions
+        .iter()
+        .find_map(|instruction| instruction.source_loc.as_ref())
+        .map(source_location);
+    blocks.push(Block {
+        path: path.clone(),
+        location,
+    });
+
+    // Blocks are numbered in the order of the instructions, which is deterministic for a given
+    // kernel, so the blocks registered when the launcher defines a kernel loaded from a
+    // compilation cache match the counters of the cached kernel.
+    for (position, instruction) in instructions.iter().enumerate() {
+        let Operation::Branch(branch) = &instruction.operation else {
+            continue;
+        };
+        let path = |kind: &str| format!("{path}/{position}:{kind}");
+
+        match branch {
+            Branch::If(op) => instrument_block(&op.scope, path("if"), counters, blocks),
+            Branch::IfElse(op) => {
+                instrument_block(&op.scope_if, path("if"), counters, blocks);
+                instrument_block(&op.scope_else, path("else"), counters, blocks);
+            }
+            Branch::Switch(op) => {
+                for (value, case) in &op.cases {
+                    instrument_block(case, path(&format!("case {value}")), counters, blocks);
+                }
+                instrument_block(&op.scope_default, path("default"), counters, blocks);
+            }
+            Branch::RangeLoop(op) => instrument_block(&op.scope, path("for"), counters, blocks),
+            Branch::Loop(op) => instrument_block(&op.scope, path("loop"), counters, blocks),
+            Branch::Return | Branch::Break | Branch::Unreachable => {}
+        }
+    }
+
+    let counter = Scope::root(false).with_global_state(scope.global_state.clone());
+    count_block::expand(&counter, counters, index);
+    scope.register_all(counter.take_instructions());
+    scope.register_all(instructions);
+}
+
+#[cube]
+fn count_block(counters: &[Atomic<u32>], #[comptime] block: usize) {
+    counters[block].fetch_add(1u32);
+}
+
+/// Whether the blocks of a kernel are registered, see [`Instrumentation::is_registered`].
+pub(crate) fn is_registered(id: &KernelId) -> bool {
+    COVERAGE.is_registered(id)
+}
+
+/// Forget the blocks of a kernel, as in a new process.
+#[cfg(feature = "export_tests")]
+pub(crate) fn forget(id: &KernelId) {
+    COVERAGE.forget(id);
+}
+
+/// The counters of a kernel on the device of the client, created on its first launch.
+pub(crate) fn counters<R: Runtime>(client: &ComputeClient<R>, id: KernelId) -> BufferArg<R> {
+    COVERAGE.buffer(client, id, MAX_COVERAGE_BLOCKS)
+}
+
+/// Read the coverage of every kernel launched on the device of the client.
+pub fn coverage_report<R: Runtime>(
+    client: &ComputeClient<R>,
+) -> Result<CoverageReport, ServerError> {
+    let kernels = COVERAGE
+        .read(client)?
+        .into_iter()
+        .map(|kernel| KernelCoverage {
+            name: kernel.name,
+            id: kernel.id,
+            blocks: kernel
+                .info
+                .into_iter()
+                .zip(u32::from_bytes(&kernel.buffer))
+                .map(|(block, hits)| BlockCoverage {
+                    path: block.path,
+                    location: block.location,
+                    hits: *hits,
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(CoverageReport { kernels })
+}
+
+/// Reset the coverage of every kernel on the device of the client.
+pub fn reset_coverage<R: Runtime>(client: &ComputeClient<R>) {
+    COVERAGE.reset(client);
+}
+
+/// The coverage of the kernels launched on a device.
+#[derive(Clone, Debug, Default)]
+pub struct CoverageReport {
+    /// The coverage of every kernel, sorted by name.
+    pub kernels: Vec<KernelCoverage>,
+}
+
+/// The coverage of a kernel.
+#[derive(Clone, Debug)]
+pub struct KernelCoverage {
+    /// The name of the kernel.
+    pub name: String,
+    /// The id of the kernel, distinguishing the variants of a kernel with the same name.
+    pub id: KernelId,
+    /// The coverage of every block of the kernel, in the order of the source.
+    pub blocks: Vec<BlockCoverage>,
+}
+
+/// The coverage of a block of a kernel.
+#[derive(Clone, Debug)]
+pub struct BlockCoverage {
+    /// The path of the block in the kernel, e.g. `body/3:for/1:else` for the else branch of the
+    /// second instruction of a loop, itself the fourth instruction of the kernel.
+    pub path: String,
+    /// The source location of the first instruction of the block, when debug symbols are
+    /// enabled.
+    pub location: Option<String>,
+    /// The number of times the block was executed by any unit, wrapping on overflow.
+    pub hits: u32,
+}
+
+impl KernelCoverage {
+    /// The number of blocks that were executed at least once.
+    pub fn covered(&self) -> usize {
+        self.blocks.iter().filter(|block| block.hits > 0).count()
+    }
+}
+
+impl Display for CoverageReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for kernel in &self.kernels {
+            writeln!(
+                f,
+                "{} ({}/{} blocks) {}",
+                kernel.name,
+                kernel.covered(),
+                kernel.blocks.len(),
+                kernel.id
+            )?;
+
+            for block in &kernel.blocks {
+                write!(f, "  {:>10}  {}", block.hits, block.path)?;
+                match &block.location {
+                    Some(location) => writeln!(f, " ({location})")?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+//! Heterogeneous execution of kernels the device can't run.
+//!
+//! Kernels that need a type the device lacks, like `f64` or an `i64` atomic, are launched on a
+//! fallback runtime instead, usually the CPU runtime. The buffers are read back from the device and
+//! uploaded to the fallback runtime, and the buffers written by the kernel are copied back into the
+//! device buffers after the launch, so the surrounding pipeline keeps working on device handles and
+//! only the unsupported launch is slower.
+
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::sync::atomic::{AtomicU64, Ordering};
+use cubecl_common::stub::Mutex;
+use cubecl_ir::{Branch, DeviceProperties, Operation, Scope, Type};
+use cubecl_runtime::{
+    client::LaunchFallback,
+    id::KernelId,
+    kernel::{CubeKernel, KernelTask, Visibility},
+    server::{Binding, CopyDescriptor, CubeCount, ExecutionMode, KernelArguments},
+};
+use cubecl_zspace::strides;
+use hashbrown::HashMap;
+
+use crate::KernelBindingLayout;
+use crate::prelude::{ComputeClient, KernelDefinition, Runtime};
+
+/// When launches run on the fallback runtime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FallbackPolicy {
+    /// Always launch on the device, even when it doesn't support the kernel.
+    Never,
+    /// Launch on the fallback runtime when the device doesn't support a type used by the kernel.
+    #[default]
+    Unsupported,
+    /// Always launch on the fallback runtime.
+    Always,
+}
+
+/// Launch the kernels a device doesn't support on a fallback runtime.
+///
+/// The fallback is installed on the client of the device, and applies to every kernel launched
+/// with that client. Kernels using tensor maps or textures always run on the device, since those
+/// are bound to it.
+///
+/// ```ignore
+/// let fallback = Fallback::new(CpuRuntime::client(&Default::default()));
+/// let client = client.with_fallback(Arc::new(fallback));
+/// kernel::launch::<R>(&client, cube_count, cube_dim, input, output);
+/// ```
+pub struct Fallback<F: Runtime> {
+    client: ComputeClient<F>,
+    policy: FallbackPolicy,
+    /// Which buffers each kernel launched on the fallback runtime writes to, `None` for the
+    /// kernels launched on the device.
+    kernels: Mutex<HashMap<KernelId, Option<Arc<[bool]>>>>,
+    launches: AtomicU64,
+}
+
+impl<F: Runtime> Fallback<F> {
+    /// Fall back to the runtime of `client` for unsupported kernels.
+    pub fn new(client: ComputeClient<F>) -> Self {
+        Self {
+            client,
+            policy: FallbackPolicy::default(),
+            kernels: Mutex::new(HashMap::new()),
+            launches: AtomicU64::new(0),
+        }
+    }
+
+    /// When launches run on the fallback runtime, [unsupported](FallbackPolicy::Unsupported) by
+    /// default.
+    pub fn policy(mut self, policy: FallbackPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The number of launches that ran on the fallback runtime.
+    pub fn launches(&self) -> u64 {
+        self.launches.load(Ordering::Relaxed)
+    }
+
+    /// Which buffers `kernel` writes to if it runs on the fallback runtime, decided once per kernel.
+    fn written(
+        &self,
+        kernel: &dyn CubeKernel,
+        properties: &DeviceProperties,
+    ) -> Option<Arc<[bool]>> {
+        if self.policy == FallbackPolicy::Never {
+            return None;
+        }
+
+        let mut kernels = self.kernels.lock().unwrap();
+        kernels
+            .entry(kernel.id())
+            .or_insert_with(|| {
+                let definition = kernel.define();
+                let bound_to_device =
+                    !definition.tensor_maps.is_empty() || !definition.textures.is_empty();
+                let supported = self.policy == FallbackPolicy::Unsupported
+                    && unsupported_types(&definition, properties).is_empty();
+                if bound_to_device || supported {
+                    return None;
+                }
+
+                let layout = KernelBindingLayout::from_definition(&definition);
+                let written = layout
+                    .buffers
+                    .iter()
+                    .map(|buffer| buffer.visibility == Visibility::ReadWrite)
+                    .collect();
+                Some(written)
+            })
+            .clone()
+    }
+}
+
+impl<R: Runtime, F: Runtime> LaunchFallback<R> for Fallback<F> {
+    fn accepts(&self, client: &ComputeClient<R>, kernel: &dyn CubeKernel) -> bool {
+        self.written(kernel, client.properties()).is_some()
+    }
+
+    unsafe fn launch(
+        &self,
+        client: &ComputeClient<R>,
+        kernel: Box<dyn CubeKernel>,
+        count: CubeCount,
+        bindings: KernelArguments,
+        mode: ExecutionMode,
+    ) {
+        let written = self
+            .written(kernel.as_ref(), client.properties())
+            .expect("Only accepted kernels are launched on the fallback runtime");
+
+        let count = match count {
+            CubeCount::Dynamic(binding) => {
+                let data = client.read_tensor(vec![copy_descriptor(binding)]);
+                let count = data[0]
+                    .chunks_exact(size_of::<u32>())
+                    .map(|bytes| u32::from_ne_bytes(bytes.try_into().unwrap()))
+                    .collect::<Vec<_>>();
+                CubeCount::Static(count[0], count[1], count[2])
+            }
+            count => count,
+        };
+
+        let buffers = client
+            .read_tensor(
+                bindings
+                    .buffers
+                    .iter()
+                    .cloned()
+                    .map(copy_descriptor)
+                    .collect(),
+            )
+            .iter()
+            .map(|data| self.client.create_from_slice(data))
+            .collect::<Vec<_>>();
+        let mut arguments = KernelArguments::new().with_buffers(
+            buffers
+                .iter()
+                .map(|handle| handle.clone().binding())
+                .collect(),
+        );
+        arguments.info = bindings.info;
+
+        let task = Box::new(KernelTask::<F::Compiler, _>::new(kernel));
+        match mode {
+            ExecutionMode::Checked => self.client.launch(task, count, arguments),
+            // SAFETY: The caller upholds the guarantees of unchecked launches.
+            _ => unsafe { self.client.launch_unchecked(task, count, arguments) },
+        }
+
+        // Buffers the kernel only reads are left untouched on the device.
+        let (targets, results): (Vec<_>, Vec<_>) = bindings
+            .buffers
+            .into_iter()
+            .zip(buffers)
+            .zip(written.iter())
+            .filter_map(|(buffers, written)| written.then_some(buffers))
+            .unzip();
+        for (target, data) in targets.into_iter().zip(self.client.read(results)) {
+            client.write(target, data);
+        }
+
+        self.launches.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Copy the used part of a binding as bytes.
+fn copy_descriptor(binding: Binding) -> CopyDescriptor {
+    let size = binding.size_in_used() as usize;
+    CopyDescriptor::new(binding, [size].into(), strides![1], 1)
+}
+
+/// The scalar and atomic types used by the kernel that aren't supported by a device with the
+/// given properties, in the order they are first used.
+pub fn unsupported_types(
+    definition: &KernelDefinition,
+    properties: &DeviceProperties,
+) -> Vec<Type> {
+    let mut types = definition
+        .buffers
+        .iter()
+        .map(|buffer| buffer.value.ty)
+        .chain(definition.scalars.iter().map(|scalar| scalar.ty.into()))
+        .collect::<Vec<_>>();
+    collect_types(&definition.body, &mut types);
+
+    let mut unsupported = Vec::new();
+    for ty in types {
+        let ty = ty.value_type().as_scalar();
+        let supported = match ty {
+            Type::Atomic(_) => !properties.atomic_type_usage(ty).is_empty(),
+            Type::Scalar(_) if !ty.is_bool() => properties.supports_type(ty),
+            _ => true,
+        };
+        if !supported && !unsupported.contains(&ty) {
+            unsupported.push(ty);
+        }
+    }
+    unsupported
+}
+
+fn collect_types(scope: &Scope, types: &mut Vec<Type>) {
+    for instruction in scope.instructions.borrow().iter() {
+        types.extend(instruction.out.map(|out| out.ty));
+        let Operation::Branch(branch) = &instruction.operation else {
+            continue;
+        };
+        match branch {
+            Branch::If(op) => collect_types(&op.scope, types),
+            Branch::IfElse(op) => {
+                collect_types(&op.scope_if, types);
+                collect_types(&op.scope_else, types);
+            }
+            Branch::Switch(op) => {
+                collect_types(&op.scope_default, types);
+                for (_, case) in &op.cases {
+                    collect_types(case, types);
+                }
+            }
+            Branch::RangeLoop(op) => collect_types(&op.scope, types),
+            Branch::Loop(op) => collect_types(&op.scope, types),
+            Branch::Return | Branch::Break | Branch::Unreachable => {}
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::{format, string::String, vec::Vec};
+use cubecl_common::{bytes::Bytes, device::DeviceId, stub::Mutex};
+use cubecl_ir::{Scope, SourceLoc, Value};
+use cubecl_runtime::{
+    id::KernelId,
+    server::{Handle, ServerError},
+};
+use hashbrown::HashMap;
+
+use crate::{frontend::slice::from_raw_parts, prelude::*};
+
+/// The state of a debugging instrumentation of kernels, e.g. [coverage](super::coverage_report).
+///
+/// Instrumented kernels get a buffer of `u32` bound after their arguments, one per kernel and
+/// device, which is created on the first launch and read back on demand. The information needed
+/// to interpret the buffer, e.g. what each element counts, is registered when the kernel is built.
+pub(crate) struct Instrumentation<T> {
+    kernels: Mutex<Option<HashMap<KernelId, (String, T)>>>,
+    buffers: Mutex<Option<HashMap<(DeviceId, KernelId), Handle>>>,
+}
+
+/// The buffer of an instrumented kernel read back from a device.
+pub(crate) struct InstrumentedKernel<T> {
+    pub name: String,
+    pub id: KernelId,
+    pub info: T,
+    pub buffer: Bytes,
+}
+
+impl<T: Clone> Instrumentation<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            kernels: Mutex::new(None),
+            buffers: Mutex::new(None),
+        }
+    }
+
+    /// Register the information of a kernel when it's built.
+    pub(crate) fn register(&self, id: KernelId, name: &str, info: T) {
+        self.kernels
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(id, (name.into(), info));
+    }
+
+    /// Whether the information of a kernel is registered. It isn't when a new process loads the
+    /// kernel from a compilation cache, since the kernel isn't built.
+    pub(crate) fn is_registered(&self, id: &KernelId) -> bool {
+        self.kernels
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|kernels| kernels.contains_key(id))
+    }
+
+    /// Forget the information of a kernel, as in a new process.
+    #[cfg(feature = "export_tests")]
+    pub(crate) fn forget(&self, id: &KernelId) {
+        if let Some(kernels) = self.kernels.lock().unwrap().as_mut() {
+            kernels.remove(id);
+        }
+    }
+
+    /// The buffer of a kernel on the device of the client, zero-initialized with `len` elements
+    /// on its first launch.
+    pub(crate) fn buffer<R: Runtime>(
+        &self,
+        client: &ComputeClient<R>,
+        id: KernelId,
+        len: usize,
+    ) -> BufferArg<R> {
+        let handle = self
+            .buffers
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .entry((client.device_id(), id))
+            .or_insert_with(|| client.create_from_slice(u32::as_bytes(&alloc::vec![0; len])))
+            .clone();
+
+        // SAFETY: The buffer is created with `len` elements.
+        unsafe { BufferArg::from_raw_parts(handle, len) }
+    }
+
+    /// Read the buffer of every kernel launched on the device of the client.
+    pub(crate) fn read<R: Runtime>(
+        &self,
+        client: &ComputeClient<R>,
+    ) -> Result<Vec<InstrumentedKernel<T>>, ServerError> {
+        let device_id = client.device_id();
+        let handles = self
+            .buffers
+            .lock()
+            .unwrap()
+            .iter()
+            .flatten()
+            .filter(|((device, _), _)| *device == device_id)
+            .map(|((_, id), handle)| (id.clone(), handle.clone()))
+            .collect::<Vec<_>>();
+
+        let mut kernels = Vec::with_capacity(handles.len());
+        for (id, handle) in handles {
+            let buffer = client.read_one(handle)?;
+            let kernel = self
+                .kernels
+                .lock()
+                .unwrap()
+                .as_ref()
+                .and_then(|kernels| kernels.get(&id).cloned());
+
+            if let Some((name, info)) = kernel {
+                kernels.push(InstrumentedKernel {
+                    name,
+                    id,
+                    info,
+                    buffer,
+                });
+            }
+        }
+        kernels.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(kernels)
+    }
+
+    /// Drop the buffers of every kernel on the device of the client, so they are zeroed again on
+    /// their next launch.
+    pub(crate) fn reset<R: Runtime>(&self, client: &ComputeClient<R>) {
+        let device_id = client.device_id();
+        if let Some(buffers) = self.buffers.lock().unwrap().as_mut() {
+            buffers.retain(|(device, _), _| *device != device_id);
+        }
+    }
+}
+
+/// Instrument a kernel with its buffer viewed as a slice of `len` elements.
+///
+/// The slice is defined at the start of the kernel, after the instrumentation, so it's available
+/// in every block.
+pub(crate) fn with_slice(
+    scope: &Scope,
+    buffer: Value,
+    len: usize,
+    instrument: impl FnOnce(&SliceExpand<Atomic<u32>>),
+) {
+    let prologue = scope.child();
+    let offset = 0usize.into_expand(&prologue);
+    let len = len.into_expand(&prologue);
+    let slice = from_raw_parts::<Atomic<u32>>(&prologue, buffer, offset, len);
+
+    instrument(&slice);
+
+    let instructions = scope.take_instructions();
+    scope.register_all(prologue.take_instructions());
+    scope.register_all(instructions);
+}
+
+/// Format a source location as `file:line:column`.
+pub(crate) fn source_location(loc: &SourceLoc) -> String {
+    format!("{}:{}:{}", loc.source.file, loc.line, loc.column)
+}
@@ -1,18 +1,27 @@
 use alloc::{boxed::Box, vec::Vec};
-use core::marker::PhantomData;
+use core::{any::TypeId, hash::Hash, marker::PhantomData};
 
+use super::{arithmetic_traps, coverage};
 use crate::Runtime;
-use crate::prelude::{BufferArg, TensorArg, TensorMapArg, TensorMapKind};
-use crate::{InfoBuilder, KernelSettings, ScalarArgType};
+use crate::prelude::{
+    BufferArg, CubePrimitive, TensorArg, TensorMapArg, TensorMapKind, TextureArg, TextureMeta,
+};
+use crate::{INFO_ALIGN, InfoBuilder, KernelSettings, ScalarArgType};
 #[cfg(feature = "std")]
 use core::cell::RefCell;
+use cubecl_common::{
+    hash::{StableHash, StableHasher},
+    stub::Mutex,
+};
 use cubecl_ir::{AddressType, Scope, StorageType, Type};
-use cubecl_runtime::server::{Binding, CubeCount, TensorMapBinding};
+use cubecl_runtime::server::{Binding, CubeCount, ExecutionMode, TensorMapBinding, TextureBinding};
 use cubecl_runtime::{
     client::ComputeClient,
+    id::Instrumentation,
     kernel::{CubeKernel, KernelTask},
     server::KernelArguments,
 };
+use hashbrown::HashMap;
 
 #[cfg(feature = "std")]
 std::thread_local! {
@@ -21,12 +30,41 @@ std::thread_local! {
     static SCOPE: RefCell<Scope> = RefCell::new(Scope::root(false));
 }
 
+/// Number of launches with the same value before a specialized argument, e.g. a
+/// [specialized scalar](crate::prelude::InputScalar::specialized), is folded into the kernel.
+pub(crate) const SPECIALIZE_AFTER: u32 = 2;
+/// Maximum number of distinct values folded for each argument of a kernel, which bounds the number
+/// of kernel variants compiled because of specialization.
+pub(crate) const MAX_SPECIALIZATIONS: usize = 8;
+/// Maximum size in bytes of the scalar arguments and static metadata of a launch once a
+/// [constant array](crate::prelude::ConstantArray) is added, which is the size of the kernel
+/// parameters on CUDA.
+///
+/// The limit applies to every runtime so kernels stay portable, even though wgpu and HIP keep
+/// reading the info from a storage buffer.
+pub const MAX_CONSTANT_ARRAY_SIZE: usize = 4096;
+
+/// The number of launches with every value of a specialized argument.
+type LaunchCounts = HashMap<StableHash, u32>;
+
+/// The launch counts of the specialized arguments, keyed by kernel and argument.
+static SPECIALIZATIONS: Mutex<Option<HashMap<(TypeId, u32), LaunchCounts>>> = Mutex::new(None);
+
 /// Prepare a kernel for [launch](KernelLauncher::launch).
 pub struct KernelLauncher<R: Runtime> {
     buffers: Vec<Binding>,
     tensor_maps: Vec<TensorMapBinding>,
+    textures: Vec<TextureBinding>,
     address_type: AddressType,
     pub settings: KernelSettings,
+    /// The kernel the specialized arguments are tracked for.
+    kernel: Option<TypeId>,
+    /// The number of specialized arguments registered so far.
+    specialized: u32,
+    /// Whether the shapes of the tensor arguments are specialized.
+    specialize_shapes: bool,
+    /// Whether a constant array is registered, which bounds the size of the info.
+    constant_array: bool,
     #[cfg(not(feature = "std"))]
     info: InfoBuilder,
     #[cfg(not(feature = "std"))]
@@ -55,6 +93,59 @@ impl<R: Runtime> KernelLauncher<R> {
         fun(&mut self.info)
     }
 
+    /// Track the launches of the specialized arguments for the kernel `K`, which is done by the
+    /// `launch` functions of `#[cube(launch)]` kernels. Specialized arguments are never folded
+    /// without a kernel.
+    pub fn specialize_for<K: 'static>(&mut self) {
+        self.kernel = Some(TypeId::of::<K>());
+    }
+
+    /// Fold the shapes of the tensor arguments into the kernel when the same shape is launched
+    /// repeatedly, so loops over the shape can be unrolled and divisions by it become constant.
+    ///
+    /// Done by the `launch` functions of kernels declared with
+    /// `#[cube(launch, specialize_shapes)]`. Each hot shape compiles a new variant of the kernel,
+    /// so this should only be used for kernels whose tensors take a few distinct shapes. Strides
+    /// are always passed at runtime, and aliased tensors are never specialized.
+    pub fn specialize_shapes(&mut self) {
+        self.specialize_shapes = true;
+    }
+
+    /// Whether the shapes of the tensor arguments are [specialized](Self::specialize_shapes).
+    pub(crate) fn specializes_shapes(&self) -> bool {
+        self.specialize_shapes
+    }
+
+    /// Record a launch of the next specialized argument with `value`, returning whether the value
+    /// repeated enough for the argument to be folded into the kernel.
+    ///
+    /// Arguments are identified by the order they are registered in, so every launch of a kernel
+    /// must register its specialized arguments in the same order.
+    pub(crate) fn specialize(&mut self, value: &impl Hash) -> bool {
+        let argument = self.specialized;
+        self.specialized += 1;
+        let Some(kernel) = self.kernel else {
+            return false;
+        };
+
+        let mut specializations = SPECIALIZATIONS.lock().unwrap();
+        let counts = specializations
+            .get_or_insert_with(HashMap::new)
+            .entry((kernel, argument))
+            .or_default();
+        let value = StableHasher::hash_one(value);
+
+        if let Some(count) = counts.get_mut(&value) {
+            *count = count.saturating_add(1);
+            return *count >= SPECIALIZE_AFTER;
+        }
+        if counts.len() < MAX_SPECIALIZATIONS {
+            counts.insert(value, 1);
+        }
+
+        false
+    }
+
     /// Register a scalar to be launched.
     pub fn register_scalar<C: ScalarArgType>(&mut self, scalar: C) {
         self.with_info(|info| info.scalars.push(scalar));
@@ -65,17 +156,45 @@ impl<R: Runtime> KernelLauncher<R> {
         self.with_info(|info| info.scalars.push_raw(bytes, dtype));
     }
 
+    /// Register the values of a [constant array](crate::prelude::ConstantArray) from raw data.
+    ///
+    /// # Panics
+    ///
+    /// On [launch](Self::launch), if the scalar arguments and the static metadata would take more
+    /// than [`MAX_CONSTANT_ARRAY_SIZE`] bytes with the array.
+    pub fn register_constant_array(&mut self, bytes: &[u8], dtype: StorageType) {
+        self.constant_array = true;
+        self.with_info(|info| info.scalars.push_raw(bytes, dtype));
+    }
+
     /// Launch the kernel.
     #[track_caller]
     pub fn launch<K: CubeKernel>(
-        self,
+        mut self,
         cube_count: CubeCount,
         kernel: K,
         client: &ComputeClient<R>,
     ) {
+        self.register_instrumentation(&kernel, client);
         let bindings = self.into_bindings();
-        let kernel = Box::new(KernelTask::<R::Compiler, K>::new(kernel));
 
+        if let Some(fallback) = client.fallback()
+            && fallback.accepts(client, &kernel)
+        {
+            // SAFETY: Using checked execution mode.
+            unsafe {
+                fallback.launch(
+                    client,
+                    Box::new(kernel),
+                    cube_count,
+                    bindings,
+                    ExecutionMode::Checked,
+                )
+            };
+            return;
+        }
+
+        let kernel = Box::new(KernelTask::<R::Compiler, K>::new(kernel));
         client.launch(kernel, cube_count, bindings)
     }
 
@@ -89,19 +208,63 @@ impl<R: Runtime> KernelLauncher<R> {
     ///   other unpredictable behaviour.
     #[track_caller]
     pub unsafe fn launch_unchecked<K: CubeKernel>(
-        self,
+        mut self,
         cube_count: CubeCount,
         kernel: K,
         client: &ComputeClient<R>,
     ) {
+        self.register_instrumentation(&kernel, client);
+        let bindings = self.into_bindings();
+
+        if let Some(fallback) = client.fallback()
+            && fallback.accepts(client, &kernel)
+        {
+            unsafe {
+                fallback.launch(
+                    client,
+                    Box::new(kernel),
+                    cube_count,
+                    bindings,
+                    ExecutionMode::Unchecked,
+                )
+            };
+            return;
+        }
+
         unsafe {
-            let bindings = self.into_bindings();
             let kernel = Box::new(KernelTask::<R::Compiler, K>::new(kernel));
-
             client.launch_unchecked(kernel, cube_count, bindings)
         }
     }
 
+    /// Push the instrumentation buffers of the kernel after its arguments, matching the buffers
+    /// added by the [builder](crate::prelude::KernelBuilder) for the
+    /// [instrumentation](cubecl_runtime::id::Instrumentation) of the kernel id.
+    fn register_instrumentation<K: CubeKernel>(&mut self, kernel: &K, client: &ComputeClient<R>) {
+        // The id of kernels launched with the macros has the instrumentation of the settings.
+        if self.settings.instrumentation == Instrumentation::default() {
+            return;
+        }
+
+        let id = kernel.id();
+        let instrumentation = id.instrumentation;
+        // The instrumentation is registered when the kernel is built, which doesn't happen when
+        // it's loaded from a compilation cache, so the kernel is defined here instead.
+        if (instrumentation.coverage && !coverage::is_registered(&id))
+            || (instrumentation.arithmetic_traps && !arithmetic_traps::is_registered(&id))
+        {
+            kernel.define();
+        }
+
+        let ty = Type::atomic(u32::as_type_native_unchecked());
+        if instrumentation.coverage {
+            self.register_buffer(coverage::counters(client, id.clone()), ty);
+        }
+        if instrumentation.arithmetic_traps {
+            self.register_buffer(arithmetic_traps::buffer(client, id), ty);
+        }
+    }
+
     /// We need to create the bindings in the same order they are defined in the compilation step.
     ///
     /// The function [`crate::KernelIntegrator::integrate`] stars by registering the input tensors followed
@@ -116,8 +279,18 @@ impl<R: Runtime> KernelLauncher<R> {
         let address_type = self.address_type;
         let info = self.with_info(|info| info.finish(address_type));
 
+        // The scalars and the static metadata are the kernel parameters on CUDA, the dynamic
+        // metadata is always uploaded in a buffer.
+        let size = info.dynamic_metadata_offset * INFO_ALIGN;
+        assert!(
+            !self.constant_array || size <= MAX_CONSTANT_ARRAY_SIZE,
+            "The scalar arguments and static metadata take {size} bytes with the constant array, \
+             which doesn't fit in {MAX_CONSTANT_ARRAY_SIZE} bytes, use a buffer instead"
+        );
+
         bindings.buffers = self.buffers;
         bindings.tensor_maps = self.tensor_maps;
+        bindings.textures = self.textures;
         bindings.info = info;
 
         bindings
@@ -183,6 +356,46 @@ impl<R: Runtime> KernelLauncher<R> {
         let map = map.metadata.clone();
         self.tensor_maps.push(TensorMapBinding { binding, map });
     }
+
+    /// Push a new texture to the state. The texels are of type `ty`, with one channel per lane.
+    pub fn register_texture(&mut self, texture: TextureArg<R>, ty: Type) {
+        let channels = ty.vector_size();
+        assert!(
+            matches!(channels, 1 | 2 | 4),
+            "Textures must have 1, 2 or 4 channels, got {channels}"
+        );
+        let texel_size = ty.size();
+        assert!(
+            texture.row_pitch >= texture.width * texel_size,
+            "Texture rows of {} texels don't fit in a pitch of {} bytes",
+            texture.width,
+            texture.row_pitch
+        );
+        let required =
+            texture.row_pitch * texture.height.saturating_sub(1) + texture.width * texel_size;
+        assert!(
+            texture.binding.size_in_used() >= required as u64,
+            "Texture of {}x{} texels needs {required} bytes, the buffer only has {}",
+            texture.width,
+            texture.height,
+            texture.binding.size_in_used()
+        );
+
+        let meta = TextureMeta {
+            width: texture.width,
+            height: texture.height,
+            row_pitch: texture.row_pitch,
+            channels,
+            storage_ty: ty.storage_type(),
+            filter: texture.filter,
+            address_mode: texture.address_mode,
+            normalized_coords: texture.normalized_coords,
+        };
+        self.textures.push(TextureBinding {
+            binding: texture.binding,
+            meta,
+        });
+    }
 }
 
 impl<R: Runtime> KernelLauncher<R> {
@@ -190,8 +403,13 @@ impl<R: Runtime> KernelLauncher<R> {
         Self {
             address_type: settings.address_type,
             settings,
+            kernel: None,
+            specialized: 0,
+            specialize_shapes: false,
+            constant_array: false,
             buffers: Vec::new(),
             tensor_maps: Vec::new(),
+            textures: Vec::new(),
             _runtime: PhantomData,
             #[cfg(not(feature = "std"))]
             info: InfoBuilder::default(),
@@ -0,0 +1,86 @@
+use core::time::Duration;
+
+use alloc::vec::Vec;
+use cubecl_runtime::server::Handle;
+
+use crate::{self as cubecl, calculate_cube_count_elemwise, future, prelude::*};
+
+/// The bytes copied by every sample, enough to saturate the memory bandwidth of most devices.
+const COPY_SIZE: usize = 16 * 1024 * 1024;
+/// The widest load measured, in bits.
+const MAX_LOAD_WIDTH: u32 = 512;
+const NUM_SAMPLES: usize = 5;
+/// Narrower loads within this ratio of the fastest one are preferred, since they leave more
+/// tensors vectorizable.
+const TOLERANCE: f64 = 1.05;
+
+#[cube(launch_unchecked)]
+fn copy_kernel<N: Size>(input: &[Vector<u32, N>], output: &mut [Vector<u32, N>]) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS];
+    }
+}
+
+/// Measure the fastest load width of the device in bits with a memory copy at every vector size,
+/// or `None` if the copy can't be profiled.
+///
+/// Runtimes return it from [`Runtime::measure_load_width`], which the client calls when its
+/// [load width is calibrated](ComputeClient::calibrate_load_width).
+pub fn measure_load_width<R: Runtime>(client: &ComputeClient<R>) -> Option<u32> {
+    let max_vector_size = usize::min(
+        client.properties().hardware.max_vector_size,
+        (MAX_LOAD_WIDTH / u32::BITS) as usize,
+    );
+    let input = client.empty(COPY_SIZE);
+    let output = client.empty(COPY_SIZE);
+
+    let durations = (0..=max_vector_size.ilog2())
+        .map(|i| 1 << i)
+        .filter_map(|vector_size| {
+            let duration = profile_copy(client, &input, &output, vector_size)?;
+            Some((vector_size as u32 * u32::BITS, duration))
+        })
+        .collect::<Vec<_>>();
+
+    let fastest = durations.iter().map(|(_, duration)| *duration).min()?;
+
+    durations
+        .iter()
+        .find(|(_, duration)| *duration <= fastest.mul_f64(TOLERANCE))
+        .map(|(width, _)| *width)
+}
+
+/// The fastest of a few copies with the given vector size, or `None` if the copy can't be
+/// profiled.
+fn profile_copy<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+    vector_size: VectorSize,
+) -> Option<Duration> {
+    let num_elems = COPY_SIZE / size_of::<u32>();
+    let num_vectors = num_elems / vector_size;
+    let cube_dim = CubeDim::new(client, num_vectors);
+    let cube_count = calculate_cube_count_elemwise(client, num_vectors, cube_dim);
+
+    let launch = || unsafe {
+        copy_kernel::launch_unchecked(
+            client,
+            cube_count.clone(),
+            cube_dim,
+            vector_size,
+            BufferArg::from_raw_parts(input.clone(), num_elems),
+            BufferArg::from_raw_parts(output.clone(), num_elems),
+        )
+    };
+
+    // Warm up, which also compiles the kernel.
+    launch();
+
+    (0..NUM_SAMPLES)
+        .filter_map(|_| {
+            let (_, duration) = client.profile(launch, "measure_load_width").ok()?;
+            Some(future::block_on(duration.resolve()).duration())
+        })
+        .min()
+}
@@ -1,5 +1,18 @@
+mod access_analysis;
+pub(crate) mod arithmetic_traps;
 mod builder;
+mod cost_model;
+pub(crate) mod coverage;
+mod fallback;
+mod instrumentation;
 mod launcher;
+mod load_width;
 
+pub use access_analysis::*;
+pub use arithmetic_traps::*;
 pub use builder::*;
+pub use cost_model::*;
+pub use coverage::*;
+pub use fallback::*;
 pub use launcher::*;
+pub use load_width::*;
@@ -0,0 +1,181 @@
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+use cubecl_ir::{
+    Arithmetic, BinaryOperands, ConstantValue, Id, Instruction, Operator, ReadScalarArrayOperands,
+    Scope, StorageType, Value,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{ScalarArgType, prelude::*, unexpanded};
+
+/// A small read-only array of scalars, uploaded with the kernel arguments instead of being bound
+/// as a buffer. Useful for parameter blocks read by every unit, like filter taps or the weights of
+/// a tiny network.
+///
+/// The values are stored with the scalar arguments of the kernel. On CUDA they are kernel
+/// parameters, which live in constant memory, so reads that are uniform across a plane are
+/// broadcast instead of going through the cache hierarchy. Other runtimes, like wgpu and HIP, read
+/// them from the info storage buffer like any scalar, which only saves a binding. The space is
+/// limited to [`MAX_CONSTANT_ARRAY_SIZE`](crate::compute::MAX_CONSTANT_ARRAY_SIZE) bytes with the
+/// other scalars and the static metadata, so bigger arrays should use a regular buffer.
+///
+/// The length is part of the kernel, so launching with a different length compiles a new kernel.
+pub struct ConstantArray<E: Scalar> {
+    _ty: PhantomData<E>,
+}
+
+/// Expand type of a [constant array](ConstantArray).
+pub struct ConstantArrayExpand<E: Scalar> {
+    id: Id,
+    len: usize,
+    _ty: PhantomData<E>,
+}
+
+impl<E: Scalar> Clone for ConstantArrayExpand<E> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            len: self.len,
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<E: Scalar> CubeType for ConstantArray<E> {
+    type ExpandType = ConstantArrayExpand<E>;
+}
+
+impl<E: Scalar> ExpandTypeClone for ConstantArrayExpand<E> {
+    fn clone_unchecked(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<E: Scalar> IntoExpand for ConstantArrayExpand<E> {
+    type Expand = Self;
+
+    fn into_expand(self, _scope: &Scope) -> Self::Expand {
+        self
+    }
+}
+
+impl<E: Scalar> IntoMut for ConstantArrayExpand<E> {
+    fn into_mut(self, _scope: &Scope) -> Self {
+        self
+    }
+}
+
+impl<E: Scalar> CubeDebug for ConstantArrayExpand<E> {}
+
+impl<E: Scalar> AsRefExpand for ConstantArrayExpand<E> {
+    fn __expand_ref_method(&self, _: &Scope) -> &Self {
+        self
+    }
+}
+
+impl<E: Scalar> AsMutExpand for ConstantArrayExpand<E> {
+    fn __expand_ref_mut_method(&mut self, _: &Scope) -> &mut Self {
+        self
+    }
+}
+
+impl<E: Scalar> ConstantArray<E> {
+    /// Get the value at `index`. Out of bounds indices are clamped to the last value.
+    #[allow(unused_variables)]
+    pub fn get(&self, index: usize) -> E {
+        unexpanded!()
+    }
+
+    /// The number of values in the array.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        unexpanded!()
+    }
+}
+
+impl<E: Scalar> ConstantArrayExpand<E> {
+    /// Expand method of [get](ConstantArray::get).
+    pub fn __expand_get_method(
+        &self,
+        scope: &Scope,
+        index: NativeExpand<usize>,
+    ) -> NativeExpand<E> {
+        let index: Value = index.into();
+        let last = index.ty.constant(ConstantValue::UInt(self.len as u64 - 1));
+        let clamped = scope.create_value(index.ty);
+        scope.register(Instruction::new(
+            Arithmetic::Min(BinaryOperands {
+                lhs: index,
+                rhs: last,
+            }),
+            clamped,
+        ));
+
+        let out = scope.create_value(E::__expand_as_type(scope));
+        scope.register(Instruction::new(
+            Operator::ReadScalarArray(ReadScalarArrayOperands {
+                id: self.id,
+                index: clamped,
+            }),
+            out,
+        ));
+        out.into()
+    }
+
+    /// Expand method of [len](ConstantArray::len).
+    pub fn __expand_len_method(&self, _scope: &Scope) -> usize {
+        self.len
+    }
+}
+
+/// Launch argument of a [constant array](ConstantArray).
+pub struct ConstantArrayArg {
+    data: Vec<u8>,
+    dtype: StorageType,
+}
+
+impl ConstantArrayArg {
+    /// Create a constant array from `values`, which must not be empty.
+    pub fn new<T: ScalarArgType>(values: &[T]) -> Self {
+        assert!(!values.is_empty(), "Constant arrays can't be empty");
+        Self {
+            data: T::as_bytes(values).to_vec(),
+            dtype: T::cube_type(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ConstantArrayCompilationArg {
+    len: usize,
+}
+
+impl<E: Scalar> LaunchArg for ConstantArray<E> {
+    type RuntimeArg<R: Runtime> = ConstantArrayArg;
+    type CompilationArg = ConstantArrayCompilationArg;
+
+    fn register<R: Runtime>(
+        arg: Self::RuntimeArg<R>,
+        launcher: &mut KernelLauncher<R>,
+    ) -> Self::CompilationArg {
+        let ty = launcher.with_scope(|scope| E::__expand_as_type(scope).storage_type());
+        assert_eq!(
+            arg.dtype, ty,
+            "Constant array values must match the element type of the kernel"
+        );
+        let len = arg.data.len() / ty.size();
+
+        launcher.register_constant_array(&arg.data, ty);
+        ConstantArrayCompilationArg { len }
+    }
+
+    fn expand(arg: &Self::CompilationArg, builder: &mut KernelBuilder) -> ConstantArrayExpand<E> {
+        let ty = E::__expand_as_type(&builder.scope).storage_type();
+        ConstantArrayExpand {
+            id: builder.scalar_array(ty, arg.len),
+            len: arg.len,
+            _ty: PhantomData,
+        }
+    }
+}
@@ -1,22 +1,26 @@
 mod array;
 mod base;
 mod cell;
+mod constant;
 mod iter;
 mod registry;
 mod sequence;
 mod shared_memory;
 pub mod slice;
 mod tensor;
+mod texture;
 mod vector;
 
 pub(crate) use base::*;
 
 pub use array::*;
 pub use cell::*;
+pub use constant::*;
 pub use iter::*;
 pub use registry::*;
 pub use sequence::*;
 pub use shared_memory::*;
 pub use slice::*;
 pub use tensor::*;
+pub use texture::*;
 pub use vector::*;
@@ -6,6 +6,7 @@ use crate::{
 use alloc::boxed::Box;
 use core::ops::{Deref, DerefMut};
 use cubecl_ir::VectorSize;
+use cubecl_zspace::Shape;
 
 use crate as cubecl;
 
@@ -14,6 +15,10 @@ use crate as cubecl;
 #[derive(CubeType)]
 pub struct Tensor<T: CubePrimitive> {
     pub(super) meta: TensorMeta,
+    /// The shape folded into the kernel, when it is specialized.
+    #[cube(comptime)]
+    #[expect(dead_code, reason = "only used in expand")]
+    pub(super) shape: Option<Shape>,
     pub(super) buffer: [T],
 }
 
@@ -28,7 +33,11 @@ pub struct OwnedTensor<T: CubePrimitive> {
 impl<T: CubePrimitive> TensorExpand<T> {
     /// Expand only because `[T]` can't be passed to a function
     pub fn __expand_from_parts(meta: TensorMetaExpand, buffer: NativeExpand<[T]>) -> Self {
-        Self { meta, buffer }
+        Self {
+            meta,
+            shape: None,
+            buffer,
+        }
     }
 }
 
@@ -84,6 +93,14 @@ mod metadata {
         /// Obtain the shape of input at dimension dim
         pub fn shape(&self, dim: usize) -> usize {
             intrinsic!(|scope| {
+                // Dimensions out of the folded shape are read at runtime, like without folding.
+                if let Some(shape) = &self.shape
+                    && let Some(dim) = dim.expand.as_const()
+                    && let Some(size) = shape.get(dim.as_usize())
+                {
+                    return size.into_expand(scope);
+                }
+
                 let dim: Value = dim.into();
                 let list = self.__extract_list(scope);
                 let out = scope.create_value(usize::__expand_as_type(scope));
@@ -150,7 +167,12 @@ mod metadata {
 
         /// Returns the rank of the tensor.
         pub fn rank(&self) -> usize {
-            self.meta.rank
+            intrinsic!(|scope| {
+                match &self.shape {
+                    Some(shape) => shape.len().into_expand(scope),
+                    None => self.meta.rank,
+                }
+            })
         }
     }
 }
@@ -83,6 +83,9 @@ impl<R: Runtime> core::fmt::Debug for TensorBinding<R> {
 pub struct TensorCompilationArg {
     pub meta: TensorMetaCompilationArg,
     pub buffer: BufferCompilationArg,
+    /// The shape folded into the kernel, when the kernel
+    /// [specializes its shapes](KernelLauncher::specialize_shapes).
+    pub shape: Option<Shape>,
 }
 
 impl<C: CubePrimitive> LaunchArg for Tensor<C> {
@@ -96,6 +99,12 @@ impl<C: CubePrimitive> LaunchArg for Tensor<C> {
         let ty = launcher.with_scope(|scope| C::__expand_as_type(scope));
         let len = arg.size() / ty.vector_size();
         let meta_arg = TensorMetaLaunch::new(len, arg.shape().len());
+        let shape = match &arg {
+            TensorArg::Handle { handle } if launcher.specializes_shapes() => launcher
+                .specialize(&handle.shape)
+                .then(|| handle.shape.clone()),
+            _ => None,
+        };
         let buffer = match &arg {
             TensorArg::Handle { .. } => BufferCompilationArg { inplace: None },
             TensorArg::Alias { input_pos, .. } => BufferCompilationArg {
@@ -104,7 +113,11 @@ impl<C: CubePrimitive> LaunchArg for Tensor<C> {
         };
         launcher.register_tensor(arg, ty);
         let meta = TensorMeta::register(meta_arg, launcher);
-        TensorCompilationArg { meta, buffer }
+        TensorCompilationArg {
+            meta,
+            buffer,
+            shape,
+        }
     }
 
     fn expand(arg: &Self::CompilationArg, builder: &mut KernelBuilder) -> TensorExpand<C> {
@@ -117,7 +130,11 @@ impl<C: CubePrimitive> LaunchArg for Tensor<C> {
         let len = expand_buffer_length_native(scope, buffer);
         let buffer =
             slice::from_raw_parts::<C>(scope, buffer, 0usize.into_expand(scope), len.into());
-        TensorExpand { meta, buffer }
+        TensorExpand {
+            meta,
+            shape: arg.shape.clone(),
+            buffer,
+        }
     }
 }
 
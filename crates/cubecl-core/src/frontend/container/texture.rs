@@ -0,0 +1,152 @@
+use core::marker::PhantomData;
+
+use crate as cubecl;
+use crate::{
+    ir::{Instruction, TextureOps},
+    prelude::*,
+};
+use cubecl_ir::VectorSize;
+use cubecl_macros::intrinsic;
+use cubecl_runtime::server::{Binding, Handle};
+
+pub use cubecl_runtime::texture::*;
+
+/// A read-only 2D texture backed by a pitched buffer, currently only supported on CUDA.
+///
+/// Sampling goes through the texture cache and uses the hardware for filtering and addressing,
+/// which makes it a good fit for image processing kernels that read neighbouring pixels at
+/// fractional positions. The texture is an opaque object at runtime, its size and layout are set
+/// on the [launch argument](TextureArg).
+///
+/// Textures are supported when the device supports [`OpaqueType::Texture`](cubecl_ir::OpaqueType).
+#[derive(Clone)]
+pub struct Texture<E: CubePrimitive> {
+    _ty: PhantomData<E>,
+}
+pub type TextureExpand<E> = NativeExpand<Texture<E>>;
+
+impl<E: CubePrimitive> Copy for Texture<E> {}
+
+#[cube]
+impl<E: CubePrimitive> Texture<E> {
+    /// Sample the texture at the coordinates `x` and `y`. Texel centers are at half-integer
+    /// coordinates, so with [linear filtering](TextureFilter::Linear), `(0.5, 0.5)` returns the
+    /// first texel and `(1.0, 0.5)` the average of the first two texels of the first row.
+    ///
+    /// Vectorized elements sample every channel of the texel at once.
+    pub fn sample(&self, x: f32, y: f32) -> E {
+        intrinsic!(|scope| {
+            let out = scope.create_value(E::__expand_as_type(scope));
+            scope.register(Instruction::new(
+                TextureOps::Sample2d {
+                    texture: self.expand,
+                    x: x.expand,
+                    y: y.expand,
+                },
+                out,
+            ));
+            out.into()
+        })
+    }
+}
+
+impl<E: CubePrimitive> IntoMut for NativeExpand<Texture<E>> {
+    fn into_mut(self, _scope: &Scope) -> Self {
+        self
+    }
+}
+
+impl<E: CubePrimitive> CubeType for Texture<E> {
+    type ExpandType = NativeExpand<Texture<E>>;
+}
+
+impl<E: CubePrimitive> AsMutExpand for NativeExpand<Texture<E>> {
+    fn __expand_ref_mut_method(&mut self, _: &Scope) -> &mut Self {
+        self
+    }
+}
+
+impl<E: CubePrimitive> Vectorized for Texture<E> {}
+impl<E: CubePrimitive> VectorizedExpand for NativeExpand<Texture<E>> {
+    fn vector_size(&self) -> VectorSize {
+        1
+    }
+}
+
+/// Launch argument of a [texture](Texture).
+pub struct TextureArg<R: Runtime> {
+    /// The buffer holding the texels, row by row.
+    pub binding: Binding,
+    /// Width of the texture in texels.
+    pub width: usize,
+    /// Height of the texture in texels.
+    pub height: usize,
+    /// Distance between two rows in bytes.
+    pub row_pitch: usize,
+    /// Filtering mode.
+    pub filter: TextureFilter,
+    /// Addressing mode.
+    pub address_mode: TextureAddressMode,
+    /// Whether coordinates are normalized.
+    pub normalized_coords: bool,
+    _runtime: PhantomData<R>,
+}
+
+impl<R: Runtime> TextureArg<R> {
+    /// Create a texture of `width x height` texels from `handle`, with rows `row_pitch` bytes
+    /// apart. The texture uses unnormalized coordinates, nearest filtering and clamps coordinates
+    /// to its edges by default.
+    ///
+    /// The row pitch must be a multiple of the texture pitch alignment of the device, which is
+    /// 32 bytes on current CUDA devices.
+    pub fn new(handle: Handle, width: usize, height: usize, row_pitch: usize) -> Self {
+        Self {
+            binding: handle.binding(),
+            width,
+            height,
+            row_pitch,
+            filter: TextureFilter::Nearest,
+            address_mode: TextureAddressMode::Clamp,
+            normalized_coords: false,
+            _runtime: PhantomData,
+        }
+    }
+
+    /// Set the filtering mode.
+    pub fn with_filter(mut self, filter: TextureFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the addressing mode.
+    pub fn with_address_mode(mut self, address_mode: TextureAddressMode) -> Self {
+        self.address_mode = address_mode;
+        self
+    }
+
+    /// Use coordinates normalized to `[0, 1)`.
+    pub fn with_normalized_coords(mut self) -> Self {
+        self.normalized_coords = true;
+        self
+    }
+}
+
+impl<E: CubePrimitive> LaunchArg for Texture<E> {
+    type RuntimeArg<R: Runtime> = TextureArg<R>;
+    type CompilationArg = ();
+
+    fn register<R: Runtime>(
+        arg: Self::RuntimeArg<R>,
+        launcher: &mut KernelLauncher<R>,
+    ) -> Self::CompilationArg {
+        let ty = launcher.with_scope(|scope| E::__expand_as_type(scope));
+        launcher.register_texture(arg, ty);
+    }
+
+    fn expand(
+        _arg: &Self::CompilationArg,
+        builder: &mut KernelBuilder,
+    ) -> NativeExpand<Texture<E>> {
+        builder.texture().into()
+    }
+}
@@ -1,7 +1,7 @@
 use alloc::vec::Vec;
 use cubecl::prelude::*;
 use cubecl_common::{e4m3, e5m2, ue8m0};
-use cubecl_ir::{Instruction, Operator, Value};
+use cubecl_ir::{ConstantValue, Instruction, Operator, Value};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -16,6 +16,8 @@ use crate::{
 pub struct InputScalar {
     data: [u8; 8],
     dtype: StorageType,
+    /// The value folded into the kernel once it repeats, see [`InputScalar::specialized`].
+    specialization: Option<ConstantValue>,
 }
 
 #[derive(Clone)]
@@ -71,6 +73,7 @@ impl InputScalar {
         let mut out = InputScalar {
             data: Default::default(),
             dtype,
+            specialization: None,
         };
         fn write<E: ScalarArgType>(val: impl num_traits::ToPrimitive, out: &mut [u8]) {
             let val = [E::from(val).unwrap()];
@@ -111,6 +114,34 @@ impl InputScalar {
         };
         out
     }
+
+    /// Creates an [`InputScalar`] that is folded into the kernel as a constant when the same
+    /// value is launched repeatedly, e.g. a stride of `1` or an `alpha` of `1.0`.
+    ///
+    /// Each folded value compiles a new variant of the kernel, so this should only be used for
+    /// scalars that usually take a few distinct values, and where a constant enables faster
+    /// inner loops. Launches are tracked per kernel and argument, and the number of folded values of
+    /// each argument is bounded, other values are passed at runtime. Only launches through the
+    /// `launch` functions of `#[cube(launch)]` kernels are folded, see
+    /// [`KernelLauncher::specialize_for`].
+    ///
+    /// # Panics
+    ///
+    /// If the given numeric element can't be transformed into the passed [`ElemType`].
+    pub fn specialized<E: num_traits::ToPrimitive>(val: E, dtype: impl Into<StorageType>) -> Self {
+        let dtype: StorageType = dtype.into();
+        let value = match dtype.elem_type() {
+            ElemType::Float(_) => val.to_f64().map(ConstantValue::Float),
+            ElemType::Int(_) => val.to_i64().map(ConstantValue::Int),
+            ElemType::UInt(_) => val.to_u64().map(ConstantValue::UInt),
+            ElemType::Bool => None,
+        };
+
+        InputScalar {
+            specialization: value,
+            ..Self::new(val, dtype)
+        }
+    }
 }
 
 #[cube]
@@ -141,6 +172,15 @@ impl LaunchArg for InputScalar {
         launcher: &mut KernelLauncher<R>,
     ) -> Self::CompilationArg {
         let dtype = arg.dtype;
+        if let Some(value) = arg.specialization
+            && launcher.specialize(&(dtype, value))
+        {
+            return InputScalarCompilationArg {
+                ty: dtype,
+                value: Some(value),
+            };
+        }
+
         launcher.register_scalar_raw(&arg.data[..dtype.size()], dtype);
         InputScalarCompilationArg::new(arg.dtype)
     }
@@ -149,6 +189,12 @@ impl LaunchArg for InputScalar {
         arg: &Self::CompilationArg,
         builder: &mut KernelBuilder,
     ) -> <Self as CubeType>::ExpandType {
+        if let Some(value) = arg.value {
+            return InputScalarExpand {
+                expand: arg.ty.constant(value),
+            };
+        }
+
         let expand = builder.create_value(arg.ty.into());
         let id = builder.scalar(arg.ty);
         builder.register(Instruction::new(Operator::ReadScalar(id), expand));
@@ -159,10 +205,12 @@ impl LaunchArg for InputScalar {
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct InputScalarCompilationArg {
     ty: StorageType,
+    /// The value folded into the kernel, when the scalar is specialized.
+    value: Option<ConstantValue>,
 }
 
 impl InputScalarCompilationArg {
     pub fn new(ty: StorageType) -> Self {
-        Self { ty }
+        Self { ty, value: None }
     }
 }
@@ -45,6 +45,7 @@ pub use cubecl_macros::*;
 pub use cubecl_runtime::benchmark;
 pub use cubecl_runtime::client;
 pub use cubecl_runtime::compiler::{CompilationError, Compiler, CubeTask};
+pub use cubecl_runtime::dry_run;
 pub use cubecl_runtime::memory_management::MemoryUsage;
 pub use cubecl_runtime::server;
 pub use cubecl_runtime::tune;
@@ -125,20 +125,21 @@ impl InstructionVisitor for BufferVisibility {
     ) -> Vec<Instruction> {
         let mut visitor = Visitor(self);
 
-        visitor.visit_instruction(
-            &mut inst,
-            analyses,
-            |this, val| {
-                if let Some(id) = global_buffer_id(val) {
-                    this.set_readable(id as usize);
-                }
-            },
-            |this, val| {
-                if let Some(id) = global_buffer_id(val) {
-                    this.set_writable(id as usize);
-                }
-            },
-        );
+        visitor.visit_operation(&mut inst.operation, analyses, |this, val| {
+            if let Some(id) = global_buffer_id(val) {
+                this.set_readable(id as usize);
+            }
+        });
+
+        // Pointers into a buffer are only addresses, so a pointer output doesn't write to the
+        // buffer. Writes go through the operations storing to those pointers.
+        let pointers = inst.write_pointers();
+        let out = inst.out.filter(|out| !out.ty.is_ptr());
+        for value in pointers.iter().chain(out.iter()) {
+            if let Some(id) = global_buffer_id(value) {
+                visitor.set_writable(id as usize);
+            }
+        }
 
         vec![inst]
     }
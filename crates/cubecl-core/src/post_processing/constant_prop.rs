@@ -572,6 +572,7 @@ fn try_const_eval_operator(op: &mut Operator, out_ty: Option<Type>) -> Option<Co
         | Operator::Reinterpret(_)
         | Operator::Select(_)
         | Operator::ReadBuiltin(_)
-        | Operator::ReadScalar(_) => None,
+        | Operator::ReadScalar(_)
+        | Operator::ReadScalarArray(_) => None,
     }
 }
@@ -1,7 +1,7 @@
 use std::println;
 
 use crate::{
-    self as cubecl, cmma::Cube, prelude::barrier::Barrier,
+    self as cubecl, KernelBindingLayout, cmma::Cube, prelude::barrier::Barrier,
     runtime_tests::binary::assert_equals_approx,
 };
 
@@ -729,6 +729,31 @@ pub fn test_simple_cube_expected(m: usize, n: usize, k: usize) -> Vec<f32> {
 //     assert_eq!(expected, actual);
 // }
 
+/// Stores to a tensor go through the matrix list pointer, but must still make the destination
+/// buffer writable.
+pub fn test_store_tensor_visibility<R: Runtime>(client: ComputeClient<R>) {
+    let kernel = kernel_simple_f16_workgroup_tensor::KernelSimpleF16WorkgroupTensor::<R>::new(
+        KernelSettings::default(),
+        client,
+        BufferCompilationArg { inplace: None },
+        BufferCompilationArg { inplace: None },
+        BufferCompilationArg { inplace: None },
+        (),
+        (16, 16, 16),
+    );
+    let layout = KernelBindingLayout::new(&kernel);
+
+    let visibility = layout
+        .buffers
+        .iter()
+        .map(|b| b.visibility)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        visibility,
+        [Visibility::Read, Visibility::Read, Visibility::ReadWrite]
+    );
+}
+
 pub fn test_cmma_cast_f16<R: Runtime>(client: ComputeClient<R>, cube_dimensions: CubeDim) {
     if !client.features().matmul.cmma.contains(&MmaConfig {
         a_type: ElemType::Float(FloatKind::F16).into(),
@@ -1752,6 +1777,12 @@ macro_rules! testgen_cmma {
             );
         }
 
+        #[$crate::runtime_tests::test_log::test]
+        fn test_store_tensor_visibility() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::cmma::test_store_tensor_visibility::<TestRuntime>(client);
+        }
+
         #[$crate::runtime_tests::test_log::test]
         fn test_cmma_cast_f16() {
             let client = TestRuntime::client(&Default::default());
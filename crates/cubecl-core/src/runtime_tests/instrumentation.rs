@@ -0,0 +1,273 @@
+use crate::{
+    self as cubecl,
+    compute::{
+        TrapKind, arithmetic_traps, coverage, coverage_report, reset_arithmetic_traps,
+        reset_coverage,
+    },
+    prelude::*,
+};
+use alloc::vec::Vec;
+use cubecl_runtime::id::Instrumentation;
+
+#[cube(launch)]
+pub fn kernel_coverage(output: &mut [u32]) {
+    if UNIT_POS < 3 {
+        output[UNIT_POS as usize] = 1;
+    } else {
+        output[UNIT_POS as usize] = 2;
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_warm_cache(output: &mut [u32], divisor: &[u32]) {
+    if UNIT_POS < 3 {
+        output[UNIT_POS as usize] = 1;
+    } else {
+        output[UNIT_POS as usize] = 2 / divisor[0];
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_arithmetic(lhs: &[i32], rhs: &[i32], output: &mut [i32], #[comptime] shift: bool) {
+    if shift {
+        output[0] = lhs[0] << rhs[0];
+    } else {
+        output[0] = lhs[0] / rhs[0];
+    }
+}
+
+/// Launch a kernel with the given instrumentation, regardless of the config.
+fn launch_instrumented<R: Runtime>(
+    client: &ComputeClient<R>,
+    instrumentation: Instrumentation,
+    output: BufferArg<R>,
+) {
+    let settings = KernelSettings::default()
+        .cube_dim(CubeDim::new_1d(4))
+        .instrumentation(instrumentation);
+    let mut launcher = KernelLauncher::<R>::new(settings.clone());
+    let output = <[u32] as LaunchArg>::register(output, &mut launcher);
+    let kernel = kernel_coverage::KernelCoverage::<R>::new(settings, client.clone(), output);
+
+    launcher.launch(CubeCount::Static(1, 1, 1), kernel, client);
+}
+
+pub fn test_coverage_counters<R: Runtime>(client: ComputeClient<R>) {
+    let instrumentation = Instrumentation {
+        coverage: true,
+        arithmetic_traps: false,
+    };
+    reset_coverage(&client);
+
+    let output = client.empty(4 * size_of::<u32>());
+    let arg = unsafe { BufferArg::from_raw_parts(output.clone(), 4) };
+    launch_instrumented(&client, instrumentation, arg);
+
+    // The counters are bound after the arguments, so the output is untouched.
+    let actual = client.read_one_unchecked(output);
+    assert_eq!(u32::from_bytes(&actual), &[1, 1, 1, 2]);
+
+    let report = coverage_report(&client).unwrap();
+    let kernel = report
+        .kernels
+        .iter()
+        .find(|kernel| kernel.name == "kernel_coverage")
+        .expect("The kernel should be instrumented");
+    assert_eq!(kernel.id.instrumentation, instrumentation);
+
+    let hits = kernel
+        .blocks
+        .iter()
+        .map(|block| (block.path.as_str(), block.hits))
+        .collect::<Vec<_>>();
+    assert_eq!(hits.len(), 3);
+    assert_eq!(hits[0], ("body", 4));
+    assert!(hits[1].0.ends_with(":if"));
+    assert_eq!(hits[1].1, 3);
+    assert!(hits[2].0.ends_with(":else"));
+    assert_eq!(hits[2].1, 1);
+    assert_eq!(kernel.covered(), 3);
+
+    reset_coverage(&client);
+    let report = coverage_report(&client).unwrap();
+    assert!(
+        report
+            .kernels
+            .iter()
+            .all(|kernel| kernel.name != "kernel_coverage")
+    );
+}
+
+/// The arithmetic exceptions of `lhs / rhs`, or `lhs << rhs` when `shift` is set.
+fn trapped_arithmetic<R: Runtime>(
+    client: &ComputeClient<R>,
+    lhs: i32,
+    rhs: i32,
+    shift: bool,
+) -> Vec<TrapKind> {
+    reset_arithmetic_traps(client);
+
+    let settings = KernelSettings::default().instrumentation(Instrumentation {
+        coverage: false,
+        arithmetic_traps: true,
+    });
+    let lhs = client.create_from_slice(i32::as_bytes(&[lhs]));
+    let rhs = client.create_from_slice(i32::as_bytes(&[rhs]));
+    let output = client.empty(size_of::<i32>());
+
+    let mut launcher = KernelLauncher::<R>::new(settings.clone());
+    let lhs =
+        unsafe { <[i32] as LaunchArg>::register(BufferArg::from_raw_parts(lhs, 1), &mut launcher) };
+    let rhs =
+        unsafe { <[i32] as LaunchArg>::register(BufferArg::from_raw_parts(rhs, 1), &mut launcher) };
+    let output = unsafe {
+        <[i32] as LaunchArg>::register(BufferArg::from_raw_parts(output, 1), &mut launcher)
+    };
+    let kernel = kernel_arithmetic::KernelArithmetic::<R>::new(
+        settings,
+        client.clone(),
+        lhs,
+        rhs,
+        output,
+        shift,
+    );
+    launcher.launch(CubeCount::Static(1, 1, 1), kernel, client);
+
+    arithmetic_traps(client)
+        .unwrap()
+        .into_iter()
+        .filter(|trap| trap.kernel == "kernel_arithmetic")
+        .map(|trap| trap.site.kind)
+        .collect()
+}
+
+pub fn test_arithmetic_traps<R: Runtime>(client: ComputeClient<R>) {
+    assert_eq!(trapped_arithmetic(&client, 7, 2, false), []);
+    assert_eq!(
+        trapped_arithmetic(&client, 7, 0, false),
+        [TrapKind::DivisionByZero]
+    );
+    assert_eq!(
+        trapped_arithmetic(&client, i32::MIN, -1, false),
+        [TrapKind::DivisionOverflow]
+    );
+    assert_eq!(trapped_arithmetic(&client, 1, 4, true), []);
+    assert_eq!(
+        trapped_arithmetic(&client, 1, 32, true),
+        [TrapKind::ShiftOverflow]
+    );
+    assert_eq!(
+        trapped_arithmetic(&client, 1, -1, true),
+        [TrapKind::ShiftOverflow]
+    );
+}
+
+/// Launching a kernel whose instrumentation isn't registered, like a kernel loaded from a warm
+/// compilation cache by a new process, still reports it.
+pub fn test_instrumentation_from_warm_cache<R: Runtime>(client: ComputeClient<R>) {
+    let instrumentation = Instrumentation {
+        coverage: true,
+        arithmetic_traps: true,
+    };
+    let divisor = client.create_from_slice(u32::as_bytes(&[0]));
+    let launch = || {
+        let settings = KernelSettings::default()
+            .cube_dim(CubeDim::new_1d(4))
+            .instrumentation(instrumentation);
+        let mut launcher = KernelLauncher::<R>::new(settings.clone());
+        let output = client.empty(4 * size_of::<u32>());
+        let output = unsafe {
+            <[u32] as LaunchArg>::register(BufferArg::from_raw_parts(output, 4), &mut launcher)
+        };
+        let divisor = unsafe {
+            <[u32] as LaunchArg>::register(
+                BufferArg::from_raw_parts(divisor.clone(), 1),
+                &mut launcher,
+            )
+        };
+        let kernel =
+            kernel_warm_cache::KernelWarmCache::<R>::new(settings, client.clone(), output, divisor);
+        launcher.launch(CubeCount::Static(1, 1, 1), kernel, &client);
+    };
+    let trapped = || {
+        arithmetic_traps(&client)
+            .unwrap()
+            .into_iter()
+            .filter(|trap| trap.kernel == "kernel_warm_cache")
+            .map(|trap| trap.site.kind)
+            .collect::<Vec<_>>()
+    };
+    let covered = || {
+        coverage_report(&client)
+            .unwrap()
+            .kernels
+            .into_iter()
+            .find(|kernel| kernel.name == "kernel_warm_cache")
+    };
+
+    // The first launch builds the kernel, which is then cached by the server.
+    launch();
+    let id = covered().expect("The kernel should be instrumented").id;
+
+    assert_eq!(trapped(), [TrapKind::DivisionByZero]);
+
+    coverage::forget(&id);
+    arithmetic_traps::forget(&id);
+    reset_coverage(&client);
+    reset_arithmetic_traps(&client);
+    launch();
+
+    let kernel = covered().expect("The cached kernel should be instrumented");
+    assert_eq!(kernel.blocks.len(), 3);
+    assert_eq!(kernel.covered(), 3);
+    assert_eq!(trapped(), [TrapKind::DivisionByZero]);
+}
+
+pub fn test_uninstrumented_launch<R: Runtime>(client: ComputeClient<R>) {
+    let output = client.empty(4 * size_of::<u32>());
+    let arg = unsafe { BufferArg::from_raw_parts(output.clone(), 4) };
+    launch_instrumented(&client, Instrumentation::default(), arg);
+
+    let actual = client.read_one_unchecked(output);
+    assert_eq!(u32::from_bytes(&actual), &[1, 1, 1, 2]);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_instrumentation {
+    () => {
+        use super::*;
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_coverage_counters() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::instrumentation::test_coverage_counters::<TestRuntime>(
+                client,
+            );
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_arithmetic_traps() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::instrumentation::test_arithmetic_traps::<TestRuntime>(
+                client,
+            );
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_instrumentation_from_warm_cache() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::instrumentation::test_instrumentation_from_warm_cache::<
+                TestRuntime,
+            >(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_uninstrumented_launch() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::instrumentation::test_uninstrumented_launch::<TestRuntime>(
+                client,
+            );
+        }
+    };
+}
@@ -1,10 +1,22 @@
-use std::{boxed::Box, println};
-
-use alloc::string::{String, ToString};
-
-use crate::{self as cubecl, as_bytes};
+use std::{boxed::Box, panic::AssertUnwindSafe, println};
+
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    self as cubecl, BindingMismatch, KernelBindingLayout, as_bytes,
+    compute::{Fallback, FallbackPolicy, MAX_CONSTANT_ARRAY_SIZE, unsupported_types},
+};
 use cubecl::prelude::*;
-use cubecl_runtime::server::{ResourceLimitError, ServerError};
+use cubecl_ir::{ElemType, FloatKind, Metadata, Operation};
+use cubecl_runtime::{
+    kernel::KernelDefinition,
+    server::{Handle, KernelArguments, ResourceLimitError, ServerError},
+};
 
 #[derive(CubeLaunch, CubeType)]
 pub struct ComptimeTag {
@@ -45,6 +57,44 @@ pub fn kernel_dynamic_addressing(output: &mut [f32]) {
     }
 }
 
+#[cube(launch)]
+pub fn kernel_scale(output: &mut [f32], alpha: InputScalar) {
+    if UNIT_POS == 0 {
+        output[0] *= alpha.get::<f32>();
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_accumulate(input: &[f32], output: &mut [f32]) {
+    let mut acc = 0.0;
+    for i in 0..4usize {
+        acc += input[UNIT_POS as usize + i] * 2.0;
+    }
+    output[UNIT_POS as usize] = acc;
+}
+
+#[cube(launch, specialize_shapes)]
+pub fn kernel_tensor_shape(input: &Tensor<f32>, output: &mut [f32]) {
+    output[0] = input.shape(1) as f32;
+    output[1] = input.rank() as f32;
+}
+
+#[cube(launch, specialize_shapes)]
+pub fn kernel_tensor_shape_out_of_rank(input: &Tensor<f32>, output: &mut [f32]) {
+    output[0] = input.shape(4) as f32;
+}
+
+#[cube(launch)]
+pub fn kernel_constant_array(
+    output: &mut [f32],
+    scale: f32,
+    taps: &ConstantArray<f32>,
+    offset: f32,
+) {
+    let i = UNIT_POS as usize;
+    output[i] = taps.get(i) * scale + offset + taps.len() as f32;
+}
+
 #[cube(launch)]
 pub fn kernel_inplace(input: &[f32], output: &mut [f32]) {
     if UNIT_POS == 0 {
@@ -52,6 +102,14 @@ pub fn kernel_inplace(input: &[f32], output: &mut [f32]) {
     }
 }
 
+#[cube(launch)]
+pub fn kernel_write_read_only(input: &[f32], output: &mut [f32]) {
+    if UNIT_POS == 0 {
+        let input = unsafe { input.as_mut_unchecked() };
+        input[0] = output[0];
+    }
+}
+
 #[cube(launch)]
 pub fn kernel_with_max_shared(
     output: &mut [u32],
@@ -113,6 +171,321 @@ pub fn test_kernel_with_comptime_tag<R: Runtime>(client: ComputeClient<R>) {
     assert_eq!(actual[0], f32::new(1.0));
 }
 
+/// Register the arguments of `kernel_scale` like its `launch` function does.
+fn scale_kernel<R: Runtime>(
+    client: &ComputeClient<R>,
+    output: &Handle,
+    alpha: InputScalar,
+) -> (KernelLauncher<R>, kernel_scale::KernelScale<R>) {
+    let settings = KernelSettings::default().cube_dim(CubeDim::new_1d(1));
+    let mut launcher = KernelLauncher::<R>::new(settings.clone());
+    launcher.specialize_for::<kernel_scale::KernelScale<R>>();
+    let output = unsafe {
+        <[f32] as LaunchArg>::register(BufferArg::from_raw_parts(output.clone(), 1), &mut launcher)
+    };
+    let alpha = InputScalar::register(alpha, &mut launcher);
+    let kernel = kernel_scale::KernelScale::<R>::new(settings, client.clone(), output, alpha);
+
+    (launcher, kernel)
+}
+
+pub fn test_kernel_specialized_scalar<R: Runtime>(client: ComputeClient<R>) {
+    let handle = client.create_from_slice(f32::as_bytes(&[1.0]));
+    let dtype = f32::as_type_native_unchecked().storage_type();
+    let alpha = InputScalar::specialized(2.0, dtype);
+
+    let mut launches = Vec::new();
+    for _ in 0..3 {
+        let (launcher, kernel) = scale_kernel(&client, &handle, alpha);
+        let folded = kernel
+            .define()
+            .scalars
+            .iter()
+            .all(|scalar| scalar.ty != dtype);
+        launches.push((kernel.id(), folded));
+        launcher.launch(CubeCount::Static(1, 1, 1), kernel, &client);
+    }
+
+    // The first launch passes the scalar at runtime, the repeated ones fold it into a new variant.
+    assert!(!launches[0].1);
+    assert!(launches[1].1 && launches[2].1);
+    assert_ne!(launches[0].0, launches[1].0);
+    assert_eq!(launches[1].0, launches[2].0);
+
+    let actual = client.read_one_unchecked(handle);
+    let actual = f32::from_bytes(&actual);
+    assert_eq!(actual[0], 8.0);
+
+    // The launches of other kernels are tracked separately, and never folded without a kernel.
+    struct OtherKernel;
+    let settings = KernelSettings::default();
+    let mut launcher = KernelLauncher::<R>::new(settings.clone());
+    launcher.specialize_for::<OtherKernel>();
+    let arg = InputScalar::register(alpha, &mut launcher);
+    assert_eq!(arg, InputScalarCompilationArg::new(dtype));
+    for _ in 0..3 {
+        let mut launcher = KernelLauncher::<R>::new(settings.clone());
+        let arg = InputScalar::register(alpha, &mut launcher);
+        assert_eq!(arg, InputScalarCompilationArg::new(dtype));
+    }
+}
+
+/// Register the arguments of `kernel_tensor_shape` like its `launch` function does.
+fn tensor_shape_kernel<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &Handle,
+    output: &Handle,
+) -> (KernelLauncher<R>, kernel_tensor_shape::KernelTensorShape<R>) {
+    let settings = KernelSettings::default().cube_dim(CubeDim::new_1d(1));
+    let mut launcher = KernelLauncher::<R>::new(settings.clone());
+    launcher.specialize_for::<kernel_tensor_shape::KernelTensorShape<R>>();
+    launcher.specialize_shapes();
+    let input = unsafe {
+        <Tensor<f32> as LaunchArg>::register(
+            TensorArg::from_raw_parts(input.clone(), [3, 1].into(), [2, 3].into()),
+            &mut launcher,
+        )
+    };
+    let output = unsafe {
+        <[f32] as LaunchArg>::register(BufferArg::from_raw_parts(output.clone(), 2), &mut launcher)
+    };
+    let kernel =
+        kernel_tensor_shape::KernelTensorShape::<R>::new(settings, client.clone(), input, output);
+
+    (launcher, kernel)
+}
+
+/// Whether the kernel reads a tensor shape from the metadata.
+fn reads_shape(definition: &KernelDefinition) -> bool {
+    definition
+        .body
+        .instructions
+        .borrow()
+        .iter()
+        .any(|instruction| {
+            matches!(
+                instruction.operation,
+                Operation::Metadata(Metadata::Shape { .. })
+            )
+        })
+}
+
+pub fn test_kernel_specialized_shape<R: Runtime>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(f32::as_bytes(&[0.0; 6]));
+    let output = client.empty(2 * size_of::<f32>());
+
+    let mut launches = Vec::new();
+    for _ in 0..3 {
+        let (launcher, kernel) = tensor_shape_kernel(&client, &input, &output);
+        launches.push((kernel.id(), !reads_shape(&kernel.define())));
+        launcher.launch(CubeCount::Static(1, 1, 1), kernel, &client);
+
+        let actual = client.read_one_unchecked(output.clone());
+        assert_eq!(f32::from_bytes(&actual), &[3.0, 2.0]);
+    }
+
+    // The first launch passes the shape at runtime, the repeated ones fold it into a new variant.
+    assert!(!launches[0].1);
+    assert!(launches[1].1 && launches[2].1);
+    assert_ne!(launches[0].0, launches[1].0);
+    assert_eq!(launches[1].0, launches[2].0);
+
+    // A constant dimension out of the folded rank is read at runtime instead of panicking.
+    let settings = KernelSettings::default().cube_dim(CubeDim::new_1d(1));
+    for _ in 0..3 {
+        let mut launcher = KernelLauncher::<R>::new(settings.clone());
+        launcher.specialize_for::<kernel_tensor_shape_out_of_rank::KernelTensorShapeOutOfRank<R>>();
+        launcher.specialize_shapes();
+        let input = unsafe {
+            <Tensor<f32> as LaunchArg>::register(
+                TensorArg::from_raw_parts(input.clone(), [3, 1].into(), [2, 3].into()),
+                &mut launcher,
+            )
+        };
+        let output = unsafe {
+            <[f32] as LaunchArg>::register(
+                BufferArg::from_raw_parts(output.clone(), 2),
+                &mut launcher,
+            )
+        };
+        let kernel = kernel_tensor_shape_out_of_rank::KernelTensorShapeOutOfRank::<R>::new(
+            settings.clone(),
+            client.clone(),
+            input,
+            output,
+        );
+        assert!(reads_shape(&kernel.define()));
+    }
+}
+
+pub fn test_kernel_constant_array<R: Runtime>(client: ComputeClient<R>) {
+    let handle = client.empty(5 * size_of::<f32>());
+
+    kernel_constant_array::launch(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(5),
+        unsafe { BufferArg::from_raw_parts(handle.clone(), 5) },
+        2.0,
+        ConstantArrayArg::new(&[1.0f32, 2.0, 3.0, 4.0]),
+        1.0,
+    );
+
+    let actual = client.read_one_unchecked(handle.clone());
+    let actual = f32::from_bytes(&actual);
+
+    // The last unit reads out of bounds, which is clamped to the last value.
+    assert_eq!(actual, &[7.0, 9.0, 11.0, 13.0, 13.0]);
+
+    let empty = std::panic::catch_unwind(|| ConstantArrayArg::new::<f32>(&[]));
+    assert!(empty.is_err(), "Empty constant arrays should be rejected");
+
+    // The scalars fill the space with the length of the output in the static metadata.
+    let taps = vec![1.0f32; MAX_CONSTANT_ARRAY_SIZE / size_of::<f32>() - 4];
+    kernel_constant_array::launch(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(5),
+        unsafe { BufferArg::from_raw_parts(handle.clone(), 5) },
+        2.0,
+        ConstantArrayArg::new(&taps),
+        1.0,
+    );
+
+    // The scalars alone fit, but not with the static metadata. Launched on another thread, since
+    // the scalars registered before the panic are left in the launch state of the thread.
+    let taps = vec![1.0f32; MAX_CONSTANT_ARRAY_SIZE / size_of::<f32>() - 2];
+    let too_big = std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                kernel_constant_array::launch(
+                    &client,
+                    CubeCount::Static(1, 1, 1),
+                    CubeDim::new_1d(5),
+                    unsafe { BufferArg::from_raw_parts(handle.clone(), 5) },
+                    2.0,
+                    ConstantArrayArg::new(&taps),
+                    1.0,
+                )
+            })
+            .join()
+    });
+    assert!(
+        too_big.is_err(),
+        "Constant arrays that don't fit with the other scalars should be rejected"
+    );
+}
+
+pub fn test_kernel_binding_layout<R: Runtime>(client: ComputeClient<R>) {
+    let kernel = kernel_inplace::KernelInplace::<R>::new(
+        KernelSettings::default(),
+        client.clone(),
+        BufferCompilationArg { inplace: None },
+        BufferCompilationArg { inplace: None },
+    );
+    let layout = KernelBindingLayout::new(&kernel);
+
+    let visibility = layout
+        .buffers
+        .iter()
+        .map(|b| b.visibility)
+        .collect::<Vec<_>>();
+    assert_eq!(visibility, [Visibility::Read, Visibility::ReadWrite]);
+    assert!(
+        layout
+            .buffers
+            .iter()
+            .all(|b| b.elem_size == size_of::<f32>())
+    );
+
+    let input = client.create_from_slice(f32::as_bytes(&[1.0]));
+    let output = client.empty(3);
+    let arguments = KernelArguments::new().with_buffers(vec![input.binding()]);
+    assert_eq!(
+        layout.validate(&arguments),
+        Err(BindingMismatch::BufferCount {
+            expected: 2,
+            actual: 1
+        })
+    );
+
+    let input = client.create_from_slice(f32::as_bytes(&[1.0]));
+    let arguments = KernelArguments::new().with_buffers(vec![input.binding(), output.binding()]);
+    assert!(matches!(
+        layout.validate(&arguments),
+        Err(BindingMismatch::BufferSize { index: 1, .. })
+    ));
+}
+
+pub fn test_kernel_write_read_only<R: Runtime>(client: ComputeClient<R>) {
+    if !cfg!(feature = "read-only-checks") {
+        // Writes to read-only arguments are only checked with the `read-only-checks` feature.
+        return;
+    }
+
+    let kernel = kernel_write_read_only::KernelWriteReadOnly::<R>::new(
+        KernelSettings::default(),
+        client,
+        BufferCompilationArg { inplace: None },
+        BufferCompilationArg { inplace: None },
+    );
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| kernel.define()));
+
+    assert!(result.is_err(), "Writing to a read-only input should panic");
+}
+
+fn accumulate_definition<R: Runtime>(client: &ComputeClient<R>) -> KernelDefinition {
+    kernel_accumulate::KernelAccumulate::<R>::new(
+        KernelSettings::default().cube_dim(CubeDim::new_1d(4)),
+        client.clone(),
+        BufferCompilationArg { inplace: None },
+        BufferCompilationArg { inplace: None },
+    )
+    .define()
+}
+
+pub fn test_kernel_fallback<R: Runtime>(client: ComputeClient<R>) {
+    let definition = accumulate_definition(&client);
+    assert_eq!(unsupported_types(&definition, client.properties()), []);
+
+    let f32_ty = StorageType::Scalar(ElemType::Float(FloatKind::F32));
+    let mut properties = client.properties().clone();
+    properties.features.types.storage.remove(&f32_ty);
+    assert_eq!(
+        unsupported_types(&definition, &properties),
+        [Type::Scalar(f32_ty)]
+    );
+
+    let launch = |policy: FallbackPolicy| {
+        let fallback = Arc::new(Fallback::new(client.clone()).policy(policy));
+        let client = client.with_fallback(fallback.clone());
+        let input = client.create_from_slice(as_bytes![f32: 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let output = client.empty(4 * size_of::<f32>());
+
+        kernel_accumulate::launch::<R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(4),
+            unsafe { BufferArg::from_raw_parts(input.clone(), 7) },
+            unsafe { BufferArg::from_raw_parts(output.clone(), 4) },
+        );
+
+        // The results are written back to the handles of the launch.
+        let actual = client.read_one_unchecked(output);
+        assert_eq!(f32::from_bytes(&actual), [12.0, 20.0, 28.0, 36.0]);
+        let actual = client.read_one_unchecked(input);
+        assert_eq!(
+            f32::from_bytes(&actual),
+            [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+        fallback.launches()
+    };
+
+    assert_eq!(launch(FallbackPolicy::Unsupported), 0);
+    // The fallback runtime is the device's here, which still goes through the host transfers.
+    assert_eq!(launch(FallbackPolicy::Always), 1);
+}
+
 pub fn test_kernel_with_generics<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
     let handle = client.create_from_slice(as_bytes![F: 0.0, 1.0]);
 
@@ -162,6 +535,25 @@ pub fn test_kernel_inplace<R: Runtime>(client: ComputeClient<R>) {
     assert_eq!(actual[0], 5.0);
 }
 
+/// The L2 persistence hint only changes caching, so results must be unaffected.
+pub fn test_kernel_l2_persisting<R: Runtime>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(f32::as_bytes(&[1.0, 2.0]));
+    let output = client.empty(size_of::<f32>());
+
+    kernel_inplace::launch(
+        &client.with_l2_persisting(&input, 1.0),
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { BufferArg::from_raw_parts(input, 2) },
+        unsafe { BufferArg::from_raw_parts(output.clone(), 1) },
+    );
+
+    let actual = client.read_one_unchecked(output);
+    let actual = f32::from_bytes(&actual);
+
+    assert_eq!(actual[0], 6.0);
+}
+
 pub fn test_kernel_zero_cube_count<R: Runtime>(client: ComputeClient<R>) {
     // A zero-element fill resolves to `Static(0, 0, 0)`. Launching it is a no-op.
     let handle = client.create_from_slice(f32::as_bytes(&[7.0, 8.0]));
@@ -391,6 +783,52 @@ macro_rules! testgen_launch {
             cubecl_core::runtime_tests::launch::test_kernel_without_generics::<TestRuntime>(client);
         }
 
+        #[$crate::runtime_tests::test_log::test]
+        fn test_launch_binding_layout() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::launch::test_kernel_binding_layout::<TestRuntime>(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_launch_write_read_only() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::launch::test_kernel_write_read_only::<TestRuntime>(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_launch_specialized_scalar() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::launch::test_kernel_specialized_scalar::<TestRuntime>(
+                client,
+            );
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_launch_fallback() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::launch::test_kernel_fallback::<TestRuntime>(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_launch_specialized_shape() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::launch::test_kernel_specialized_shape::<TestRuntime>(
+                client,
+            );
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_launch_constant_array() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::launch::test_kernel_constant_array::<TestRuntime>(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_launch_l2_persisting() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::launch::test_kernel_l2_persisting::<TestRuntime>(client);
+        }
+
         #[$crate::runtime_tests::test_log::test]
         fn test_launch_zero_cube_count() {
             let client = TestRuntime::client(&Default::default());
@@ -17,6 +17,7 @@ pub mod different_rank;
 pub mod enums;
 pub mod file;
 pub mod index;
+pub mod instrumentation;
 pub mod launch;
 pub mod metadata;
 pub mod minifloat;
@@ -32,6 +33,7 @@ pub mod stream;
 pub mod synchronization;
 pub mod tensor;
 pub mod tensormap;
+pub mod texture;
 pub mod to_client;
 pub mod topology;
 pub mod traits;
@@ -152,8 +154,10 @@ macro_rules! testgen_untyped {
         cubecl_core::testgen_sync_plane!();
         cubecl_core::testgen_tensor_indexing!();
         cubecl_core::testgen_debug!();
+        cubecl_core::testgen_instrumentation!();
         cubecl_core::testgen_binary_untyped!();
         cubecl_core::testgen_cluster!();
+        cubecl_core::testgen_texture!();
 
         cubecl_core::testgen_enums!();
         cubecl_core::testgen_comparison!();
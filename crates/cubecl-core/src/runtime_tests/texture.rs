@@ -0,0 +1,79 @@
+use crate::{self as cubecl};
+use alloc::vec::Vec;
+use cubecl::prelude::*;
+use cubecl_ir::OpaqueType;
+use cubecl_runtime::server::Handle;
+
+#[cube(launch)]
+fn texture_sample(texture: &Texture<f32>, output: &mut [f32], offset: f32, y: f32) {
+    let x = f32::cast_from(UNIT_POS) + offset;
+    output[UNIT_POS as usize] = texture.sample(x, y);
+}
+
+/// Samples an 8x2 texture holding `0..16`, between horizontal neighbours on the first row and at
+/// texel centers on the second row.
+pub fn test_texture_sample<R: Runtime>(client: ComputeClient<R>) {
+    if !client.properties().supports_type(OpaqueType::Texture) {
+        // We can't execute the test, skip.
+        return;
+    }
+
+    let (width, height) = (8, 2);
+    let values = (0..width * height).map(|i| i as f32).collect::<Vec<_>>();
+    let texels = client.create_from_slice(f32::as_bytes(&values));
+    let row_pitch = width * size_of::<f32>();
+
+    let sample = |texels: &Handle, filter: TextureFilter, offset: f32, y: f32| {
+        let output = client.empty(width * size_of::<f32>());
+        texture_sample::launch::<R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(width as u32),
+            TextureArg::new(texels.clone(), width, height, row_pitch).with_filter(filter),
+            unsafe { BufferArg::from_raw_parts(output.clone(), width) },
+            offset,
+            y,
+        );
+        f32::from_bytes(&client.read_one_unchecked(output)).to_vec()
+    };
+
+    let linear = sample(&texels, TextureFilter::Linear, 1.0, 0.5);
+    // The last unit samples past the edge, which is clamped to the last texel.
+    let expected = (0..width)
+        .map(|i| (i as f32 + 0.5).min(width as f32 - 1.0))
+        .collect::<Vec<_>>();
+    for (actual, expected) in linear.iter().zip(&expected) {
+        // Bilinear weights only have 8 bits of fraction.
+        assert!(
+            (actual - expected).abs() < 1e-2,
+            "Expected {expected:?}, got {linear:?}"
+        );
+    }
+
+    let nearest = sample(&texels, TextureFilter::Nearest, 0.5, 1.5);
+    let expected = (width..2 * width).map(|i| i as f32).collect::<Vec<_>>();
+    assert_eq!(nearest, expected);
+
+    // The texture objects of freed memory are destroyed, and new memory gets its own.
+    core::mem::drop(texels);
+    client.memory_cleanup();
+    let values = values.iter().map(|value| value * 2.0).collect::<Vec<_>>();
+    let texels = client.create_from_slice(f32::as_bytes(&values));
+    let nearest = sample(&texels, TextureFilter::Nearest, 0.5, 1.5);
+    let expected = expected.iter().map(|value| value * 2.0).collect::<Vec<_>>();
+    assert_eq!(nearest, expected);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_texture {
+    () => {
+        use super::*;
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_texture_sample() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::texture::test_texture_sample::<TestRuntime>(client);
+        }
+    };
+}
@@ -0,0 +1,99 @@
+//! Counts the heap allocations made by a full kernel launch through the generated `launch`
+//! function, including the registration of the arguments and the building of the info.
+//!
+//! Only the allocations of the launching thread are counted, the server of the dry-run runtime
+//! records every launch for its report on its own thread. The allocations of the servers are
+//! checked by the `launch_allocations` test of `cubecl-runtime`.
+//!
+//! The launcher reuses a thread-local scope and info builder with `std`, without it every launch
+//! creates its own.
+#![cfg(feature = "std")]
+
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+use cubecl_runtime::dry_run::{DryRunDevice, DryRunRuntime};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+const WARMUP: usize = 100;
+const LAUNCHES: usize = 10_000;
+/// The allocations left in a launch, which are moved to the server: the boxed kernel, its name,
+/// the list of its buffers and its info.
+const ALLOCATIONS_PER_LAUNCH: usize = 4;
+
+struct CountingAllocator;
+
+std::thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    // The thread local may already be destroyed when a thread exits.
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+// SAFETY: Every call is forwarded to the system allocator.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[cube(launch)]
+fn add_scalar(input: &[f32], output: &mut [f32], value: f32) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS] + value;
+    }
+}
+
+#[test]
+fn launching_a_kernel_allocates_a_bounded_amount() {
+    let client = ComputeClient::<DryRunRuntime>::load(&DryRunDevice::default());
+    let input = client.empty(64 * size_of::<f32>());
+    let output = client.empty(64 * size_of::<f32>());
+
+    let launch = |count| {
+        let before = ALLOCATIONS.with(Cell::get);
+        for _ in 0..count {
+            // SAFETY: The buffers hold 64 elements.
+            unsafe {
+                add_scalar::launch::<DryRunRuntime>(
+                    &client,
+                    CubeCount::Static(1, 1, 1),
+                    CubeDim::new_1d(64),
+                    BufferArg::from_raw_parts(input.clone(), 64),
+                    BufferArg::from_raw_parts(output.clone(), 64),
+                    1.0,
+                )
+            };
+        }
+        client.flush().unwrap();
+        ALLOCATIONS.with(Cell::get) - before
+    };
+
+    launch(WARMUP);
+    // Leave out the allocations of the flush itself, measured with nothing to launch.
+    let flush = launch(0);
+    let allocations = launch(LAUNCHES) - flush;
+
+    assert!(
+        allocations <= ALLOCATIONS_PER_LAUNCH * LAUNCHES,
+        "Launching a kernel made {} allocations, expected at most {ALLOCATIONS_PER_LAUNCH}",
+        allocations as f64 / LAUNCHES as f64
+    );
+}
@@ -340,6 +340,7 @@ impl<M: DialectWmmaCompiler<Self>> DialectTypes<Self> for CudaDialect<M> {
                 write!(f, "cuda::barrier<cuda::thread_scope_thread>::arrival_token")
             }
             Item::TensorMap => f.write_str("CUtensorMap"),
+            Item::Texture => f.write_str("cudaTextureObject_t"),
             Item::Barrier(BarrierLevel::Unit) => {
                 f.write_str("cuda::barrier<cuda::thread_scope_thread>")
             }
@@ -361,6 +362,7 @@ impl<M: DialectWmmaCompiler<Self>> DialectBindings<Self> for CudaDialect<M> {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[KernelArg<Self>],
+        textures: &[KernelArg<Self>],
         buffers: &[KernelArg<Self>],
         flags: &Flags<Self>,
     ) -> std::fmt::Result {
@@ -380,7 +382,7 @@ extern \"C\" __global__ void __launch_bounds__({})",
         }
         writeln!(f, "{kernel_name} (")?;
 
-        shared::compile_bindings(f, tensor_maps, buffers, flags.has_info)?;
+        shared::compile_bindings(f, tensor_maps, textures, buffers, flags.has_info)?;
         if flags.use_grid_constants {
             shared::compile_info_static(f, flags)?;
         } else {
@@ -340,6 +340,7 @@ impl<M: DialectWmmaCompiler<Self>> DialectTypes<Self> for HipDialect<M> {
                 panic!("Barrier object not supported in HIP")
             }
             Item::TensorMap => panic!("TensorMap not supported on HIP"),
+            Item::Texture => panic!("Textures not supported on HIP"),
         }
     }
 
@@ -355,6 +356,7 @@ impl<M: DialectWmmaCompiler<Self>> DialectBindings<Self> for HipDialect<M> {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[KernelArg<Self>],
+        textures: &[KernelArg<Self>],
         buffers: &[KernelArg<Self>],
         flags: &Flags<Self>,
     ) -> std::fmt::Result {
@@ -366,7 +368,7 @@ extern \"C\" __global__ void __launch_bounds__({}) {kernel_name}(
 ",
             flags.cube_dim.num_elems()
         )?;
-        shared::compile_bindings::<Self>(f, tensor_maps, buffers, flags.has_info)?;
+        shared::compile_bindings::<Self>(f, tensor_maps, textures, buffers, flags.has_info)?;
         shared::compile_info_dynamic::<Self>(f, flags)?;
         f.write_str("\n)")?;
 
@@ -378,6 +378,7 @@ struct alignas({alignment}) {item} {{"
                 unimplemented!("metal doesn't support barrier object")
             }
             Item::TensorMap => unimplemented!("TensorMap not supported on Metal"),
+            Item::Texture => unimplemented!("Textures not supported on Metal"),
         }
     }
 
@@ -411,6 +412,7 @@ impl DialectBindings<Self> for MslDialect {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[KernelArg<Self>],
+        textures: &[KernelArg<Self>],
         buffers: &[KernelArg<Self>],
         flags: &Flags<Self>,
     ) -> std::fmt::Result {
@@ -426,6 +428,7 @@ void {kernel_name}("
             tensor_maps.is_empty(),
             "Tensor maps aren't supported for metal"
         );
+        debug_assert!(textures.is_empty(), "Textures aren't supported for metal");
         for b in buffers.iter() {
             format_global_binding_arg(b, &mut buffer_idx, f)?;
         }
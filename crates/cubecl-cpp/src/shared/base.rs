@@ -243,6 +243,11 @@ impl<D: Dialect> CppCompiler<D> {
             .into_iter()
             .map(|b| self.compile_binding(b))
             .collect();
+        let textures = value
+            .textures
+            .into_iter()
+            .map(|b| self.compile_binding(b))
+            .collect();
         let buffers = value
             .buffers
             .into_iter()
@@ -306,6 +311,7 @@ impl<D: Dialect> CppCompiler<D> {
 
         ComputeKernel {
             tensor_maps,
+            textures,
             buffers,
             scalars,
             meta_static_len: self.info.metadata.static_len() as usize,
@@ -801,6 +807,16 @@ impl<D: Dialect> CppCompiler<D> {
                     }
                 }
             }
+            ir::Operation::Texture(texture_ops) => match texture_ops {
+                ir::TextureOps::Sample2d { texture, x, y } => {
+                    instructions.push(Instruction::TextureSample2d {
+                        texture: self.compile_value(texture),
+                        x: self.compile_value(x),
+                        y: self.compile_value(y),
+                        out: self.compile_value(out.unwrap()),
+                    });
+                }
+            },
             ir::Operation::Marker(_) => {}
             ir::Operation::ConstructAggregate(..) | ir::Operation::ExtractAggregateField(..) => {
                 unreachable!("Should be disaggregated at this point")
@@ -1749,6 +1765,11 @@ impl<D: Dialect> CppCompiler<D> {
                 id,
                 out: self.compile_value(out),
             }),
+            ir::Operator::ReadScalarArray(op) => instructions.push(Instruction::ReadScalarArray {
+                id: op.id,
+                index: self.compile_value(op.index),
+                out: self.compile_value(out),
+            }),
         };
     }
 
@@ -2007,6 +2028,7 @@ impl<D: Dialect> CppCompiler<D> {
                 Item::BarrierToken(barrier_level)
             }
             ir::OpaqueType::TensorMap => Item::TensorMap,
+            ir::OpaqueType::Texture => Item::Texture,
         }
     }
 }
@@ -114,6 +114,7 @@ pub trait DialectBindings<D: Dialect> {
         f: &mut std::fmt::Formatter<'_>,
         kernel_name: &str,
         tensor_maps: &[KernelArg<D>],
+        textures: &[KernelArg<D>],
         buffers: &[KernelArg<D>],
         flags: &Flags<D>,
     ) -> std::fmt::Result;
@@ -86,6 +86,11 @@ pub enum Instruction<D: Dialect> {
         id: Id,
         out: Value<D>,
     },
+    ReadScalarArray {
+        id: Id,
+        index: Value<D>,
+        out: Value<D>,
+    },
     Store(UnaryInstruction<D>),
     Load(UnaryInstruction<D>),
     SpecialCast(UnaryInstruction<D>),
@@ -282,6 +287,12 @@ pub enum Instruction<D: Dialect> {
         tensor_map: Value<D>,
         indices: Vec<Value<D>>,
     },
+    TextureSample2d {
+        texture: Value<D>,
+        x: Value<D>,
+        y: Value<D>,
+        out: Value<D>,
+    },
     Line {
         file: Cow<'static, str>,
         line: u32,
@@ -741,6 +752,27 @@ if({pos} == 0) {{
                     "cuda::device::experimental::cp_async_bulk_tensor_{rank}d_shared_to_global(&{tensor_map}, {indices} {smem_ptr});"
                 )
             }
+            Instruction::TextureSample2d { texture, x, y, out } => {
+                let item = out.item();
+                let elem = *item.elem();
+                let vectorization = item.vectorization();
+                let fetch_ty = texel_fetch_type(elem, vectorization);
+                let texel = format!("{out}_texel");
+                writeln!(
+                    f,
+                    "const {fetch_ty} {texel} = tex2D<{fetch_ty}>({texture}, {x}, {y});"
+                )?;
+                if vectorization == 1 {
+                    writeln!(f, "{} = {elem}({texel});", out.fmt_left())
+                } else {
+                    let components = ["x", "y", "z", "w"][..vectorization]
+                        .iter()
+                        .map(|c| format!("{elem}({texel}.{c})"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    writeln!(f, "{} = {item}{{ {components} }};", out.fmt_left())
+                }
+            }
             Instruction::SpecialCast(UnaryInstruction { input, out }) => {
                 // Only supported in CUDA so I'm putting it here. Move to dialect if necessary.
                 #[cfg(not(feature = "cuda"))]
@@ -761,10 +793,37 @@ if({pos} == 0) {{
                 let elem = *out.item().elem();
                 writeln!(f, "{} = info.scalars_{elem}[{id}];", out.fmt_left())
             }
+            Instruction::ReadScalarArray { id, index, out } => {
+                let elem = *out.item().elem();
+                writeln!(
+                    f,
+                    "{} = info.scalars_{elem}[{id} + {index}];",
+                    out.fmt_left()
+                )
+            }
         }
     }
 }
 
+/// The type returned by `tex2D` for texels of `vectorization` channels of `elem`. Half precision
+/// textures are always read as `float`.
+fn texel_fetch_type<D: Dialect>(elem: Elem<D>, vectorization: usize) -> String {
+    let (scalar, vector) = match elem {
+        Elem::F16 | Elem::BF16 | Elem::F32 => ("float", "float"),
+        Elem::I8 => ("signed char", "char"),
+        Elem::U8 => ("unsigned char", "uchar"),
+        Elem::I16 => ("short", "short"),
+        Elem::U16 => ("unsigned short", "ushort"),
+        Elem::I32 => ("int", "int"),
+        Elem::U32 => ("unsigned int", "uint"),
+        elem => panic!("Unsupported texture element {elem}"),
+    };
+    match vectorization {
+        1 => scalar.to_string(),
+        n => format!("{vector}{n}"),
+    }
+}
+
 struct Fma<D: Dialect> {
     _dialect: PhantomData<D>,
 }
@@ -22,6 +22,7 @@ pub enum Item<D: Dialect> {
     Barrier(BarrierLevel),
     BarrierToken(BarrierLevel),
     TensorMap,
+    Texture,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -81,7 +82,7 @@ impl<D: Dialect> Item<D> {
             | Item::DynamicArray(item) => item.elem(),
             Item::Fragment(frag) => &frag.elem,
             Item::BarrierToken(..) => &Elem::None,
-            Item::TensorMap => &Elem::None,
+            Item::TensorMap | Item::Texture => &Elem::None,
             Item::Barrier(..) => &Elem::None,
         }
     }
@@ -105,6 +106,7 @@ impl<D: Dialect> Item<D> {
             Item::Barrier(..) => panic!("Can't set elem of barrier"),
             Item::BarrierToken(..) => panic!("Can't set elem of barrier token"),
             Item::TensorMap => panic!("Can't set elem of tensor map"),
+            Item::Texture => panic!("Can't set elem of texture"),
         }
     }
 
@@ -121,6 +123,7 @@ impl<D: Dialect> Item<D> {
             Item::Barrier(..) => panic!("Can't set elem of barrier"),
             Item::BarrierToken(..) => panic!("Can't get elem of barrier token"),
             Item::TensorMap => panic!("Can't get elem of tensor map"),
+            Item::Texture => panic!("Can't get elem of texture"),
         }
     }
 
@@ -134,7 +137,7 @@ impl<D: Dialect> Item<D> {
             | Item::DynamicArray(inner) => inner.vectorization(),
             Item::Fragment(_) => 1,
             Item::Barrier(..) | Item::BarrierToken(..) => 1,
-            Item::TensorMap => 1,
+            Item::TensorMap | Item::Texture => 1,
         }
     }
 
@@ -151,6 +154,7 @@ impl<D: Dialect> Item<D> {
             Item::Barrier(..) => size_of::<u64>(),
             Item::BarrierToken(..) => size_of::<u64>(),
             Item::TensorMap => 128,
+            Item::Texture => size_of::<u64>(),
         }
     }
 
@@ -194,6 +198,7 @@ impl<D: Dialect> Item<D> {
             Item::Barrier(barrier_level) => Item::Barrier(*barrier_level),
             Item::BarrierToken(barrier_level) => Item::BarrierToken(*barrier_level),
             Item::TensorMap => Item::TensorMap,
+            Item::Texture => Item::Texture,
         }
     }
 
@@ -240,6 +245,7 @@ impl<D: Dialect> Item<D> {
             Item::Barrier(barrier_level) => Item::Barrier(*barrier_level),
             Item::BarrierToken(barrier_level) => Item::BarrierToken(*barrier_level),
             Item::TensorMap => Item::TensorMap,
+            Item::Texture => Item::Texture,
         }
     }
 
@@ -29,6 +29,7 @@ impl<D: Dialect> SharedMemory<D> {
 #[derive(Debug, Clone)]
 pub struct ComputeKernel<D: Dialect> {
     pub tensor_maps: Vec<KernelArg<D>>,
+    pub textures: Vec<KernelArg<D>>,
     pub buffers: Vec<KernelArg<D>>,
     pub scalars: Vec<(Elem<D>, usize)>,
     pub info: cubecl_core::Info,
@@ -68,6 +69,7 @@ impl<D: Dialect> Display for ComputeKernel<D> {
             f,
             &self.kernel_name,
             &self.tensor_maps,
+            &self.textures,
             &self.buffers,
             &self.flags,
         )?;
@@ -176,6 +178,7 @@ struct info_st {{
 pub fn compile_bindings<D: Dialect>(
     f: &mut core::fmt::Formatter<'_>,
     tensor_maps: &[KernelArg<D>],
+    textures: &[KernelArg<D>],
     buffers: &[KernelArg<D>],
     trailing_comma: bool,
 ) -> core::fmt::Result {
@@ -190,17 +193,17 @@ pub fn compile_bindings<D: Dialect>(
             binding.value
         )
     }));
-    args.extend(buffers.iter().map(|binding| {
-        let ty = binding.value.item();
-        match binding.vis {
-            Visibility::Read | Visibility::Uniform => {
-                format!("const {ty} __restrict__ {}", binding.value)
-            }
-            _ => {
-                format!("{ty} __restrict__ {}", binding.value)
-            }
-        }
-    }));
+    args.extend(
+        textures
+            .iter()
+            .map(|binding| format!("const {} {}", binding.value.item(), binding.value)),
+    );
+    // Read-only buffers are pointers to `const`, which is part of their type.
+    args.extend(
+        buffers
+            .iter()
+            .map(|binding| format!("{} __restrict__ {}", binding.value.item(), binding.value)),
+    );
 
     write!(f, "{}", args.join(", "))?;
     if trailing_comma {
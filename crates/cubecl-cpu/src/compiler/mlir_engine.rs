@@ -46,16 +46,7 @@ impl MlirEngine {
         shared_memories: SharedMemories,
         addr_type: StorageType,
     ) -> Self {
-        let registry = DialectRegistry::new();
-        register_all_dialects(&registry);
-        register_all_passes();
-
-        let context = Context::new();
-        register_all_llvm_translations(&context);
-        context.enable_multi_threading(false);
-        context.append_dialect_registry(&registry);
-        context.load_all_available_dialects();
-
+        let context = Self::context();
         let mut module = Module::new(&context, kernel.options.kernel_name.clone());
 
         module.visit_kernel(&kernel, func, global_state, &shared_memories, addr_type);
@@ -76,6 +67,20 @@ impl MlirEngine {
         mlir_kernel
     }
 
+    /// Create a context with all the dialects, passes and translations loaded.
+    pub(super) fn context() -> Context {
+        let registry = DialectRegistry::new();
+        register_all_dialects(&registry);
+        register_all_passes();
+
+        let context = Context::new();
+        register_all_llvm_translations(&context);
+        context.enable_multi_threading(false);
+        context.append_dialect_registry(&registry);
+        context.load_all_available_dialects();
+        context
+    }
+
     pub fn dump_object(&self, path: &str) {
         self.0.execution_engine.dump_to_object_file(path);
     }
@@ -22,7 +22,7 @@ use cubecl_core::{
     prelude::KernelDefinition,
     server::ExecutionMode,
 };
-use cubecl_opt::OptimizerBuilder;
+use cubecl_opt::{Optimizer, OptimizerBuilder};
 use mlir_engine::MlirEngine;
 
 use crate::compiler::passes::{
@@ -48,37 +48,7 @@ impl Compiler for MlirCompiler {
         mode: ExecutionMode, // TODO support this by adding array bound checking
         addr_type: StorageType,
     ) -> Result<Self::Representation, CompilationError> {
-        let errors = kernel.body.pop_errors();
-        if !errors.is_empty() {
-            let mut reason = "Can't compile mlir kernel".to_string();
-            for error in errors {
-                reason += error.as_str();
-                reason += "\n";
-            }
-
-            return Err(CompilationError::Validation {
-                reason,
-                backtrace: BackTrace::capture(),
-            });
-        }
-
-        #[cfg(feature = "mlir-dump")]
-        dump_scope(&kernel.body, &kernel.options.kernel_name);
-        let mut opt = OptimizerBuilder::default()
-            .with_transformer(ErfTransform)
-            .with_transformer(HypotTransform)
-            .with_transformer(RhypotTransform)
-            .with_visitor(CheckedIoVisitor::new(
-                mode,
-                kernel.options.kernel_name.clone(),
-            ))
-            .with_visitor(DisaggregateVisitor::default())
-            .with_processor(SaturatingArithmeticProcessor::new(true))
-            .with_processor(PredicateProcessor)
-            .optimize(kernel.body.clone(), kernel.cube_dim);
-
-        let mut shared_memories = SharedMemories::default();
-        shared_memories.visit(&opt);
+        let (mut opt, shared_memories) = optimize(&kernel, mode)?;
 
         #[cfg(feature = "mlir-dump")]
         dump_opt(&opt, &kernel.options.kernel_name);
@@ -98,6 +68,79 @@ impl Compiler for MlirCompiler {
     fn extension(&self) -> &'static str {
         "mlir"
     }
+
+    fn compiles_source(&self) -> bool {
+        // The MLIR module is executed by the engine built during compilation.
+        false
+    }
+}
+
+/// Validate and optimize the kernel, then collect its shared memories.
+fn optimize(
+    kernel: &KernelDefinition,
+    mode: ExecutionMode,
+) -> Result<(Optimizer, SharedMemories), CompilationError> {
+    let errors = kernel.body.pop_errors();
+    if !errors.is_empty() {
+        let mut reason = "Can't compile mlir kernel".to_string();
+        for error in errors {
+            reason += error.as_str();
+            reason += "\n";
+        }
+
+        return Err(CompilationError::Validation {
+            reason,
+            backtrace: BackTrace::capture(),
+        });
+    }
+
+    #[cfg(feature = "mlir-dump")]
+    dump_scope(&kernel.body, &kernel.options.kernel_name);
+    let opt = OptimizerBuilder::default()
+        .with_transformer(ErfTransform)
+        .with_transformer(HypotTransform)
+        .with_transformer(RhypotTransform)
+        .with_visitor(CheckedIoVisitor::new(
+            mode,
+            kernel.options.kernel_name.clone(),
+        ))
+        .with_visitor(DisaggregateVisitor::default())
+        .with_processor(SaturatingArithmeticProcessor::new(true))
+        .with_processor(PredicateProcessor)
+        .optimize(kernel.body.clone(), kernel.cube_dim);
+
+    let mut shared_memories = SharedMemories::default();
+    shared_memories.visit(&opt);
+
+    Ok((opt, shared_memories))
+}
+
+/// Export a kernel as the MLIR module compiled by the CPU runtime, in textual format, to compare
+/// its code generation with MLIR pipelines or analyse it with MLIR tooling.
+///
+/// The module is emitted before it's lowered to LLVM. It isn't a `gpu` or `linalg` dialect
+/// module: it only uses the `func`, `scf`, `arith`, `math`, `memref` and `vector` dialects, the
+/// builtins like `UNIT_POS` are arguments of the kernel function, and the cube dispatch loop lives
+/// outside of it. Feeding it to a GPU pipeline requires outlining it into a `gpu.module` first.
+#[cfg(feature = "mlir-cpu-export")]
+pub fn export_cpu_mlir(
+    kernel: KernelDefinition,
+    mode: ExecutionMode,
+    addr_type: StorageType,
+) -> Result<String, CompilationError> {
+    let (mut opt, shared_memories) = optimize(&kernel, mode)?;
+
+    let context = MlirEngine::context();
+    let mut module = module::Module::new(&context, kernel.options.kernel_name.clone());
+    module.visit_kernel(
+        &kernel,
+        &mut opt.main,
+        &opt.global_state,
+        &shared_memories,
+        addr_type,
+    );
+
+    Ok(module.to_text())
 }
 
 #[cfg(feature = "mlir-dump")]
@@ -127,3 +170,32 @@ fn dump_opt(opt: &cubecl_opt::Optimizer, name: &str) {
         std::fs::write(path.join("cubecl-opt.ir.dot"), opt.main.dot_viz()).unwrap();
     }
 }
+
+#[cfg(all(test, feature = "mlir-cpu-export"))]
+mod tests {
+    use cubecl_core::{
+        KernelSettings,
+        compute::KernelBuilder,
+        ir::AddressType,
+        prelude::{BufferCompilationArg, LaunchArg},
+    };
+
+    use super::*;
+
+    #[test]
+    fn export_cpu_mlir_emits_kernel_function() {
+        let mut builder = KernelBuilder::default();
+        AddressType::U32.register(&builder.scope);
+        <[f32] as LaunchArg>::expand(&BufferCompilationArg { inplace: None }, &mut builder);
+        let kernel = builder.build(KernelSettings::default());
+
+        let mlir = export_cpu_mlir(
+            kernel,
+            ExecutionMode::Checked,
+            AddressType::U32.unsigned_type(),
+        )
+        .unwrap();
+
+        assert!(mlir.contains("func.func"), "{mlir}");
+    }
+}
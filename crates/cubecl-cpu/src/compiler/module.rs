@@ -102,6 +102,12 @@ impl<'a> Module<'a> {
         self.module.as_operation().verify();
     }
 
+    /// The module in MLIR's textual format, before any pass is run.
+    #[cfg(feature = "mlir-cpu-export")]
+    pub(super) fn to_text(&self) -> String {
+        self.module.as_operation().to_string()
+    }
+
     pub(super) fn into_execution_engine(self) -> ExecutionEngine {
         ExecutionEngine::new(&self.module, 0, &[], true, false)
     }
@@ -187,6 +187,7 @@ impl<'a> Visitor<'a> {
             Operation::CoopMma(_)
             | Operation::Plane(_)
             | Operation::Tma(_)
+            | Operation::Texture(_)
             | Operation::TensorIndexing(_) => {
                 panic!("{operation} is not supported on CPU.");
             }
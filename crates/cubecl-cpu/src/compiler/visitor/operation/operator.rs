@@ -200,6 +200,31 @@ impl<'a> Visitor<'a> {
                 };
                 self.insert_value(out, value);
             }
+            Operator::ReadScalarArray(op) => {
+                let memref = *self
+                    .args_manager
+                    .scalars_memref
+                    .get(&out.storage_type())
+                    .unwrap();
+                let offset = self
+                    .block
+                    .const_int_from_type(
+                        self.context,
+                        self.location,
+                        op.id as i64,
+                        Type::index(self.context),
+                    )
+                    .unwrap();
+                let index = self.get_index(op.index, op.index.ty, false);
+                let index =
+                    self.append_operation_with_result(arith::addi(offset, index, self.location));
+                let value = self.append_operation_with_result(memref::load(
+                    memref,
+                    &[index],
+                    self.location,
+                ));
+                self.insert_value(out, value);
+            }
         }
     }
 
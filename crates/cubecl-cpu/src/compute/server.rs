@@ -205,6 +205,42 @@ impl ComputeServer for CpuServer {
         stream.bind(reserved, memory);
     }
 
+    fn initialize_memory_aligned(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        size: u64,
+        alignment: u64,
+        stream_id: StreamId,
+    ) -> Result<(), IoError> {
+        let stream = self.scheduler.stream(&stream_id);
+        let reserved = stream.empty_aligned(size, alignment)?;
+        stream.bind(reserved, memory);
+        Ok(())
+    }
+
+    fn create_from_host(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        data: Bytes,
+        stream_id: StreamId,
+    ) -> Result<u64, Bytes> {
+        let max_alignment = self.utilities.properties.memory.alignment;
+        let stream = self.scheduler.stream(&stream_id);
+        let storage = stream.memory_management.storage().wrap(data)?;
+        let (ptr, _) = stream
+            .memory_management
+            .storage()
+            .get(&storage)
+            .get_write_ptr_and_length();
+        // Host memory is only as aligned as its allocation, which may be less than the pools.
+        let alignment = Ord::min(1 << ptr.addr().trailing_zeros(), max_alignment);
+
+        let reserved = stream.memory_management.reserve_storage(storage);
+        stream.bind(reserved, memory);
+
+        Ok(alignment)
+    }
+
     fn read(
         &mut self,
         descriptors: Vec<CopyDescriptor>,
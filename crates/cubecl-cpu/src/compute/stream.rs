@@ -116,6 +116,14 @@ impl CpuStream {
         self.memory_management.reserve(size)
     }
 
+    pub fn empty_aligned(
+        &mut self,
+        size: u64,
+        alignment: u64,
+    ) -> Result<ManagedMemoryHandle, IoError> {
+        self.memory_management.reserve_aligned(size, alignment)
+    }
+
     /// Maps handles to their corresponding buffers.
     pub fn bind(&mut self, reserved: ManagedMemoryHandle, new: ManagedMemoryHandle) {
         self.memory_management.bind(reserved, new, 0).unwrap();
@@ -1,8 +1,12 @@
 use crate::{
     CudaCompiler,
     compute::{
-        MB, context::CudaContext, io::controller::PinnedMemoryManagedAllocController,
-        storage::gpu::GpuResource, stream::CudaStreamBackend, sync::Fence,
+        MB,
+        context::{AccessPolicyWindow, CudaContext},
+        io::controller::PinnedMemoryManagedAllocController,
+        storage::gpu::GpuResource,
+        stream::CudaStreamBackend,
+        sync::Fence,
     },
 };
 use cubecl_common::{
@@ -27,9 +31,10 @@ use cubecl_runtime::{
     logging::ServerLogger,
     memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryHandle},
     stream::ResolvedStreams,
+    texture::TextureMeta,
 };
 use cudarc::driver::sys::{
-    CUDA_MEMCPY2D_st, CUmemorytype, CUstream_st, CUtensorMap, cuMemcpy2DAsync_v2,
+    CUDA_MEMCPY2D_st, CUmemorytype, CUstream_st, CUtensorMap, CUtexObject, cuMemcpy2DAsync_v2,
 };
 use std::{ffi::c_void, ops::DerefMut, sync::Arc};
 
@@ -60,6 +65,32 @@ impl<'a> Command<'a> {
             .get_resource(binding.memory, binding.offset_start, binding.offset_end)
     }
 
+    /// The texture object sampling the binding with the layout of `meta`, created on its first
+    /// use and destroyed when the memory of the binding is deallocated.
+    pub fn texture(
+        &mut self,
+        binding: Binding,
+        meta: TextureMeta,
+    ) -> Result<CUtexObject, LaunchError> {
+        let memory = &mut self.streams.get(&binding.stream).memory_management_gpu;
+        let id = memory.get_storage(binding.memory.clone())?.id;
+        let ptr = memory
+            .get_resource(binding.memory, binding.offset_start, binding.offset_end)?
+            .ptr;
+        if let Some(texture) = memory.storage().texture(id, ptr, &meta) {
+            return Ok(texture);
+        }
+
+        let texture = self.ctx.create_texture_object(ptr, &meta)?;
+        self.streams
+            .get(&binding.stream)
+            .memory_management_gpu
+            .storage()
+            .register_texture(id, ptr, meta, texture);
+
+        Ok(texture)
+    }
+
     /// Get the stream cursor.
     pub fn cursor(&self) -> u64 {
         self.streams.cursor
@@ -76,6 +107,7 @@ impl<'a> Command<'a> {
 
     /// Explicitly cleanup gpu memory on the current stream.
     pub fn memory_cleanup(&mut self) {
+        self.ctx.release_l2_set_aside();
         self.streams.current().memory_management_gpu.cleanup(true)
     }
 
@@ -105,6 +137,18 @@ impl<'a> Command<'a> {
         Ok(handle)
     }
 
+    /// Reserve memory starting at a multiple of `alignment` bytes.
+    pub fn reserve_aligned(
+        &mut self,
+        size: u64,
+        alignment: u64,
+    ) -> Result<ManagedMemoryHandle, IoError> {
+        self.streams
+            .current()
+            .memory_management_gpu
+            .reserve_aligned(size, alignment)
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     pub fn empty(&mut self, size: u64) -> Result<Handle, IoError> {
         let handle = Handle::new(self.streams.current, size);
@@ -376,7 +420,16 @@ impl<'a> Command<'a> {
 
         let data = match should_stage {
             true => {
-                let mut buffer = self.reserve_pinned(size, None).unwrap();
+                // Allocating pinned memory isn't allowed while the stream is captured, so the
+                // staging pool must have been warmed up before the capture.
+                let Some(mut buffer) = self.reserve_pinned(size, None) else {
+                    return Err(IoError::Unknown {
+                        description: format!(
+                            "Can't reserve a pinned staging buffer of {size} bytes"
+                        ),
+                        backtrace: BackTrace::capture(),
+                    });
+                };
                 data.copy_into(&mut buffer);
                 buffer
             }
@@ -399,10 +452,10 @@ impl<'a> Command<'a> {
             )
         }?;
 
-        current.drop_queue.push(data);
+        current.retain(data);
 
         if should_flush {
-            current.drop_queue.flush(|| Fence::new(current.sys));
+            current.flush_drop_queue();
         }
 
         Ok(())
@@ -454,6 +507,22 @@ impl<'a> Command<'a> {
         Box::pin(async { fence.wait_sync() })
     }
 
+    /// Maximum number of cubes of the kernel resident on one streaming multiprocessor, compiling
+    /// it if needed.
+    pub fn max_active_cubes(
+        &mut self,
+        kernel_id: KernelId,
+        kernel: Box<dyn CubeTask<CudaCompiler>>,
+        mode: ExecutionMode,
+        logger: Arc<ServerLogger>,
+    ) -> Result<u32, LaunchError> {
+        if !self.ctx.module_names.contains_key(&kernel_id) {
+            self.ctx.compile_kernel(&kernel_id, kernel, mode, logger)?;
+        }
+
+        self.ctx.max_active_cubes(&kernel_id)
+    }
+
     /// Executes a registered CUDA kernel with the specified parameters.
     ///
     /// # Parameters
@@ -465,6 +534,7 @@ impl<'a> Command<'a> {
     /// * `tensor_maps` - Tensor maps for structured memory access.
     /// * `resources` - GPU resources (e.g., buffers) used by the kernel.
     /// * `scalars` - Scalar arguments passed to the kernel.
+    /// * `access_policy_window` - Buffer that should persist in the L2 cache, if any.
     /// * `logger` - The logger to use to write compilation & runtime info.
     ///
     /// # Panics
@@ -478,8 +548,10 @@ impl<'a> Command<'a> {
         mode: ExecutionMode,
         dispatch_count: (u32, u32, u32),
         tensor_maps: &[CUtensorMap],
+        textures: &[CUtexObject],
         resources: &[GpuResource],
         const_info: Option<*mut c_void>,
+        access_policy_window: Option<AccessPolicyWindow>,
         logger: Arc<ServerLogger>,
     ) -> Result<(), LaunchError> {
         if !self.ctx.module_names.contains_key(&kernel_id) {
@@ -493,12 +565,14 @@ impl<'a> Command<'a> {
             kernel_id,
             dispatch_count,
             tensor_maps,
+            textures,
             resources,
             const_info,
+            access_policy_window,
         );
 
         if stream.drop_queue.should_flush() {
-            stream.drop_queue.flush(|| Fence::new(stream.sys));
+            stream.flush_drop_queue();
         }
 
         if let Err(err) = result {
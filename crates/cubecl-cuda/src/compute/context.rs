@@ -3,6 +3,7 @@ use cubecl_cpp::formatter::format_cpp;
 use cubecl_cpp::{cuda::arch::CudaArchitecture, shared::CompilationOptions};
 use cubecl_runtime::{
     compiler::CompilationError,
+    texture::{TextureAddressMode, TextureFilter, TextureMeta},
     validation::{validate_cube_dim, validate_units},
 };
 
@@ -15,6 +16,7 @@ use crate::{
 use cubecl_core::{
     compilation_cache::CompilationCache,
     hash::StableHash,
+    ir::{ElemType, FloatKind, IntKind, StorageType, UIntKind},
     server::ResourceLimitError,
     {ir::DeviceProperties, prelude::*},
 };
@@ -22,7 +24,12 @@ use cubecl_runtime::timestamp_profiler::TimestampProfiler;
 use cubecl_runtime::{compiler::CubeTask, logging::ServerLogger};
 use cudarc::driver::DriverError;
 use cudarc::driver::sys::CUfunc_st;
-use cudarc::driver::sys::{CUctx_st, CUfunction_attribute, CUtensorMap};
+use cudarc::driver::sys::{
+    CUDA_RESOURCE_DESC, CUDA_TEXTURE_DESC, CUaccessPolicyWindow, CUaccessProperty, CUaddress_mode,
+    CUarray_format, CUctx_st, CUdevice, CUdeviceptr, CUfilter_mode, CUfunction_attribute,
+    CUlaunchAttribute, CUlaunchAttributeID, CUlaunchConfig, CUlimit, CUresourcetype, CUtensorMap,
+    CUtexObject, cuCtxResetPersistingL2Cache, cuCtxSetLimit, cuLaunchKernelEx,
+};
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::ffi::c_char;
@@ -41,6 +48,155 @@ pub(crate) struct CudaContext {
     pub arch: CudaArchitecture,
     pub compilation_options: CompilationOptions,
     pub properties: DeviceProperties,
+    /// Kernel parameters of the last launch, kept around so launches don't allocate.
+    launch_params: Vec<*mut c_void>,
+    /// Persisting L2 set-aside of the context, `None` when access policy windows aren't
+    /// supported.
+    l2_set_aside: Option<L2SetAside>,
+}
+
+/// Limits of the L2 cache persistence of a device.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct L2PersistingLimits {
+    /// Maximum number of bytes covered by an access policy window.
+    pub max_window_bytes: usize,
+    /// Maximum size of the L2 set-aside for persisting accesses.
+    pub max_persisting_bytes: usize,
+}
+
+/// A buffer that should persist in the L2 cache during a launch.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AccessPolicyWindow {
+    pub ptr: CUdeviceptr,
+    pub size: usize,
+    pub hit_ratio: f32,
+}
+
+/// The persisting L2 set-aside of a context, sized to the window of the last hinted launch.
+#[derive(Debug)]
+pub(crate) struct L2SetAside {
+    limits: L2PersistingLimits,
+    /// Size of the set-aside, zero when it's released.
+    size: usize,
+    /// Start of the window of the last hinted launch, whose lines may persist.
+    ptr: Option<CUdeviceptr>,
+}
+
+impl L2SetAside {
+    pub fn new(limits: L2PersistingLimits) -> Self {
+        Self {
+            limits,
+            size: 0,
+            ptr: None,
+        }
+    }
+
+    /// The launch attribute making accesses to `window` persist in the L2 cache, resizing the
+    /// set-aside to the window.
+    ///
+    /// Launches without a window keep the set-aside and the lines persisted by earlier launches,
+    /// so unhinted kernels interleaved with the hinted ones don't evict the hinted buffer. The
+    /// persisted lines are reset when the window moves to another buffer, and the set-aside is
+    /// only given back to the whole cache by [`release`](Self::release).
+    ///
+    /// # Safety
+    ///
+    /// The context of the set-aside must be current.
+    pub unsafe fn update(
+        &mut self,
+        window: Option<AccessPolicyWindow>,
+    ) -> Result<Option<CUlaunchAttribute>, DriverError> {
+        let Some(window) = window else {
+            return Ok(None);
+        };
+
+        if self.ptr.is_some_and(|ptr| ptr != window.ptr) {
+            // SAFETY: The context is current, guaranteed by the caller.
+            unsafe { cuCtxResetPersistingL2Cache().result()? };
+        }
+        self.ptr = Some(window.ptr);
+
+        let num_bytes = window.size.min(self.limits.max_window_bytes);
+        let size = num_bytes.min(self.limits.max_persisting_bytes);
+        if size != self.size {
+            // SAFETY: The context is current, guaranteed by the caller.
+            unsafe {
+                cuCtxSetLimit(CUlimit::CU_LIMIT_PERSISTING_L2_CACHE_SIZE, size).result()?;
+            }
+            self.size = size;
+        }
+
+        // Persisting more lines than the set-aside can hold would thrash it, so the hit ratio is
+        // scaled down for windows bigger than the set-aside.
+        let fit = size as f32 / num_bytes.max(1) as f32;
+
+        // SAFETY: `CUlaunchAttribute` is a plain C struct, zero is a valid bit pattern.
+        let mut attribute: CUlaunchAttribute = unsafe { core::mem::zeroed() };
+        attribute.id = CUlaunchAttributeID::CU_LAUNCH_ATTRIBUTE_ACCESS_POLICY_WINDOW;
+        attribute.value.accessPolicyWindow = CUaccessPolicyWindow {
+            base_ptr: window.ptr as *mut c_void,
+            num_bytes,
+            hitRatio: window.hit_ratio * fit,
+            hitProp: CUaccessProperty::CU_ACCESS_PROPERTY_PERSISTING,
+            missProp: CUaccessProperty::CU_ACCESS_PROPERTY_STREAMING,
+        };
+
+        Ok(Some(attribute))
+    }
+
+    /// Reset the persisted lines to normal and release the set-aside, so the whole cache is
+    /// available to the following launches.
+    ///
+    /// # Safety
+    ///
+    /// The context of the set-aside must be current.
+    pub unsafe fn release(&mut self) -> Result<(), DriverError> {
+        if self.size > 0 {
+            // SAFETY: The context is current, guaranteed by the caller.
+            unsafe {
+                cuCtxResetPersistingL2Cache().result()?;
+                cuCtxSetLimit(CUlimit::CU_LIMIT_PERSISTING_L2_CACHE_SIZE, 0).result()?;
+            }
+            self.size = 0;
+        }
+        self.ptr = None;
+        Ok(())
+    }
+}
+
+/// Keeps track of who owns the CUDA context of a server.
+///
+/// The primary context is retained by the server and released when the guard is dropped, while
+/// a context provided by the host application is left untouched.
+#[derive(Debug)]
+pub(crate) struct ContextGuard {
+    retained: Option<CUdevice>,
+}
+
+impl ContextGuard {
+    /// The primary context of `device`, retained with `cuDevicePrimaryCtxRetain`.
+    pub fn primary(device: CUdevice) -> Self {
+        Self {
+            retained: Some(device),
+        }
+    }
+
+    /// A context owned by the host application.
+    pub fn external() -> Self {
+        Self { retained: None }
+    }
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        if let Some(device) = self.retained.take() {
+            // SAFETY: The primary context of `device` was retained exactly once when the server
+            // was created, so this release balances the reference count.
+            unsafe {
+                let _ = cudarc::driver::result::primary_ctx::release(device);
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -63,6 +219,7 @@ impl CudaContext {
         properties: DeviceProperties,
         context: *mut CUctx_st,
         arch: CudaArchitecture,
+        l2_persisting: Option<L2PersistingLimits>,
     ) -> Self {
         Self {
             context,
@@ -84,13 +241,15 @@ impl CudaContext {
             timestamps: TimestampProfiler::default(),
             compilation_options,
             properties,
+            launch_params: Vec::new(),
+            l2_set_aside: l2_persisting.map(L2SetAside::new),
         }
     }
 
     /// Switches the current CUDA context to this context.
     pub fn unsafe_set_current(&self) -> Result<(), DriverError> {
-        // SAFETY: `self.context` is a valid CUDA context obtained from `primary_ctx::retain`
-        // during server initialization and remains valid for the server's lifetime.
+        // SAFETY: `self.context` is either the primary context retained during server
+        // initialization or a context the host application keeps alive for the server's lifetime.
         unsafe { cudarc::driver::result::ctx::set_current(self.context) }
     }
 
@@ -274,19 +433,134 @@ impl CudaContext {
         Ok(())
     }
 
+    /// Release the persisting L2 set-aside kept by the launches hinted with an access policy
+    /// window.
+    pub fn release_l2_set_aside(&mut self) {
+        let Some(set_aside) = &mut self.l2_set_aside else {
+            return;
+        };
+
+        // SAFETY: The context of this server is current while it handles commands.
+        if let Err(err) = unsafe { set_aside.release() } {
+            log::warn!("Can't release the persisting L2 cache: {err}");
+        }
+    }
+
+    /// Create a texture object sampling `ptr` with the layout of `meta`. The texture objects are
+    /// cached and destroyed by the [storage](crate::compute::storage::gpu::GpuStorage) of the
+    /// memory they sample.
+    pub fn create_texture_object(
+        &self,
+        ptr: CUdeviceptr,
+        meta: &TextureMeta,
+    ) -> Result<CUtexObject, LaunchError> {
+        let unsupported = |reason: String| LaunchError::Unknown {
+            reason,
+            backtrace: BackTrace::capture(),
+        };
+        let (format, is_integer) = texture_format(meta.storage_ty).ok_or_else(|| {
+            unsupported(format!("Unsupported texture element {}", meta.storage_ty))
+        })?;
+        if is_integer && meta.filter == TextureFilter::Linear {
+            return Err(unsupported(
+                "Linear filtering is only supported for float textures".into(),
+            ));
+        }
+        if !meta.normalized_coords
+            && matches!(
+                meta.address_mode,
+                TextureAddressMode::Wrap | TextureAddressMode::Mirror
+            )
+        {
+            return Err(unsupported(
+                "Wrap and mirror addressing require normalized coordinates".into(),
+            ));
+        }
+
+        // SAFETY: Both descriptors are plain C structs for which all zeroes is the default.
+        let mut resource: CUDA_RESOURCE_DESC = unsafe { core::mem::zeroed() };
+        resource.resType = CUresourcetype::CU_RESOURCE_TYPE_PITCH2D;
+        // SAFETY: `pitch2D` is the union variant selected by `resType`.
+        unsafe {
+            let pitch = &mut resource.res.pitch2D;
+            pitch.devPtr = ptr;
+            pitch.format = format;
+            pitch.numChannels = meta.channels as u32;
+            pitch.width = meta.width;
+            pitch.height = meta.height;
+            pitch.pitchInBytes = meta.row_pitch;
+        }
+
+        let address_mode = match meta.address_mode {
+            TextureAddressMode::Clamp => CUaddress_mode::CU_TR_ADDRESS_MODE_CLAMP,
+            TextureAddressMode::Border => CUaddress_mode::CU_TR_ADDRESS_MODE_BORDER,
+            TextureAddressMode::Wrap => CUaddress_mode::CU_TR_ADDRESS_MODE_WRAP,
+            TextureAddressMode::Mirror => CUaddress_mode::CU_TR_ADDRESS_MODE_MIRROR,
+        };
+        // SAFETY: See above.
+        let mut texture_desc: CUDA_TEXTURE_DESC = unsafe { core::mem::zeroed() };
+        texture_desc.addressMode = [address_mode; 3];
+        texture_desc.filterMode = match meta.filter {
+            TextureFilter::Nearest => CUfilter_mode::CU_TR_FILTER_MODE_POINT,
+            TextureFilter::Linear => CUfilter_mode::CU_TR_FILTER_MODE_LINEAR,
+        };
+        if is_integer {
+            texture_desc.flags |= CU_TRSF_READ_AS_INTEGER;
+        }
+        if meta.normalized_coords {
+            texture_desc.flags |= CU_TRSF_NORMALIZED_COORDINATES;
+        }
+
+        let mut texture = 0;
+        // SAFETY: The descriptors are fully initialized and `ptr` is a live device allocation of
+        // at least `row_pitch * height` bytes, checked when the texture was registered.
+        unsafe {
+            cudarc::driver::sys::cuTexObjectCreate(
+                &mut texture,
+                &resource,
+                &texture_desc,
+                core::ptr::null(),
+            )
+            .result()
+            .map_err(|err| unsupported(format!("Failed to create texture object: {err}")))?;
+        }
+
+        Ok(texture)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn execute_task(
         &mut self,
         stream: &mut Stream,
         kernel_id: KernelId,
         dispatch_count: (u32, u32, u32),
         tensor_maps: &[CUtensorMap],
+        textures: &[CUtexObject],
         resources: &[GpuResource],
         const_info: Option<*mut c_void>,
+        access_policy_window: Option<AccessPolicyWindow>,
     ) -> Result<(), LaunchError> {
-        let mut bindings = tensor_maps
-            .iter()
-            .map(|map| map as *const _ as *mut c_void)
-            .collect::<Vec<_>>();
+        let access_policy = match &mut self.l2_set_aside {
+            // SAFETY: The context of this server is current during launches.
+            Some(set_aside) => {
+                unsafe { set_aside.update(access_policy_window) }.map_err(|err| {
+                    LaunchError::Unknown {
+                        reason: format!("{err}"),
+                        backtrace: BackTrace::capture(),
+                    }
+                })?
+            }
+            None => None,
+        };
+
+        let bindings = &mut self.launch_params;
+        bindings.clear();
+        bindings.extend(tensor_maps.iter().map(|map| map as *const _ as *mut c_void));
+        bindings.extend(
+            textures
+                .iter()
+                .map(|texture| texture as *const _ as *mut c_void),
+        );
         bindings.extend(resources.iter().map(|memory| memory.binding));
         bindings.extend(const_info);
 
@@ -306,17 +580,41 @@ impl CudaContext {
                 reason: format!("{err}"),
                 backtrace: BackTrace::capture(),
             })?;
-            cudarc::driver::result::launch_kernel(
-                kernel.func,
-                dispatch_count,
-                (cube_dim.x, cube_dim.y, cube_dim.z),
-                // Shared memory is collected into a single buffer, with each shared memory being
-                // an offset pointer
-                kernel.shared_mem_bytes as u32,
-                stream.sys,
-                &mut bindings,
-            )
-            .map_err(|err| LaunchError::Unknown {
+
+            let launched = match access_policy {
+                Some(mut attribute) => {
+                    let mut config: CUlaunchConfig = core::mem::zeroed();
+                    config.gridDimX = dispatch_count.0;
+                    config.gridDimY = dispatch_count.1;
+                    config.gridDimZ = dispatch_count.2;
+                    config.blockDimX = cube_dim.x;
+                    config.blockDimY = cube_dim.y;
+                    config.blockDimZ = cube_dim.z;
+                    config.sharedMemBytes = kernel.shared_mem_bytes as u32;
+                    config.hStream = stream.sys;
+                    config.attrs = &mut attribute;
+                    config.numAttrs = 1;
+
+                    cuLaunchKernelEx(
+                        &config,
+                        kernel.func,
+                        bindings.as_mut_ptr(),
+                        core::ptr::null_mut(),
+                    )
+                    .result()
+                }
+                None => cudarc::driver::result::launch_kernel(
+                    kernel.func,
+                    dispatch_count,
+                    (cube_dim.x, cube_dim.y, cube_dim.z),
+                    // Shared memory is collected into a single buffer, with each shared memory
+                    // being an offset pointer
+                    kernel.shared_mem_bytes as u32,
+                    stream.sys,
+                    bindings,
+                ),
+            };
+            launched.map_err(|err| LaunchError::Unknown {
                 reason: format!("{err}"),
                 backtrace: BackTrace::capture(),
             })?;
@@ -325,6 +623,41 @@ impl CudaContext {
         Ok(())
     }
 
+    /// Maximum number of cubes of a compiled kernel resident on one streaming multiprocessor.
+    pub fn max_active_cubes(&self, kernel_id: &KernelId) -> Result<u32, LaunchError> {
+        let kernel = self
+            .module_names
+            .get(kernel_id)
+            .ok_or_else(|| LaunchError::Unknown {
+                reason: format!("Kernel {kernel_id:?} isn't compiled"),
+                backtrace: BackTrace::capture(),
+            })?;
+        let to_launch_error = |err: DriverError| LaunchError::Unknown {
+            reason: format!("{err}"),
+            backtrace: BackTrace::capture(),
+        };
+
+        // SAFETY: `kernel.func` is a valid function handle from a loaded module.
+        unsafe {
+            // Same opt-in as the launch, otherwise kernels using more than 48KB of shared
+            // memory would report no occupancy.
+            cudarc::driver::result::function::set_function_attribute(
+                kernel.func,
+                CUfunction_attribute::CU_FUNC_ATTRIBUTE_MAX_DYNAMIC_SHARED_SIZE_BYTES,
+                kernel.shared_mem_bytes as i32,
+            )
+            .map_err(to_launch_error)?;
+            let cubes = cudarc::driver::result::occupancy::max_active_block_per_multiprocessor(
+                kernel.func,
+                kernel.cube_dim.num_elems() as i32,
+                kernel.shared_mem_bytes,
+            )
+            .map_err(to_launch_error)?;
+
+            Ok(cubes as u32)
+        }
+    }
+
     fn validate_shared(&self, repr: &Option<CudaComputeKernel>) -> Result<(), LaunchError> {
         let requested = repr.as_ref().map(|repr| repr.shared_memory_size());
         let max = self.properties.hardware.max_shared_memory_size;
@@ -342,3 +675,115 @@ impl CudaContext {
         }
     }
 }
+
+/// Reads texels as integers instead of normalized floats, `CU_TRSF_READ_AS_INTEGER` in `cuda.h`.
+const CU_TRSF_READ_AS_INTEGER: u32 = 0x01;
+/// Uses coordinates in `[0, 1)`, `CU_TRSF_NORMALIZED_COORDINATES` in `cuda.h`.
+const CU_TRSF_NORMALIZED_COORDINATES: u32 = 0x02;
+
+/// The array format of texels of type `ty`, and whether it's an integer format.
+fn texture_format(ty: StorageType) -> Option<(CUarray_format, bool)> {
+    use CUarray_format::*;
+
+    let format = match ty {
+        StorageType::Scalar(ElemType::Float(FloatKind::F32 | FloatKind::Flex32)) => {
+            (CU_AD_FORMAT_FLOAT, false)
+        }
+        StorageType::Scalar(ElemType::Float(FloatKind::F16)) => (CU_AD_FORMAT_HALF, false),
+        StorageType::Scalar(ElemType::Int(IntKind::I8)) => (CU_AD_FORMAT_SIGNED_INT8, true),
+        StorageType::Scalar(ElemType::Int(IntKind::I16)) => (CU_AD_FORMAT_SIGNED_INT16, true),
+        StorageType::Scalar(ElemType::Int(IntKind::I32)) => (CU_AD_FORMAT_SIGNED_INT32, true),
+        StorageType::Scalar(ElemType::UInt(UIntKind::U8)) => (CU_AD_FORMAT_UNSIGNED_INT8, true),
+        StorageType::Scalar(ElemType::UInt(UIntKind::U16)) => (CU_AD_FORMAT_UNSIGNED_INT16, true),
+        StorageType::Scalar(ElemType::UInt(UIntKind::U32)) => (CU_AD_FORMAT_UNSIGNED_INT32, true),
+        _ => return None,
+    };
+    Some(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cudarc::driver::{
+        result,
+        sys::{CUdevice_attribute, cuCtxGetLimit},
+    };
+
+    fn persisting_limit() -> usize {
+        let mut limit = 0;
+        // SAFETY: `limit` outlives the call, which only writes the current limit to it.
+        unsafe {
+            cuCtxGetLimit(&mut limit, CUlimit::CU_LIMIT_PERSISTING_L2_CACHE_SIZE)
+                .result()
+                .unwrap();
+        }
+        limit
+    }
+
+    #[test_log::test]
+    fn l2_set_aside_is_sized_to_the_window_and_kept_until_released() {
+        result::init().unwrap();
+        let device = result::device::get(0).unwrap();
+
+        // SAFETY: The primary context is retained and current for the whole test, the windows
+        // are only passed as launch attributes and never dereferenced.
+        unsafe {
+            let ctx = result::primary_ctx::retain(device).unwrap();
+            result::ctx::set_current(ctx).unwrap();
+
+            let attribute =
+                |attribute| result::device::get_attribute(device, attribute).unwrap_or(0) as usize;
+            let limits = L2PersistingLimits {
+                max_window_bytes: attribute(
+                    CUdevice_attribute::CU_DEVICE_ATTRIBUTE_MAX_ACCESS_POLICY_WINDOW_SIZE,
+                ),
+                max_persisting_bytes: attribute(
+                    CUdevice_attribute::CU_DEVICE_ATTRIBUTE_MAX_PERSISTING_L2_CACHE_SIZE,
+                ),
+            };
+            if limits.max_window_bytes == 0 || limits.max_persisting_bytes == 0 {
+                // Devices before compute capability 8.0 have no persisting L2 cache.
+                result::primary_ctx::release(device).unwrap();
+                return;
+            }
+
+            let mut set_aside = L2SetAside::new(limits);
+            let window = |size| AccessPolicyWindow {
+                ptr: 0,
+                size,
+                hit_ratio: 1.0,
+            };
+
+            let size = (limits.max_persisting_bytes / 2).min(limits.max_window_bytes);
+            let attribute = set_aside.update(Some(window(size))).unwrap().unwrap();
+            assert_eq!(persisting_limit(), size);
+            assert_eq!(attribute.value.accessPolicyWindow.num_bytes, size);
+            assert_eq!(attribute.value.accessPolicyWindow.hitRatio, 1.0);
+
+            // Windows bigger than the set-aside only persist the part of their accesses that fit.
+            let size = limits.max_window_bytes;
+            let attribute = set_aside.update(Some(window(size))).unwrap().unwrap();
+            let set_aside_size = size.min(limits.max_persisting_bytes);
+            assert_eq!(persisting_limit(), set_aside_size);
+            assert_eq!(
+                attribute.value.accessPolicyWindow.hitRatio,
+                set_aside_size as f32 / size as f32
+            );
+
+            // Unhinted launches interleaved with the hinted ones keep the set-aside.
+            for _ in 0..2 {
+                assert!(set_aside.update(None).unwrap().is_none());
+                assert_eq!(persisting_limit(), set_aside_size);
+                assert!(set_aside.update(Some(window(size))).unwrap().is_some());
+                assert_eq!(persisting_limit(), set_aside_size);
+            }
+
+            set_aside.release().unwrap();
+            assert_eq!(persisting_limit(), 0);
+            assert!(set_aside.update(None).unwrap().is_none());
+            assert_eq!(persisting_limit(), 0);
+
+            result::primary_ctx::release(device).unwrap();
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use core::ffi::{c_char, c_int, c_ulonglong, c_void};
+use cudarc::driver::sys::{CUdevice, CUresult, cuDeviceGetPCIBusId};
+use libloading::Library;
+
+type NvmlReturn = c_int;
+type NvmlDevice = *mut c_void;
+
+const NVML_SUCCESS: NvmlReturn = 0;
+
+#[cfg(target_os = "windows")]
+const NVML_LIBRARIES: &[&str] = &["nvml.dll"];
+#[cfg(not(target_os = "windows"))]
+const NVML_LIBRARIES: &[&str] = &["libnvidia-ml.so.1", "libnvidia-ml.so"];
+
+/// Large enough for the `domain:bus:device.function` id of any device.
+const PCI_BUS_ID_LEN: usize = 32;
+
+type GetEnergyFn = unsafe extern "C" fn(NvmlDevice, *mut c_ulonglong) -> NvmlReturn;
+type ShutdownFn = unsafe extern "C" fn() -> NvmlReturn;
+
+/// Reads the energy counter of a device through NVML, loaded at runtime.
+///
+/// NVML ships with the driver rather than the toolkit, so it may be missing, and older devices
+/// don't expose the counter. In both cases [`EnergyCounter::new`] returns `None`.
+pub(crate) struct EnergyCounter {
+    device: NvmlDevice,
+    get_energy: GetEnergyFn,
+    shutdown: ShutdownFn,
+    // Keeps the symbols above valid.
+    _library: Library,
+}
+
+impl core::fmt::Debug for EnergyCounter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EnergyCounter").finish()
+    }
+}
+
+impl EnergyCounter {
+    /// Opens the counter of the given CUDA device.
+    ///
+    /// NVML enumerates devices in its own order, ignoring `CUDA_VISIBLE_DEVICES`, so the device
+    /// is looked up by its PCI bus id rather than its ordinal.
+    pub(crate) fn new(device: CUdevice) -> Option<Self> {
+        let mut bus_id = [0 as c_char; PCI_BUS_ID_LEN];
+        // SAFETY: `bus_id` is valid for `PCI_BUS_ID_LEN` bytes, and the driver null-terminates
+        // the id it writes.
+        let status =
+            unsafe { cuDeviceGetPCIBusId(bus_id.as_mut_ptr(), PCI_BUS_ID_LEN as c_int, device) };
+        if status != CUresult::CUDA_SUCCESS {
+            return None;
+        }
+
+        // SAFETY: Loading NVML only runs its initialization routines, which have no
+        // preconditions.
+        let library = NVML_LIBRARIES
+            .iter()
+            .find_map(|name| unsafe { Library::new(name) }.ok())?;
+
+        // SAFETY: The symbol types match the NVML headers, and `device` is only read after
+        // `nvmlDeviceGetHandleByPciBusId_v2` succeeded.
+        unsafe {
+            let init = library
+                .get::<unsafe extern "C" fn() -> NvmlReturn>(b"nvmlInit_v2\0")
+                .ok()?;
+            let shutdown = *library.get::<ShutdownFn>(b"nvmlShutdown\0").ok()?;
+            let get_handle = library
+                .get::<unsafe extern "C" fn(*const c_char, *mut NvmlDevice) -> NvmlReturn>(
+                    b"nvmlDeviceGetHandleByPciBusId_v2\0",
+                )
+                .ok()?;
+            let get_energy = *library
+                .get::<GetEnergyFn>(b"nvmlDeviceGetTotalEnergyConsumption\0")
+                .ok()?;
+
+            if init() != NVML_SUCCESS {
+                return None;
+            }
+
+            // From here, dropping the counter balances the initialization.
+            let mut counter = Self {
+                device: core::ptr::null_mut(),
+                get_energy,
+                shutdown,
+                _library: library,
+            };
+
+            if get_handle(bus_id.as_ptr(), &mut counter.device) != NVML_SUCCESS {
+                return None;
+            }
+
+            // Devices older than Volta don't have the counter.
+            counter.read()?;
+            Some(counter)
+        }
+    }
+
+    /// Total energy consumed by the device since the driver was loaded, in millijoules.
+    pub(crate) fn read(&self) -> Option<u64> {
+        let mut energy = 0;
+        // SAFETY: `device` is a valid handle and `energy` is a valid output location.
+        let status = unsafe { (self.get_energy)(self.device, &mut energy) };
+
+        (status == NVML_SUCCESS).then_some(energy)
+    }
+}
+
+impl Drop for EnergyCounter {
+    fn drop(&mut self) {
+        // SAFETY: NVML counts its initializations, so this only releases the one done in `new`.
+        unsafe {
+            (self.shutdown)();
+        }
+    }
+}
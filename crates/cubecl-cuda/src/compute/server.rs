@@ -4,9 +4,10 @@ use crate::{
     compute::{
         command::Command,
         communication::{get_nccl_comm_id, get_nccl_dtype_count, to_nccl_op},
-        context::CudaContext,
+        context::{AccessPolicyWindow, ContextGuard, CudaContext},
+        energy::EnergyCounter,
         stream::CudaStreamBackend,
-        sync::Fence,
+        sync::{ExternalSemaphores, Fence},
     },
 };
 use cubecl_common::{
@@ -19,9 +20,10 @@ use cubecl_core::{
     ir::{ElemType, FloatKind, IntKind, MemoryDeviceProperties, StorageType, UIntKind},
     prelude::*,
     server::{
-        Binding, CommunicationId, CopyDescriptor, Handle, KernelArguments, LaunchError,
-        ProfileError, ProfilingToken, ReduceOperation, ServerCommunication, ServerError,
-        ServerUtilities, StreamErrorMode, TensorMapBinding, TensorMapMeta,
+        Binding, CommunicationId, CompletionCallback, CopyDescriptor, ExternalSemaphore,
+        ExternalSemaphoreHandle, Handle, IoError, KernelArguments, LaunchError, ProfileError,
+        ProfilingToken, ReduceOperation, ServerCommunication, ServerError, ServerUtilities,
+        StreamErrorMode, TensorMapBinding, TensorMapMeta, TextureBinding,
     },
 };
 use cubecl_runtime::{
@@ -35,10 +37,12 @@ use cubecl_runtime::{
     stream::MultiStream,
 };
 use cudarc::driver::sys::{
-    CUstream_st, CUtensorMapDataType, CUtensorMapFloatOOBfill, CUtensorMapInterleave,
-    CUtensorMapL2promotion, CUtensorMapSwizzle, cuTensorMapEncodeIm2col, cuTensorMapEncodeTiled,
+    CUresult, CUstream, CUstream_st, CUtensorMapDataType, CUtensorMapFloatOOBfill,
+    CUtensorMapInterleave, CUtensorMapL2promotion, CUtensorMapSwizzle, cuStreamAddCallback,
+    cuTensorMapEncodeIm2col, cuTensorMapEncodeTiled,
 };
 use std::{
+    cell::OnceCell,
     collections::{HashMap, hash_map::Entry},
     ffi::c_void,
     mem::MaybeUninit,
@@ -55,6 +59,11 @@ pub struct CudaServer {
     utilities: Arc<ServerUtilities<Self>>,
     comm_stream: *mut CUstream_st,
     communicators: HashMap<CommunicationId, *mut cudarc::nccl::sys::ncclComm>,
+    /// Opened on the first query, since loading NVML isn't free.
+    energy: OnceCell<Option<EnergyCounter>>,
+    semaphores: ExternalSemaphores,
+    /// Declared last so the context outlives every resource allocated in it.
+    _context_guard: ContextGuard,
 }
 
 // SAFETY: `CudaServer` is only accessed from one thread at a time via the `DeviceHandle`,
@@ -125,6 +134,29 @@ impl ComputeServer for CudaServer {
         command.bind(reserved, memory);
     }
 
+    fn initialize_memory_aligned(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        size: u64,
+        alignment: u64,
+        stream_id: StreamId,
+    ) -> Result<(), IoError> {
+        let mut command = match self.command_no_inputs(
+            stream_id,
+            StreamErrorMode {
+                ignore: true,
+                flush: false,
+            },
+        ) {
+            Ok(val) => val,
+            Err(err) => unreachable!("{err}"),
+        };
+
+        let reserved = command.reserve_aligned(size, alignment)?;
+        command.bind(reserved, memory);
+        Ok(())
+    }
+
     fn write(&mut self, descriptors: Vec<(CopyDescriptor, Bytes)>, stream_id: StreamId) {
         let mut command = match self.command(
             stream_id,
@@ -173,7 +205,7 @@ impl ComputeServer for CudaServer {
         )?;
 
         let current = command.streams.current();
-        current.drop_queue.flush(|| Fence::new(current.sys));
+        current.flush_drop_queue();
         current.memory_management_gpu.storage().flush();
 
         Ok(())
@@ -194,6 +226,40 @@ impl ComputeServer for CudaServer {
         }
     }
 
+    fn on_complete(&mut self, stream_id: StreamId, callback: CompletionCallback) {
+        let command = self.command_no_inputs(
+            stream_id,
+            StreamErrorMode {
+                ignore: false,
+                flush: true,
+            },
+        );
+        let mut command = match command {
+            Ok(command) => command,
+            Err(err) => return callback(Err(err)),
+        };
+        let stream = command.streams.current().sys;
+        let user_data = Box::into_raw(Box::new(callback)) as *mut c_void;
+
+        // SAFETY: `stream` is a valid stream owned by the server and `user_data` is a leaked
+        // `CompletionCallback` that is reclaimed exactly once, either by `host_callback` when the
+        // driver invokes it or below when the registration fails.
+        //
+        // Unlike `cuLaunchHostFunc`, a stream callback receives the status of the stream, so the
+        // errors of the previously submitted work reach the callback.
+        unsafe {
+            if let Err(err) =
+                cuStreamAddCallback(stream, Some(host_callback), user_data, 0).result()
+            {
+                let callback = Box::from_raw(user_data as *mut CompletionCallback);
+                callback(Err(ServerError::Generic {
+                    reason: format!("Can't register the completion callback: {err}"),
+                    backtrace: BackTrace::capture(),
+                }));
+            }
+        }
+    }
+
     fn start_profile(&mut self, stream_id: StreamId) -> Result<ProfilingToken, ServerError> {
         cubecl_common::future::block_on(self.sync(stream_id))?;
         Ok(self.ctx.timestamps.start())
@@ -273,6 +339,92 @@ impl ComputeServer for CudaServer {
         };
         command.allocation_mode(mode)
     }
+
+    fn max_cubes_per_unit(
+        &mut self,
+        kernel: Self::Kernel,
+        mode: ExecutionMode,
+        stream_id: StreamId,
+    ) -> Result<Option<u32>, ServerError> {
+        let mut kernel_id = kernel.id();
+        let logger = self.streams.logger.clone();
+        kernel_id.mode(mode);
+        let mut command = self.command_no_inputs(
+            stream_id,
+            StreamErrorMode {
+                ignore: true,
+                flush: false,
+            },
+        )?;
+
+        Ok(Some(
+            command.max_active_cubes(kernel_id, kernel, mode, logger)?,
+        ))
+    }
+
+    fn energy_consumption(&mut self) -> Option<u64> {
+        let index = self.device_id.index_id as i32;
+        self.energy
+            .get_or_init(|| {
+                let device = cudarc::driver::result::device::get(index).ok()?;
+                EnergyCounter::new(device)
+            })
+            .as_ref()?
+            .read()
+    }
+
+    fn import_semaphore(
+        &mut self,
+        handle: ExternalSemaphoreHandle,
+    ) -> Result<ExternalSemaphore, ServerError> {
+        self.unsafe_set_current();
+        self.semaphores.import(handle)
+    }
+
+    fn signal_semaphore(
+        &mut self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream_id: StreamId,
+    ) -> Result<(), ServerError> {
+        let stream = self
+            .command_no_inputs(
+                stream_id,
+                StreamErrorMode {
+                    ignore: false,
+                    flush: true,
+                },
+            )?
+            .streams
+            .current()
+            .sys;
+        self.semaphores.signal(semaphore, value, stream)
+    }
+
+    fn wait_semaphore(
+        &mut self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream_id: StreamId,
+    ) -> Result<(), ServerError> {
+        let stream = self
+            .command_no_inputs(
+                stream_id,
+                StreamErrorMode {
+                    ignore: false,
+                    flush: true,
+                },
+            )?
+            .streams
+            .current()
+            .sys;
+        self.semaphores.wait(semaphore, value, stream)
+    }
+
+    fn release_semaphore(&mut self, semaphore: ExternalSemaphore) {
+        self.unsafe_set_current();
+        self.semaphores.release(semaphore);
+    }
 }
 
 impl ServerCommunication for CudaServer {
@@ -546,6 +698,8 @@ impl CudaServer {
         mem_alignment: usize,
         device_id: DeviceId,
         utilities: ServerUtilities<Self>,
+        context_guard: ContextGuard,
+        external_stream: Option<*mut CUstream_st>,
     ) -> Self {
         let config = CubeClRuntimeConfig::get();
         let max_streams = config.streaming.max_streams;
@@ -566,12 +720,16 @@ impl CudaServer {
                     mem_alignment,
                     utilities.logger.clone(),
                     stream_priority,
+                    external_stream,
                 ),
                 max_streams,
             ),
             utilities: Arc::new(utilities),
             comm_stream,
             communicators: HashMap::default(),
+            energy: OnceCell::new(),
+            semaphores: ExternalSemaphores::default(),
+            _context_guard: context_guard,
         }
     }
 
@@ -650,7 +808,15 @@ impl CudaServer {
             .grid_constants;
         let mut command = self.command(
             stream_id,
-            bindings.buffers.iter(),
+            bindings
+                .buffers
+                .iter()
+                .chain(bindings.textures.iter().map(|texture| &texture.binding))
+                .chain(
+                    bindings
+                        .access_policy_window()
+                        .map(|window| &window.binding),
+                ),
             StreamErrorMode {
                 ignore: true,
                 flush: false,
@@ -701,6 +867,17 @@ impl CudaServer {
             (None, handle)
         };
 
+        let access_policy_window = bindings.access_policy_window().map(|window| {
+            let resource = command
+                .resource(window.binding.clone())
+                .expect("Access policy window resource exists.");
+            AccessPolicyWindow {
+                ptr: resource.ptr,
+                size: resource.size as usize,
+                hit_ratio: window.hit_ratio,
+            }
+        });
+
         let mut resources = bindings
             .buffers
             .into_iter()
@@ -867,6 +1044,12 @@ impl CudaServer {
             tensor_maps.push(binding);
         }
 
+        let textures = bindings
+            .textures
+            .into_iter()
+            .map(|TextureBinding { binding, meta }| command.texture(binding, meta))
+            .collect::<Result<Vec<_>, _>>()?;
+
         resources.extend(
             info_binding
                 .into_iter()
@@ -879,8 +1062,10 @@ impl CudaServer {
             mode,
             count,
             &tensor_maps,
+            &textures,
             &resources,
             info_const,
+            access_policy_window,
             logger,
         )?;
 
@@ -892,6 +1077,20 @@ impl CudaServer {
     }
 }
 
+/// Stream callback registered by [`CudaServer::on_complete`](ComputeServer::on_complete).
+///
+/// # Safety
+///
+/// `user_data` must be a leaked `Box<CompletionCallback>` that isn't reclaimed elsewhere.
+unsafe extern "C" fn host_callback(_stream: CUstream, status: CUresult, user_data: *mut c_void) {
+    // SAFETY: Guaranteed by the caller, the driver invokes each stream callback exactly once.
+    let callback = unsafe { Box::from_raw(user_data as *mut CompletionCallback) };
+    callback(status.result().map_err(|err| ServerError::Generic {
+        reason: format!("A task submitted before the completion callback failed: {err}"),
+        backtrace: BackTrace::capture(),
+    }));
+}
+
 fn elem_to_tensor_map_type(ty: StorageType) -> CUtensorMapDataType {
     use cudarc::driver::sys::CUtensorMapDataType::*;
     match ty {
@@ -1,3 +1,4 @@
+use crate::compute::stream::is_capturing;
 use cubecl_common::backtrace::BackTrace;
 use cubecl_core::server::IoError;
 use cubecl_runtime::storage::{ComputeStorage, StorageHandle, StorageId, StorageUtilization};
@@ -13,6 +14,9 @@ pub const PINNED_MEMORY_ALIGNMENT: usize = core::mem::size_of::<u128>();
 pub struct PinnedMemoryStorage {
     memory: HashMap<StorageId, PinnedMemory>,
     mem_alignment: usize,
+    /// The stream the staging buffers are copied on.
+    stream: cudarc::driver::sys::CUstream,
+    deallocations: Vec<StorageId>,
 }
 
 /// A pinned memory resource allocated on the host.
@@ -38,11 +42,13 @@ impl PinnedMemoryStorage {
     /// Creates a new [`PinnedMemoryStorage`] instance.
     ///
     /// Initializes the storage with the default pinned memory alignment
-    /// defined by [`PINNED_MEMORY_ALIGNMENT`].
-    pub fn new() -> Self {
+    /// defined by [`PINNED_MEMORY_ALIGNMENT`], for the staging buffers of the given stream.
+    pub fn new(stream: cudarc::driver::sys::CUstream) -> Self {
         Self {
             memory: HashMap::new(),
             mem_alignment: PINNED_MEMORY_ALIGNMENT,
+            stream,
+            deallocations: Vec::new(),
         }
     }
 }
@@ -88,6 +94,18 @@ impl ComputeStorage for PinnedMemoryStorage {
         tracing::instrument(level = "trace", skip(self, size))
     )]
     fn alloc(&mut self, size: u64) -> Result<StorageHandle, IoError> {
+        // `cuMemAllocHost` synchronizes the device, which would invalidate a capture.
+        if is_capturing(self.stream) {
+            return Err(IoError::Unknown {
+                description: format!(
+                    "Can't allocate {size} bytes of pinned memory while the stream is being \
+                     captured into a graph, run the workload once before capturing to warm up \
+                     the staging buffers"
+                ),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
         // SAFETY: Calling CUDA driver FFI to allocate page-locked (pinned) host memory.
         // The returned pointer is stored and freed via `cuMemFreeHost` on deallocation.
         let resource = unsafe {
@@ -117,16 +135,25 @@ impl ComputeStorage for PinnedMemoryStorage {
 
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn dealloc(&mut self, id: StorageId) {
-        if let Some(resource) = self.memory.remove(&id) {
-            // SAFETY: `resource.ptr` was allocated by `cuMemAllocHost_v2` and has not been
-            // freed yet. After this call, the pointer is invalid and removed from `self.memory`.
-            unsafe {
-                cudarc::driver::sys::cuMemFreeHost(resource.ptr);
-            }
-        }
+        self.deallocations.push(id);
     }
 
     fn flush(&mut self) {
-        // We don't wait for dealloc.
+        // `cuMemFreeHost` synchronizes the device, so the deallocations wait for the capture to
+        // end.
+        if is_capturing(self.stream) {
+            return;
+        }
+
+        for id in self.deallocations.drain(..) {
+            if let Some(resource) = self.memory.remove(&id) {
+                // SAFETY: `resource.ptr` was allocated by `cuMemAllocHost_v2` and has not been
+                // freed yet. After this call, the pointer is invalid and removed from
+                // `self.memory`.
+                unsafe {
+                    cudarc::driver::sys::cuMemFreeHost(resource.ptr);
+                }
+            }
+        }
     }
 }
@@ -1,8 +1,14 @@
-use crate::compute::uninit_vec;
+use crate::compute::{stream::is_capturing, sync::Fence, uninit_vec};
 use cubecl_common::backtrace::BackTrace;
 use cubecl_core::server::IoError;
-use cubecl_runtime::storage::{ComputeStorage, StorageHandle, StorageId, StorageUtilization};
-use cudarc::driver::DriverError;
+use cubecl_runtime::{
+    storage::{ComputeStorage, StorageHandle, StorageId, StorageUtilization},
+    texture::TextureMeta,
+};
+use cudarc::driver::{
+    DriverError,
+    sys::{CUdeviceptr, CUtexObject},
+};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy)]
@@ -18,6 +24,11 @@ enum AllocationKind {
 pub struct GpuStorage {
     memory: HashMap<StorageId, (cudarc::driver::sys::CUdeviceptr, AllocationKind)>,
     deallocations: Vec<StorageId>,
+    /// Texture objects sampling each allocation, destroyed with it.
+    textures: HashMap<StorageId, Vec<(CUdeviceptr, TextureMeta, CUtexObject)>>,
+    /// Texture objects of deallocated memory, destroyed once kernels enqueued before the fence,
+    /// which may still sample them, are completed.
+    retired_textures: Vec<(Fence, Vec<CUtexObject>)>,
     ptr_bindings: PtrBindings,
     stream: cudarc::driver::sys::CUstream,
     mem_alignment: usize,
@@ -51,6 +62,8 @@ impl GpuStorage {
         Self {
             memory: HashMap::new(),
             deallocations: Vec::new(),
+            textures: HashMap::new(),
+            retired_textures: Vec::new(),
             ptr_bindings: PtrBindings::new(),
             stream,
             mem_alignment,
@@ -61,6 +74,20 @@ impl GpuStorage {
     ///
     /// This method processes all pending deallocations by freeing the associated GPU memory.
     fn perform_deallocations(&mut self) {
+        self.destroy_retired_textures();
+
+        let textures = self
+            .deallocations
+            .iter()
+            .filter_map(|id| self.textures.remove(id))
+            .flatten()
+            .map(|(_, _, texture)| texture)
+            .collect::<Vec<_>>();
+        if !textures.is_empty() {
+            self.retired_textures
+                .push((Fence::new(self.stream), textures));
+        }
+
         self.deallocations
             .drain(..)
             .filter_map(|id| self.memory.remove(&id))
@@ -79,6 +106,71 @@ impl GpuStorage {
                 }
             });
     }
+
+    /// The cached texture object sampling `ptr` in the allocation `id` with the layout of `meta`.
+    pub fn texture(
+        &self,
+        id: StorageId,
+        ptr: CUdeviceptr,
+        meta: &TextureMeta,
+    ) -> Option<CUtexObject> {
+        self.textures
+            .get(&id)?
+            .iter()
+            .find(|(texture_ptr, texture_meta, _)| *texture_ptr == ptr && texture_meta == meta)
+            .map(|(_, _, texture)| *texture)
+    }
+
+    /// Cache a texture object sampling the allocation `id`, which is destroyed when the
+    /// allocation is deallocated.
+    pub fn register_texture(
+        &mut self,
+        id: StorageId,
+        ptr: CUdeviceptr,
+        meta: TextureMeta,
+        texture: CUtexObject,
+    ) {
+        self.textures
+            .entry(id)
+            .or_default()
+            .push((ptr, meta, texture));
+    }
+
+    /// Destroys the retired texture objects that can't be sampled by a running kernel anymore.
+    fn destroy_retired_textures(&mut self) {
+        let (reached, pending) = core::mem::take(&mut self.retired_textures)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(fence, _)| fence.is_reached());
+        self.retired_textures = pending;
+
+        for (fence, textures) in reached {
+            let _ = fence.wait_sync();
+            destroy_textures(&textures);
+        }
+    }
+}
+
+impl Drop for GpuStorage {
+    fn drop(&mut self) {
+        for (fence, textures) in self.retired_textures.drain(..) {
+            let _ = fence.wait_sync();
+            destroy_textures(&textures);
+        }
+        for textures in self.textures.values() {
+            let textures = textures.iter().map(|(_, _, texture)| *texture);
+            destroy_textures(&textures.collect::<Vec<_>>());
+        }
+    }
+}
+
+fn destroy_textures(textures: &[CUtexObject]) {
+    for texture in textures {
+        // SAFETY: The texture objects were created on the context of the storage, and aren't
+        // sampled by any kernel anymore.
+        unsafe {
+            let _ = cudarc::driver::sys::cuTexObjectDestroy(*texture);
+        }
+    }
 }
 
 // SAFETY: `GpuResource` contains CUDA device pointers that are safe to send between
@@ -162,11 +254,26 @@ impl ComputeStorage for GpuStorage {
         )
     }
 
+    fn address(&mut self, handle: &StorageHandle) -> Option<u64> {
+        self.memory.get(&handle.id).map(|(ptr, _)| *ptr)
+    }
+
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", skip(self, size))
     )]
     fn alloc(&mut self, size: u64) -> Result<StorageHandle, IoError> {
+        // Memory allocated during a capture belongs to the graph, so it can't be pooled.
+        if is_capturing(self.stream) {
+            return Err(IoError::Unknown {
+                description: format!(
+                    "Can't allocate {size} bytes while the stream is being captured into a graph, \
+                     run the workload once before capturing to warm up the memory pools"
+                ),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
         let id = StorageId::new();
         // SAFETY: Calling CUDA driver FFI to allocate device memory. First tries async
         // allocation on the stream; falls back to synchronous allocation if that fails.
@@ -207,6 +314,10 @@ impl ComputeStorage for GpuStorage {
 
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn flush(&mut self) {
-        self.perform_deallocations();
+        // Freeing during a capture would record the free in the graph and replay it on every
+        // launch, so the deallocations wait for the capture to end.
+        if !is_capturing(self.stream) {
+            self.perform_deallocations();
+        }
     }
 }
@@ -5,6 +5,7 @@ use crate::compute::{
     },
     sync::Fence,
 };
+use cubecl_common::bytes::Bytes;
 use cubecl_core::{
     MemoryConfiguration,
     ir::MemoryDeviceProperties,
@@ -18,7 +19,10 @@ use cubecl_runtime::{
     },
     stream::EventStreamBackend,
 };
-use std::{mem::MaybeUninit, sync::Arc};
+use cudarc::driver::sys::{
+    self, CUgraph, CUstream, CUstreamCaptureStatus, CUuserObject_flags, CUuserObjectRetain_flags,
+};
+use std::{ffi::c_void, mem::MaybeUninit, sync::Arc};
 
 #[derive(Debug)]
 pub struct Stream {
@@ -29,6 +33,109 @@ pub struct Stream {
     pub drop_queue: drop_queue::PendingDropQueue<Fence>,
 }
 
+impl Stream {
+    /// Flush the drop queue of the stream.
+    ///
+    /// Skipped while the stream is captured, since flushing waits on a previous fence, which
+    /// isn't allowed during a capture. The staged bytes are released on the next flush instead.
+    pub fn flush_drop_queue(&mut self) {
+        if !is_capturing(self.sys) {
+            self.drop_queue.flush(|| Fence::new(self.sys));
+        }
+    }
+
+    /// Keep the host bytes read by a copy enqueued on the stream alive until the copy completes.
+    ///
+    /// A copy recorded while the stream is captured runs again on every launch of the graph, long
+    /// after any fence of the drop queue, so its bytes are owned by the graph instead.
+    pub fn retain(&mut self, data: Bytes) {
+        match capturing_graph(self.sys) {
+            Some(graph) => retain_in_graph(graph, data),
+            None => self.drop_queue.push(data),
+        }
+    }
+}
+
+/// The graph the given stream is being captured into, if any.
+pub(crate) fn capturing_graph(stream: CUstream) -> Option<CUgraph> {
+    // SAFETY: `stream` is a valid CUDA stream, the optional outputs are null, and the status and
+    // graph are only read after the query succeeded.
+    unsafe {
+        let mut status = MaybeUninit::uninit();
+        let mut graph = core::ptr::null_mut();
+        sys::cuStreamGetCaptureInfo_v2(
+            stream,
+            status.as_mut_ptr(),
+            core::ptr::null_mut(),
+            &mut graph,
+            core::ptr::null_mut(),
+            core::ptr::null_mut(),
+        )
+        .result()
+        .ok()?;
+
+        let active = status.assume_init() == CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_ACTIVE;
+        (active && !graph.is_null()).then_some(graph)
+    }
+}
+
+/// Hand the value to the graph as a user object, so it is dropped once the graph and all its
+/// executable instances are destroyed.
+///
+/// If the graph can't take it, the value is leaked, since the graph may still read it.
+pub(crate) fn retain_in_graph<T: Send + 'static>(graph: CUgraph, data: T) {
+    /// Called by the driver on one of its threads, which must not call into CUDA. Dropping
+    /// staged bytes only returns their pinned buffer to the pool.
+    unsafe extern "C" fn release<T>(data: *mut c_void) {
+        // SAFETY: `data` is the box leaked below, released once when the last reference to the
+        // user object is dropped.
+        drop(unsafe { Box::from_raw(data.cast::<T>()) });
+    }
+
+    let data = Box::into_raw(Box::new(data));
+
+    // SAFETY: `data` stays valid until `release` runs, and the reference created with the object
+    // is moved to the graph, so the graph holds the only one.
+    unsafe {
+        let mut object = MaybeUninit::uninit();
+        let created = sys::cuUserObjectCreate(
+            object.as_mut_ptr(),
+            data.cast(),
+            Some(release::<T>),
+            1,
+            CUuserObject_flags::CU_USER_OBJECT_NO_DESTRUCTOR_SYNC as u32,
+        );
+        if created.result().is_err() {
+            return;
+        }
+
+        let _ = sys::cuGraphRetainUserObject(
+            graph,
+            object.assume_init(),
+            1,
+            CUuserObjectRetain_flags::CU_GRAPH_USER_OBJECT_MOVE as u32,
+        )
+        .result();
+    }
+}
+
+/// Whether the given stream is currently being captured into a CUDA graph, e.g. inside a
+/// `torch.cuda.graph` region when the stream is shared with the host application.
+///
+/// Synchronizations and allocations are illegal on a captured stream and would invalidate the
+/// whole capture, so the server checks this before doing any of them.
+pub(crate) fn is_capturing(stream: CUstream) -> bool {
+    // SAFETY: `stream` is a valid CUDA stream and `cuStreamIsCapturing` writes the capture
+    // status through the out pointer on success; we only read it after checking the result.
+    unsafe {
+        let mut status = MaybeUninit::uninit();
+        match sys::cuStreamIsCapturing(stream, status.as_mut_ptr()).result() {
+            Ok(()) => status.assume_init() != CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_NONE,
+            Err(_) => false,
+        }
+    }
+}
+
 impl drop_queue::Fence for Fence {
     fn sync(self) {
         let _ = self.wait_sync().ok();
@@ -42,6 +149,8 @@ pub struct CudaStreamBackend {
     mem_alignment: usize,
     logger: Arc<ServerLogger>,
     priority: StreamPriority,
+    /// Stream provided by the host application, shared by every `CubeCL` stream when set.
+    external: Option<cudarc::driver::sys::CUstream>,
 }
 
 /// Create a non-blocking CUDA stream, applying the requested priority hint.
@@ -103,7 +212,10 @@ impl EventStreamBackend for CudaStreamBackend {
     type Event = Fence;
 
     fn create_stream(&self) -> Self::Stream {
-        let stream = create_cuda_stream(self.priority);
+        let stream = match self.external {
+            Some(stream) => stream,
+            None => create_cuda_stream(self.priority),
+        };
 
         let storage = GpuStorage::new(self.mem_alignment, stream);
         let memory_management_gpu = MemoryManagement::from_configuration(
@@ -116,7 +228,7 @@ impl EventStreamBackend for CudaStreamBackend {
         // We use the same page size and memory pools configuration for CPU pinned memory, since we
         // expect the CPU to have at least the same amount of RAM as GPU memory.
         let memory_management_cpu = MemoryManagement::from_configuration(
-            PinnedMemoryStorage::new(),
+            PinnedMemoryStorage::new(stream),
             &MemoryDeviceProperties {
                 max_page_size: self.mem_props.max_page_size,
                 alignment: PINNED_MEMORY_ALIGNMENT as u64,
@@ -158,3 +270,82 @@ impl EventStreamBackend for CudaStreamBackend {
         stream.errors.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        time::{Duration, Instant},
+    };
+
+    /// Records when the value retained by a graph is dropped.
+    struct Released(Arc<AtomicBool>);
+
+    impl Drop for Released {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+
+    #[test_log::test]
+    fn graphs_retain_captured_values() {
+        use cudarc::driver::{result, sys::CUstreamCaptureMode};
+
+        result::init().unwrap();
+        let device = result::device::get(0).unwrap();
+        let released = Arc::new(AtomicBool::new(false));
+
+        // SAFETY: Every handle is created in the retained primary context, checked before use,
+        // and destroyed before the context is released.
+        unsafe {
+            let ctx = result::primary_ctx::retain(device).unwrap();
+            result::ctx::set_current(ctx).unwrap();
+            let stream = create_cuda_stream(StreamPriority::Default);
+            assert!(capturing_graph(stream).is_none());
+
+            sys::cuStreamBeginCapture_v2(
+                stream,
+                CUstreamCaptureMode::CU_STREAM_CAPTURE_MODE_RELAXED,
+            )
+            .result()
+            .unwrap();
+            assert!(is_capturing(stream));
+            let graph = capturing_graph(stream).unwrap();
+            retain_in_graph(graph, Released(released.clone()));
+
+            let mut captured = MaybeUninit::uninit();
+            sys::cuStreamEndCapture(stream, captured.as_mut_ptr())
+                .result()
+                .unwrap();
+            let captured = captured.assume_init();
+            assert_eq!(captured, graph);
+
+            let mut exec = MaybeUninit::uninit();
+            sys::cuGraphInstantiateWithFlags(exec.as_mut_ptr(), captured, 0)
+                .result()
+                .unwrap();
+            let exec = exec.assume_init();
+            sys::cuGraphDestroy(captured).result().unwrap();
+
+            // The executable instance keeps the value alive across replays.
+            for _ in 0..2 {
+                sys::cuGraphLaunch(exec, stream).result().unwrap();
+                sys::cuStreamSynchronize(stream).result().unwrap();
+                assert!(!released.load(Ordering::Acquire));
+            }
+
+            sys::cuGraphExecDestroy(exec).result().unwrap();
+            sys::cuCtxSynchronize().result().unwrap();
+            sys::cuStreamDestroy_v2(stream).result().unwrap();
+            result::primary_ctx::release(device).unwrap();
+        }
+
+        // The driver drops the value asynchronously once the last instance is destroyed.
+        let start = Instant::now();
+        while !released.load(Ordering::Acquire) {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
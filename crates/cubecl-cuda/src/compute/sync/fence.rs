@@ -1,3 +1,4 @@
+use crate::compute::stream::is_capturing;
 use cubecl_common::backtrace::BackTrace;
 use cubecl_core::server::ServerError;
 use cudarc::driver::sys::{CUevent_flags, CUevent_st, CUevent_wait_flags, CUstream_st};
@@ -10,6 +11,8 @@ use cudarc::driver::sys::{CUevent_flags, CUevent_st, CUevent_wait_flags, CUstrea
 #[derive(Debug)]
 pub struct Fence {
     event: *mut CUevent_st,
+    /// Recorded on a stream being captured into a CUDA graph, which can't be waited on.
+    captured: bool,
 }
 
 // If we don't close the stream or destroy the event, it is safe.
@@ -28,6 +31,8 @@ impl Fence {
     ///
     /// The [stream](CUevent_st) must be initialized.
     pub fn new(stream: *mut CUstream_st) -> Self {
+        let captured = is_capturing(stream);
+
         // SAFETY: `stream` must be a valid, initialized CUDA stream (enforced by the doc
         // contract). The event is created and immediately recorded on the stream.
         unsafe {
@@ -35,13 +40,26 @@ impl Fence {
                 cudarc::driver::result::event::create(CUevent_flags::CU_EVENT_DEFAULT).unwrap();
             cudarc::driver::result::event::record(event, stream).unwrap();
 
-            Self { event }
+            Self { event, captured }
         }
     }
 
     /// Wait for the [Fence] to be reached, ensuring that all previous tasks enqueued to the
     /// [stream](CUstream_st) are completed.
     pub fn wait_sync(self) -> Result<(), ServerError> {
+        if self.captured {
+            // SAFETY: `self.event` is a valid event created in `Fence::new`, destroying it
+            // doesn't synchronize, which is allowed during a capture.
+            unsafe {
+                let _ = cudarc::driver::result::event::destroy(self.event);
+            }
+            return Err(ServerError::Generic {
+                reason: "Can't synchronize a CUDA stream while it is being captured into a graph"
+                    .into(),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
         // SAFETY: `self.event` is a valid event created in `Fence::new`. We synchronize
         // (block) until the event completes, then destroy it. `self` is consumed so the
         // event cannot be double-freed.
@@ -63,6 +81,13 @@ impl Fence {
         Ok(())
     }
 
+    /// Whether the [Fence] was reached, without blocking.
+    pub fn is_reached(&self) -> bool {
+        // SAFETY: `self.event` is a valid event created in `Fence::new`.
+        let result = unsafe { cudarc::driver::sys::cuEventQuery(self.event) };
+        result == cudarc::driver::sys::CUresult::CUDA_SUCCESS
+    }
+
     /// Wait for the [Fence] to be reached, ensuring that all previous tasks enqueued to the
     /// [stream](CUstream_st) are completed on the [original stream](CUstream_st) before new tasks
     /// are registered on the [provided stream](CUstream_st).
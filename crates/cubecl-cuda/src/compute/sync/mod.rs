@@ -1,3 +1,5 @@
 mod fence;
+mod semaphore;
 
 pub use fence::*;
+pub use semaphore::*;
@@ -0,0 +1,135 @@
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::server::{ExternalSemaphore, ExternalSemaphoreHandle, ServerError};
+use cudarc::driver::sys::{
+    CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC, CUDA_EXTERNAL_SEMAPHORE_SIGNAL_PARAMS,
+    CUDA_EXTERNAL_SEMAPHORE_WAIT_PARAMS, CUexternalSemaphore, CUexternalSemaphoreHandleType,
+    CUstream_st, cuDestroyExternalSemaphore, cuImportExternalSemaphore,
+    cuSignalExternalSemaphoresAsync, cuWaitExternalSemaphoresAsync,
+};
+use std::{collections::HashMap, ffi::c_void, mem::MaybeUninit};
+
+/// Timeline semaphores imported from other APIs, signaled and waited on in stream order.
+#[derive(Debug, Default)]
+pub struct ExternalSemaphores {
+    semaphores: HashMap<u64, CUexternalSemaphore>,
+    next_id: u64,
+}
+
+impl ExternalSemaphores {
+    /// Import a timeline semaphore, the context must be current.
+    pub fn import(
+        &mut self,
+        handle: ExternalSemaphoreHandle,
+    ) -> Result<ExternalSemaphore, ServerError> {
+        // SAFETY: The descriptor is a plain C struct, zero is the default for every field.
+        let mut desc: CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC = unsafe { core::mem::zeroed() };
+        match handle {
+            ExternalSemaphoreHandle::OpaqueFd(fd) => {
+                desc.type_ = CUexternalSemaphoreHandleType::CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_TIMELINE_SEMAPHORE_FD;
+                desc.handle.fd = fd;
+            }
+            ExternalSemaphoreHandle::OpaqueWin32(handle) => {
+                desc.type_ = CUexternalSemaphoreHandleType::CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_TIMELINE_SEMAPHORE_WIN32;
+                desc.handle.win32.handle = handle as *mut c_void;
+            }
+        }
+
+        let mut semaphore = MaybeUninit::uninit();
+        // SAFETY: The descriptor is fully initialized and the driver takes ownership of the file
+        // descriptor on success, as documented for `ExternalSemaphoreHandle::OpaqueFd`.
+        let semaphore = unsafe {
+            cuImportExternalSemaphore(semaphore.as_mut_ptr(), &desc)
+                .result()
+                .map_err(|err| ServerError::Generic {
+                    reason: format!("Can't import the external semaphore: {err}"),
+                    backtrace: BackTrace::capture(),
+                })?;
+            semaphore.assume_init()
+        };
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.semaphores.insert(id, semaphore);
+
+        Ok(ExternalSemaphore { id })
+    }
+
+    /// Signal `value` on the semaphore once the work enqueued on the stream is completed.
+    pub fn signal(
+        &self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream: *mut CUstream_st,
+    ) -> Result<(), ServerError> {
+        let semaphore = self.get(semaphore)?;
+        // SAFETY: The parameters are a plain C struct, zero is the default for every field.
+        let mut params: CUDA_EXTERNAL_SEMAPHORE_SIGNAL_PARAMS = unsafe { core::mem::zeroed() };
+        params.params.fence.value = value;
+
+        // SAFETY: `semaphore` was imported by this server and `stream` is owned by it.
+        unsafe {
+            cuSignalExternalSemaphoresAsync(&semaphore, &params, 1, stream)
+                .result()
+                .map_err(|err| ServerError::Generic {
+                    reason: format!("Can't signal the external semaphore: {err}"),
+                    backtrace: BackTrace::capture(),
+                })
+        }
+    }
+
+    /// Delay the work enqueued next on the stream until the semaphore reaches `value`.
+    pub fn wait(
+        &self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream: *mut CUstream_st,
+    ) -> Result<(), ServerError> {
+        let semaphore = self.get(semaphore)?;
+        // SAFETY: The parameters are a plain C struct, zero is the default for every field.
+        let mut params: CUDA_EXTERNAL_SEMAPHORE_WAIT_PARAMS = unsafe { core::mem::zeroed() };
+        params.params.fence.value = value;
+
+        // SAFETY: `semaphore` was imported by this server and `stream` is owned by it.
+        unsafe {
+            cuWaitExternalSemaphoresAsync(&semaphore, &params, 1, stream)
+                .result()
+                .map_err(|err| ServerError::Generic {
+                    reason: format!("Can't wait on the external semaphore: {err}"),
+                    backtrace: BackTrace::capture(),
+                })
+        }
+    }
+
+    /// Destroy the semaphore, the context must be current.
+    pub fn release(&mut self, semaphore: ExternalSemaphore) {
+        if let Some(semaphore) = self.semaphores.remove(&semaphore.id) {
+            // SAFETY: The semaphore was imported by this server and is removed from the map, so
+            // it can't be used again. The caller ensures no enqueued operation still uses it.
+            unsafe {
+                let _ = cuDestroyExternalSemaphore(semaphore);
+            }
+        }
+    }
+
+    fn get(&self, semaphore: ExternalSemaphore) -> Result<CUexternalSemaphore, ServerError> {
+        self.semaphores
+            .get(&semaphore.id)
+            .copied()
+            .ok_or_else(|| ServerError::Generic {
+                reason: format!("Unknown external semaphore {}", semaphore.id),
+                backtrace: BackTrace::capture(),
+            })
+    }
+}
+
+impl Drop for ExternalSemaphores {
+    fn drop(&mut self) {
+        for (_, semaphore) in self.semaphores.drain() {
+            // SAFETY: The semaphores were imported by the server, which is dropped before its
+            // context.
+            unsafe {
+                let _ = cuDestroyExternalSemaphore(semaphore);
+            }
+        }
+    }
+}
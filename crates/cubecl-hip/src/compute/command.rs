@@ -96,6 +96,18 @@ impl<'a> Command<'a> {
         Ok(handle)
     }
 
+    /// Reserve memory starting at a multiple of `alignment` bytes.
+    pub fn reserve_aligned(
+        &mut self,
+        size: u64,
+        alignment: u64,
+    ) -> Result<ManagedMemoryHandle, IoError> {
+        self.streams
+            .current()
+            .memory_management_gpu
+            .reserve_aligned(size, alignment)
+    }
+
     /// Get the stream cursor.
     pub fn cursor(&self) -> u64 {
         self.streams.cursor
@@ -382,6 +394,22 @@ impl<'a> Command<'a> {
         Box::pin(async { fence.wait_sync() })
     }
 
+    /// Maximum number of cubes of the kernel resident on one compute unit, compiling it if
+    /// needed.
+    pub fn max_active_cubes(
+        &mut self,
+        kernel_id: KernelId,
+        kernel: Box<dyn CubeTask<HipCompiler>>,
+        mode: ExecutionMode,
+        logger: Arc<ServerLogger>,
+    ) -> Result<u32, LaunchError> {
+        if !self.ctx.module_names.contains_key(&kernel_id) {
+            self.ctx.compile_kernel(&kernel_id, kernel, mode, logger)?;
+        }
+
+        self.ctx.max_active_cubes(&kernel_id)
+    }
+
     /// Executes a registered HIP kernel with the specified parameters.
     ///
     /// # Parameters
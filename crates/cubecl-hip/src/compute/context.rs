@@ -32,6 +32,8 @@ pub(crate) struct HipContext {
     pub compilation_options: CompilationOptions,
     pub properties: DeviceProperties,
     pub compilation_cache: Option<CompilationCache<StableHash, CompilationCacheEntry>>,
+    /// Kernel parameters of the last launch, kept around so launches don't allocate.
+    launch_params: Vec<cubecl_hip_sys::hipDeviceptr_t>,
 }
 
 #[derive(Debug)]
@@ -69,6 +71,7 @@ impl HipContext {
                 }
             },
             properties,
+            launch_params: Vec::new(),
         }
     }
 
@@ -330,10 +333,9 @@ impl HipContext {
         dispatch_count: (u32, u32, u32),
         resources: &[GpuResource],
     ) -> Result<(), LaunchError> {
-        let mut bindings = resources
-            .iter()
-            .map(|memory| memory.binding)
-            .collect::<Vec<_>>();
+        let bindings = &mut self.launch_params;
+        bindings.clear();
+        bindings.extend(resources.iter().map(|memory| memory.binding));
 
         let kernel = self.module_names.get(&kernel_id).unwrap();
         let cube_dim = kernel.cube_dim;
@@ -375,6 +377,40 @@ impl HipContext {
         }
     }
 
+    /// Maximum number of cubes of a compiled kernel resident on one compute unit.
+    pub fn max_active_cubes(&self, kernel_id: &KernelId) -> Result<u32, LaunchError> {
+        let kernel = self
+            .module_names
+            .get(kernel_id)
+            .ok_or_else(|| LaunchError::Unknown {
+                reason: format!("Kernel {kernel_id:?} isn't compiled"),
+                backtrace: BackTrace::capture(),
+            })?;
+        let mut cubes = 0;
+
+        // SAFETY: `kernel.func` is a valid function handle from a loaded module, and `cubes` is
+        // a valid output location.
+        let status = unsafe {
+            cubecl_hip_sys::hipModuleOccupancyMaxActiveBlocksPerMultiprocessor(
+                &mut cubes,
+                kernel.func,
+                kernel.cube_dim.num_elems() as i32,
+                kernel.shared_mem_bytes,
+            )
+        };
+
+        if status != HIP_SUCCESS {
+            return Err(LaunchError::Unknown {
+                reason: format!(
+                    "Unable to query the occupancy of kernel {kernel_id:?} with status {status:?}"
+                ),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
+        Ok(cubes as u32)
+    }
+
     fn validate_shared(&self, repr: &Option<HipComputeKernel>) -> Result<(), LaunchError> {
         let requested = repr.as_ref().map(|repr| repr.shared_memory_size());
         let max = self.properties.hardware.max_shared_memory_size;
@@ -0,0 +1,159 @@
+use core::ffi::{c_float, c_int, c_uint, c_ulonglong};
+use cubecl_hip_sys::HIP_SUCCESS;
+use libloading::Library;
+
+type RsmiStatus = c_int;
+
+const RSMI_STATUS_SUCCESS: RsmiStatus = 0;
+
+const RSMI_LIBRARIES: &[&str] = &["librocm_smi64.so.1", "librocm_smi64.so"];
+
+/// The partition bits of a rocm-smi PCI id, which aren't part of the PCI address.
+const BDF_PARTITION_MASK: u64 = 0xF << 28;
+
+type GetEnergyFn =
+    unsafe extern "C" fn(c_uint, *mut c_ulonglong, *mut c_float, *mut c_ulonglong) -> RsmiStatus;
+type ShutdownFn = unsafe extern "C" fn() -> RsmiStatus;
+
+/// Reads the energy counter of a device through rocm-smi, loaded at runtime.
+///
+/// rocm-smi may not be installed, and some devices don't expose the counter. In both cases
+/// [`EnergyCounter::new`] returns `None`.
+pub(crate) struct EnergyCounter {
+    index: c_uint,
+    get_energy: GetEnergyFn,
+    shutdown: ShutdownFn,
+    // Keeps the symbols above valid.
+    _library: Library,
+}
+
+impl core::fmt::Debug for EnergyCounter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EnergyCounter")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl EnergyCounter {
+    /// Opens the counter of the HIP device with the given ordinal.
+    ///
+    /// rocm-smi enumerates all the devices of the system, ignoring `HIP_VISIBLE_DEVICES`, so the
+    /// device is looked up by its PCI address rather than its ordinal.
+    pub(crate) fn new(device: c_int) -> Option<Self> {
+        let bdf_id = pci_bdf_id(device)?;
+
+        // SAFETY: Loading rocm-smi only runs its initialization routines, which have no
+        // preconditions.
+        let library = RSMI_LIBRARIES
+            .iter()
+            .find_map(|name| unsafe { Library::new(name) }.ok())?;
+
+        // SAFETY: The symbol types match the rocm-smi headers, and the outputs are valid
+        // locations.
+        unsafe {
+            let init = library
+                .get::<unsafe extern "C" fn(u64) -> RsmiStatus>(b"rsmi_init\0")
+                .ok()?;
+            let shutdown = *library.get::<ShutdownFn>(b"rsmi_shut_down\0").ok()?;
+            let num_devices = *library
+                .get::<unsafe extern "C" fn(*mut c_uint) -> RsmiStatus>(
+                    b"rsmi_num_monitor_devices\0",
+                )
+                .ok()?;
+            let get_pci_id = *library
+                .get::<unsafe extern "C" fn(c_uint, *mut u64) -> RsmiStatus>(
+                    b"rsmi_dev_pci_id_get\0",
+                )
+                .ok()?;
+            let get_energy = *library
+                .get::<GetEnergyFn>(b"rsmi_dev_energy_count_get\0")
+                .ok()?;
+
+            if init(0) != RSMI_STATUS_SUCCESS {
+                return None;
+            }
+
+            // From here, dropping the counter balances the initialization.
+            let mut counter = Self {
+                index: 0,
+                get_energy,
+                shutdown,
+                _library: library,
+            };
+
+            let mut count = 0;
+            if num_devices(&mut count) != RSMI_STATUS_SUCCESS {
+                return None;
+            }
+            counter.index = (0..count).find(|&index| {
+                let mut id = 0;
+                get_pci_id(index, &mut id) == RSMI_STATUS_SUCCESS
+                    && id & !BDF_PARTITION_MASK == bdf_id
+            })?;
+
+            counter.read()?;
+            Some(counter)
+        }
+    }
+
+    /// Total energy consumed by the device since the driver was loaded, in millijoules.
+    pub(crate) fn read(&self) -> Option<u64> {
+        let mut count = 0;
+        let mut resolution = 0.0;
+        let mut timestamp = 0;
+        // SAFETY: All the outputs are valid locations.
+        let status =
+            unsafe { (self.get_energy)(self.index, &mut count, &mut resolution, &mut timestamp) };
+
+        // The counter is in units of `resolution` microjoules.
+        (status == RSMI_STATUS_SUCCESS).then(|| (count as f64 * resolution as f64 / 1000.0) as u64)
+    }
+}
+
+impl Drop for EnergyCounter {
+    fn drop(&mut self) {
+        // SAFETY: rocm-smi counts its initializations, so this only releases the one done in
+        // `new`.
+        unsafe {
+            (self.shutdown)();
+        }
+    }
+}
+
+/// The PCI address of a HIP device, encoded like the ids of rocm-smi.
+fn pci_bdf_id(device: c_int) -> Option<u64> {
+    // SAFETY: `hipDeviceProp_tR0600` is a plain-old-data struct; zeroing it is valid, and it is
+    // only read after the query succeeded.
+    let props = unsafe {
+        let mut props = core::mem::zeroed::<cubecl_hip_sys::hipDeviceProp_tR0600>();
+        let status = cubecl_hip_sys::hipGetDevicePropertiesR0600(&mut props, device);
+        (status == HIP_SUCCESS).then_some(props)?
+    };
+
+    Some(bdf_id(
+        props.pciDomainID as u32,
+        props.pciBusID as u8,
+        props.pciDeviceID as u8,
+    ))
+}
+
+/// Encodes a PCI address like `rsmi_dev_pci_id_get`, with the function and partition left out
+/// since HIP exposes one device per physical function.
+fn bdf_id(domain: u32, bus: u8, device: u8) -> u64 {
+    ((domain as u64) << 32) | ((bus as u64) << 8) | (((device & 0x1F) as u64) << 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bdf_id_matches_rocm_smi_layout() {
+        // 0001:c3:1f.0, as reported by rocm-smi.
+        assert_eq!(bdf_id(1, 0xc3, 0x1f), 0x0000_0001_0000_c3f8);
+        // Partitions of the same device share its address.
+        let partitioned = bdf_id(0, 0x03, 0) | (2 << 28);
+        assert_eq!(partitioned & !BDF_PARTITION_MASK, bdf_id(0, 0x03, 0));
+    }
+}
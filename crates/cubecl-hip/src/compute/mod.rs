@@ -2,6 +2,7 @@ mod server;
 
 pub(crate) mod command;
 pub(crate) mod context;
+pub(crate) mod energy;
 pub(crate) mod fence;
 pub(crate) mod io;
 pub(crate) mod storage;
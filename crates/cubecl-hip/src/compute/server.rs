@@ -1,6 +1,9 @@
 use super::storage::gpu::{GpuResource, GpuStorage};
 use crate::{
-    compute::{command::Command, context::HipContext, fence::Fence, stream::HipStreamBackend},
+    compute::{
+        command::Command, context::HipContext, energy::EnergyCounter, fence::Fence,
+        stream::HipStreamBackend,
+    },
     runtime::HipCompiler,
 };
 use cubecl_common::{bytes::Bytes, future::DynFut, profile::ProfileDuration, stream_id::StreamId};
@@ -11,7 +14,7 @@ use cubecl_core::{
     ir::MemoryDeviceProperties,
     prelude::*,
     server::{
-        Binding, CopyDescriptor, KernelArguments, ProfileError, ProfilingToken,
+        Binding, CopyDescriptor, IoError, KernelArguments, ProfileError, ProfilingToken,
         ServerCommunication, ServerError, ServerUtilities, StreamErrorMode,
     },
 };
@@ -25,13 +28,16 @@ use cubecl_runtime::{
     storage::{ComputeStorage, ManagedResource},
     stream::MultiStream,
 };
-use std::sync::Arc;
+use std::{cell::OnceCell, sync::Arc};
 
 #[derive(Debug)]
 pub struct HipServer {
     ctx: HipContext,
     streams: MultiStream<HipStreamBackend>,
     utilities: Arc<ServerUtilities<Self>>,
+    device_index: u32,
+    /// Opened on the first query, since loading rocm-smi isn't free.
+    energy: OnceCell<Option<EnergyCounter>>,
 }
 
 // SAFETY: `HipServer` is only accessed from one thread at a time via the `DeviceHandle`
@@ -85,6 +91,29 @@ impl ComputeServer for HipServer {
         command.bind(reserved, memory);
     }
 
+    fn initialize_memory_aligned(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        size: u64,
+        alignment: u64,
+        stream_id: StreamId,
+    ) -> Result<(), IoError> {
+        let mut command = match self.command_no_inputs(
+            stream_id,
+            StreamErrorMode {
+                ignore: true,
+                flush: false,
+            },
+        ) {
+            Ok(val) => val,
+            Err(err) => unreachable!("{err}"),
+        };
+
+        let reserved = command.reserve_aligned(size, alignment)?;
+        command.bind(reserved, memory);
+        Ok(())
+    }
+
     fn read(
         &mut self,
         descriptors: Vec<CopyDescriptor>,
@@ -252,6 +281,36 @@ impl ComputeServer for HipServer {
         };
         command.allocation_mode(mode)
     }
+
+    fn max_cubes_per_unit(
+        &mut self,
+        kernel: Self::Kernel,
+        mode: ExecutionMode,
+        stream_id: StreamId,
+    ) -> Result<Option<u32>, ServerError> {
+        let mut kernel_id = kernel.id();
+        let logger = self.streams.logger.clone();
+        kernel_id.mode(mode);
+        let mut command = self.command_no_inputs(
+            stream_id,
+            StreamErrorMode {
+                ignore: true,
+                flush: false,
+            },
+        )?;
+
+        Ok(Some(
+            command.max_active_cubes(kernel_id, kernel, mode, logger)?,
+        ))
+    }
+
+    fn energy_consumption(&mut self) -> Option<u64> {
+        let index = self.device_index as i32;
+        self.energy
+            .get_or_init(|| EnergyCounter::new(index))
+            .as_ref()?
+            .read()
+    }
 }
 
 impl ServerCommunication for HipServer {
@@ -266,6 +325,7 @@ impl HipServer {
         mem_config: MemoryConfiguration,
         mem_alignment: usize,
         is_integrated: bool,
+        device_index: u32,
         utilities: ServerUtilities<Self>,
     ) -> Self {
         let config = CubeClRuntimeConfig::get();
@@ -285,6 +345,8 @@ impl HipServer {
                 max_streams,
             ),
             utilities: Arc::new(utilities),
+            device_index,
+            energy: OnceCell::new(),
         }
     }
 
@@ -388,9 +450,13 @@ impl HipServer {
             buffers,
             info,
             tensor_maps,
+            textures,
+            // HIP has no control over the persistence of L2 cache lines.
+            ..
         } = bindings;
 
         debug_assert!(tensor_maps.is_empty(), "Can't use tensor maps on HIP");
+        debug_assert!(textures.is_empty(), "Can't use textures on HIP");
 
         let info = command
             .create_with_data(bytemuck::cast_slice(&info.data))
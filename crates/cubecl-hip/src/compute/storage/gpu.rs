@@ -123,6 +123,10 @@ impl ComputeStorage for GpuStorage {
         )
     }
 
+    fn address(&mut self, handle: &StorageHandle) -> Option<u64> {
+        self.memory.get(&handle.id).map(|ptr| *ptr as u64)
+    }
+
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", skip(self, size))
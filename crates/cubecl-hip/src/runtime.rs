@@ -5,6 +5,7 @@ use crate::{
 };
 use core::ffi::c_int;
 use cubecl_common::{
+    backtrace::BackTrace,
     device::{Device, DeviceService},
     profile::TimingMethod,
 };
@@ -15,7 +16,7 @@ use cubecl_core::{
         ContiguousElements, DeviceProperties, HardwareProperties, MatrixLayout,
         MemoryDeviceProperties, MmaProperties, TargetProperties, VectorSize, features::Plane,
     },
-    server::ServerUtilities,
+    server::{ServerError, ServerUtilities},
     zspace::{Shape, Strides, striding::has_pitched_row_major_strides},
 };
 use cubecl_cpp::{
@@ -61,6 +62,8 @@ impl DeviceService for HipServer {
         #[allow(unused_assignments)]
         let mut prop_max_threads = 0;
         let mut max_cube_dim = (1, 1, 1);
+        #[allow(unused_assignments)]
+        let mut prop_num_compute_units = 0;
         let mut mem_alignment = 32;
         // SAFETY: Calling HIP FFI to query device properties. The `MaybeUninit` is
         // initialized by `hipGetDevicePropertiesR0600` on success (asserted below), so
@@ -87,6 +90,7 @@ impl DeviceService for HipServer {
             max_cube_dim.0 = ll_device_props.maxThreadsDim[0] as u32;
             max_cube_dim.1 = ll_device_props.maxThreadsDim[1] as u32;
             max_cube_dim.2 = ll_device_props.maxThreadsDim[2] as u32;
+            prop_num_compute_units = ll_device_props.multiProcessorCount as u32;
 
             // Just to be sure we check both.
             mem_alignment = usize::max(mem_alignment, ll_device_props.textureAlignment);
@@ -144,7 +148,7 @@ impl DeviceService for HipServer {
             max_cube_count,
             max_units_per_cube: prop_max_threads,
             max_cube_dim,
-            num_streaming_multiprocessors: None,
+            num_streaming_multiprocessors: Some(prop_num_compute_units),
             num_tensor_cores: None,
             min_tensor_cores_dim: if supported_wmma_combinations.is_empty() {
                 None
@@ -202,6 +206,7 @@ impl DeviceService for HipServer {
             options.memory_config,
             mem_alignment,
             is_integrated,
+            device_id.index_id as u32,
             utilities,
         )
     }
@@ -220,6 +225,18 @@ impl Runtime for HipRuntime {
         ComputeClient::load(device)
     }
 
+    fn try_client(device: &Self::Device) -> Result<ComputeClient<Self>, ServerError> {
+        let count = device_count();
+        if device.index >= count {
+            return Err(ServerError::Generic {
+                reason: format!("HIP device {} not found, {count} available", device.index),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
+        Ok(Self::client(device))
+    }
+
     fn name(_client: &ComputeClient<Self>) -> &'static str {
         "hip"
     }
@@ -255,30 +272,36 @@ impl Runtime for HipRuntime {
         }
     }
 
+    fn measure_load_width(client: &ComputeClient<Self>) -> Option<u32> {
+        cubecl_core::compute::measure_load_width(client)
+    }
+
     fn enumerate_devices(
         _: u16,
         _: &<Self::Server as cubecl_core::server::ComputeServer>::Info,
     ) -> Vec<cubecl_core::device::DeviceId> {
-        fn device_count() -> usize {
-            let mut device_count: c_int = 0;
-            let result;
-            // SAFETY: Calling HIP FFI to get the number of available devices.
-            // `device_count` is a valid mutable pointer to a stack-allocated `c_int`.
-            unsafe {
-                result = hipGetDeviceCount(&mut device_count);
-            }
-            if result == HIP_SUCCESS {
-                device_count.try_into().unwrap_or(0)
-            } else {
-                0
-            }
-        }
         (0..device_count())
             .map(|i| DeviceId::new(0, i as u16))
             .collect()
     }
 }
 
+/// The number of HIP devices available, zero if the driver can't be queried.
+fn device_count() -> usize {
+    let mut device_count: c_int = 0;
+    let result;
+    // SAFETY: Calling HIP FFI to get the number of available devices.
+    // `device_count` is a valid mutable pointer to a stack-allocated `c_int`.
+    unsafe {
+        result = hipGetDeviceCount(&mut device_count);
+    }
+    if result == HIP_SUCCESS {
+        device_count.try_into().unwrap_or(0)
+    } else {
+        0
+    }
+}
+
 /// Checks whether the GPU with the given device ID is an integrated (APU) device.
 ///
 /// # Safety
@@ -30,6 +30,7 @@ mod runtime_properties;
 mod scope;
 mod synchronization;
 mod tensor_indexing;
+mod texture;
 mod tma;
 mod r#type;
 mod type_hash;
@@ -60,6 +61,7 @@ pub use runtime_properties::*;
 pub use scope::*;
 pub use synchronization::*;
 pub use tensor_indexing::*;
+pub use texture::*;
 pub use tma::*;
 pub use r#type::*;
 pub use variable::*;
@@ -3,7 +3,7 @@ use core::fmt::Display;
 use super::{Branch, CoopMma, NonSemantic, Plane, Synchronization, Type, Value};
 use crate::{
     AddressSpace, Arithmetic, AtomicOp, Bitwise, Id, InstructionModes, Memory, Metadata,
-    OperationArgs, OperationReflect, Operator, Scope, TensorIndexingOps, TmaOps,
+    OperationArgs, OperationReflect, Operator, Scope, TensorIndexingOps, TextureOps, TmaOps,
     comparison::Comparison, marker::Marker,
 };
 use crate::{BarrierOps, SourceLoc, TypeHash};
@@ -80,6 +80,8 @@ pub enum Operation {
     #[operation(nested)]
     Tma(TmaOps),
     #[operation(nested)]
+    Texture(TextureOps),
+    #[operation(nested)]
     TensorIndexing(TensorIndexingOps),
     /// Non-semantic instructions (i.e. comments, debug info)
     #[operation(nested)]
@@ -127,6 +129,18 @@ impl Instruction {
     pub fn ty(&self) -> Type {
         self.out().ty
     }
+
+    /// The pointers the instruction stores to, including `out` for operations storing through
+    /// their output pointer, like [`CoopMma::StoreTensor`].
+    pub fn write_pointers(&self) -> Vec<Value> {
+        let mut pointers = self.operation.write_pointers();
+        if let Operation::CoopMma(CoopMma::StoreTensor { .. }) = &self.operation
+            && let Some(out) = self.out
+        {
+            pointers.push(out);
+        }
+        pointers
+    }
 }
 
 impl Display for Instruction {
@@ -205,6 +219,7 @@ impl Display for Operation {
             Operation::NonSemantic(non_semantic) => write!(f, "{non_semantic}"),
             Operation::Barrier(barrier_ops) => write!(f, "{barrier_ops}"),
             Operation::Tma(tma_ops) => write!(f, "{tma_ops}"),
+            Operation::Texture(texture_ops) => write!(f, "{texture_ops}"),
             Operation::TensorIndexing(ops) => write!(f, "{ops}"),
             Operation::Marker(marker) => write!(f, "{marker}"),
         }
@@ -34,6 +34,9 @@ pub enum Operator {
     ReadBuiltin(Builtin),
     #[operation(pure)]
     ReadScalar(Id),
+    /// Read the scalar at a dynamic offset from the scalar `id`, used for arrays of scalars.
+    #[operation(pure)]
+    ReadScalarArray(ReadScalarArrayOperands),
 }
 
 impl Display for Operator {
@@ -61,6 +64,9 @@ impl Display for Operator {
             Operator::Reinterpret(op) => write!(f, "reinterpret({})", op.input),
             Operator::ReadBuiltin(builtin) => write!(f, "read_builtin({builtin:?})"),
             Operator::ReadScalar(id) => write!(f, "read_scalar({id})"),
+            Operator::ReadScalarArray(op) => {
+                write!(f, "read_scalar_array({}, {})", op.id, op.index)
+            }
         }
     }
 }
@@ -109,6 +115,14 @@ pub struct CopyMemoryOperands {
     pub len: usize,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeHash, PartialEq, Eq, Hash, OperationArgs)]
+#[allow(missing_docs)]
+pub struct ReadScalarArrayOperands {
+    pub id: Id,
+    pub index: Value,
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, TypeHash, PartialEq, Eq, Hash, OperationArgs)]
 #[allow(missing_docs)]
@@ -11,9 +11,9 @@ use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
 
 use crate::{
-    AddressSpace, AggregateExtractOperands, CubeFnSource, DeviceProperties, FastMath, Function,
-    OpaqueType, Operation, OperationReflect, Processor, SourceLoc, StorageType, TargetProperties,
-    TypeHash, arena::DropBump,
+    AddressSpace, AggregateExtractOperands, Branch, CubeFnSource, DeviceProperties, FastMath,
+    Function, OpaqueType, Operation, OperationReflect, Processor, SourceLoc, StorageType,
+    TargetProperties, TypeHash, arena::DropBump,
 };
 
 use super::{Allocator, Id, Instruction, Type, Value, processing::ScopeProcessing};
@@ -45,19 +45,28 @@ pub struct Scope {
 
 pub type GlobalState = Rc<RefCell<GlobalStateInner>>;
 
+/// State shared by all the scopes of a kernel.
+///
+/// Only the parts needed to lower the kernel are serialized, the type maps are only used during
+/// expansion and the device properties are set by the compiler of the target device.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, TypeHash, Default)]
 pub struct GlobalStateInner {
     #[partial_eq(skip)]
     #[eq(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub reference_arena: DropBump,
     pub allocator: Allocator,
 
     pub global_args: Vec<Value>,
     pub functions: BTreeMap<Id, Function>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub typemap: TypeMap,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub sizemap: SizeMap,
     pub modes: InstructionModes,
     pub target_properties: TargetProperties,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub device_properties: Option<Rc<DeviceProperties>>,
 }
 
@@ -120,9 +129,13 @@ pub enum ReadingStrategy {
 }
 
 impl Scope {
-    /// Set the device properties.
+    /// Set the device properties, which are only cloned when they change, since they're set on
+    /// every launch.
     pub fn device_properties(&self, properties: &DeviceProperties) {
-        self.state_mut().device_properties = Some(Rc::new(properties.clone()));
+        let mut state = self.state_mut();
+        if state.device_properties.as_deref() != Some(properties) {
+            state.device_properties = Some(Rc::new(properties.clone()));
+        }
     }
 
     pub fn state(&self) -> Ref<'_, GlobalStateInner> {
@@ -162,6 +175,45 @@ impl Scope {
         self
     }
 
+    /// Share `global_state` with this scope, every scope nested in it and the scopes of the
+    /// functions in the state. Needed after deserialization, where each scope gets its own empty
+    /// state.
+    pub fn attach_global_state(&mut self, global_state: GlobalState) {
+        let mut functions = core::mem::take(&mut global_state.borrow_mut().functions);
+        for function in functions.values_mut() {
+            function.scope.link_global_state(&global_state);
+        }
+        global_state.borrow_mut().functions = functions;
+
+        self.link_global_state(&global_state);
+    }
+
+    fn link_global_state(&mut self, global_state: &GlobalState) {
+        self.global_state = global_state.clone();
+
+        for inst in self.instructions.get_mut() {
+            let Operation::Branch(branch) = &mut inst.operation else {
+                continue;
+            };
+            match branch {
+                Branch::If(op) => op.scope.link_global_state(global_state),
+                Branch::IfElse(op) => {
+                    op.scope_if.link_global_state(global_state);
+                    op.scope_else.link_global_state(global_state);
+                }
+                Branch::Switch(op) => {
+                    for (_, case) in &mut op.cases {
+                        case.link_global_state(global_state);
+                    }
+                    op.scope_default.link_global_state(global_state);
+                }
+                Branch::RangeLoop(op) => op.scope.link_global_state(global_state),
+                Branch::Loop(op) => op.scope.link_global_state(global_state),
+                Branch::Return | Branch::Break | Branch::Unreachable => {}
+            }
+        }
+    }
+
     /// Create a new immutable value of type specified by `ty`.
     pub fn create_value(&self, ty: Type) -> Value {
         let id = self.new_local_index();
@@ -339,6 +391,14 @@ impl Scope {
         value
     }
 
+    /// Obtain the index-th texture
+    pub fn texture(&self, id: Id) -> Value {
+        let ty = Type::Opaque(OpaqueType::Texture);
+        let value = self.create_value(ty);
+        self.state_mut().global_args.insert(id as usize, value);
+        value
+    }
+
     pub fn update_source(&self, source: CubeFnSource) {
         if self.debug.enabled {
             self.debug.sources.borrow_mut().insert(source.clone());
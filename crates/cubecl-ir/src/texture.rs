@@ -0,0 +1,26 @@
+use crate::TypeHash;
+use core::fmt::Display;
+
+use crate::OperationReflect;
+
+use super::Value;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeHash, PartialEq, Eq, Hash, OperationReflect)]
+#[operation(opcode_name = TextureOpCode)]
+/// Operations available on a read-only texture
+pub enum TextureOps {
+    /// Sample a 2D texture at the floating point coordinates `x` and `y`. The filtering and
+    /// addressing of the sample are properties of the texture itself.
+    Sample2d { texture: Value, x: Value, y: Value },
+}
+
+impl Display for TextureOps {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TextureOps::Sample2d { texture, x, y } => {
+                write!(f, "texture_sample_2d({texture}, {x}, {y})")
+            }
+        }
+    }
+}
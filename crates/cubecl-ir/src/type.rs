@@ -74,6 +74,7 @@ pub enum OpaqueType {
     Barrier(BarrierLevel),
     BarrierToken(BarrierLevel),
     TensorMap,
+    Texture,
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -335,6 +336,7 @@ impl OpaqueType {
             OpaqueType::Barrier(_) => 8,
             OpaqueType::BarrierToken(_) => 8,
             OpaqueType::TensorMap => 128,
+            OpaqueType::Texture => 8,
         }
     }
 
@@ -850,7 +852,7 @@ impl Type {
             | Type::Aggregate(..) => false,
             Type::Opaque(opaque) => match opaque {
                 // Can only exist in memory
-                OpaqueType::Barrier(..) | OpaqueType::TensorMap => false,
+                OpaqueType::Barrier(..) | OpaqueType::TensorMap | OpaqueType::Texture => false,
                 OpaqueType::BarrierToken(..) => true,
             },
         }
@@ -946,6 +948,7 @@ impl Display for OpaqueType {
             OpaqueType::Barrier(level) => write!(f, "barrier<{level}>"),
             OpaqueType::BarrierToken(level) => write!(f, "barrier_token<{level}>"),
             OpaqueType::TensorMap => f.write_str("tensor_map"),
+            OpaqueType::Texture => f.write_str("texture"),
         }
     }
 }
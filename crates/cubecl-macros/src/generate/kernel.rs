@@ -4,7 +4,7 @@ use darling::usage::{CollectLifetimes as _, CollectTypeParams as _, GenericsExt
 use inflections::case::to_snake_case;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, quote_spanned};
-use syn::{Ident, TypeParamBound, parse_quote};
+use syn::{Ident, Type, TypeParamBound, parse_quote};
 
 use crate::{
     parse::{
@@ -176,13 +176,22 @@ impl Launch {
         let launch_arg = prelude_type("LaunchArg");
         let mut define = quote! {};
 
-        let expand_fn = |ident, ty| {
+        let expand_fn = |ident, ty: Type| {
+            let read_only =
+                matches!(&ty, Type::Reference(reference) if reference.mutability.is_none());
             let ty = self.func.analysis.process_ty(&ty);
             let ty = strip_ref(ty);
             let ty = anon_lifetime_to_static(ty);
 
-            quote! {
-                let mut #ident = <#ty as #launch_arg>::expand(&self.#ident.dynamic_cast(), &mut builder);
+            match read_only {
+                true => quote! {
+                    let mut #ident = builder.read_only(|builder| {
+                        <#ty as #launch_arg>::expand(&self.#ident.dynamic_cast(), builder)
+                    });
+                },
+                false => quote! {
+                    let mut #ident = <#ty as #launch_arg>::expand(&self.#ident.dynamic_cast(), &mut builder);
+                },
             }
         };
         for param in self.runtime_params() {
@@ -196,6 +205,7 @@ impl Launch {
 
     fn define_body(&self) -> TokenStream {
         let kernel_builder = prelude_type("KernelBuilder");
+        let kernel_metadata = prelude_type("KernelMetadata");
         let io_map = self.io_mappings();
         let mut mapping = HashMap::new();
         for param in self.func.sig.parameters.iter() {
@@ -235,6 +245,7 @@ impl Launch {
 
         quote! {
             let mut builder = #kernel_builder::default();
+            builder.kernel_id(#kernel_metadata::id(self));
             builder.runtime_properties(__R::target_properties());
             builder.device_properties(self.client.properties());
 
@@ -425,6 +436,7 @@ impl Launch {
                         #kernel_id::new::<Self>()
                             .address_type(address_type)
                             .cube_dim(self.settings.cube_dim.clone())
+                            .instrumentation(self.settings.instrumentation)
                             .info(#info_ty_name #info_generics {
                                 #(#info_names: self.#info_names.clone(),)*
                                 #phantom_data_init
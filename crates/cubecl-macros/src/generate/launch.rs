@@ -150,11 +150,18 @@ impl Launch {
         let kernel_generics = kernel_generics.1.as_turbofish();
         let comptime_args = self.comptime_params().map(|it| &it.name);
         let (registers, args) = self.arg_registers();
+        let specialize_shapes = self
+            .args
+            .specialize_shapes
+            .is_present()
+            .then(|| quote![launcher.specialize_shapes();]);
 
         quote! {
             #settings
 
             let mut launcher = #kernel_launcher::<__R>::new(__settings.clone());
+            launcher.specialize_for::<#kernel_name #kernel_generics>();
+            #specialize_shapes
             launcher.with_scope(|scope| {
                 scope.device_properties(__client.properties());
                 #generic_registers
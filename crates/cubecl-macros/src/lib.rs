@@ -36,6 +36,8 @@ mod statement;
 /// # Arguments
 /// * `launch` - generates a function to launch the kernel
 /// * `launch_unchecked` - generates a launch function without checks
+/// * `specialize_shapes` - folds the shapes of the tensor arguments into the kernel once they
+///   repeat, see `KernelLauncher::specialize_shapes`
 /// * `debug` - panics after generation to print the output to console
 /// * `create_dummy_kernel` - Generates a function to create a kernel without launching it. Used for
 ///   testing.
@@ -38,6 +38,8 @@ pub(crate) struct KernelArgs {
     pub explicit_define: Flag,
     #[darling(default)]
     pub address_type: AddressType,
+    /// Fold repeated tensor shapes into the kernel
+    pub specialize_shapes: Flag,
 }
 
 #[derive(Default, FromMeta, PartialEq, Eq, Clone, Copy)]
@@ -217,6 +217,30 @@ impl ComputeServer for MetalServer {
             .expect("Failed to bind memory");
     }
 
+    fn create_from_host(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        data: Bytes,
+        stream_id: StreamId,
+    ) -> Result<u64, Bytes> {
+        let mut resolved = self
+            .streams
+            .resolve(stream_id, std::iter::empty(), false)
+            .expect("Failed to resolve stream for create_from_host");
+        let cursor = resolved.cursor;
+        let stream = resolved.current();
+
+        let storage = stream.memory_management.storage().wrap(data)?;
+        let alignment = stream.memory_management.storage().alignment() as u64;
+        let reserved = stream.memory_management.reserve_storage(storage);
+        stream
+            .memory_management
+            .bind(reserved, memory, cursor)
+            .expect("Failed to bind memory");
+
+        Ok(alignment)
+    }
+
     fn read(
         &mut self,
         descriptors: Vec<CopyDescriptor>,
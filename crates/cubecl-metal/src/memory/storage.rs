@@ -1,8 +1,13 @@
+use cubecl_common::bytes::{Bytes, Writer};
 use cubecl_runtime::storage::{ComputeStorage, StorageHandle, StorageId, StorageUtilization};
 use objc2::rc::Retained;
 use objc2::runtime::ProtocolObject;
 use objc2_metal::{MTLBuffer, MTLDevice, MTLResourceOptions};
 use std::collections::HashMap;
+use std::ptr::NonNull;
+
+/// The page size of Apple Silicon, which buffers wrapping host memory must be aligned to.
+const PAGE_SIZE: usize = 16384;
 
 /// Wrapper for `MTLBuffer` that is Send + Sync.
 #[derive(Debug, Clone)]
@@ -26,6 +31,8 @@ impl MetalBufferHandle {
 #[derive(Debug)]
 pub struct MetalStorage {
     buffers: HashMap<StorageId, MetalBufferHandle>,
+    /// Host memory wrapped by buffers, kept alive until the buffer is deallocated.
+    host: HashMap<StorageId, Bytes>,
     device: Retained<ProtocolObject<dyn MTLDevice>>,
 }
 
@@ -33,9 +40,51 @@ impl MetalStorage {
     pub fn new(device: Retained<ProtocolObject<dyn MTLDevice>>) -> Self {
         Self {
             buffers: HashMap::new(),
+            host: HashMap::new(),
             device,
         }
     }
+
+    /// Create a buffer using host memory without a copy, or give it back if it isn't aligned to
+    /// a page or can't be mutated in place.
+    pub fn wrap(&mut self, mut bytes: Bytes) -> Result<StorageHandle, Bytes> {
+        let size = bytes.len();
+        if size == 0 || !size.is_multiple_of(PAGE_SIZE) {
+            return Err(bytes);
+        }
+        let ptr = match bytes.write(Writer::new().no_copy()) {
+            Ok(memory) if memory.as_ptr().addr().is_multiple_of(PAGE_SIZE) => {
+                NonNull::new(memory.as_mut_ptr()).unwrap()
+            }
+            _ => return Err(bytes),
+        };
+
+        // SAFETY: The memory is kept alive in `host` as long as the buffer, and isn't accessed
+        // from the host while wrapped.
+        let buffer = unsafe {
+            (*self.device).newBufferWithBytesNoCopy_length_options_deallocator(
+                ptr.cast(),
+                size,
+                MTLResourceOptions::StorageModeShared,
+                None,
+            )
+        };
+        let Some(buffer) = buffer else {
+            return Err(bytes);
+        };
+
+        let id = StorageId::new();
+        self.buffers.insert(id, MetalBufferHandle::new(buffer));
+        self.host.insert(id, bytes);
+
+        Ok(StorageHandle::new(
+            id,
+            StorageUtilization {
+                offset: 0,
+                size: size as u64,
+            },
+        ))
+    }
 }
 
 impl ComputeStorage for MetalStorage {
@@ -74,7 +123,9 @@ impl ComputeStorage for MetalStorage {
     }
 
     fn dealloc(&mut self, id: StorageId) {
+        // The buffer must be released before the host memory it wraps.
         self.buffers.remove(&id);
+        self.host.remove(&id);
     }
 
     fn flush(&mut self) {
@@ -1,5 +1,8 @@
 use crate::{MetalCompiler, MetalDevice, compute::MetalServer};
-use cubecl_common::device::{Device, DeviceService};
+use cubecl_common::{
+    backtrace::BackTrace,
+    device::{Device, DeviceService},
+};
 use cubecl_core::{
     Runtime,
     device::{DeviceId, ServerUtilitiesHandle},
@@ -8,6 +11,7 @@ use cubecl_core::{
         MemoryDeviceProperties, TargetProperties, Type, UIntKind,
         features::{AtomicUsage, Plane, TypeUsage},
     },
+    server::ServerError,
     zspace::{Shape, Strides, striding::has_pitched_row_major_strides},
 };
 use cubecl_cpp::{
@@ -126,6 +130,17 @@ impl Runtime for MetalRuntime {
         ComputeClient::load(device)
     }
 
+    fn try_client(device: &Self::Device) -> Result<ComputeClient<Self>, ServerError> {
+        if crate::device::default_device().is_none() {
+            return Err(ServerError::Generic {
+                reason: "No Metal device found".into(),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
+        Ok(Self::client(device))
+    }
+
     fn name(_client: &ComputeClient<Self>) -> &'static str {
         "metal"
     }
@@ -144,6 +159,10 @@ impl Runtime for MetalRuntime {
         }
     }
 
+    fn measure_load_width(client: &ComputeClient<Self>) -> Option<u32> {
+        cubecl_core::compute::measure_load_width(client)
+    }
+
     fn enumerate_devices(
         type_id: u16,
         _info: &<Self::Server as cubecl_core::server::ComputeServer>::Info,
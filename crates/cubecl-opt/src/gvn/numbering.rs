@@ -145,6 +145,7 @@ impl ValueTable {
             | Operation::NonSemantic(_)
             | Operation::Barrier(_)
             | Operation::Tma(_)
+            | Operation::Texture(_)
             | Operation::TensorIndexing(_)
             | Operation::Marker(_) => Err(None),
             Operation::ConstructAggregate(..) | Operation::ExtractAggregateField(..) => {
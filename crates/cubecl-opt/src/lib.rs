@@ -552,11 +552,25 @@ impl Function {
                 }
             },
             |_, val| {
-                if let Some(id) = global_buffer_id(val) {
+                // Pointers into a buffer are only addresses, writes through them are tracked
+                // below.
+                if !val.ty.is_ptr()
+                    && let Some(id) = global_buffer_id(val)
+                {
                     state.set_buffer_writable(id);
                 }
             },
         );
+
+        for node in self.node_indices().collect::<Vec<_>>() {
+            for inst in self[node].ops.borrow().values() {
+                for ptr in inst.write_pointers() {
+                    if let Some(id) = global_buffer_id(&ptr) {
+                        state.set_buffer_writable(id);
+                    }
+                }
+            }
+        }
     }
 
     fn apply_post_ssa_passes(&mut self, state: &GlobalState) {
@@ -0,0 +1,261 @@
+#![warn(missing_docs)]
+
+//! Python bindings to inspect and drive a `CubeCL` runtime from notebooks.
+//!
+//! See [`add_runtime`] to expose a runtime and its registered kernels in a Python module.
+
+use cubecl_common::device::{Device, DeviceId};
+use cubecl_runtime::{
+    client::ComputeClient,
+    registry::{KernelRegistry, KernelRegistryError},
+    runtime::Runtime,
+    server::{CubeCount, KernelArguments, MetadataBindingInfo},
+    tune::{autotune_snapshot, clear_autotune_caches, resume_tuning},
+};
+use numpy::{
+    PyArrayDescrMethods, PyUntypedArray, PyUntypedArrayMethods, npyffi::flags::NPY_ARRAY_WRITEABLE,
+};
+use pyo3::{
+    exceptions::{PyKeyError, PyRuntimeError, PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyCFunction, PyDict, PyTuple},
+};
+
+/// Add the functions inspecting and driving the runtime `R` to the given module, launching the
+/// kernels of the given registry.
+///
+/// Devices are selected with the `type_id` and `index_id` of their [id](DeviceId), both
+/// defaulting to zero.
+pub fn add_runtime<R: Runtime>(
+    module: &Bound<'_, PyModule>,
+    registry: &'static KernelRegistry<R>,
+) -> PyResult<()> {
+    let py = module.py();
+
+    module.add_function(PyCFunction::new_closure(
+        py,
+        Some(c"devices"),
+        Some(
+            c"devices(type_id=0)\n--\n\nThe (type_id, index_id) of every device of the given type.",
+        ),
+        |args, kwargs| -> PyResult<Vec<(u16, u16)>> {
+            let type_id = argument(args, kwargs, 0, "type_id")?.unwrap_or(0);
+            let client = R::client(&R::Device::default());
+
+            Ok(client
+                .enumerate_devices(type_id)
+                .into_iter()
+                .map(|id| (id.type_id, id.index_id))
+                .collect())
+        },
+    )?)?;
+
+    module.add_function(PyCFunction::new_closure(
+        py,
+        Some(c"memory_usage"),
+        Some(c"memory_usage(type_id=0, index_id=0)\n--\n\nThe memory statistics of a device."),
+        |args, kwargs| -> PyResult<Py<PyDict>> {
+            let client = client::<R>(args, kwargs, 0)?;
+            let usage = client
+                .memory_usage()
+                .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+
+            let dict = PyDict::new(args.py());
+            dict.set_item("number_allocs", usage.number_allocs)?;
+            dict.set_item("bytes_in_use", usage.bytes_in_use)?;
+            dict.set_item("bytes_padding", usage.bytes_padding)?;
+            dict.set_item("bytes_reserved", usage.bytes_reserved)?;
+            Ok(dict.unbind())
+        },
+    )?)?;
+
+    module.add_function(PyCFunction::new_closure(
+        py,
+        Some(c"autotune_cache"),
+        Some(c"autotune_cache()\n--\n\nThe results cached by every tuner of the process."),
+        |args, _kwargs| -> PyResult<Vec<Py<PyDict>>> {
+            autotune_snapshot()
+                .into_iter()
+                .map(|tuner| {
+                    let entries = tuner
+                        .entries
+                        .into_iter()
+                        .map(|entry| (entry.key, entry.fastest_index))
+                        .collect::<Vec<_>>();
+
+                    let dict = PyDict::new(args.py());
+                    dict.set_item("name", tuner.name)?;
+                    dict.set_item("device_id", tuner.device_id)?;
+                    dict.set_item("entries", entries)?;
+                    Ok(dict.unbind())
+                })
+                .collect()
+        },
+    )?)?;
+
+    module.add_function(PyCFunction::new_closure(
+        py,
+        Some(c"clear_autotune_cache"),
+        Some(c"clear_autotune_cache()\n--\n\nForget the cached results, so the next executions are tuned again."),
+        |_args, _kwargs| clear_autotune_caches(),
+    )?)?;
+
+    module.add_function(PyCFunction::new_closure(
+        py,
+        Some(c"resume_tuning"),
+        Some(c"resume_tuning()\n--\n\nContinue the searches interrupted by their time budget on the next execution of their key, returning their number."),
+        |_args, _kwargs| resume_tuning(),
+    )?)?;
+
+    module.add_function(PyCFunction::new_closure(
+        py,
+        Some(c"kernels"),
+        Some(c"kernels()\n--\n\nThe names of the registered kernels."),
+        |_args, _kwargs| registry.names(),
+    )?)?;
+
+    module.add_function(PyCFunction::new_closure(
+        py,
+        Some(c"launch"),
+        Some(c"launch(name, cube_count, arrays, info=None, type_id=0, index_id=0)\n--\n\nLaunch a registered kernel with the given contiguous NumPy arrays as bindings, writing the results back to the writeable arrays.\n\n`info` is the list of 64-bit words holding the scalars and metadata of the kernel, packed in the layout it was compiled with: the scalars sorted by type, then the static metadata. It is required by kernels reading a scalar, a length or a shape."),
+        |args, kwargs| -> PyResult<()> {
+            let name: String = required(args, kwargs, 0, "name")?;
+            let (x, y, z): (u32, u32, u32) = required(args, kwargs, 1, "cube_count")?;
+            let arrays: Vec<Bound<'_, PyUntypedArray>> = required(args, kwargs, 2, "arrays")?;
+            let info: Option<Vec<u64>> = argument(args, kwargs, 3, "info")?.flatten();
+            let client = client::<R>(args, kwargs, 4)?;
+
+            launch(
+                registry,
+                &client,
+                &name,
+                CubeCount::Static(x, y, z),
+                &arrays,
+                info.unwrap_or_default(),
+            )
+        },
+    )?)?;
+
+    Ok(())
+}
+
+fn launch<R: Runtime>(
+    registry: &KernelRegistry<R>,
+    client: &ComputeClient<R>,
+    name: &str,
+    count: CubeCount,
+    arrays: &[Bound<'_, PyUntypedArray>],
+    info: Vec<u64>,
+) -> PyResult<()> {
+    let mut handles = Vec::with_capacity(arrays.len());
+
+    for array in arrays {
+        if !array.is_contiguous() {
+            return Err(PyValueError::new_err("Arrays must be contiguous"));
+        }
+
+        // SAFETY: The array is contiguous, so its data spans `len * itemsize` bytes, and the GIL
+        // is held for the whole launch so it can't be resized or freed.
+        let data = unsafe {
+            let raw = *array.as_array_ptr();
+            std::slice::from_raw_parts(raw.data as *const u8, array_size(array))
+        };
+        handles.push(client.create_from_slice(data));
+    }
+
+    let bindings = handles
+        .iter()
+        .map(|handle| handle.clone().binding())
+        .collect();
+
+    registry
+        .launch(
+            client,
+            name,
+            count,
+            KernelArguments::new()
+                .with_buffers(bindings)
+                .with_info(MetadataBindingInfo::custom(info)),
+        )
+        .map_err(|err| match err {
+            KernelRegistryError::NotFound { .. } => PyKeyError::new_err(err.to_string()),
+            KernelRegistryError::AlreadyRegistered { .. } => {
+                PyRuntimeError::new_err(err.to_string())
+            }
+        })?;
+
+    // The launch is asynchronous, flushing raises the errors it recorded on the stream.
+    client
+        .flush()
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+
+    for (array, handle) in arrays.iter().zip(handles) {
+        // SAFETY: The array object is valid for as long as the borrow is held.
+        let raw = unsafe { *array.as_array_ptr() };
+
+        if raw.flags & NPY_ARRAY_WRITEABLE == 0 {
+            continue;
+        }
+
+        let bytes = client
+            .read_one(handle)
+            .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+        let size = array_size(array).min(bytes.len());
+
+        // SAFETY: The array is contiguous and writeable, and `size` doesn't exceed its length.
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), raw.data as *mut u8, size);
+        }
+    }
+
+    Ok(())
+}
+
+fn array_size(array: &Bound<'_, PyUntypedArray>) -> usize {
+    array.len() * array.dtype().itemsize()
+}
+
+/// The client of the device selected by the `type_id` and `index_id` arguments, starting at the
+/// given position.
+fn client<R: Runtime>(
+    args: &Bound<'_, PyTuple>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+    position: usize,
+) -> PyResult<ComputeClient<R>> {
+    let type_id = argument(args, kwargs, position, "type_id")?.unwrap_or(0);
+    let index_id = argument(args, kwargs, position + 1, "index_id")?.unwrap_or(0);
+
+    Ok(R::client(&R::Device::from_id(DeviceId {
+        type_id,
+        index_id,
+    })))
+}
+
+fn required<'py, T: FromPyObject<'py>>(
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+    position: usize,
+    name: &str,
+) -> PyResult<T> {
+    argument(args, kwargs, position, name)?
+        .ok_or_else(|| PyTypeError::new_err(format!("Missing required argument '{name}'")))
+}
+
+fn argument<'py, T: FromPyObject<'py>>(
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+    position: usize,
+    name: &str,
+) -> PyResult<Option<T>> {
+    if let Ok(value) = args.get_item(position) {
+        return value.extract().map(Some);
+    }
+
+    match kwargs {
+        Some(kwargs) => kwargs
+            .get_item(name)?
+            .map(|value| value.extract())
+            .transpose(),
+        None => Ok(None),
+    }
+}
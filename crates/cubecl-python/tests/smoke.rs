@@ -0,0 +1,125 @@
+use cubecl_ir::{ElemType, StorageType, UIntKind};
+use cubecl_runtime::{
+    client::ComputeClient,
+    compiler::{CompilationError, CubeTask},
+    dry_run::{DryRunCompiler, DryRunDevice, DryRunEvent, DryRunKernel, DryRunRuntime},
+    id::KernelId,
+    kernel::{CompiledKernel, KernelMetadata},
+    registry::KernelRegistry,
+    server::{CubeDim, ExecutionMode},
+};
+use pyo3::{exceptions::PyKeyError, prelude::*, types::PyDict};
+
+static KERNELS: KernelRegistry<DryRunRuntime> = KernelRegistry::new();
+
+/// A kernel only recorded by the dry-run server.
+struct Noop;
+
+impl KernelMetadata for Noop {
+    fn id(&self) -> KernelId {
+        KernelId::new::<Self>()
+    }
+
+    fn address_type(&self) -> StorageType {
+        ElemType::UInt(UIntKind::U32).into()
+    }
+}
+
+impl CubeTask<DryRunCompiler> for Noop {
+    fn compile(
+        &self,
+        _compiler: &mut DryRunCompiler,
+        _compilation_options: &(),
+        _mode: ExecutionMode,
+        _address_type: StorageType,
+    ) -> Result<CompiledKernel<DryRunCompiler>, CompilationError> {
+        Ok(CompiledKernel {
+            entrypoint_name: "noop".into(),
+            debug_name: None,
+            source: String::new(),
+            repr: Some(DryRunKernel {
+                name: "noop".into(),
+                shared_memory: 0,
+            }),
+            cube_dim: CubeDim::new_single(),
+            debug_info: None,
+        })
+    }
+}
+
+#[test]
+fn bindings_can_be_called_from_python() {
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "cubecl_smoke").unwrap();
+        cubecl_python::add_runtime(&module, &KERNELS).unwrap();
+
+        let kernels: Vec<String> = module
+            .getattr("kernels")
+            .unwrap()
+            .call0()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!(kernels.is_empty());
+
+        let _devices: Vec<(u16, u16)> = module
+            .getattr("devices")
+            .unwrap()
+            .call0()
+            .unwrap()
+            .extract()
+            .unwrap();
+
+        let usage = module.getattr("memory_usage").unwrap().call0().unwrap();
+        let usage = usage.downcast::<PyDict>().unwrap();
+        assert!(usage.contains("bytes_reserved").unwrap());
+
+        module.getattr("autotune_cache").unwrap().call0().unwrap();
+        let resumed: usize = module
+            .getattr("resume_tuning")
+            .unwrap()
+            .call0()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(resumed, 0);
+
+        let launched = module.getattr("launch").unwrap().call1((
+            "missing",
+            (1u32, 1u32, 1u32),
+            Vec::<PyObject>::new(),
+        ));
+        assert!(launched.unwrap_err().is_instance_of::<PyKeyError>(py));
+    });
+}
+
+#[test]
+fn launch_binds_arrays_and_info() {
+    KERNELS.register("noop", || Box::new(Noop)).unwrap();
+
+    Python::with_gil(|py| {
+        let module = PyModule::new(py, "cubecl_launch").unwrap();
+        cubecl_python::add_runtime(&module, &KERNELS).unwrap();
+
+        let numpy = py.import("numpy").unwrap();
+        let array = numpy
+            .getattr("arange")
+            .unwrap()
+            .call1((4, "float32"))
+            .unwrap();
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("info", vec![4u64, 1]).unwrap();
+        module
+            .getattr("launch")
+            .unwrap()
+            .call(("noop", (2u32, 1u32, 1u32), vec![array]), Some(&kwargs))
+            .unwrap();
+    });
+
+    let client = ComputeClient::<DryRunRuntime>::load(&DryRunDevice::default());
+    let launched = client.info().report().events.into_iter().any(|event| {
+        matches!(event, DryRunEvent::Launch(launch) if launch.cube_count == Some((2, 1, 1)))
+    });
+    assert!(launched);
+}
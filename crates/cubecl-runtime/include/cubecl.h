@@ -0,0 +1,72 @@
+/*
+ * C API exported by `cubecl_runtime::export_c_api!`.
+ *
+ * Kernels are registered by name on the Rust side and launched from C with device buffers as
+ * bindings. All functions are thread-safe; clients and handles must be destroyed exactly once.
+ * Panics never unwind into C code: functions returning a pointer return NULL instead, and
+ * functions returning a status return CUBECL_PANIC.
+ */
+#ifndef CUBECL_H
+#define CUBECL_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct CubeclClient CubeclClient;
+typedef struct CubeclHandle CubeclHandle;
+
+typedef enum CubeclStatus {
+    CUBECL_OK = 0,
+    CUBECL_NULL_POINTER = 1,
+    CUBECL_INVALID_NAME = 2,
+    CUBECL_KERNEL_NOT_FOUND = 3,
+    CUBECL_BUFFER_TOO_SMALL = 4,
+    CUBECL_SERVER_ERROR = 5,
+    CUBECL_KERNEL_ALREADY_REGISTERED = 6,
+    CUBECL_VALIDATION_ERROR = 7,
+    CUBECL_COMPILATION_ERROR = 8,
+    CUBECL_TOO_MANY_RESOURCES = 9,
+    CUBECL_OUT_OF_MEMORY = 10,
+    CUBECL_IO_ERROR = 11,
+    /* A panic was caught at the API boundary. */
+    CUBECL_PANIC = 12,
+} CubeclStatus;
+
+/* Create a client on the device with the given type and index. Returns NULL on failure. */
+CubeclClient *cubecl_client_create(uint16_t type_id, uint16_t index_id);
+void cubecl_client_destroy(CubeclClient *client);
+
+/*
+ * Upload `len` bytes to the device. Returns NULL on invalid arguments. The upload is queued, so
+ * its errors are returned by the next launch or read on the client.
+ */
+CubeclHandle *cubecl_upload(const CubeclClient *client, const uint8_t *data, size_t len);
+/* Allocate `len` uninitialized bytes on the device. Returns NULL on invalid arguments. */
+CubeclHandle *cubecl_empty(const CubeclClient *client, size_t len);
+size_t cubecl_handle_size(const CubeclHandle *handle);
+void cubecl_handle_destroy(CubeclHandle *handle);
+
+/*
+ * Launch the kernel registered under `name` over `count[0] x count[1] x count[2]` cubes.
+ *
+ * `info` holds the scalars and metadata of the kernel, packed in the layout it was compiled with:
+ * the scalars sorted by type, then the static metadata. It is passed as is, and can be NULL with
+ * `info_len` zero for kernels that read neither scalars nor buffer lengths and shapes. The launch
+ * is flushed, so its compilation, resource and launch errors are returned as a status.
+ */
+CubeclStatus cubecl_launch(const CubeclClient *client, const char *name, const uint32_t count[3],
+                           const CubeclHandle *const *handles, size_t num_handles,
+                           const uint64_t *info, size_t info_len);
+/* Read a buffer into `out`, which must hold at least `cubecl_handle_size(handle)` bytes. */
+CubeclStatus cubecl_read(const CubeclClient *client, const CubeclHandle *handle, uint8_t *out,
+                         size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CUBECL_H */
@@ -24,8 +24,6 @@ impl MemoryLayoutPolicy for PitchedMemoryLayoutPolicy {
         stream_id: StreamId,
         descriptors: &[MemoryLayoutDescriptor],
     ) -> (Handle, Vec<MemoryLayout>) {
-        let mut total_size = 0u64;
-
         let (sizes, strides): (Vec<_>, Vec<_>) = descriptors
             .iter()
             .map(|descriptor| {
@@ -55,14 +53,15 @@ impl MemoryLayoutPolicy for PitchedMemoryLayoutPolicy {
                         strides[i] = strides[i + 1] * descriptor.shape[i + 1];
                     }
                 }
-                total_size += size.next_multiple_of(self.mem_alignment) as u64;
                 (size, strides)
             })
             .unzip();
 
+        let alignments = alignments(descriptors, self.mem_alignment);
+        let total_size = total_size(&sizes, &alignments, self.mem_alignment);
         let base_handle = Handle::new(stream_id, total_size);
 
-        let layouts = offset_handles(base_handle.clone(), &sizes, self.mem_alignment)
+        let layouts = offset_handles(base_handle.clone(), &sizes, &alignments)
             .into_iter()
             .zip(strides)
             .map(|(handle, strides)| MemoryLayout::new(handle, strides))
@@ -91,19 +90,19 @@ impl MemoryLayoutPolicy for ContiguousMemoryLayoutPolicy {
         stream_id: StreamId,
         descriptors: &[MemoryLayoutDescriptor],
     ) -> (Handle, Vec<MemoryLayout>) {
-        let mut total_size = 0u64;
         let (sizes, strides): (Vec<_>, Vec<_>) = descriptors
             .iter()
             .map(|desc| {
                 let size = desc.shape.iter().product::<usize>() * desc.elem_size;
-                total_size += size.next_multiple_of(self.mem_alignment) as u64;
                 (size, contiguous_strides(&desc.shape))
             })
             .unzip();
 
+        let alignments = alignments(descriptors, self.mem_alignment);
+        let total_size = total_size(&sizes, &alignments, self.mem_alignment);
         let base_handle = Handle::new(stream_id, total_size);
 
-        let layouts = offset_handles(base_handle.clone(), &sizes, self.mem_alignment)
+        let layouts = offset_handles(base_handle.clone(), &sizes, &alignments)
             .into_iter()
             .zip(strides)
             .map(|(handle, stride)| MemoryLayout::new(handle, stride))
@@ -122,24 +121,45 @@ pub(crate) fn contiguous_strides(shape: &Shape) -> Strides {
     strides
 }
 
+/// The alignment of every allocation, which is the memory alignment unless the descriptor
+/// requests a larger one.
+fn alignments(descriptors: &[MemoryLayoutDescriptor], mem_alignment: usize) -> Vec<usize> {
+    descriptors
+        .iter()
+        .map(|desc| Ord::max(desc.alignment.unwrap_or(1), mem_alignment))
+        .collect()
+}
+
+/// The size of a buffer holding every sub-slice at its alignment, padded to the memory alignment.
+fn total_size(sizes_bytes: &[usize], alignments: &[usize], mem_alignment: usize) -> u64 {
+    let end = sizes_bytes
+        .iter()
+        .zip(alignments)
+        .fold(0usize, |offset, (size, align)| {
+            offset.next_multiple_of(*align) + size
+        });
+    end.next_multiple_of(mem_alignment) as u64
+}
+
 /// Take a list of sub-slices of a buffer and create a list of offset handles.
-/// Sizes must be in bytes and handles will be aligned to the memory alignment.
+/// Sizes must be in bytes and every handle starts at the offset aligned to its alignment.
 pub fn offset_handles(
     base_handle: Handle,
     sizes_bytes: &[usize],
-    buffer_align: usize,
+    alignments: &[usize],
 ) -> Vec<Handle> {
     let total_size = base_handle.size() as usize;
-    let mut offset = 0;
+    let mut offset = 0usize;
     let mut out = Vec::new();
 
-    for size in sizes_bytes {
+    for (size, align) in sizes_bytes.iter().zip(alignments) {
+        offset = offset.next_multiple_of(*align);
         let handle = base_handle
             .clone()
             .offset_start(offset as u64)
             .offset_end((total_size - offset - size) as u64);
         out.push(handle);
-        offset += size.next_multiple_of(buffer_align);
+        offset += size;
     }
 
     out
@@ -1,21 +1,27 @@
 use crate::{
+    allocator::contiguous_strides,
     config::{TypeNameFormatLevel, type_name_format},
     kernel::KernelMetadata,
     logging::ProfileLevel,
     memory_management::{MemoryAllocationMode, MemoryUsage},
     runtime::Runtime,
     server::{
-        CommunicationId, ComputeServer, CopyDescriptor, CubeCount, ExecutionMode, Handle, IoError,
-        KernelArguments, MemoryLayout, MemoryLayoutDescriptor, MemoryLayoutPolicy,
-        MemoryLayoutStrategy, ProfileError, ReduceOperation, ServerCommunication, ServerError,
-        ServerUtilities,
+        AccessPolicyWindow, Binding, CommunicationId, CompletionCallback, ComputeServer,
+        CopyDescriptor, CubeCount, ExecutionMode, ExternalSemaphore, ExternalSemaphoreHandle,
+        Handle, HostHandle, IoError, KernelArguments, MemoryLayout, MemoryLayoutDescriptor,
+        MemoryLayoutPolicy, MemoryLayoutStrategy, Occupancy, ProfileError, ReduceOperation,
+        ServerCommunication, ServerError, ServerUtilities,
     },
     storage::{ComputeStorage, ManagedResource},
 };
-use alloc::{format, sync::Arc, vec, vec::Vec};
+use alloc::{boxed::Box, format, string::String, sync::Arc, vec, vec::Vec};
 
+mod fallback;
 #[cfg(not(target_family = "wasm"))]
 mod lazy;
+mod staged;
+pub(crate) mod watch;
+
 use cubecl_common::{
     backtrace::BackTrace,
     bytes::{AllocationProperty, Bytes},
@@ -27,6 +33,10 @@ use cubecl_common::{
 use cubecl_ir::{DeviceProperties, ElemType, VectorSize, features::Features};
 use cubecl_zspace::Shape;
 
+pub use fallback::LaunchFallback;
+pub use staged::StagedUploader;
+pub use watch::{WatchEvent, WatchpointId};
+
 #[allow(unused)]
 use cubecl_common::profile::TimingMethod;
 use cubecl_common::stream_id::StreamId;
@@ -37,6 +47,8 @@ pub struct ComputeClient<R: Runtime> {
     device: DeviceHandle<R::Server>,
     utilities: Arc<ServerUtilities<R::Server>>,
     stream_id: Option<StreamId>,
+    access_policy_window: Option<AccessPolicyWindow>,
+    fallback: Option<Arc<dyn LaunchFallback<R>>>,
 }
 
 impl<R: Runtime> Clone for ComputeClient<R> {
@@ -45,11 +57,18 @@ impl<R: Runtime> Clone for ComputeClient<R> {
             device: self.device.clone(),
             utilities: self.utilities.clone(),
             stream_id: self.stream_id,
+            access_policy_window: self.access_policy_window.clone(),
+            fallback: self.fallback.clone(),
         }
     }
 }
 
 impl<R: Runtime> ComputeClient<R> {
+    /// The id of the device the client is bound to.
+    pub fn device_id(&self) -> DeviceId {
+        self.device.device_id()
+    }
+
     /// Get the info of the current backend.
     pub fn info(&self) -> &<R::Server as ComputeServer>::Info {
         &self.utilities.info
@@ -65,9 +84,17 @@ impl<R: Runtime> ComputeClient<R> {
             device: context,
             utilities,
             stream_id: None,
+            access_policy_window: None,
+            fallback: None,
         }
     }
 
+    /// Whether a server is already registered for the given device, in which case
+    /// [`init`](Self::init) would panic.
+    pub fn is_registered<D: Device>(device: &D) -> bool {
+        DeviceHandle::<R::Server>::is_registered(device.to_id())
+    }
+
     /// Load the client for the given device.
     pub fn load<D: Device>(device: &D) -> Self {
         let context = DeviceHandle::<R::Server>::new(device.to_id());
@@ -82,6 +109,8 @@ impl<R: Runtime> ComputeClient<R> {
             device: context,
             utilities,
             stream_id: None,
+            access_policy_window: None,
+            fallback: None,
         }
     }
 
@@ -101,6 +130,41 @@ impl<R: Runtime> ComputeClient<R> {
         self.stream_id = Some(stream_id);
     }
 
+    /// A client whose launches hint that `handle` is re-read many times and should persist in the
+    /// L2 cache, for example the weights of a matrix-vector product during decoding.
+    ///
+    /// `hit_ratio` is the fraction of the accesses to `handle` that should persist, between zero
+    /// and one. It can be lowered when other data re-read by the kernels should also stay in the
+    /// cache. Only CUDA devices with compute capability 8.0 or higher act on the hint, other runtimes
+    /// launch kernels as usual.
+    ///
+    /// On CUDA, the persisting part of the L2 cache is sized to `handle` by the hinted launches.
+    /// It's kept across launches without the hint, resized by a hint on another buffer, and
+    /// released by [`memory_cleanup`](Self::memory_cleanup).
+    pub fn with_l2_persisting(&self, handle: &Handle, hit_ratio: f32) -> Self {
+        Self {
+            access_policy_window: Some(AccessPolicyWindow::new(
+                handle.clone().binding(),
+                hit_ratio.clamp(0.0, 1.0),
+            )),
+            ..self.clone()
+        }
+    }
+
+    /// A client whose kernels are launched by `fallback` when it [accepts](LaunchFallback::accepts)
+    /// them, for example kernels using a type the device doesn't support.
+    pub fn with_fallback(&self, fallback: Arc<dyn LaunchFallback<R>>) -> Self {
+        Self {
+            fallback: Some(fallback),
+            ..self.clone()
+        }
+    }
+
+    /// The [fallback](Self::with_fallback) of the launches of this client, if any.
+    pub fn fallback(&self) -> Option<&Arc<dyn LaunchFallback<R>>> {
+        self.fallback.as_ref()
+    }
+
     fn do_read(&self, descriptors: Vec<CopyDescriptor>) -> DynFut<Result<Vec<Bytes>, ServerError>> {
         let stream_id = self.stream_id();
         self.device
@@ -258,13 +322,68 @@ impl<R: Runtime> ComputeClient<R> {
             .unwrap_or_resume()
     }
 
+    /// Lay out the allocations, checking that every requested alignment can be guaranteed.
+    fn layout(
+        &self,
+        stream_id: StreamId,
+        descriptors: &[MemoryLayoutDescriptor],
+    ) -> Result<(Handle, Vec<MemoryLayout>), IoError> {
+        if let Some(alignment) = descriptors
+            .iter()
+            .filter_map(|desc| desc.alignment)
+            .find(|align| !align.is_power_of_two())
+        {
+            return Err(IoError::UnsupportedAlignment {
+                alignment,
+                backtrace: BackTrace::capture(),
+            });
+        }
+
+        // Memory pages are aligned to the device memory alignment, so are the slices within them.
+        // Larger alignments are requested when initializing the memory.
+        let base_alignment = descriptors
+            .iter()
+            .filter_map(|desc| desc.alignment)
+            .fold(self.utilities.properties.memory.alignment, |acc, align| {
+                Ord::max(acc, align as u64)
+            });
+        let (mut handle_base, mut layouts) =
+            self.utilities.layout_policy.apply(stream_id, descriptors);
+        handle_base.base_alignment = base_alignment;
+        for layout in layouts.iter_mut() {
+            layout.memory.base_alignment = base_alignment;
+        }
+
+        Ok((handle_base, layouts))
+    }
+
+    /// Initializes the memory of the base handle of a layout, aligned to its base alignment.
+    fn initialize_memory(&self, handle_base: Handle, stream_id: StreamId) -> Result<(), IoError> {
+        let (size, alignment) = (handle_base.size(), handle_base.base_alignment);
+        let memory = handle_base.memory;
+
+        if alignment <= self.utilities.properties.memory.alignment {
+            self.device.submit(move |server| {
+                server.initialize_memory(memory, size, stream_id);
+            });
+            return Ok(());
+        }
+
+        // The runtime might not support over-aligned memory, so the error must be surfaced.
+        self.device
+            .submit_blocking(move |server| {
+                server.initialize_memory_aligned(memory, size, alignment, stream_id)
+            })
+            .unwrap_or_resume()
+    }
+
     fn do_create_from_slices(
         &self,
         descriptors: Vec<MemoryLayoutDescriptor>,
         slices: Vec<Vec<u8>>,
     ) -> Result<Vec<MemoryLayout>, IoError> {
         let stream_id = self.stream_id();
-        let (handle_base, layouts) = self.utilities.layout_policy.apply(stream_id, &descriptors);
+        let (handle_base, layouts) = self.layout(stream_id, &descriptors)?;
 
         let descriptors = descriptors
             .into_iter()
@@ -283,9 +402,8 @@ impl<R: Runtime> ComputeClient<R> {
             })
             .collect::<Vec<_>>();
 
-        let (size, memory) = (handle_base.size(), handle_base.memory);
+        self.initialize_memory(handle_base, stream_id)?;
         self.device.submit(move |server| {
-            server.initialize_memory(memory, size, stream_id);
             server.write(descriptors, stream_id);
         });
 
@@ -298,7 +416,7 @@ impl<R: Runtime> ComputeClient<R> {
         data: Vec<Bytes>,
     ) -> Result<Vec<MemoryLayout>, IoError> {
         let stream_id = self.stream_id();
-        let (handle_base, layouts) = self.utilities.layout_policy.apply(stream_id, &descriptors);
+        let (handle_base, layouts) = self.layout(stream_id, &descriptors)?;
 
         let descriptors = descriptors
             .into_iter()
@@ -317,9 +435,8 @@ impl<R: Runtime> ComputeClient<R> {
             })
             .collect::<Vec<_>>();
 
-        let (size, memory) = (handle_base.size(), handle_base.memory);
+        self.initialize_memory(handle_base, stream_id)?;
         self.device.submit(move |server| {
-            server.initialize_memory(memory, size, stream_id);
             server.write(descriptors, stream_id);
         });
 
@@ -405,6 +522,56 @@ impl<R: Runtime> ComputeClient<R> {
         .memory
     }
 
+    /// Returns a resource handle wrapping the host memory of the given [Bytes] without a copy,
+    /// when the device shares memory with the host, e.g. the CPU runtime, Apple Silicon or
+    /// integrated GPUs with Vulkan. Otherwise the data is copied as with [create](Self::create),
+    /// which [`HostHandle::zero_copy`] reports.
+    ///
+    /// Wrapping saves a copy and a device allocation, which matters for large read-only data like
+    /// model weights. The data usually has to be aligned to a page and must not be shared.
+    pub fn create_from_host(&self, data: Bytes) -> HostHandle {
+        let stream_id = self.stream_id();
+        let mut handle = Handle::new(stream_id, data.len() as u64);
+        let memory = handle.memory.clone();
+
+        let wrapped = self
+            .device
+            .submit_blocking(move |server| server.create_from_host(memory, data, stream_id))
+            .unwrap_or_resume();
+
+        match wrapped {
+            Ok(alignment) => {
+                handle.base_alignment = alignment;
+                HostHandle {
+                    handle,
+                    zero_copy: true,
+                }
+            }
+            Err(data) => {
+                // Back the handle reserved for the wrapped memory with a device allocation.
+                self.initialize_memory(handle.clone(), stream_id).unwrap();
+                self.write(handle.clone().binding(), data);
+                HostHandle {
+                    handle,
+                    zero_copy: false,
+                }
+            }
+        }
+    }
+
+    /// Writes `data` to the start of the memory of `binding`, which must be large enough to hold
+    /// it.
+    pub fn write(&self, binding: Binding, data: Bytes) {
+        let stream_id = self.stream_id();
+        let shape: Shape = [data.len()].into();
+        let strides = contiguous_strides(&shape);
+        let descriptor = CopyDescriptor::new(binding, shape, strides, 1);
+
+        self.device.submit(move |server| {
+            server.write(vec![(descriptor, data)], stream_id);
+        });
+    }
+
     /// Given a resource and shape, stores it and returns the tensor handle and strides.
     /// This may or may not return contiguous strides. The layout is up to the runtime, and care
     /// should be taken when indexing.
@@ -504,12 +671,9 @@ impl<R: Runtime> ComputeClient<R> {
         descriptors: Vec<MemoryLayoutDescriptor>,
     ) -> Result<Vec<MemoryLayout>, IoError> {
         let stream_id = self.stream_id();
-        let (handle_base, layouts) = self.utilities.layout_policy.apply(stream_id, &descriptors);
+        let (handle_base, layouts) = self.layout(stream_id, &descriptors)?;
 
-        let (size, memory) = (handle_base.size(), handle_base.memory);
-        self.device.submit(move |server| {
-            server.initialize_memory(memory, size, stream_id);
-        });
+        self.initialize_memory(handle_base, stream_id)?;
 
         Ok(layouts)
     }
@@ -521,6 +685,15 @@ impl<R: Runtime> ComputeClient<R> {
         self.do_empty(vec![descriptor]).unwrap().remove(0).memory
     }
 
+    /// Reserves `size` bytes in the storage starting at a multiple of `alignment` bytes, see
+    /// [`MemoryLayoutDescriptor::with_alignment`].
+    pub fn empty_aligned(&self, size: usize, alignment: usize) -> Result<Handle, IoError> {
+        let shape: Shape = [size].into();
+        let descriptor = MemoryLayoutDescriptor::new(MemoryLayoutStrategy::Contiguous, shape, 1)
+            .with_alignment(alignment);
+        Ok(self.do_empty(vec![descriptor])?.remove(0).memory)
+    }
+
     /// Reserves `shape` in the storage, and returns a tensor handle for it.
     /// See [`ComputeClient::create_tensor`]
     pub fn empty_tensor(&self, shape: Shape, elem_size: usize) -> MemoryLayout {
@@ -568,14 +741,7 @@ impl<R: Runtime> ComputeClient<R> {
             return;
         }
 
-        let stream_id = self.stream_id();
-        let sizes = sizes.to_vec();
-        let stagings = self
-            .device
-            .submit_blocking(move |server| server.staging(&sizes, stream_id))
-            .unwrap_or_resume();
-
-        let stagings = match stagings {
+        let stagings = match self.reserve_staging(sizes) {
             Ok(val) => val,
             Err(_) => return,
         };
@@ -589,6 +755,21 @@ impl<R: Runtime> ComputeClient<R> {
             });
     }
 
+    /// Reserves staging buffers of the given sizes, maybe in pinned memory.
+    fn reserve_staging(&self, sizes: Vec<usize>) -> Result<Vec<Bytes>, ServerError> {
+        let stream_id = self.stream_id();
+
+        self.device
+            .submit_blocking(move |server| server.staging(&sizes, stream_id))
+            .unwrap_or_resume()
+    }
+
+    /// Creates a [`StagedUploader`] that overlaps host-to-device copies with compute through
+    /// double-buffered staging memory.
+    pub fn staged_uploader(&self) -> StagedUploader<R> {
+        StagedUploader::new(self.clone())
+    }
+
     /// Transfer data from one client to another
     #[cfg_attr(
         feature = "tracing",
@@ -701,7 +882,8 @@ impl<R: Runtime> ComputeClient<R> {
         let device_id_dst = dst_server.device.device_id();
 
         let mut dst_server = dst_server.clone();
-        let handle = Handle::new(stream_id_dst, src_descriptor.handle.size_in_used());
+        let mut handle = Handle::new(stream_id_dst, src_descriptor.handle.size_in_used());
+        handle.base_alignment = dst_server.utilities.properties.memory.alignment;
         let handle_cloned = handle.clone();
 
         let device_ids = vec![device_id_src, device_id_dst];
@@ -742,7 +924,7 @@ impl<R: Runtime> ComputeClient<R> {
         &self,
         kernel: <R::Server as ComputeServer>::Kernel,
         count: CubeCount,
-        bindings: KernelArguments,
+        mut bindings: KernelArguments,
         mode: ExecutionMode,
         stream_id: StreamId,
     ) {
@@ -753,7 +935,12 @@ impl<R: Runtime> ComputeClient<R> {
             return;
         }
 
+        if bindings.access_policy_window.is_none() {
+            bindings.access_policy_window = self.access_policy_window.clone();
+        }
+
         let level = self.utilities.logger.profile_level();
+        let kernel_name = kernel.name();
 
         match level {
             None | Some(ProfileLevel::ExecutionOnly) => {
@@ -795,6 +982,39 @@ impl<R: Runtime> ComputeClient<R> {
                 result
             }
         }
+
+        if self.has_watchpoints() {
+            self.check_watchpoints(kernel_name);
+        }
+    }
+
+    /// How many cubes of the `kernel` can be resident on the device at the same time, used to
+    /// size the grid of persistent kernels.
+    ///
+    /// The kernel is compiled with the execution `mode` it will be launched with if needed, but
+    /// isn't launched, since bounds checks change the resources used by the kernel. Runtimes that
+    /// can't query the occupancy of a compiled kernel return an [estimate](Occupancy::estimate).
+    pub fn occupancy(
+        &self,
+        kernel: <R::Server as ComputeServer>::Kernel,
+        mode: ExecutionMode,
+    ) -> Result<Occupancy, ServerError> {
+        let stream_id = self.stream_id();
+        let estimate = Occupancy::estimate(self.properties(), kernel.id().cube_dim);
+
+        let cubes_per_unit = self
+            .device
+            .submit_blocking(move |server| server.max_cubes_per_unit(kernel, mode, stream_id))
+            .unwrap_or_resume()?;
+
+        Ok(match cubes_per_unit {
+            Some(cubes_per_unit) => Occupancy {
+                cubes_per_unit,
+                exact: true,
+                ..estimate
+            },
+            None => estimate,
+        })
     }
 
     /// Launches the `kernel` with the given `bindings`.
@@ -862,16 +1082,50 @@ impl<R: Runtime> ComputeClient<R> {
     pub fn sync(&self) -> DynFut<Result<(), ServerError>> {
         let stream_id = self.stream_id();
 
-        let fut = self
+        let profiling = self.utilities.logger.profile_level().is_some();
+        let (fut, energy) = self
             .device
-            .submit_blocking(move |server| server.sync(stream_id))
+            .submit_blocking(move |server| {
+                let fut = server.sync(stream_id);
+                let energy = if profiling {
+                    server.energy_consumption()
+                } else {
+                    None
+                };
+                (fut, energy)
+            })
             .unwrap_or_resume();
 
-        self.utilities.logger.profile_summary();
+        self.utilities.logger.profile_summary(energy);
 
         fut
     }
 
+    /// Invoke the given callback on the host once every task previously submitted on this
+    /// client's stream is completed, without blocking the caller.
+    ///
+    /// This allows building asynchronous pipelines, e.g. recycling host buffers or scheduling the
+    /// next batch, without polling or doing a full [sync](Self::sync). The callback receives the
+    /// given token, identifying the work it completes, and the error of the stream if any task
+    /// failed.
+    ///
+    /// The callback may run on a driver thread, so it should be short and must not submit work
+    /// to the compute client itself; forward a message through a channel instead.
+    pub fn on_complete<T, F>(&self, token: T, callback: F)
+    where
+        T: Send + 'static,
+        F: FnOnce(T, Result<(), ServerError>) + Send + 'static,
+    {
+        let stream_id = self.stream_id();
+        let callback: CompletionCallback = Box::new(move |result| callback(token, result));
+
+        self.device
+            .submit(move |server| server.on_complete(stream_id, callback));
+        // The callback is registered on the device thread, which only happens once the queue is
+        // flushed.
+        self.device.flush_queue();
+    }
+
     /// Get the features supported by the compute server.
     pub fn properties(&self) -> &DeviceProperties {
         &self.utilities.properties
@@ -907,6 +1161,84 @@ impl<R: Runtime> ComputeClient<R> {
             .unwrap_or_resume()
     }
 
+    /// Total energy consumed by the device in millijoules, read once every task previously
+    /// submitted on this client's stream is completed.
+    ///
+    /// Only the difference between two readings is meaningful. Returns `None` when the runtime
+    /// can't read the power sensors of the device.
+    pub fn energy_consumption(&self) -> Option<u64> {
+        let stream_id = self.stream_id();
+
+        self.device
+            .submit_blocking(move |server| {
+                // Skip the sync when the runtime has no energy counter.
+                server.energy_consumption()?;
+                let _ = cubecl_common::future::block_on(server.sync(stream_id));
+                server.energy_consumption()
+            })
+            .unwrap_or_resume()
+    }
+
+    /// Import a timeline semaphore created by another API, e.g. a renderer, to synchronize with
+    /// it on the device.
+    ///
+    /// Returns an error when the runtime can't share semaphores with other APIs.
+    pub fn import_semaphore(
+        &self,
+        handle: ExternalSemaphoreHandle,
+    ) -> Result<ExternalSemaphore, ServerError> {
+        self.device
+            .submit_blocking(move |server| server.import_semaphore(handle))
+            .unwrap_or_resume()
+    }
+
+    /// Create a timeline semaphore starting at `0` and export it, so another API can import the
+    /// returned handle, which is owned by the caller.
+    ///
+    /// Returns an error when the runtime can't share semaphores with other APIs.
+    pub fn export_semaphore(
+        &self,
+    ) -> Result<(ExternalSemaphore, ExternalSemaphoreHandle), ServerError> {
+        self.device
+            .submit_blocking(move |server| server.export_semaphore())
+            .unwrap_or_resume()
+    }
+
+    /// Set the semaphore to `value` once every task previously submitted on this client's stream
+    /// is completed, so another API can wait on the results without a [sync](Self::sync).
+    pub fn signal_semaphore(
+        &self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+    ) -> Result<(), ServerError> {
+        let stream_id = self.stream_id();
+
+        self.device
+            .submit_blocking(move |server| server.signal_semaphore(semaphore, value, stream_id))
+            .unwrap_or_resume()
+    }
+
+    /// Delay the tasks submitted next on this client's stream until the semaphore reaches
+    /// `value`, e.g. until another API is done writing the inputs. The host isn't blocked.
+    pub fn wait_semaphore(
+        &self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+    ) -> Result<(), ServerError> {
+        let stream_id = self.stream_id();
+
+        self.device
+            .submit_blocking(move |server| server.wait_semaphore(semaphore, value, stream_id))
+            .unwrap_or_resume()
+    }
+
+    /// Release a semaphore imported with [`import_semaphore`](Self::import_semaphore) or created
+    /// with [`export_semaphore`](Self::export_semaphore).
+    pub fn release_semaphore(&self, semaphore: ExternalSemaphore) {
+        self.device
+            .submit(move |server| server.release_semaphore(semaphore));
+    }
+
     /// Get all devices of a specific type available to this runtime
     pub fn enumerate_devices(&self, type_id: u16) -> Vec<DeviceId> {
         R::enumerate_devices(type_id, self.info())
@@ -1107,11 +1439,13 @@ impl<R: Runtime> ComputeClient<R> {
     }
 
     /// Returns all vector sizes that are useful to perform optimal IO operation on the given element.
+    ///
+    /// The largest vector size is bounded by the [load width](Self::io_load_width) of the device.
     pub fn io_optimized_vector_sizes(
         &self,
         size: usize,
     ) -> impl Iterator<Item = VectorSize> + Clone {
-        let load_width = self.properties().hardware.load_width as usize;
+        let load_width = self.io_load_width() as usize;
         let size_bits = size * 8;
         let max = load_width / size_bits;
         let max = usize::min(self.properties().hardware.max_vector_size, max);
@@ -1121,4 +1455,90 @@ impl<R: Runtime> ComputeClient<R> {
 
         (0..num_candidates).map(|i| 2usize.pow(i)).rev()
     }
+
+    /// The width in bits of the loads used to pick the
+    /// [vector sizes of IO operations](Self::io_optimized_vector_sizes).
+    ///
+    /// Never runs a measurement: it's the width found by
+    /// [`calibrate_load_width`](Self::calibrate_load_width), or measured on this device by a
+    /// previous process, falling back to the load width of the device properties. The width is
+    /// resolved by the first call and doesn't change afterwards.
+    pub fn io_load_width(&self) -> u32 {
+        *self.utilities.load_width.call_once(|| {
+            self.stored_load_width()
+                .unwrap_or(self.properties().hardware.load_width)
+        })
+    }
+
+    /// Measure the load width of the device with [`Runtime::measure_load_width`] and use it for
+    /// the [vector sizes of IO operations](Self::io_optimized_vector_sizes).
+    ///
+    /// The measurement copies a few buffers of 16 MiB, so it should be called right after
+    /// creating the client, before any kernel is tuned or profiled. It runs at most once per
+    /// device, concurrent callers wait for it, and it's cached with the fingerprint of the device
+    /// so later processes don't run it again. Once the load width is in use it doesn't change,
+    /// so calling this after [`io_load_width`](Self::io_load_width) only returns the width in use.
+    pub fn calibrate_load_width(&self) -> u32 {
+        *self.utilities.load_width.call_once(|| {
+            self.measured_load_width()
+                .unwrap_or(self.properties().hardware.load_width)
+        })
+    }
+
+    /// The load width measured on this device by a previous process.
+    #[cfg(std_io)]
+    fn stored_load_width(&self) -> Option<u32> {
+        load_width_cache().get(&self.device_fingerprint()).copied()
+    }
+
+    /// The load width measured on this device by a previous process.
+    #[cfg(not(std_io))]
+    fn stored_load_width(&self) -> Option<u32> {
+        None
+    }
+
+    /// The load width measured on this device, by this process or a previous one.
+    #[cfg(std_io)]
+    fn measured_load_width(&self) -> Option<u32> {
+        let fingerprint = self.device_fingerprint();
+        let mut cache = load_width_cache();
+        if let Some(width) = cache.get(&fingerprint) {
+            return Some(*width);
+        }
+
+        let width = R::measure_load_width(self)?;
+        if let Err(err) = cache.insert(fingerprint, width) {
+            log::warn!("Can't persist the load width: {err:?}");
+        }
+        Some(width)
+    }
+
+    /// The load width measured on this device.
+    #[cfg(not(std_io))]
+    fn measured_load_width(&self) -> Option<u32> {
+        R::measure_load_width(self)
+    }
+
+    /// Identify the device from its server info and properties, so results measured on one
+    /// device aren't applied to another one sharing the same id.
+    pub(crate) fn device_fingerprint(&self) -> String {
+        let properties = format!("{:?}-{:?}", self.info(), self.properties());
+        format!("{:x}", md5::compute(properties))
+    }
+}
+
+/// The load widths measured on every device, stored next to the autotune results.
+#[cfg(std_io)]
+fn load_width_cache() -> cubecl_common::cache::Cache<String, u32> {
+    use crate::config::RuntimeConfig;
+
+    let root = crate::config::CubeClRuntimeConfig::get()
+        .autotune
+        .cache
+        .root();
+    let options = cubecl_common::cache::CacheOption::default()
+        .root(root)
+        .name("vectorization");
+
+    cubecl_common::cache::Cache::new("load-width", options)
 }
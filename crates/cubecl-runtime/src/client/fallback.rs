@@ -0,0 +1,33 @@
+//! Launches redirected away from the device of a client.
+
+use super::ComputeClient;
+use crate::kernel::CubeKernel;
+use crate::runtime::Runtime;
+use crate::server::{CubeCount, ExecutionMode, KernelArguments};
+use alloc::boxed::Box;
+
+/// Runs the launches the device of a client can't, for example on another runtime.
+///
+/// Installed on a client with [`ComputeClient::with_fallback`], and consulted by the launchers
+/// of kernels before compiling them for the device.
+pub trait LaunchFallback<R: Runtime>: Send + Sync {
+    /// Whether `kernel` should be launched by the fallback instead of the device of `client`.
+    fn accepts(&self, client: &ComputeClient<R>, kernel: &dyn CubeKernel) -> bool;
+
+    /// Launch an [accepted](Self::accepts) `kernel` in place of `client`. The results are written
+    /// back to the buffers of `bindings` on the device, so they can be used as if the kernel ran
+    /// there.
+    ///
+    /// # Safety
+    ///
+    /// When `mode` isn't [checked](ExecutionMode::Checked), the kernel must uphold the same
+    /// guarantees as for [`ComputeClient::launch_unchecked`].
+    unsafe fn launch(
+        &self,
+        client: &ComputeClient<R>,
+        kernel: Box<dyn CubeKernel>,
+        count: CubeCount,
+        bindings: KernelArguments,
+        mode: ExecutionMode,
+    );
+}
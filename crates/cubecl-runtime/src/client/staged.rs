@@ -0,0 +1,104 @@
+//! Double-buffered host-to-device uploads through pinned staging memory.
+
+use super::ComputeClient;
+use crate::runtime::Runtime;
+use crate::server::{Handle, MemoryLayout};
+use alloc::vec;
+use cubecl_common::bytes::Bytes;
+use cubecl_zspace::Shape;
+
+/// Uploads a stream of host slices to the device through staging buffers, reserving the buffer
+/// of the next push ahead of time.
+///
+/// While the buffer of a push is in flight, owned by the server until its copy completes, the
+/// buffer of the next push is already reserved and ready to be filled by the host. Filling it
+/// therefore overlaps with the previous copy and with any kernel queued on the same stream,
+/// instead of serializing every host-to-device copy against compute.
+///
+/// Buffers are reserved through [`ComputeServer::staging`](crate::server::ComputeServer::staging),
+/// so they are pinned on runtimes that support it. When the runtime doesn't provide staging
+/// memory, uploads fall back to regular host allocations.
+///
+/// Buffers aren't reused: every push hands its buffer to the server, which owns it until the copy
+/// completes, so a new ready buffer is reserved with the size of the pushed slice after each push.
+/// The first push reserves one more buffer, since nothing is ready yet. The reservation is
+/// a round trip to the server, served from its staging pool on runtimes that have one, e.g. the
+/// pinned memory pool on CUDA, which only allocates when the pool can't fit the request. A push
+/// whose size differs from the previous one reserves a second buffer instead of using the ready
+/// one.
+pub struct StagedUploader<R: Runtime> {
+    client: ComputeClient<R>,
+    /// The staging buffer that the next [`push`](Self::push) will fill.
+    ready: Option<Bytes>,
+    /// Whether the runtime provides staging memory.
+    supported: bool,
+}
+
+impl<R: Runtime> StagedUploader<R> {
+    pub(super) fn new(client: ComputeClient<R>) -> Self {
+        Self {
+            client,
+            ready: None,
+            supported: true,
+        }
+    }
+
+    /// Upload the given data, returning the handle where it will be stored.
+    ///
+    /// The copy is queued on the client's stream, so kernels launched afterwards observe the
+    /// data without any explicit synchronization.
+    pub fn push(&mut self, data: &[u8]) -> Handle {
+        let bytes = self.fill(data);
+        let handle = self.client.create(bytes);
+        self.prefetch(data.len());
+        handle
+    }
+
+    /// Upload the given data as a tensor, see [`ComputeClient::create_tensor`].
+    pub fn push_tensor(&mut self, data: &[u8], shape: Shape, elem_size: usize) -> MemoryLayout {
+        let bytes = self.fill(data);
+        let layout = self.client.create_tensor(bytes, shape, elem_size);
+        self.prefetch(data.len());
+        layout
+    }
+
+    /// Whether uploads actually go through staging memory reserved by the runtime.
+    pub fn is_staged(&self) -> bool {
+        self.supported
+    }
+
+    /// Copy the data into the ready buffer, reserving a new one if it doesn't fit.
+    fn fill(&mut self, data: &[u8]) -> Bytes {
+        let ready = match self.ready.take() {
+            Some(bytes) if bytes.len() == data.len() => Some(bytes),
+            _ => self.reserve(data.len()),
+        };
+
+        match ready {
+            Some(mut bytes) => {
+                bytes.copy_from_slice(data);
+                bytes
+            }
+            None => Bytes::from_bytes_vec(data.to_vec()),
+        }
+    }
+
+    /// Reserve the buffer used by the next push while the current one is in flight.
+    fn prefetch(&mut self, size: usize) {
+        self.ready = self.reserve(size);
+    }
+
+    fn reserve(&mut self, size: usize) -> Option<Bytes> {
+        if !self.supported || size == 0 {
+            return None;
+        }
+
+        match self.client.reserve_staging(vec![size]) {
+            Ok(mut stagings) => stagings.pop(),
+            Err(_) => {
+                self.supported = false;
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Watchpoints on device buffers, to find which kernel corrupts a tensor.
+
+use super::ComputeClient;
+use crate::{
+    config::{TypeNameFormatLevel, type_name_format},
+    runtime::Runtime,
+    server::Handle,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Write, ops::Range};
+
+/// Identifies a watchpoint registered with [`ComputeClient::watch`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WatchpointId(u64);
+
+/// A launch that changed the watched range of a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    /// The name of the kernel that changed the range.
+    pub kernel: String,
+    /// The indices of the changed elements, relative to the buffer.
+    pub elements: Vec<usize>,
+}
+
+/// The watchpoints of a device.
+#[derive(Default)]
+pub(crate) struct Watchpoints {
+    next_id: u64,
+    watchpoints: Vec<Watchpoint>,
+}
+
+struct Watchpoint {
+    id: WatchpointId,
+    handle: Handle,
+    elem_size: usize,
+    start: usize,
+    filter: Option<String>,
+    last: Vec<u8>,
+    events: Vec<WatchEvent>,
+}
+
+/// The maximum number of changed elements written to the log for a single launch.
+const MAX_LOGGED_CHANGES: usize = 16;
+
+impl<R: Runtime> ComputeClient<R> {
+    /// Watch the given range of elements of a buffer, with elements of `elem_size` bytes.
+    ///
+    /// After every launch of a kernel whose name contains `filter`, or of every kernel without a
+    /// filter, the range is read back and compared with its previous value. Every change is logged
+    /// with the name of the kernel, and recorded until the watchpoint is removed with
+    /// [`unwatch`](Self::unwatch).
+    ///
+    /// Each check synchronizes with the device, so watchpoints are only meant for debugging, e.g.
+    /// to bisect which kernel corrupts a tensor.
+    pub fn watch(
+        &self,
+        handle: &Handle,
+        elem_size: usize,
+        range: Range<usize>,
+        filter: Option<&str>,
+    ) -> WatchpointId {
+        let handle = slice(handle, elem_size, &range);
+        let last = self.read_one_unchecked(handle.clone()).to_vec();
+
+        let mut watchpoints = self.utilities.watchpoints.lock();
+        let id = WatchpointId(watchpoints.next_id);
+        watchpoints.next_id += 1;
+        watchpoints.watchpoints.push(Watchpoint {
+            id,
+            handle,
+            elem_size,
+            start: range.start,
+            filter: filter.map(ToString::to_string),
+            last,
+            events: Vec::new(),
+        });
+
+        id
+    }
+
+    /// Remove a watchpoint, returning the launches that changed its range.
+    pub fn unwatch(&self, id: WatchpointId) -> Vec<WatchEvent> {
+        let mut watchpoints = self.utilities.watchpoints.lock();
+        let Some(index) = watchpoints.watchpoints.iter().position(|it| it.id == id) else {
+            return Vec::new();
+        };
+
+        watchpoints.watchpoints.remove(index).events
+    }
+
+    /// Check the watchpoints matching the kernel that was just launched.
+    pub(super) fn check_watchpoints(&self, kernel: &str) {
+        let mut watchpoints = self.utilities.watchpoints.lock();
+
+        for watchpoint in watchpoints.watchpoints.iter_mut() {
+            if let Some(filter) = &watchpoint.filter
+                && !kernel.contains(filter.as_str())
+            {
+                continue;
+            }
+
+            let current = match self.read_one(watchpoint.handle.clone()) {
+                Ok(bytes) => bytes.to_vec(),
+                Err(err) => {
+                    log::warn!("Can't read watchpoint {:?}: {err}", watchpoint.id);
+                    continue;
+                }
+            };
+            let elements = watchpoint
+                .last
+                .chunks(watchpoint.elem_size)
+                .zip(current.chunks(watchpoint.elem_size))
+                .enumerate()
+                .filter(|(_, (last, current))| last != current)
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+
+            if !elements.is_empty() {
+                let kernel = type_name_format(kernel, TypeNameFormatLevel::Balanced);
+                log::info!(
+                    "Watchpoint {:?} changed by {kernel}:{}",
+                    watchpoint.id,
+                    watchpoint.describe(&elements, &current)
+                );
+                watchpoint.events.push(WatchEvent {
+                    kernel,
+                    elements: elements
+                        .into_iter()
+                        .map(|index| watchpoint.start + index)
+                        .collect(),
+                });
+            }
+
+            watchpoint.last = current;
+        }
+    }
+
+    /// Whether any watchpoint is registered on the device.
+    pub(super) fn has_watchpoints(&self) -> bool {
+        !self.utilities.watchpoints.lock().watchpoints.is_empty()
+    }
+}
+
+impl Watchpoint {
+    fn describe(&self, elements: &[usize], current: &[u8]) -> String {
+        let mut description = String::new();
+
+        for index in elements.iter().take(MAX_LOGGED_CHANGES) {
+            let bytes = index * self.elem_size..(index + 1) * self.elem_size;
+            let _ = write!(
+                description,
+                "\n  [{}] {:02x?} -> {:02x?}",
+                self.start + index,
+                &self.last[bytes.clone()],
+                &current[bytes]
+            );
+        }
+        if elements.len() > MAX_LOGGED_CHANGES {
+            let _ = write!(
+                description,
+                "\n  ... {} more",
+                elements.len() - MAX_LOGGED_CHANGES
+            );
+        }
+
+        description
+    }
+}
+
+/// Restrict the handle to the given range of elements.
+fn slice(handle: &Handle, elem_size: usize, range: &Range<usize>) -> Handle {
+    let len = handle.size_in_used() as usize / elem_size;
+    assert!(
+        range.start <= range.end && range.end <= len,
+        "The watched range {range:?} is out of the {len} elements of the buffer"
+    );
+
+    handle
+        .clone()
+        .offset_start((range.start * elem_size) as u64)
+        .offset_end(((len - range.end) * elem_size) as u64)
+}
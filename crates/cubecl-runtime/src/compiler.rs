@@ -88,4 +88,11 @@ pub trait Compiler: Sync + Send + 'static + Clone + core::fmt::Debug {
     /// The default extension for the runtime's kernel/shader code.
     /// Might change based on which compiler is used.
     fn extension(&self) -> &'static str;
+
+    /// Whether the backend compiles the [source](crate::kernel::CompiledKernel::source) of the
+    /// kernels, rather than their [representation](Self::Representation). Only then can the
+    /// source be replaced, e.g. by an edit in hot-reload mode.
+    fn compiles_source(&self) -> bool {
+        true
+    }
 }
@@ -1,3 +1,4 @@
+use crate::config::experimental::ExperimentalConfig;
 use crate::config::memory::MemoryConfig;
 use crate::config::streaming::StreamingConfig;
 
@@ -32,6 +33,10 @@ pub struct CubeClRuntimeConfig {
     /// Configuration for memory settings.
     #[serde(default)]
     pub memory: MemoryConfig,
+
+    /// Configuration for experimental features.
+    #[serde(default)]
+    pub experimental: ExperimentalConfig,
 }
 
 impl RuntimeConfig for CubeClRuntimeConfig {
@@ -115,6 +120,26 @@ impl RuntimeConfig for CubeClRuntimeConfig {
             }
         };
 
+        if let Ok(val) = std::env::var("CUBECL_HOT_RELOAD") {
+            self.compilation.hot_reload = Some(val.into());
+        }
+
+        if let Ok(val) = std::env::var("CUBECL_COVERAGE") {
+            self.compilation.coverage = matches!(val.as_str(), "1" | "true");
+        }
+
+        if let Ok(val) = std::env::var("CUBECL_ARITHMETIC_TRAPS") {
+            self.compilation.arithmetic_traps = matches!(val.as_str(), "1" | "true");
+        }
+
+        if let Ok(val) = std::env::var("CUBECL_EXPERIMENTAL") {
+            self.experimental.enabled = val
+                .split(',')
+                .map(|feature| feature.trim().to_string())
+                .filter(|feature| !feature.is_empty())
+                .collect();
+        }
+
         if let Ok(val) = std::env::var("CUBECL_AUTOTUNE_LEVEL") {
             match val.as_str() {
                 "minimal" | "0" => {
@@ -12,6 +12,18 @@ pub struct CompilationConfig {
     #[serde(default)]
     #[cfg(std_io)]
     pub cache: Option<CacheConfig>,
+    /// Directory where the generated kernel sources are written, and edited sources are picked
+    /// up, when the `hot-reload` feature is enabled.
+    #[serde(default)]
+    #[cfg(std_io)]
+    pub hot_reload: Option<std::path::PathBuf>,
+    /// Whether kernels are instrumented with a counter per block to report code coverage.
+    #[serde(default)]
+    pub coverage: bool,
+    /// Whether kernels are instrumented with checks for integer arithmetic exceptions, e.g.
+    /// divisions by zero.
+    #[serde(default)]
+    pub arithmetic_traps: bool,
     /// Controls whether kernel launches enforce bounds checks.
     #[serde(default)]
     pub check_mode: BoundsCheckMode,
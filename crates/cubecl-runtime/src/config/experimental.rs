@@ -0,0 +1,22 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Configuration of the experimental features, which are disabled unless listed.
+///
+/// Experimental features are algorithms shipped before they are trusted, e.g. a new matmul
+/// variant. Enabling them for a process allows comparing runs with and without them.
+#[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ExperimentalConfig {
+    /// The names of the enabled features, or `all` to enable every feature.
+    #[serde(default)]
+    pub enabled: Vec<String>,
+}
+
+impl ExperimentalConfig {
+    /// Whether the experimental feature with the given name is enabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled
+            .iter()
+            .any(|feature| feature == name || feature == "all")
+    }
+}
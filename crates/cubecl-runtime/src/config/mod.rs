@@ -5,6 +5,8 @@ pub mod autotune;
 pub mod cache;
 /// Compilation config module.
 pub mod compilation;
+/// Experimental features config module.
+pub mod experimental;
 /// Memory config module.
 pub mod memory;
 /// Profiling config module.
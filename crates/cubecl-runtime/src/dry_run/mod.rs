@@ -0,0 +1,30 @@
+//! Simulation of the memory used by a sequence of launches, without a device.
+//!
+//! The [dry-run runtime](DryRunRuntime) goes through the same memory management as the other
+//! runtimes, but never allocates device memory nor executes kernels. Allocations, deallocations
+//! and the shared memory required by every launch are recorded in a [report](DryRunReport), so
+//! the peak memory of a model configuration can be estimated on a machine without the target GPU:
+//!
+//! ```ignore
+//! let mut properties = DryRunServer::default_properties();
+//! properties.hardware.max_shared_memory_size = 100 * 1024;
+//!
+//! let device = DryRunDevice::new(1);
+//! let server = DryRunServer::new(properties, MemoryConfiguration::default());
+//! let client = ComputeClient::<DryRunRuntime>::init(&device, server);
+//!
+//! run_model(&client);
+//!
+//! let report = client.info().report();
+//! println!("Peak memory: {} bytes", report.peak_bytes_reserved);
+//! ```
+
+mod report;
+mod runtime;
+mod server;
+mod storage;
+
+pub use report::*;
+pub use runtime::*;
+pub use server::*;
+pub use storage::*;
@@ -0,0 +1,110 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use crate::server::CubeDim;
+
+/// An event recorded by the [dry-run server](super::DryRunServer).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DryRunEvent {
+    /// A new page of device memory was allocated.
+    Allocate {
+        /// The size of the page in bytes.
+        size: u64,
+    },
+    /// A page of device memory was deallocated.
+    Deallocate {
+        /// The size of the page in bytes.
+        size: u64,
+    },
+    /// A kernel was launched.
+    Launch(DryRunLaunch),
+}
+
+/// A kernel launch recorded by the [dry-run server](super::DryRunServer).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DryRunLaunch {
+    /// The name of the kernel.
+    pub name: &'static str,
+    /// The number of cubes launched, or `None` if the count is read from a buffer.
+    pub cube_count: Option<(u32, u32, u32)>,
+    /// The dimensions of a cube.
+    pub cube_dim: CubeDim,
+    /// The shared memory required by every cube, in bytes.
+    pub shared_memory: usize,
+}
+
+/// The memory used by the launches of a dry-run client.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DryRunReport {
+    /// Every allocation, deallocation and launch, in order.
+    pub events: Vec<DryRunEvent>,
+    /// The bytes of device memory currently reserved by the memory pools.
+    pub bytes_reserved: u64,
+    /// The most bytes of device memory reserved at once, which is what the device must provide.
+    pub peak_bytes_reserved: u64,
+    /// The most bytes used by live buffers at once, excluding padding and unused pool memory.
+    pub peak_bytes_in_use: u64,
+    /// The most shared memory required by a single launch, in bytes.
+    pub peak_shared_memory: usize,
+}
+
+impl DryRunReport {
+    /// The recorded launches, in order.
+    pub fn launches(&self) -> impl Iterator<Item = &DryRunLaunch> {
+        self.events.iter().filter_map(|event| match event {
+            DryRunEvent::Launch(launch) => Some(launch),
+            _ => None,
+        })
+    }
+}
+
+/// Shared access to the report of a dry-run server, available from the client with
+/// [`ComputeClient::info`](crate::client::ComputeClient::info).
+#[derive(Clone, Debug, Default)]
+pub struct DryRunRecorder {
+    report: Arc<spin::Mutex<DryRunReport>>,
+}
+
+impl DryRunRecorder {
+    /// A snapshot of the report.
+    ///
+    /// Work is recorded when the server processes it, so the client should be synced first.
+    pub fn report(&self) -> DryRunReport {
+        self.report.lock().clone()
+    }
+
+    /// Clear the events and reset the peaks to the memory currently reserved, to measure another
+    /// sequence of launches.
+    pub fn reset(&self) {
+        let mut report = self.report.lock();
+        let bytes_reserved = report.bytes_reserved;
+        *report = DryRunReport {
+            bytes_reserved,
+            peak_bytes_reserved: bytes_reserved,
+            ..Default::default()
+        };
+    }
+
+    pub(crate) fn allocate(&self, size: u64) {
+        let mut report = self.report.lock();
+        report.events.push(DryRunEvent::Allocate { size });
+        report.bytes_reserved += size;
+        report.peak_bytes_reserved = report.peak_bytes_reserved.max(report.bytes_reserved);
+    }
+
+    pub(crate) fn deallocate(&self, size: u64) {
+        let mut report = self.report.lock();
+        report.events.push(DryRunEvent::Deallocate { size });
+        report.bytes_reserved -= size;
+    }
+
+    pub(crate) fn launch(&self, launch: DryRunLaunch) {
+        let mut report = self.report.lock();
+        report.peak_shared_memory = report.peak_shared_memory.max(launch.shared_memory);
+        report.events.push(DryRunEvent::Launch(launch));
+    }
+
+    pub(crate) fn in_use(&self, bytes: u64) {
+        let mut report = self.report.lock();
+        report.peak_bytes_in_use = report.peak_bytes_in_use.max(bytes);
+    }
+}
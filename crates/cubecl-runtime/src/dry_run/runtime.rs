@@ -0,0 +1,194 @@
+use alloc::{string::String, vec::Vec};
+use cubecl_common::device::{Device, DeviceId, DeviceService, ServerUtilitiesHandle};
+use cubecl_ir::{AddressSpace, Branch, ElemType, Operation, Scope, StorageType, TargetProperties};
+use cubecl_zspace::{Shape, Strides};
+
+use super::DryRunServer;
+use crate::{
+    client::ComputeClient,
+    compiler::{CompilationError, Compiler},
+    kernel::KernelDefinition,
+    memory_management::MemoryConfiguration,
+    runtime::Runtime,
+    server::{ComputeServer, ExecutionMode},
+};
+
+/// A simulated device of the [dry-run runtime](DryRunRuntime).
+///
+/// Every index is a separate device, so configurations can be simulated side by side.
+#[derive(new, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct DryRunDevice {
+    /// The index of the device.
+    pub index: u16,
+}
+
+impl Device for DryRunDevice {
+    fn from_id(device_id: DeviceId) -> Self {
+        Self {
+            index: device_id.index_id,
+        }
+    }
+
+    fn to_id(&self) -> DeviceId {
+        DeviceId {
+            type_id: 0,
+            index_id: self.index,
+        }
+    }
+}
+
+impl DeviceService for DryRunServer {
+    fn init(_device_id: DeviceId) -> Self {
+        DryRunServer::new(
+            DryRunServer::default_properties(),
+            MemoryConfiguration::default(),
+        )
+    }
+
+    fn utilities(&self) -> ServerUtilitiesHandle {
+        ComputeServer::utilities(self) as ServerUtilitiesHandle
+    }
+}
+
+/// A kernel compiled by the [dry-run compiler](DryRunCompiler).
+#[derive(Clone, Debug)]
+pub struct DryRunKernel {
+    /// The name of the kernel.
+    pub name: String,
+    /// The shared memory required by every cube, in bytes.
+    pub shared_memory: usize,
+}
+
+impl core::fmt::Display for DryRunKernel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}: {} bytes of shared memory",
+            self.name, self.shared_memory
+        )
+    }
+}
+
+/// A compiler that only computes the resources a kernel requires.
+#[derive(Clone, Debug)]
+pub struct DryRunCompiler;
+
+impl Compiler for DryRunCompiler {
+    type Representation = DryRunKernel;
+    type CompilationOptions = ();
+
+    fn compile(
+        &mut self,
+        kernel: KernelDefinition,
+        _compilation_options: &Self::CompilationOptions,
+        _mode: ExecutionMode,
+        _addr_type: StorageType,
+    ) -> Result<Self::Representation, CompilationError> {
+        Ok(DryRunKernel {
+            name: kernel.options.kernel_name,
+            shared_memory: shared_memory_size(&kernel.body, 0),
+        })
+    }
+
+    fn elem_size(&self, elem: ElemType) -> usize {
+        elem.size()
+    }
+
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+}
+
+/// Shared memory of the scope laid out after `offset`, respecting the alignment of every
+/// declaration.
+fn shared_memory_size(scope: &Scope, mut offset: usize) -> usize {
+    for instruction in scope.instructions.borrow().iter() {
+        match &instruction.operation {
+            Operation::DeclareVariable {
+                value_ty,
+                addr_space: AddressSpace::Shared,
+                alignment,
+            } => {
+                offset = offset.next_multiple_of(*alignment) + value_ty.size();
+            }
+            Operation::Branch(branch) => {
+                offset = match branch {
+                    Branch::If(op) => shared_memory_size(&op.scope, offset),
+                    Branch::IfElse(op) => {
+                        let offset = shared_memory_size(&op.scope_if, offset);
+                        shared_memory_size(&op.scope_else, offset)
+                    }
+                    Branch::Switch(op) => op.cases.iter().fold(
+                        shared_memory_size(&op.scope_default, offset),
+                        |offset, (_, case)| shared_memory_size(case, offset),
+                    ),
+                    Branch::RangeLoop(op) => shared_memory_size(&op.scope, offset),
+                    Branch::Loop(op) => shared_memory_size(&op.scope, offset),
+                    Branch::Return | Branch::Break | Branch::Unreachable => offset,
+                }
+            }
+            _ => {}
+        }
+    }
+    offset
+}
+
+/// A runtime that simulates the memory used by launches without executing them, see
+/// [the module documentation](super).
+#[derive(Clone, Debug)]
+pub struct DryRunRuntime;
+
+impl Runtime for DryRunRuntime {
+    type Compiler = DryRunCompiler;
+    type Server = DryRunServer;
+    type Device = DryRunDevice;
+
+    fn client(device: &Self::Device) -> ComputeClient<Self> {
+        ComputeClient::load(device)
+    }
+
+    fn name(_client: &ComputeClient<Self>) -> &'static str {
+        "dry-run"
+    }
+
+    fn max_cube_count() -> (u32, u32, u32) {
+        (u32::MAX, u16::MAX as u32, u16::MAX as u32)
+    }
+
+    fn can_read_tensor(_shape: &Shape, _strides: &Strides) -> bool {
+        true
+    }
+
+    fn target_properties() -> TargetProperties {
+        TargetProperties {
+            // Values are irrelevant, since no kernel is executed
+            mma: Default::default(),
+        }
+    }
+
+    fn enumerate_devices(_: u16, _: &<Self::Server as ComputeServer>::Info) -> Vec<DeviceId> {
+        Vec::from([DeviceId {
+            type_id: 0,
+            index_id: 0,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cubecl_ir::{FloatKind, Type, UIntKind};
+
+    #[test_log::test]
+    fn shared_memory_respects_alignment() {
+        let scope = Scope::root(false);
+        scope.create_shared(
+            Type::array(Type::scalar(ElemType::Float(FloatKind::F32)), 256),
+            None,
+        );
+        scope.create_shared(Type::scalar(ElemType::UInt(UIntKind::U8)), None);
+        scope.create_shared(Type::scalar(ElemType::Float(FloatKind::F64)), None);
+
+        assert_eq!(shared_memory_size(&scope, 0), 1024 + 8 + 8);
+    }
+}
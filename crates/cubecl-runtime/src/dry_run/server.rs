@@ -0,0 +1,264 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+use cubecl_common::{
+    bytes::Bytes,
+    future::DynFut,
+    profile::{ProfileDuration, TimingMethod},
+    stream_id::StreamId,
+    stub::Arc,
+};
+use cubecl_ir::{
+    AddressType, DeviceProperties, ElemType, FloatKind, HardwareProperties, IntKind,
+    MemoryDeviceProperties, UIntKind, VectorSize,
+    features::{Features, TypeUsage},
+};
+use hashbrown::HashMap;
+
+use super::{DryRunCompiler, DryRunLaunch, DryRunRecorder, DryRunResource, DryRunStorage};
+use crate::{
+    allocator::ContiguousMemoryLayoutPolicy,
+    compiler::CubeTask,
+    id::KernelId,
+    logging::ServerLogger,
+    memory_management::{
+        ManagedMemoryHandle, MemoryAllocationMode, MemoryConfiguration, MemoryManagement,
+        MemoryManagementOptions, MemoryUsage,
+    },
+    server::{
+        Binding, ComputeServer, CopyDescriptor, CubeCount, CubeDim, ExecutionMode, KernelArguments,
+        ProfileError, ProfilingToken, ServerCommunication, ServerError, ServerUtilities,
+    },
+    storage::ManagedResource,
+    timestamp_profiler::TimestampProfiler,
+};
+
+const ALIGNMENT: u64 = 256;
+
+/// A server that records the memory used by the work submitted to it without executing anything.
+///
+/// Buffers are managed by the same memory pools as the other runtimes, so the reserved memory
+/// matches what a device with the same properties would allocate. Reads return zeroed bytes.
+#[derive(Debug)]
+pub struct DryRunServer {
+    memory_management: MemoryManagement<DryRunStorage>,
+    recorder: DryRunRecorder,
+    kernels: HashMap<KernelId, (CubeDim, usize)>,
+    timestamps: TimestampProfiler,
+    utilities: Arc<ServerUtilities<Self>>,
+}
+
+impl DryRunServer {
+    /// Simulate a device with the given properties, managing memory with `memory_config`.
+    pub fn new(properties: DeviceProperties, memory_config: MemoryConfiguration) -> Self {
+        let recorder = DryRunRecorder::default();
+        let logger = Arc::new(ServerLogger::default());
+        let storage = DryRunStorage::new(properties.memory.alignment as usize, recorder.clone());
+        let memory_management = MemoryManagement::from_configuration(
+            storage,
+            &properties.memory,
+            memory_config,
+            logger.clone(),
+            MemoryManagementOptions::new("Dry-run Memory"),
+        );
+        let alignment = properties.memory.alignment as usize;
+        let utilities = ServerUtilities::new(
+            properties,
+            logger,
+            recorder.clone(),
+            ContiguousMemoryLayoutPolicy::new(alignment),
+        );
+
+        Self {
+            memory_management,
+            recorder,
+            kernels: HashMap::new(),
+            timestamps: TimestampProfiler::default(),
+            utilities: Arc::new(utilities),
+        }
+    }
+
+    /// The properties of a generic GPU supporting every common type, used when no properties
+    /// are provided.
+    pub fn default_properties() -> DeviceProperties {
+        let hardware = HardwareProperties {
+            load_width: 128,
+            plane_size_min: 32,
+            plane_size_max: 32,
+            max_bindings: 32,
+            max_shared_memory_size: 48 * 1024,
+            max_cube_count: (u32::MAX, u16::MAX as u32, u16::MAX as u32),
+            max_units_per_cube: 1024,
+            max_cube_dim: (1024, 1024, 64),
+            num_streaming_multiprocessors: None,
+            num_tensor_cores: None,
+            min_tensor_cores_dim: None,
+            num_cpu_cores: None,
+            max_vector_size: VectorSize::MAX,
+            cube_mma_reserved_shared_memory: 0,
+        };
+        let memory = MemoryDeviceProperties {
+            max_page_size: 1024 * 1024 * 512,
+            alignment: ALIGNMENT,
+        };
+        let mut properties =
+            DeviceProperties::new(Features::default(), memory, hardware, TimingMethod::System);
+
+        properties.register_address_type(AddressType::U32);
+        properties.register_address_type(AddressType::U64);
+        let types = [
+            ElemType::UInt(UIntKind::U8),
+            ElemType::UInt(UIntKind::U16),
+            ElemType::UInt(UIntKind::U32),
+            ElemType::UInt(UIntKind::U64),
+            ElemType::Int(IntKind::I8),
+            ElemType::Int(IntKind::I16),
+            ElemType::Int(IntKind::I32),
+            ElemType::Int(IntKind::I64),
+            ElemType::Float(FloatKind::BF16),
+            ElemType::Float(FloatKind::F16),
+            ElemType::Float(FloatKind::F32),
+            ElemType::Float(FloatKind::F64),
+        ];
+        for ty in types {
+            properties.register_type_usage(ty, TypeUsage::all());
+        }
+
+        properties
+    }
+
+    fn record_usage(&self) {
+        self.recorder
+            .in_use(self.memory_management.memory_usage().bytes_in_use);
+    }
+}
+
+impl ServerCommunication for DryRunServer {
+    const SERVER_COMM_ENABLED: bool = false;
+}
+
+impl ComputeServer for DryRunServer {
+    type Kernel = Box<dyn CubeTask<DryRunCompiler>>;
+    type Info = DryRunRecorder;
+    type MemoryLayoutPolicy = ContiguousMemoryLayoutPolicy;
+    type Storage = DryRunStorage;
+
+    fn initialize_memory(&mut self, memory: ManagedMemoryHandle, size: u64, _stream_id: StreamId) {
+        let reserved = self.memory_management.reserve(size).unwrap();
+        self.memory_management.bind(reserved, memory, 0).unwrap();
+        self.record_usage();
+    }
+
+    fn staging(
+        &mut self,
+        sizes: &[usize],
+        _stream_id: StreamId,
+    ) -> Result<Vec<Bytes>, ServerError> {
+        Ok(sizes
+            .iter()
+            .map(|size| Bytes::from_bytes_vec(vec![0; *size]))
+            .collect())
+    }
+
+    fn logger(&self) -> Arc<ServerLogger> {
+        self.utilities.logger.clone()
+    }
+
+    fn utilities(&self) -> Arc<ServerUtilities<Self>> {
+        self.utilities.clone()
+    }
+
+    fn read(
+        &mut self,
+        descriptors: Vec<CopyDescriptor>,
+        _stream_id: StreamId,
+    ) -> DynFut<Result<Vec<Bytes>, ServerError>> {
+        let bytes = descriptors
+            .iter()
+            .map(|descriptor| {
+                let size = descriptor.handle.size_in_used() as usize;
+                Bytes::from_bytes_vec(vec![0; size])
+            })
+            .collect();
+
+        Box::pin(async move { Ok(bytes) })
+    }
+
+    fn write(&mut self, _descriptors: Vec<(CopyDescriptor, Bytes)>, _stream_id: StreamId) {
+        // Nothing is stored, the buffers were already accounted for when initialized.
+    }
+
+    fn sync(&mut self, _stream_id: StreamId) -> DynFut<Result<(), ServerError>> {
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn get_resource(
+        &mut self,
+        binding: Binding,
+        _stream_id: StreamId,
+    ) -> Result<ManagedResource<DryRunResource>, ServerError> {
+        let resource = self.memory_management.get_resource(
+            binding.memory.clone(),
+            binding.offset_start,
+            binding.offset_end,
+        )?;
+
+        Ok(ManagedResource::new(binding.memory, resource))
+    }
+
+    unsafe fn launch(
+        &mut self,
+        kernel: Self::Kernel,
+        count: CubeCount,
+        _bindings: KernelArguments,
+        mode: ExecutionMode,
+        _stream_id: StreamId,
+    ) {
+        let (cube_dim, shared_memory) = *self.kernels.entry(kernel.id()).or_insert_with(|| {
+            let compiled = kernel
+                .compile(&mut DryRunCompiler, &(), mode, kernel.address_type())
+                .unwrap();
+            let shared_memory = compiled.repr.map(|repr| repr.shared_memory);
+            (compiled.cube_dim, shared_memory.unwrap_or_default())
+        });
+        let cube_count = match count {
+            CubeCount::Static(x, y, z) => Some((x, y, z)),
+            CubeCount::Dynamic(_) => None,
+        };
+
+        self.recorder.launch(DryRunLaunch {
+            name: kernel.name(),
+            cube_count,
+            cube_dim,
+            shared_memory,
+        });
+        self.record_usage();
+    }
+
+    fn flush(&mut self, _stream_id: StreamId) -> Result<(), ServerError> {
+        // Nothing is executed.
+        Ok(())
+    }
+
+    fn memory_usage(&mut self, _stream_id: StreamId) -> Result<MemoryUsage, ServerError> {
+        Ok(self.memory_management.memory_usage())
+    }
+
+    fn memory_cleanup(&mut self, _stream_id: StreamId) {
+        self.memory_management.cleanup(true);
+    }
+
+    fn start_profile(&mut self, _stream_id: StreamId) -> Result<ProfilingToken, ServerError> {
+        Ok(self.timestamps.start())
+    }
+
+    fn end_profile(
+        &mut self,
+        _stream_id: StreamId,
+        token: ProfilingToken,
+    ) -> Result<ProfileDuration, ProfileError> {
+        self.timestamps.stop(token)
+    }
+
+    fn allocation_mode(&mut self, mode: MemoryAllocationMode, _stream_id: StreamId) {
+        self.memory_management.mode(mode)
+    }
+}
@@ -0,0 +1,68 @@
+use hashbrown::HashMap;
+
+use super::DryRunRecorder;
+use crate::{
+    server::IoError,
+    storage::{ComputeStorage, StorageHandle, StorageId, StorageUtilization},
+};
+
+/// A storage that records allocations without reserving any memory.
+#[derive(Debug)]
+pub struct DryRunStorage {
+    sizes: HashMap<StorageId, u64>,
+    alignment: usize,
+    recorder: DryRunRecorder,
+}
+
+/// The resource of a [dry-run storage](DryRunStorage), which has no backing memory.
+#[derive(Debug)]
+pub struct DryRunResource {
+    /// The part of the allocation the resource refers to.
+    pub utilization: StorageUtilization,
+}
+
+impl DryRunStorage {
+    /// Record the allocations in `recorder`, with the given alignment.
+    pub fn new(alignment: usize, recorder: DryRunRecorder) -> Self {
+        Self {
+            sizes: HashMap::new(),
+            alignment,
+            recorder,
+        }
+    }
+}
+
+impl ComputeStorage for DryRunStorage {
+    type Resource = DryRunResource;
+
+    fn alignment(&self) -> usize {
+        self.alignment
+    }
+
+    fn get(&mut self, handle: &StorageHandle) -> Self::Resource {
+        DryRunResource {
+            utilization: handle.utilization.clone(),
+        }
+    }
+
+    fn alloc(&mut self, size: u64) -> Result<StorageHandle, IoError> {
+        let id = StorageId::new();
+        self.sizes.insert(id, size);
+        self.recorder.allocate(size);
+
+        Ok(StorageHandle {
+            id,
+            utilization: StorageUtilization { offset: 0, size },
+        })
+    }
+
+    fn dealloc(&mut self, id: StorageId) {
+        if let Some(size) = self.sizes.remove(&id) {
+            self.recorder.deallocate(size);
+        }
+    }
+
+    fn flush(&mut self) {
+        // Nothing to flush, deallocations are recorded immediately.
+    }
+}
@@ -0,0 +1,466 @@
+use crate::{
+    client::ComputeClient,
+    registry::{KernelRegistry, KernelRegistryError},
+    runtime::Runtime,
+    server::{
+        CubeCount, Handle, IoError, KernelArguments, LaunchError, MetadataBindingInfo, ServerError,
+    },
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::ffi::{CStr, c_char};
+use cubecl_common::device::{Device, DeviceId};
+
+/// Status returned by the fallible functions of the C API.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeclStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// The kernel name isn't valid UTF-8.
+    InvalidName = 2,
+    /// No kernel is registered under the given name.
+    KernelNotFound = 3,
+    /// The output buffer is smaller than the data to read.
+    BufferTooSmall = 4,
+    /// The server returned an error not covered by the other statuses.
+    ServerError = 5,
+    /// A kernel is already registered under the given name.
+    KernelAlreadyRegistered = 6,
+    /// The server rejected an operation during validation.
+    ValidationError = 7,
+    /// A kernel can't be compiled.
+    CompilationError = 8,
+    /// A kernel requests more resources than the device has.
+    TooManyResources = 9,
+    /// The device is out of memory.
+    OutOfMemory = 10,
+    /// A memory operation failed.
+    IoError = 11,
+    /// The call panicked, the panic was caught instead of unwinding into C code.
+    Panic = 12,
+}
+
+impl From<&KernelRegistryError> for CubeclStatus {
+    fn from(err: &KernelRegistryError) -> Self {
+        match err {
+            KernelRegistryError::NotFound { .. } => CubeclStatus::KernelNotFound,
+            KernelRegistryError::AlreadyRegistered { .. } => CubeclStatus::KernelAlreadyRegistered,
+        }
+    }
+}
+
+impl From<&ServerError> for CubeclStatus {
+    fn from(err: &ServerError) -> Self {
+        match err {
+            ServerError::Validation { .. } => CubeclStatus::ValidationError,
+            ServerError::Launch(err) => err.into(),
+            ServerError::Io(err) => err.into(),
+            // The status of the first error recorded by the stream, which caused the others.
+            ServerError::ServerUnhealthy { errors, .. } => errors
+                .first()
+                .map_or(CubeclStatus::ServerError, CubeclStatus::from),
+            _ => CubeclStatus::ServerError,
+        }
+    }
+}
+
+impl From<&LaunchError> for CubeclStatus {
+    fn from(err: &LaunchError) -> Self {
+        match err {
+            LaunchError::CompilationError(_) => CubeclStatus::CompilationError,
+            LaunchError::TooManyResources(_) => CubeclStatus::TooManyResources,
+            LaunchError::OutOfMemory { .. } => CubeclStatus::OutOfMemory,
+            LaunchError::IoError(err) => err.into(),
+            LaunchError::Unknown { .. } => CubeclStatus::ServerError,
+        }
+    }
+}
+
+impl From<&IoError> for CubeclStatus {
+    fn from(err: &IoError) -> Self {
+        match err {
+            IoError::BufferTooBig { .. } => CubeclStatus::OutOfMemory,
+            IoError::Execution(err) => err.as_ref().into(),
+            _ => CubeclStatus::IoError,
+        }
+    }
+}
+
+/// A compute client owned by C code.
+pub struct FfiClient<R: Runtime> {
+    client: ComputeClient<R>,
+}
+
+/// A device buffer owned by C code.
+pub struct FfiHandle {
+    handle: Handle,
+    size: usize,
+}
+
+/// Create a client on the device with the given id, returning null if it can't be created.
+pub fn client_create<R: Runtime>(type_id: u16, index_id: u16) -> *mut FfiClient<R> {
+    guard(core::ptr::null_mut(), || {
+        let device = R::Device::from_id(DeviceId { type_id, index_id });
+        let client = R::client(&device);
+
+        Box::into_raw(Box::new(FfiClient { client }))
+    })
+}
+
+/// Destroy a client created with [`client_create`].
+///
+/// # Safety
+///
+/// `client` must be null or a pointer returned by [`client_create`] that wasn't destroyed yet.
+pub unsafe fn client_destroy<R: Runtime>(client: *mut FfiClient<R>) {
+    guard((), || {
+        if !client.is_null() {
+            // SAFETY: Guaranteed by the caller.
+            drop(unsafe { Box::from_raw(client) });
+        }
+    })
+}
+
+/// Upload `len` bytes to the device, returning null if a pointer is null.
+///
+/// The upload is queued on the client's stream, so its errors, e.g. running out of memory, are
+/// returned by the next [`launch`] or [`read`] on the client instead.
+///
+/// # Safety
+///
+/// `client` must be a valid client and `data` must point to `len` readable bytes.
+pub unsafe fn upload<R: Runtime>(
+    client: *const FfiClient<R>,
+    data: *const u8,
+    len: usize,
+) -> *mut FfiHandle {
+    guard(core::ptr::null_mut(), || {
+        if client.is_null() || (data.is_null() && len > 0) {
+            return core::ptr::null_mut();
+        }
+        // SAFETY: Guaranteed by the caller.
+        let (client, data) = unsafe {
+            let data = match len {
+                0 => &[],
+                _ => core::slice::from_raw_parts(data, len),
+            };
+            (&*client, data)
+        };
+
+        handle_into_raw(client.client.create_from_slice(data), len)
+    })
+}
+
+/// Allocate `len` uninitialized bytes on the device, returning null if the client is null.
+///
+/// Like [`upload`], allocation errors are returned by the next [`launch`] or [`read`].
+///
+/// # Safety
+///
+/// `client` must be a valid client.
+pub unsafe fn empty<R: Runtime>(client: *const FfiClient<R>, len: usize) -> *mut FfiHandle {
+    guard(core::ptr::null_mut(), || {
+        if client.is_null() {
+            return core::ptr::null_mut();
+        }
+        // SAFETY: Guaranteed by the caller.
+        let client = unsafe { &*client };
+
+        handle_into_raw(client.client.empty(len), len)
+    })
+}
+
+/// The size in bytes of a buffer, or zero if the handle is null.
+///
+/// # Safety
+///
+/// `handle` must be null or a valid handle.
+pub unsafe fn handle_size(handle: *const FfiHandle) -> usize {
+    // SAFETY: Guaranteed by the caller.
+    unsafe { handle.as_ref() }.map_or(0, |handle| handle.size)
+}
+
+/// Release a buffer. The memory is reused once the kernels using it are completed.
+///
+/// # Safety
+///
+/// `handle` must be null or a handle that wasn't destroyed yet.
+pub unsafe fn handle_destroy(handle: *mut FfiHandle) {
+    guard((), || {
+        if !handle.is_null() {
+            // SAFETY: Guaranteed by the caller.
+            drop(unsafe { Box::from_raw(handle) });
+        }
+    })
+}
+
+/// Launch the kernel registered under `name` with the given buffers and info as bindings.
+///
+/// The bindings are passed to the kernel as is: `info` holds the `info_len` words of the scalars
+/// and metadata of the kernel, packed in the layout it was compiled with, i.e. the scalars sorted
+/// by type followed by the static metadata, see [`MetadataBindingInfo`]. Kernels reading a
+/// scalar, the length or the shape of a buffer need it, while kernels only indexing their buffers
+/// with known sizes can pass null and zero.
+///
+/// The launch is flushed before returning, so compilation, resource and launch errors, or the
+/// errors of earlier operations queued on the client's stream, are returned as a status.
+///
+/// # Safety
+///
+/// `client` must be a valid client, `name` a nul-terminated string, `count` must point to three
+/// values, `handles` to `num_handles` valid handles and `info` to `info_len` values.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn launch<R: Runtime>(
+    registry: &KernelRegistry<R>,
+    client: *const FfiClient<R>,
+    name: *const c_char,
+    count: *const u32,
+    handles: *const *const FfiHandle,
+    num_handles: usize,
+    info: *const u64,
+    info_len: usize,
+) -> CubeclStatus {
+    guard(CubeclStatus::Panic, || {
+        if client.is_null() || name.is_null() || count.is_null() {
+            return CubeclStatus::NullPointer;
+        }
+        if (handles.is_null() && num_handles > 0) || (info.is_null() && info_len > 0) {
+            return CubeclStatus::NullPointer;
+        }
+
+        // SAFETY: Guaranteed by the caller.
+        let (client, name, count, handles, info) = unsafe {
+            let handles = match num_handles {
+                0 => &[],
+                _ => core::slice::from_raw_parts(handles, num_handles),
+            };
+            let info = match info_len {
+                0 => &[],
+                _ => core::slice::from_raw_parts(info, info_len),
+            };
+            (
+                &*client,
+                CStr::from_ptr(name),
+                core::slice::from_raw_parts(count, 3),
+                handles,
+                info,
+            )
+        };
+
+        let Ok(name) = name.to_str() else {
+            return CubeclStatus::InvalidName;
+        };
+        let mut buffers = Vec::with_capacity(handles.len());
+        for handle in handles {
+            // SAFETY: Guaranteed by the caller.
+            match unsafe { handle.as_ref() } {
+                Some(handle) => buffers.push(handle.handle.clone().binding()),
+                None => return CubeclStatus::NullPointer,
+            }
+        }
+
+        let count = CubeCount::Static(count[0], count[1], count[2]);
+        let arguments = KernelArguments::new()
+            .with_buffers(buffers)
+            .with_info(MetadataBindingInfo::custom(info.to_vec()));
+        if let Err(err) = registry.launch(&client.client, name, count, arguments) {
+            return (&err).into();
+        }
+
+        // The launch is asynchronous, flushing reports the errors it recorded on the stream.
+        match client.client.flush() {
+            Ok(()) => CubeclStatus::Ok,
+            Err(err) => (&err).into(),
+        }
+    })
+}
+
+/// Read a buffer into `out`, waiting for the kernels writing to it to complete.
+///
+/// # Safety
+///
+/// `client` and `handle` must be valid, and `out` must point to `len` writable bytes.
+pub unsafe fn read<R: Runtime>(
+    client: *const FfiClient<R>,
+    handle: *const FfiHandle,
+    out: *mut u8,
+    len: usize,
+) -> CubeclStatus {
+    guard(CubeclStatus::Panic, || {
+        if client.is_null() || handle.is_null() || out.is_null() {
+            return CubeclStatus::NullPointer;
+        }
+        // SAFETY: Guaranteed by the caller.
+        let (client, handle) = unsafe { (&*client, &*handle) };
+
+        if len < handle.size {
+            return CubeclStatus::BufferTooSmall;
+        }
+
+        match client.client.read_one(handle.handle.clone()) {
+            Ok(bytes) => {
+                // SAFETY: `out` has at least `handle.size` writable bytes, which is the size of
+                // the buffer that was read.
+                unsafe {
+                    core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len().min(len));
+                }
+                CubeclStatus::Ok
+            }
+            Err(err) => (&err).into(),
+        }
+    })
+}
+
+fn handle_into_raw(handle: Handle, size: usize) -> *mut FfiHandle {
+    Box::into_raw(Box::new(FfiHandle { handle, size }))
+}
+
+/// Run `fun`, returning `on_panic` if it panics, since unwinding into C code isn't allowed.
+fn guard<T>(on_panic: T, fun: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "std")]
+    {
+        std::panic::catch_unwind(core::panic::AssertUnwindSafe(fun)).unwrap_or(on_panic)
+    }
+
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = on_panic;
+        fun()
+    }
+}
+
+/// Export the C API for the given runtime, launching the kernels of the given
+/// [registry](crate::registry::KernelRegistry).
+///
+/// This must be invoked once in the `cdylib` or `staticlib` embedding `CubeCL`, and exports the
+/// functions declared in `include/cubecl.h`:
+///
+/// ```rust,ignore
+/// static KERNELS: KernelRegistry<WgpuRuntime> = KernelRegistry::new();
+///
+/// cubecl_runtime::export_c_api!(WgpuRuntime, KERNELS);
+/// ```
+///
+/// Kernels must be registered in the registry before C code launches them, typically from an
+/// initialization function exported by the same library.
+#[macro_export]
+macro_rules! export_c_api {
+    ($runtime:ty, $registry:expr) => {
+        /// Create a client on the device with the given id.
+        #[unsafe(no_mangle)]
+        pub extern "C" fn cubecl_client_create(
+            type_id: u16,
+            index_id: u16,
+        ) -> *mut $crate::ffi::FfiClient<$runtime> {
+            $crate::ffi::client_create::<$runtime>(type_id, index_id)
+        }
+
+        /// Destroy a client.
+        ///
+        /// # Safety
+        ///
+        /// See [`client_destroy`]($crate::ffi::client_destroy).
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn cubecl_client_destroy(
+            client: *mut $crate::ffi::FfiClient<$runtime>,
+        ) {
+            unsafe { $crate::ffi::client_destroy(client) }
+        }
+
+        /// Upload bytes to the device.
+        ///
+        /// # Safety
+        ///
+        /// See [`upload`]($crate::ffi::upload).
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn cubecl_upload(
+            client: *const $crate::ffi::FfiClient<$runtime>,
+            data: *const u8,
+            len: usize,
+        ) -> *mut $crate::ffi::FfiHandle {
+            unsafe { $crate::ffi::upload(client, data, len) }
+        }
+
+        /// Allocate bytes on the device.
+        ///
+        /// # Safety
+        ///
+        /// See [`empty`]($crate::ffi::empty).
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn cubecl_empty(
+            client: *const $crate::ffi::FfiClient<$runtime>,
+            len: usize,
+        ) -> *mut $crate::ffi::FfiHandle {
+            unsafe { $crate::ffi::empty(client, len) }
+        }
+
+        /// The size in bytes of a buffer.
+        ///
+        /// # Safety
+        ///
+        /// See [`handle_size`]($crate::ffi::handle_size).
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn cubecl_handle_size(
+            handle: *const $crate::ffi::FfiHandle,
+        ) -> usize {
+            unsafe { $crate::ffi::handle_size(handle) }
+        }
+
+        /// Release a buffer.
+        ///
+        /// # Safety
+        ///
+        /// See [`handle_destroy`]($crate::ffi::handle_destroy).
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn cubecl_handle_destroy(handle: *mut $crate::ffi::FfiHandle) {
+            unsafe { $crate::ffi::handle_destroy(handle) }
+        }
+
+        /// Launch a registered kernel by name.
+        ///
+        /// # Safety
+        ///
+        /// See [`launch`]($crate::ffi::launch).
+        #[unsafe(no_mangle)]
+        #[allow(clippy::too_many_arguments)]
+        pub unsafe extern "C" fn cubecl_launch(
+            client: *const $crate::ffi::FfiClient<$runtime>,
+            name: *const ::core::ffi::c_char,
+            count: *const u32,
+            handles: *const *const $crate::ffi::FfiHandle,
+            num_handles: usize,
+            info: *const u64,
+            info_len: usize,
+        ) -> $crate::ffi::CubeclStatus {
+            unsafe {
+                $crate::ffi::launch(
+                    &$registry,
+                    client,
+                    name,
+                    count,
+                    handles,
+                    num_handles,
+                    info,
+                    info_len,
+                )
+            }
+        }
+
+        /// Read a buffer back to the host.
+        ///
+        /// # Safety
+        ///
+        /// See [`read`]($crate::ffi::read).
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn cubecl_read(
+            client: *const $crate::ffi::FfiClient<$runtime>,
+            handle: *const $crate::ffi::FfiHandle,
+            out: *mut u8,
+            len: usize,
+        ) -> $crate::ffi::CubeclStatus {
+            unsafe { $crate::ffi::read(client, handle, out, len) }
+        }
+    };
+}
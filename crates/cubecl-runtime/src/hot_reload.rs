@@ -0,0 +1,307 @@
+//! Development mode where the generated source of every compiled kernel is written to a
+//! directory, and edits to those files are compiled in place of the generated code.
+//!
+//! Enabled with the `hot-reload` feature and the `compilation.hot_reload` directory of the
+//! config, or the `CUBECL_HOT_RELOAD` environment variable. Each kernel is written as
+//! `{name}-{hash}-{mode}.{extension}`, next to a `.generated` copy of the original source. An
+//! edited file is picked up by the next launch of the kernel, which is recompiled; deleting it
+//! restores the generated code. Edits are discarded when the generated code changes, e.g. after
+//! modifying the Rust kernel.
+//!
+//! The edited source must keep the signature of the generated one, since the bindings and launch
+//! configuration still come from the kernel definition. Backends compiling the intermediate
+//! representation instead of the source, e.g. SPIR-V or MLIR, return a compilation error for edited
+//! kernels, see [`Compiler::compiles_source`](crate::compiler::Compiler::compiles_source).
+
+use crate::{
+    compiler::CompilationError,
+    config::{CubeClRuntimeConfig, RuntimeConfig},
+    id::KernelId,
+    server::ExecutionMode,
+};
+use alloc::{format, string::String};
+use cubecl_common::{
+    backtrace::BackTrace,
+    hash::{StableHash, StableHasher},
+};
+use hashbrown::HashMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::SystemTime,
+};
+
+/// A kernel source written to the hot-reload directory.
+struct KernelFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    revision: u64,
+}
+
+static FILES: spin::Mutex<Option<HashMap<(StableHash, ExecutionMode), KernelFile>>> =
+    spin::Mutex::new(None);
+
+fn directory() -> Option<&'static Path> {
+    static DIRECTORY: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    DIRECTORY
+        .get_or_init(|| {
+            let directory = CubeClRuntimeConfig::get().compilation.hot_reload.clone()?;
+            if let Err(err) = fs::create_dir_all(&directory) {
+                log::warn!("Can't create the hot-reload directory {directory:?}: {err}");
+                return None;
+            }
+            Some(directory)
+        })
+        .as_deref()
+}
+
+/// The revision of the edited sources of a kernel, `0` while none of them is edited.
+///
+/// Added to the [kernel id](KernelId), so an edit invalidates the compiled kernel.
+pub(crate) fn revision(id: &KernelId) -> u64 {
+    if directory().is_none() {
+        return 0;
+    }
+
+    revision_of(id.stable_hash())
+}
+
+fn revision_of(hash: StableHash) -> u64 {
+    let mut files = FILES.lock();
+    let Some(files) = files.as_mut() else {
+        return 0;
+    };
+
+    let mut revision = 0u64;
+    for mode in [
+        ExecutionMode::Checked,
+        ExecutionMode::Validate,
+        ExecutionMode::Unchecked,
+    ] {
+        if let Some(file) = files.get_mut(&(hash, mode)) {
+            revision = revision.rotate_left(21) ^ file.refresh();
+        }
+    }
+
+    revision
+}
+
+/// Write the generated source of a kernel to the hot-reload directory, and replace it with the
+/// edited version of the file, if any.
+///
+/// Returns an error when the file is edited but the backend doesn't compile the source.
+pub(crate) fn apply(
+    name: &str,
+    id: &KernelId,
+    mode: ExecutionMode,
+    extension: &str,
+    compiles_source: bool,
+    source: &mut String,
+) -> Result<(), CompilationError> {
+    let Some(directory) = directory() else {
+        return Ok(());
+    };
+
+    apply_in(
+        directory,
+        name,
+        id.stable_hash(),
+        mode,
+        extension,
+        compiles_source,
+        source,
+    )
+}
+
+fn apply_in(
+    directory: &Path,
+    name: &str,
+    hash: StableHash,
+    mode: ExecutionMode,
+    extension: &str,
+    compiles_source: bool,
+    source: &mut String,
+) -> Result<(), CompilationError> {
+    let path = directory.join(format!("{name}-{:x}-{mode:?}.{extension}", hash as u64));
+    let generated_path = path.with_extension(format!("{extension}.generated"));
+
+    match fs::read_to_string(&generated_path) {
+        Ok(generated) if &generated == source && path.exists() => match fs::read_to_string(&path) {
+            Ok(edited) if &edited != source && !compiles_source => {
+                return Err(CompilationError::Generic {
+                    reason: format!(
+                        "Can't hot-reload {path:?}, the {extension} backend compiles the \
+                         intermediate representation instead of the source. Delete the file to \
+                         restore the generated code."
+                    ),
+                    backtrace: BackTrace::capture(),
+                });
+            }
+            Ok(edited) if &edited != source => {
+                log::info!("Hot-reloading {path:?}");
+                *source = edited;
+            }
+            Ok(_) => {}
+            Err(err) => log::warn!("Can't read {path:?}: {err}"),
+        },
+        // First compilation, or the kernel changed since the file was written.
+        _ => {
+            if let Err(err) = fs::write(&path, &source).and(fs::write(&generated_path, &source)) {
+                log::warn!("Can't write {path:?}: {err}");
+            }
+        }
+    }
+
+    let mut file = KernelFile {
+        path,
+        modified: None,
+        revision: 0,
+    };
+    file.refresh();
+    FILES
+        .lock()
+        .get_or_insert_with(HashMap::new)
+        .insert((hash, mode), file);
+
+    Ok(())
+}
+
+impl KernelFile {
+    fn refresh(&mut self) -> u64 {
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        if modified != self.modified {
+            self.modified = modified;
+            self.revision = match (
+                fs::read_to_string(&self.path),
+                fs::read_to_string(self.path.with_extension(self.generated_extension())),
+            ) {
+                (Ok(edited), Ok(generated)) if edited != generated => {
+                    StableHasher::hash_one(&edited) as u64
+                }
+                _ => 0,
+            };
+        }
+
+        self.revision
+    }
+
+    fn generated_extension(&self) -> String {
+        let extension = self.path.extension().and_then(|ext| ext.to_str());
+        format!("{}.generated", extension.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(
+        directory: &Path,
+        hash: StableHash,
+        generated: &str,
+        compiles_source: bool,
+    ) -> String {
+        let mut source = String::from(generated);
+        apply_in(
+            directory,
+            "kernel",
+            hash,
+            ExecutionMode::Checked,
+            "wgsl",
+            compiles_source,
+            &mut source,
+        )
+        .map(|_| source)
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn path(directory: &Path, hash: StableHash) -> PathBuf {
+        directory.join(format!("kernel-{:x}-Checked.wgsl", hash as u64))
+    }
+
+    /// Edit the file with a later modification time, which could otherwise be the same as the
+    /// generated one on file systems with a coarse clock.
+    fn edit(path: &Path, source: &str) {
+        fs::write(path, source).unwrap();
+        let modified = SystemTime::now() + core::time::Duration::from_secs(1);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(modified))
+            .unwrap();
+    }
+
+    #[test_log::test]
+    fn edits_replace_the_generated_source() {
+        let directory = tempfile::tempdir().unwrap();
+        let hash = 1;
+
+        assert_eq!(
+            compile(directory.path(), hash, "generated", true),
+            "generated"
+        );
+        assert_eq!(
+            fs::read_to_string(path(directory.path(), hash)).unwrap(),
+            "generated"
+        );
+        assert_eq!(revision_of(hash), 0);
+
+        edit(&path(directory.path(), hash), "edited");
+        assert_ne!(revision_of(hash), 0);
+        assert_eq!(compile(directory.path(), hash, "generated", true), "edited");
+
+        // Deleting the file restores the generated code.
+        fs::remove_file(path(directory.path(), hash)).unwrap();
+        assert_eq!(revision_of(hash), 0);
+        assert_eq!(
+            compile(directory.path(), hash, "generated", true),
+            "generated"
+        );
+    }
+
+    #[test_log::test]
+    fn edits_are_discarded_when_the_kernel_changes() {
+        let directory = tempfile::tempdir().unwrap();
+        let hash = 2;
+
+        compile(directory.path(), hash, "generated", true);
+        edit(&path(directory.path(), hash), "edited");
+
+        assert_eq!(compile(directory.path(), hash, "changed", true), "changed");
+        assert_eq!(
+            fs::read_to_string(path(directory.path(), hash)).unwrap(),
+            "changed"
+        );
+        assert_eq!(revision_of(hash), 0);
+    }
+
+    #[test_log::test]
+    fn edits_are_rejected_when_the_source_isnt_compiled() {
+        let directory = tempfile::tempdir().unwrap();
+        let hash = 3;
+
+        assert_eq!(
+            compile(directory.path(), hash, "generated", false),
+            "generated"
+        );
+        edit(&path(directory.path(), hash), "edited");
+
+        let mut source = String::from("generated");
+        let result = apply_in(
+            directory.path(),
+            "kernel",
+            hash,
+            ExecutionMode::Checked,
+            "wgsl",
+            false,
+            &mut source,
+        );
+        assert!(matches!(result, Err(CompilationError::Generic { .. })));
+        assert_eq!(source, "generated");
+    }
+}
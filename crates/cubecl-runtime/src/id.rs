@@ -5,6 +5,7 @@ use core::{
     any::{Any, TypeId},
     fmt::Display,
     hash::{Hash, Hasher},
+    sync::atomic::{AtomicI8, Ordering},
 };
 use cubecl_common::{
     format::{DebugRaw, format_str},
@@ -13,7 +14,10 @@ use cubecl_common::{
 use cubecl_ir::AddressType;
 use derive_more::{Eq, PartialEq};
 
-use crate::server::{CubeDim, ExecutionMode};
+use crate::{
+    config::{CubeClRuntimeConfig, RuntimeConfig},
+    server::{CubeDim, ExecutionMode},
+};
 
 #[macro_export(local_inner_macros)]
 /// Create a new storage ID type.
@@ -48,6 +52,46 @@ macro_rules! storage_id_type {
     };
 }
 
+static INSTRUMENTATION: AtomicI8 = AtomicI8::new(-1);
+
+/// The debugging instrumentations of a kernel, which add bindings after its arguments.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Instrumentation {
+    /// Whether the kernel is instrumented with a counter per block to report code coverage.
+    pub coverage: bool,
+    /// Whether the kernel is instrumented with checks for integer arithmetic exceptions.
+    pub arithmetic_traps: bool,
+}
+
+impl Instrumentation {
+    /// The instrumentations enabled in the config, which is only read on the first call.
+    pub fn from_config() -> Self {
+        let bits = match INSTRUMENTATION.load(Ordering::Relaxed) {
+            -1 => {
+                let compilation = &CubeClRuntimeConfig::get().compilation;
+                let bits = compilation.coverage as i8 | (compilation.arithmetic_traps as i8) << 1;
+                INSTRUMENTATION.store(bits, Ordering::Relaxed);
+                bits
+            }
+            bits => bits,
+        };
+
+        Self {
+            coverage: bits & 1 != 0,
+            arithmetic_traps: bits & 2 != 0,
+        }
+    }
+
+    fn suffix(&self) -> Option<&'static str> {
+        match (self.coverage, self.arithmetic_traps) {
+            (false, false) => None,
+            (true, false) => Some("coverage"),
+            (false, true) => Some("traps"),
+            (true, true) => Some("coverage-traps"),
+        }
+    }
+}
+
 /// Kernel unique identifier.
 #[derive(Clone, PartialEq, Eq)]
 pub struct KernelId {
@@ -59,6 +103,11 @@ pub struct KernelId {
     pub cube_dim: CubeDim,
     pub(crate) mode: ExecutionMode,
     pub(crate) info: Option<Info>,
+    /// Revision of the hot-reloaded source, `0` unless it was edited.
+    pub(crate) revision: u64,
+    /// The [instrumentation](Instrumentation) of the kernel, which must match the buffers added
+    /// when the kernel is built.
+    pub instrumentation: Instrumentation,
 }
 
 impl Hash for KernelId {
@@ -68,6 +117,8 @@ impl Hash for KernelId {
         self.cube_dim.hash(state);
         self.mode.hash(state);
         self.info.hash(state);
+        self.revision.hash(state);
+        self.instrumentation.hash(state);
     }
 }
 
@@ -110,6 +161,8 @@ impl KernelId {
             type_id: core::any::TypeId::of::<T>(),
             type_name: core::any::type_name::<T>(),
             info: None,
+            revision: 0,
+            instrumentation: Instrumentation::default(),
             cube_dim: CubeDim::new_single(),
             mode: ExecutionMode::Checked,
             address_type: Default::default(),
@@ -120,10 +173,20 @@ impl KernelId {
     ///
     /// Can be used as a persistent kernel cache key.
     pub fn stable_format(&self) -> String {
-        format!(
+        let format = format!(
             "{}-{}-{:?}-{:?}-{:?}",
             self.type_name, self.address_type, self.cube_dim, self.mode, self.info
-        )
+        );
+
+        let format = match self.revision {
+            0 => format,
+            revision => format!("{format}-{revision}"),
+        };
+
+        match self.instrumentation.suffix() {
+            Some(instrumentation) => format!("{format}-{instrumentation}"),
+            None => format,
+        }
     }
 
     /// Hash the key in a stable way that can be used between runs.
@@ -136,6 +199,12 @@ impl KernelId {
         self.cube_dim.hash(&mut hasher);
         self.mode.hash(&mut hasher);
         self.info.hash(&mut hasher);
+        if self.revision != 0 {
+            self.revision.hash(&mut hasher);
+        }
+        if let Some(instrumentation) = self.instrumentation.suffix() {
+            instrumentation.hash(&mut hasher);
+        }
 
         hasher.finalize()
     }
@@ -152,6 +221,13 @@ impl KernelId {
         self
     }
 
+    /// Set the revision of the hot-reloaded source.
+    #[cfg(all(feature = "hot-reload", std_io))]
+    pub(crate) fn revision(mut self, revision: u64) -> Self {
+        self.revision = revision;
+        self
+    }
+
     /// Set the [execution mode](ExecutionMode).
     pub fn mode(&mut self, mode: ExecutionMode) {
         self.mode = mode;
@@ -168,6 +244,12 @@ impl KernelId {
         self.address_type = addr_ty;
         self
     }
+
+    /// Set the [instrumentation](Instrumentation).
+    pub fn instrumentation(mut self, instrumentation: Instrumentation) -> Self {
+        self.instrumentation = instrumentation;
+        self
+    }
 }
 
 impl core::fmt::Debug for Info {
@@ -263,4 +345,21 @@ mod tests {
         assert!(set.contains(&value_1));
         assert!(!set.contains(&value_2));
     }
+
+    #[test_log::test]
+    pub fn kernel_id_instrumentation() {
+        let plain = KernelId::new::<()>().info("1");
+        let covered = plain.clone().instrumentation(Instrumentation {
+            coverage: true,
+            arithmetic_traps: false,
+        });
+
+        assert_ne!(plain, covered);
+        assert_ne!(plain.stable_hash(), covered.stable_hash());
+        assert!(covered.stable_format().ends_with("-coverage"));
+        assert_eq!(
+            plain.stable_format(),
+            KernelId::new::<()>().info("1").stable_format()
+        );
+    }
 }
@@ -35,10 +35,12 @@ pub trait KernelMetadata: Send + Sync + 'static {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(std_io, derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct KernelDefinition {
     pub buffers: Vec<KernelArg>,
     pub tensor_maps: Vec<KernelArg>,
+    pub textures: Vec<KernelArg>,
     pub scalars: Vec<ScalarKernelArg>,
     pub cube_dim: CubeDim,
     pub body: Scope,
@@ -46,13 +48,14 @@ pub struct KernelDefinition {
 }
 
 impl KernelDefinition {
-    /// Returns the total number of global buffers (including tensor maps)
+    /// Returns the total number of global buffers (including tensor maps and textures)
     pub fn num_global_buffers(&self) -> usize {
-        self.buffers.len() + self.tensor_maps.len()
+        self.buffers.len() + self.tensor_maps.len() + self.textures.len()
     }
 }
 
 #[derive(Default, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(std_io, derive(serde::Serialize, serde::Deserialize))]
 /// Options for a specific kernel compilation
 pub struct KernelOptions {
     /// The name of the kernel
@@ -180,11 +183,23 @@ impl<C: Compiler, K: CubeKernel> CubeTask<C> for KernelTask<C, K> {
         let entrypoint_name = gpu_ir.options.kernel_name.clone();
         let cube_dim = gpu_ir.cube_dim;
         let lower_level_ir = compiler.compile(gpu_ir, compilation_options, mode, addr_type)?;
+        #[allow(unused_mut, reason = "Used in hot-reload")]
+        let mut source = lower_level_ir.to_string();
+
+        #[cfg(all(feature = "hot-reload", std_io))]
+        crate::hot_reload::apply(
+            &entrypoint_name,
+            &self.kernel_definition.id(),
+            mode,
+            compiler.extension(),
+            compiler.compiles_source(),
+            &mut source,
+        )?;
 
         Ok(CompiledKernel {
             entrypoint_name,
             debug_name: Some(core::any::type_name::<K>()),
-            source: lower_level_ir.to_string(),
+            source,
             repr: Some(lower_level_ir),
             cube_dim,
             debug_info: None,
@@ -195,7 +210,15 @@ impl<C: Compiler, K: CubeKernel> CubeTask<C> for KernelTask<C, K> {
 impl<C: Compiler, K: CubeKernel> KernelMetadata for KernelTask<C, K> {
     // Forward ID to underlying kernel definition.
     fn id(&self) -> KernelId {
-        self.kernel_definition.id()
+        let id = self.kernel_definition.id();
+
+        #[cfg(all(feature = "hot-reload", std_io))]
+        let id = {
+            let revision = crate::hot_reload::revision(&id);
+            id.revision(revision)
+        };
+
+        id
     }
 
     // Forward name to underlying kernel definition.
@@ -208,6 +231,26 @@ impl<C: Compiler, K: CubeKernel> KernelMetadata for KernelTask<C, K> {
     }
 }
 
+impl KernelMetadata for Box<dyn CubeKernel> {
+    fn id(&self) -> KernelId {
+        self.as_ref().id()
+    }
+
+    fn name(&self) -> &'static str {
+        self.as_ref().name()
+    }
+
+    fn address_type(&self) -> StorageType {
+        self.as_ref().address_type()
+    }
+}
+
+impl CubeKernel for Box<dyn CubeKernel> {
+    fn define(&self) -> KernelDefinition {
+        self.as_ref().define()
+    }
+}
+
 impl<C: Compiler> KernelMetadata for Box<dyn CubeTask<C>> {
     // Deref and use existing ID.
     fn id(&self) -> KernelId {
@@ -0,0 +1,205 @@
+use alloc::{
+    rc::Rc,
+    string::{String, ToString},
+};
+use core::cell::RefCell;
+
+use cubecl_ir::GlobalStateInner;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::kernel::KernelDefinition;
+
+/// Current version of the serialized kernel format.
+///
+/// Bumped whenever a change to the IR breaks the format, so kernels serialized by another version
+/// of the frontend are rejected instead of being lowered incorrectly.
+pub const KERNEL_FORMAT_VERSION: u32 = 1;
+
+/// Oldest version of the serialized kernel format that can still be read.
+pub const MIN_KERNEL_FORMAT_VERSION: u32 = 1;
+
+/// Upgrades of the serialized kernel format, where the one at index `i` rewrites a kernel of
+/// version `MIN_KERNEL_FORMAT_VERSION + i` to the next version.
+///
+/// Bumping [`KERNEL_FORMAT_VERSION`] requires an upgrade from the previous version, unless
+/// [`MIN_KERNEL_FORMAT_VERSION`] is bumped as well.
+const UPGRADES: [fn(&mut Value); (KERNEL_FORMAT_VERSION - MIN_KERNEL_FORMAT_VERSION) as usize] = [];
+
+/// Error when reading a [serialized kernel](KernelDefinition::from_json).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KernelFormatError {
+    /// The kernel was serialized with a version of the format that can't be read.
+    #[error(
+        "Kernel format version {version} isn't supported, expected a version between {MIN_KERNEL_FORMAT_VERSION} and {KERNEL_FORMAT_VERSION}"
+    )]
+    UnsupportedVersion {
+        /// The version of the serialized kernel.
+        version: u32,
+    },
+    /// The serialized kernel is malformed.
+    #[error("Invalid serialized kernel: {reason}")]
+    Invalid {
+        /// The reason the kernel couldn't be read.
+        reason: String,
+    },
+}
+
+#[derive(Serialize)]
+struct SerializedKernelRef<'a> {
+    version: u32,
+    definition: &'a KernelDefinition,
+    state: &'a GlobalStateInner,
+}
+
+#[derive(Deserialize)]
+struct SerializedKernel {
+    definition: KernelDefinition,
+    state: GlobalStateInner,
+}
+
+impl KernelDefinition {
+    /// Serialize the kernel to a stable, versioned format, so it can be lowered to the target by
+    /// another process or machine, or cached across versions of the frontend.
+    ///
+    /// Device properties aren't serialized, they should be set by the compiler of the device the
+    /// kernel is lowered on.
+    pub fn to_json(&self) -> String {
+        let state = self.body.state();
+        let kernel = SerializedKernelRef {
+            version: KERNEL_FORMAT_VERSION,
+            definition: self,
+            state: &state,
+        };
+        serde_json::to_string(&kernel).expect("Kernel definitions can always be serialized")
+    }
+
+    /// Read a kernel serialized with [`to_json`](Self::to_json).
+    ///
+    /// The version is checked before the rest of the kernel is read, so kernels from an
+    /// unsupported version of the format are reported as such instead of as malformed. Kernels
+    /// from an older supported version are upgraded to the current one first.
+    pub fn from_json(json: &str) -> Result<Self, KernelFormatError> {
+        let invalid = |err: serde_json::Error| KernelFormatError::Invalid {
+            reason: err.to_string(),
+        };
+
+        let mut kernel: Value = serde_json::from_str(json).map_err(invalid)?;
+        let version = kernel
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| KernelFormatError::Invalid {
+                reason: "Missing format version".into(),
+            })?;
+        let version = u32::try_from(version)
+            .ok()
+            .filter(|version| (MIN_KERNEL_FORMAT_VERSION..=KERNEL_FORMAT_VERSION).contains(version))
+            .ok_or(KernelFormatError::UnsupportedVersion {
+                version: version.min(u32::MAX as u64) as u32,
+            })?;
+
+        for upgrade in &UPGRADES[(version - MIN_KERNEL_FORMAT_VERSION) as usize..] {
+            upgrade(&mut kernel);
+        }
+
+        let SerializedKernel {
+            mut definition,
+            state,
+        } = serde_json::from_value(kernel).map_err(invalid)?;
+        // Every deserialized scope has its own empty state, they must share the kernel's one.
+        definition
+            .body
+            .attach_global_state(Rc::new(RefCell::new(state)));
+
+        Ok(definition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kernel::KernelOptions, server::CubeDim};
+    use alloc::{boxed::Box, format, vec::Vec};
+    use cubecl_ir::{Branch, ElemType, If, Scope, Type};
+
+    fn kernel() -> KernelDefinition {
+        let body = Scope::root(false);
+        let cond = body.create_value(Type::scalar(ElemType::Bool));
+        let scope = body.child();
+        scope.create_value(Type::scalar(ElemType::Bool));
+        body.register(Branch::If(Box::new(If { cond, scope })));
+
+        KernelDefinition {
+            buffers: Vec::new(),
+            tensor_maps: Vec::new(),
+            textures: Vec::new(),
+            scalars: Vec::new(),
+            cube_dim: CubeDim::new_1d(32),
+            body,
+            options: KernelOptions {
+                kernel_name: "kernel".into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test_log::test]
+    fn kernel_format_round_trip() {
+        let kernel = kernel();
+        let deserialized = KernelDefinition::from_json(&kernel.to_json()).unwrap();
+
+        assert_eq!(format!("{}", deserialized.body), format!("{}", kernel.body));
+        assert_eq!(deserialized.options, kernel.options);
+        assert_eq!(deserialized.cube_dim, kernel.cube_dim);
+
+        // Nested scopes share the allocator, which continues where the original one stopped.
+        let instructions = deserialized.body.instructions.borrow();
+        let cubecl_ir::Operation::Branch(Branch::If(op)) = &instructions[0].operation else {
+            panic!("Expected an if branch");
+        };
+        assert!(Rc::ptr_eq(
+            &op.scope.global_state,
+            &deserialized.body.global_state
+        ));
+        assert_eq!(op.scope.new_local_index(), kernel.body.new_local_index());
+    }
+
+    /// A kernel serialized with version 1 of the format, which must stay readable as long as
+    /// [`MIN_KERNEL_FORMAT_VERSION`] is 1.
+    const KERNEL_FORMAT_V1: &str = include_str!("../tests/fixtures/kernel_format_v1.json");
+
+    #[test_log::test]
+    fn kernel_format_reads_v1_fixture() {
+        let kernel = kernel();
+        let deserialized = KernelDefinition::from_json(KERNEL_FORMAT_V1).unwrap();
+
+        assert_eq!(format!("{}", deserialized.body), format!("{}", kernel.body));
+        assert_eq!(deserialized.options, kernel.options);
+        assert_eq!(deserialized.cube_dim, kernel.cube_dim);
+    }
+
+    #[test_log::test]
+    fn kernel_format_matches_v1_fixture() {
+        // Changes to the serialized format must bump the version and add an upgrade.
+        let fixture: Value = serde_json::from_str(KERNEL_FORMAT_V1).unwrap();
+        let serialized: Value = serde_json::from_str(&kernel().to_json()).unwrap();
+
+        if KERNEL_FORMAT_VERSION == 1 {
+            assert_eq!(serialized, fixture);
+        }
+    }
+
+    #[test_log::test]
+    fn kernel_format_unsupported_version() {
+        let json = kernel().to_json().replace(
+            &format!("\"version\":{KERNEL_FORMAT_VERSION}"),
+            "\"version\":0",
+        );
+
+        assert_eq!(
+            KernelDefinition::from_json(&json).unwrap_err(),
+            KernelFormatError::UnsupportedVersion { version: 0 }
+        );
+    }
+}
@@ -17,6 +17,13 @@ pub mod id;
 /// Kernel related traits.
 pub mod kernel;
 
+/// Stable, versioned serialization of kernel definitions.
+#[cfg(std_io)]
+pub mod kernel_format;
+
+#[cfg(all(feature = "hot-reload", std_io))]
+mod hot_reload;
+
 /// Stream related utilities.
 pub mod stream;
 
@@ -26,6 +33,12 @@ pub mod client;
 /// Autotune module
 pub mod tune;
 
+/// Registry of kernels launched by name, for plugin systems and scripting layers.
+pub mod registry;
+
+/// Stable C API to launch registered kernels from non-Rust applications.
+pub mod ffi;
+
 /// Memory management module.
 pub mod memory_management;
 /// Compute server module.
@@ -33,6 +46,9 @@ pub mod server;
 /// Compute Storage module.
 pub mod storage;
 
+/// Memory simulation without a device.
+pub mod dry_run;
+
 /// `CubeCL` config module.
 pub mod config;
 
@@ -44,6 +60,9 @@ pub mod logging;
 /// TMA-related runtime types
 pub mod tma;
 
+/// Texture-related runtime types
+pub mod texture;
+
 /// Compiler trait and related types
 pub mod compiler;
 /// Runtime trait and related types
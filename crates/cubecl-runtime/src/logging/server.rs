@@ -18,7 +18,7 @@ enum LogMessage {
     Streaming(String),
     Memory(String),
     Profile(String, ProfileDuration),
-    ProfileSummary,
+    ProfileSummary(Option<u64>),
 }
 
 /// Server logger.
@@ -79,6 +79,7 @@ impl Default for ServerLogger {
             message: rec,
             logger,
             profiled: Default::default(),
+            energy: None,
         };
         // Spawn the future in the background to logs messages / durations.
         spawn_detached_fut(async_logger.process());
@@ -166,12 +167,15 @@ impl ServerLogger {
     }
 
     /// Show the profiling summary if activated and reset its state.
-    pub fn profile_summary(&self) {
+    ///
+    /// The `energy` is the energy counter of the device in millijoules, if available, used to
+    /// report the energy consumed since the previous summary.
+    pub fn profile_summary(&self, energy: Option<u64>) {
         if let Some(channel) = &self.log_channel
             && self.profile_level.is_some()
         {
             // Channel will never be full, don't care if it's closed.
-            let _ = channel.try_send(LogMessage::ProfileSummary);
+            let _ = channel.try_send(LogMessage::ProfileSummary(energy));
         }
     }
 }
@@ -180,6 +184,7 @@ struct AsyncLogger {
     message: Receiver<LogMessage>,
     logger: Logger,
     profiled: Profiled,
+    energy: Option<u64>,
 }
 
 impl AsyncLogger {
@@ -204,10 +209,19 @@ impl AsyncLogger {
                 LogMessage::Execution(name) => {
                     self.logger.log_profiling(&format!("Executing {name}"));
                 }
-                LogMessage::ProfileSummary => {
+                LogMessage::ProfileSummary(energy) => {
                     if !self.profiled.is_empty() {
                         self.logger.log_profiling(&self.profiled);
                         self.profiled = Profiled::default();
+
+                        if let (Some(start), Some(end)) = (self.energy, energy) {
+                            let consumed = end.saturating_sub(start);
+                            self.logger
+                                .log_profiling(&format!("Energy consumed: {consumed} mJ"));
+                        }
+                    }
+                    if energy.is_some() {
+                        self.energy = energy;
                     }
                 }
             }
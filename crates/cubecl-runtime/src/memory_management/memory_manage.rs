@@ -1,6 +1,6 @@
 use super::{
     MemoryConfiguration, MemoryPoolOptions, MemoryUsage, PoolType,
-    memory_pool::{ExclusiveMemoryPool, MemoryPool, PersistentPool, SlicedPool},
+    memory_pool::{DedicatedPool, ExclusiveMemoryPool, MemoryPool, PersistentPool, SlicedPool},
 };
 use crate::{
     config::{
@@ -117,6 +117,7 @@ pub enum MemoryAllocationMode {
 pub struct MemoryManagement<Storage> {
     name: String,
     persistent: PersistentPool,
+    dedicated: DedicatedPool,
     pools: Vec<DynamicPool>,
     storage: Storage,
     alloc_reserve_count: u64,
@@ -347,6 +348,7 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
                 properties.alignment,
                 pools.len() as u8,
             ),
+            dedicated: DedicatedPool::new(pools.len() as u8 + 1),
             pools,
             storage,
             alloc_reserve_count: 0,
@@ -385,6 +387,7 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
 
         self.persistent
             .cleanup(&mut self.storage, self.alloc_reserve_count, explicit);
+        self.dedicated.cleanup(&mut self.storage);
 
         for pool in self.pools.iter_mut() {
             pool.cleanup(&mut self.storage, self.alloc_reserve_count, explicit);
@@ -401,7 +404,10 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
     fn find(&self, binding: ManagedMemoryBinding) -> Result<&Slice, IoError> {
         let id = binding.descriptor();
 
-        if id.location().pool >= self.pools.len() as u8 {
+        if id.location().pool > self.pools.len() as u8 {
+            return self.dedicated.find(&binding);
+        }
+        if id.location().pool == self.pools.len() as u8 {
             return self.persistent.find(&binding);
         }
 
@@ -521,6 +527,57 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
         allocated
     }
 
+    /// Finds a spot in memory for a resource with the given size in bytes starting at a multiple of
+    /// `alignment` bytes, and returns a handle to it.
+    ///
+    /// Pooled memory is aligned to the storage alignment, so larger alignments get an allocation
+    /// of their own, which is deallocated on [cleanup](Self::cleanup) once it isn't used anymore.
+    pub fn reserve_aligned(
+        &mut self,
+        size: u64,
+        alignment: u64,
+    ) -> Result<ManagedMemoryHandle, IoError> {
+        if alignment <= self.storage.alignment() as u64 {
+            return self.reserve(size);
+        }
+
+        self.alloc_reserve_count += 1;
+        let storage = self.storage.alloc_aligned(size, alignment)?;
+
+        self.logger.log_memory(
+            |level| !matches!(level, MemoryLogLevel::Disabled),
+            || {
+                format!(
+                    "[{}] Allocated memory {} aligned to {alignment} bytes",
+                    self.name,
+                    BytesFormat::new(size)
+                )
+            },
+        );
+
+        Ok(self.dedicated.insert(storage))
+    }
+
+    /// Manage memory allocated directly with the storage, e.g. host memory wrapped without a
+    /// copy, and return a handle to [bind](Self::bind). The memory is tracked as a dedicated
+    /// allocation outside of the pools, so it's never reused for other reservations and is
+    /// deallocated on [cleanup](Self::cleanup) once it isn't used anymore.
+    pub fn reserve_storage(&mut self, storage: StorageHandle) -> ManagedMemoryHandle {
+        let size = storage.size();
+        self.logger.log_memory(
+            |level| !matches!(level, MemoryLogLevel::Disabled),
+            || {
+                format!(
+                    "[{}] Reserved memory {} allocated by the storage",
+                    self.name,
+                    BytesFormat::new(size)
+                )
+            },
+        );
+
+        self.dedicated.insert(storage)
+    }
+
     /// Fetch the storage used by the memory manager.
     ///
     /// # Notes
@@ -546,7 +603,9 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
             },
             |m1, m2| m1.combine(m2),
         );
-        memory_usage.combine(self.persistent.get_memory_usage())
+        memory_usage
+            .combine(self.persistent.get_memory_usage())
+            .combine(self.dedicated.get_memory_usage())
     }
 
     /// Print out a report of the current memory usage.
@@ -572,7 +631,10 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
         }
 
         let pool_index = descriptor.location().pool as usize;
-        if pool_index >= self.pools.len() {
+        if pool_index > self.pools.len() {
+            return self.dedicated.bind(reserved, assigned, cursor);
+        }
+        if pool_index == self.pools.len() {
             return self.persistent.bind(reserved, assigned, cursor);
         }
 
@@ -591,6 +653,7 @@ impl<Storage: ComputeStorage> core::fmt::Display for MemoryManagement<Storage> {
         f.write_str("\n# MemoryManagement\n\n")?;
         f.write_fmt(format_args!(" - name: {:?}\n", self.name))?;
         f.write_fmt(format_args!("\n## Persistent\n\n{}", self.persistent))?;
+        f.write_fmt(format_args!("\n## Dedicated\n\n{}", self.dedicated))?;
         f.write_str("\n## Dynamic\n\n")?;
 
         for pool in self.pools.iter() {
@@ -623,6 +686,7 @@ mod tests {
     use super::*;
     use crate::{memory_management::MemoryManagement, storage::BytesStorage};
     use alloc::vec;
+    use cubecl_common::bytes::Bytes;
 
     const DUMMY_MEM_PROPS: MemoryDeviceProperties = MemoryDeviceProperties {
         max_page_size: 128 * 1024 * 1024,
@@ -1098,4 +1162,39 @@ mod tests {
         assert_eq!(usage_before.bytes_in_use, usage_after.bytes_in_use);
         assert_eq!(usage_before.bytes_reserved, usage_after.bytes_reserved);
     }
+
+    #[test_log::test]
+    fn reserved_storage_is_tracked_outside_the_pools() {
+        let mut memory_management = MemoryManagement::from_configuration(
+            BytesStorage::default(),
+            &DUMMY_MEM_PROPS,
+            MemoryConfiguration::ExclusivePages,
+            Arc::new(ServerLogger::default()),
+            options(),
+        );
+        memory_management.mode(MemoryAllocationMode::Persistent);
+        let bytes = Bytes::from_bytes_vec(vec![7; 64]);
+        let storage = memory_management.storage().wrap(bytes).unwrap();
+        let storage_id = storage.id;
+
+        let reserved = memory_management.reserve_storage(storage);
+        let handle = ManagedMemoryHandle::new();
+        memory_management.bind(reserved, handle.clone(), 0).unwrap();
+        assert_eq!(memory_management.memory_usage().bytes_in_use, 64);
+        drop(handle);
+
+        // The wrapped memory is never handed out for another reservation of the same size.
+        let other = memory_management.reserve(64).unwrap();
+        let assigned = ManagedMemoryHandle::new();
+        memory_management.bind(other, assigned.clone(), 0).unwrap();
+        let other = memory_management
+            .get_storage(assigned.clone().binding())
+            .unwrap();
+        assert_ne!(other.id, storage_id);
+        assert_eq!(memory_management.memory_usage().bytes_reserved, 128);
+
+        drop(assigned);
+        memory_management.cleanup(true);
+        assert_eq!(memory_management.memory_usage().bytes_reserved, 0);
+    }
 }
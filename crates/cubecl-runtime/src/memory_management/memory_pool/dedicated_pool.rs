@@ -0,0 +1,145 @@
+use super::{ManagedMemoryBinding, ManagedMemoryHandle, Slice};
+use crate::memory_management::{BytesFormat, MemoryLocation, MemoryUsage};
+use crate::server::IoError;
+use crate::storage::{ComputeStorage, StorageHandle};
+use alloc::vec::Vec;
+use cubecl_common::backtrace::BackTrace;
+
+/// Keeps track of allocations made for a single reservation, e.g. over-aligned memory, which
+/// are never reused for other reservations and are deallocated once they aren't used anymore.
+pub struct DedicatedPool {
+    slices: Vec<Slice>,
+    location_base: MemoryLocation,
+}
+
+impl core::fmt::Display for DedicatedPool {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for slice in self.slices.iter() {
+            let state = match slice.is_free() {
+                true => "free",
+                false => "full",
+            };
+            f.write_fmt(format_args!(
+                "  - Slice {} => {state}\n",
+                BytesFormat::new(slice.effective_size())
+            ))?;
+        }
+
+        if !self.slices.is_empty() {
+            f.write_fmt(format_args!("\n{}\n", self.get_memory_usage()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DedicatedPool {
+    pub fn new(pool_pos: u8) -> Self {
+        Self {
+            slices: Vec::new(),
+            location_base: MemoryLocation::new(pool_pos, 0, 0),
+        }
+    }
+
+    /// Track an allocation made for a single reservation, keeping the offset of the handle.
+    pub fn insert(&mut self, storage_handle: StorageHandle) -> ManagedMemoryHandle {
+        let slice = Slice::new(storage_handle, 0);
+        let mut location = self.location_base;
+        location.slice = self.slices.len() as u32;
+        slice.descriptor().update_location(location);
+
+        let handle = slice.handle.clone();
+        self.slices.push(slice);
+
+        handle
+    }
+
+    pub fn find(&self, binding: &ManagedMemoryBinding) -> Result<&Slice, IoError> {
+        let slice_index = binding.descriptor().slice();
+
+        self.slices
+            .get(slice_index)
+            .ok_or_else(|| IoError::NotFound {
+                backtrace: BackTrace::capture(),
+                reason: alloc::format!("Memory slice {} doesn't exist", slice_index).into(),
+            })
+    }
+
+    pub fn bind(
+        &mut self,
+        old: ManagedMemoryHandle,
+        new: ManagedMemoryHandle,
+        cursor: u64,
+    ) -> Result<(), IoError> {
+        let slice = &mut self.slices[old.descriptor().slice()];
+        new.descriptor()
+            .update_location(old.descriptor().location());
+        slice.cursor = cursor;
+        slice.handle = new;
+
+        Ok(())
+    }
+
+    pub fn get_memory_usage(&self) -> MemoryUsage {
+        let used_slices: Vec<_> = self
+            .slices
+            .iter()
+            .filter(|slice| !slice.is_free())
+            .collect();
+
+        MemoryUsage {
+            number_allocs: used_slices.len() as u64,
+            bytes_in_use: used_slices.iter().map(|slice| slice.storage.size()).sum(),
+            bytes_padding: 0,
+            bytes_reserved: self.slices.iter().map(|slice| slice.effective_size()).sum(),
+        }
+    }
+
+    /// Deallocate the allocations that aren't used anymore, since they can't be reused.
+    pub fn cleanup<Storage: ComputeStorage>(&mut self, storage: &mut Storage) {
+        if self.slices.iter().all(|slice| !slice.is_free()) {
+            return;
+        }
+
+        let mut slices = Vec::with_capacity(self.slices.len());
+        for slice in self.slices.drain(..) {
+            if slice.is_free() {
+                storage.dealloc(slice.storage.id);
+            } else {
+                slice.descriptor().update_slice(slices.len() as u32);
+                slices.push(slice);
+            }
+        }
+
+        self.slices = slices;
+        storage.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::BytesStorage;
+
+    #[test_log::test]
+    fn dedicated_pool_keeps_offset_and_deallocates_free_slices() {
+        let mut storage = BytesStorage::default();
+        let mut pool = DedicatedPool::new(0);
+
+        let allocation = storage.alloc_aligned(100, 256).unwrap();
+        let offset = allocation.offset();
+        let kept = pool.insert(allocation);
+        let freed = pool.insert(storage.alloc(100).unwrap());
+
+        assert_eq!(
+            pool.find(&kept.clone().binding()).unwrap().storage.offset(),
+            offset
+        );
+
+        core::mem::drop(freed);
+        pool.cleanup(&mut storage);
+
+        assert_eq!(pool.get_memory_usage().number_allocs, 1);
+        assert_eq!(pool.find(&kept.binding()).unwrap().storage.offset(), offset);
+    }
+}
@@ -1,4 +1,5 @@
 mod base;
+mod dedicated_pool;
 mod exclusive_pool;
 pub(crate) mod handle;
 mod memory_page;
@@ -6,6 +7,7 @@ mod persistent_pool;
 mod sliced_pool;
 
 pub(crate) use base::*;
+pub(crate) use dedicated_pool::*;
 pub(crate) use exclusive_pool::*;
 pub(crate) use memory_page::*;
 pub(crate) use persistent_pool::*;
@@ -0,0 +1,159 @@
+use crate::{
+    client::ComputeClient,
+    runtime::Runtime,
+    server::{ComputeServer, CubeCount, KernelArguments},
+};
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use hashbrown::HashMap;
+use spin::RwLock;
+use thiserror::Error;
+
+/// Closure creating the kernel registered under a name.
+///
+/// It is only called when the kernel is launched, so registering a kernel is cheap and the
+/// compilation happens lazily on the first launch, cached by the server like any other kernel.
+pub type KernelFactory<R> =
+    Arc<dyn Fn() -> <<R as Runtime>::Server as ComputeServer>::Kernel + Send + Sync>;
+
+/// Errors returned by the [`KernelRegistry`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KernelRegistryError {
+    /// No kernel is registered under the given name.
+    #[error("No kernel registered under the name '{name}'")]
+    NotFound {
+        /// The name of the kernel.
+        name: String,
+    },
+    /// A kernel is already registered under the given name.
+    #[error("A kernel is already registered under the name '{name}'")]
+    AlreadyRegistered {
+        /// The name of the kernel.
+        name: String,
+    },
+}
+
+/// A registry of kernels indexed by name, launched with type-erased [arguments](KernelArguments).
+///
+/// The registry can be declared as a static since it is lazily initialized:
+///
+/// ```rust,ignore
+/// static KERNELS: KernelRegistry<MyRuntime> = KernelRegistry::new();
+///
+/// KERNELS.register("add", || Box::new(KernelTask::new(AddKernel)))?;
+/// KERNELS.launch(&client, "add", CubeCount::Static(1, 1, 1), arguments)?;
+/// ```
+pub struct KernelRegistry<R: Runtime> {
+    kernels: RwLock<Option<HashMap<String, KernelFactory<R>>>>,
+}
+
+impl<R: Runtime> Default for KernelRegistry<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Runtime> KernelRegistry<R> {
+    /// Create a new empty registry.
+    pub const fn new() -> Self {
+        Self {
+            kernels: RwLock::new(None),
+        }
+    }
+
+    /// Register the kernel created by `factory` under the given name.
+    pub fn register<F>(
+        &self,
+        name: impl Into<String>,
+        factory: F,
+    ) -> Result<(), KernelRegistryError>
+    where
+        F: Fn() -> <R::Server as ComputeServer>::Kernel + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let mut kernels = self.kernels.write();
+        let kernels = kernels.get_or_insert_with(HashMap::new);
+
+        if kernels.contains_key(&name) {
+            return Err(KernelRegistryError::AlreadyRegistered { name });
+        }
+
+        kernels.insert(name, Arc::new(factory));
+        Ok(())
+    }
+
+    /// Remove the kernel registered under the given name, returning whether it was registered.
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut kernels = self.kernels.write();
+
+        match kernels.as_mut() {
+            Some(kernels) => kernels.remove(name).is_some(),
+            None => false,
+        }
+    }
+
+    /// Whether a kernel is registered under the given name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factory(name).is_ok()
+    }
+
+    /// The names of all registered kernels, sorted alphabetically.
+    pub fn names(&self) -> Vec<String> {
+        let kernels = self.kernels.read();
+        let mut names = kernels
+            .iter()
+            .flat_map(|kernels| kernels.keys().cloned())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Launch the kernel registered under the given name, see [`ComputeClient::launch`].
+    pub fn launch(
+        &self,
+        client: &ComputeClient<R>,
+        name: &str,
+        count: CubeCount,
+        bindings: KernelArguments,
+    ) -> Result<(), KernelRegistryError> {
+        let factory = self.factory(name)?;
+        client.launch(factory(), count, bindings);
+
+        Ok(())
+    }
+
+    /// Launch the kernel registered under the given name without bound checks, see
+    /// [`ComputeClient::launch_unchecked`].
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`ComputeClient::launch_unchecked`] apply to the registered kernel.
+    pub unsafe fn launch_unchecked(
+        &self,
+        client: &ComputeClient<R>,
+        name: &str,
+        count: CubeCount,
+        bindings: KernelArguments,
+    ) -> Result<(), KernelRegistryError> {
+        let factory = self.factory(name)?;
+        // SAFETY: Caller has to uphold kernel being safe.
+        unsafe { client.launch_unchecked(factory(), count, bindings) };
+
+        Ok(())
+    }
+
+    fn factory(&self, name: &str) -> Result<KernelFactory<R>, KernelRegistryError> {
+        let kernels = self.kernels.read();
+
+        kernels
+            .as_ref()
+            .and_then(|kernels| kernels.get(name))
+            .cloned()
+            .ok_or_else(|| KernelRegistryError::NotFound {
+                name: name.to_string(),
+            })
+    }
+}
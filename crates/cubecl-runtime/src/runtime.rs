@@ -7,7 +7,7 @@ use cubecl_zspace::{Shape, Strides};
 use crate::{
     client::ComputeClient,
     compiler::{Compiler, CubeTask},
-    server::ComputeServer,
+    server::{ComputeServer, ServerError},
 };
 
 /// Runtime for the `CubeCL`.
@@ -22,6 +22,15 @@ pub trait Runtime: Sized + Send + Sync + 'static + core::fmt::Debug + Clone {
     /// Retrieve the compute client from the runtime device.
     fn client(device: &Self::Device) -> ComputeClient<Self>;
 
+    /// Retrieve the compute client from the runtime device, or an error if the runtime isn't
+    /// available on this machine, e.g. because its driver or the device is missing.
+    ///
+    /// Runtimes that can't tell up front create the client with [`client`](Self::client), which
+    /// panics in that case.
+    fn try_client(device: &Self::Device) -> Result<ComputeClient<Self>, ServerError> {
+        Ok(Self::client(device))
+    }
+
     /// The runtime name on the given device.
     fn name(client: &ComputeClient<Self>) -> &'static str;
 
@@ -40,6 +49,16 @@ pub trait Runtime: Sized + Send + Sync + 'static + core::fmt::Debug + Clone {
     /// Returns the properties of the target hardware architecture.
     fn target_properties() -> TargetProperties;
 
+    /// Measure the width in bits of the fastest loads of the device, which bounds the
+    /// [vector sizes of IO operations](ComputeClient::io_optimized_vector_sizes).
+    ///
+    /// Called by [`ComputeClient::calibrate_load_width`], unless a previous measurement on the
+    /// same device is cached. Returns `None` to use the load width of the device properties.
+    fn measure_load_width(client: &ComputeClient<Self>) -> Option<u32> {
+        let _ = client;
+        None
+    }
+
     /// Returns all devices available under the provided type id.
     fn enumerate_devices(
         type_id: u16,
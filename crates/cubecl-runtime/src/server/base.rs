@@ -1,6 +1,6 @@
-use super::Handle;
+use super::{ExternalSemaphore, ExternalSemaphoreHandle, Handle};
 use crate::{
-    client::ComputeClient,
+    client::{ComputeClient, watch::Watchpoints},
     compiler::CompilationError,
     config::{CubeClRuntimeConfig, RuntimeConfig, compilation::BoundsCheckMode},
     kernel::KernelMetadata,
@@ -9,6 +9,7 @@ use crate::{
     runtime::Runtime,
     server::Binding,
     storage::{ComputeStorage, ManagedResource},
+    texture::TextureMeta,
     tma::{OobFill, TensorMapFormat, TensorMapInterleave, TensorMapPrefetch, TensorMapSwizzle},
 };
 use ahash::AHasher;
@@ -98,6 +99,10 @@ pub struct ServerUtilities<Server: ComputeServer> {
     pub check_mode: BoundsCheckMode,
     /// A set containing the ids for which the inter-device communication has already been initialized.
     pub initialized_comms: RwLock<HashSet<CommunicationId>>,
+    /// The watchpoints on the buffers of the device.
+    pub(crate) watchpoints: spin::Mutex<Watchpoints>,
+    /// The load width in bits used by the device, resolved once.
+    pub(crate) load_width: spin::Once<u32>,
 }
 
 /// Defines how the memory layout is determined.
@@ -161,6 +166,8 @@ impl<S: ComputeServer> ServerUtilities<S> {
             layout_policy: allocator,
             check_mode: CubeClRuntimeConfig::get().compilation.check_mode,
             initialized_comms: RwLock::new(HashSet::default()),
+            watchpoints: Default::default(),
+            load_width: spin::Once::new(),
         }
     }
 }
@@ -327,6 +334,13 @@ pub struct StreamErrorMode {
     pub flush: bool,
 }
 
+/// A host closure invoked once previously submitted work completes, see
+/// [`ComputeServer::on_complete`].
+///
+/// The callback may run on a driver thread, so it should be short and must not submit new work
+/// to the device it is registered on.
+pub type CompletionCallback = Box<dyn FnOnce(Result<(), ServerError>) + Send>;
+
 /// The compute server is responsible for handling resources and computations over resources.
 ///
 /// Everything in the server is mutable, therefore it should be solely accessed through the
@@ -348,6 +362,27 @@ where
     /// Initializes [memory](ManagedMemoryHandle) on the given [stream](StreamId) with the given size.
     fn initialize_memory(&mut self, memory: ManagedMemoryHandle, size: u64, stream_id: StreamId);
 
+    /// Initializes [memory](ManagedMemoryHandle) on the given [stream](StreamId) with the given
+    /// size, starting at a multiple of `alignment` bytes, which is larger than the device memory
+    /// alignment.
+    ///
+    /// Runtimes whose storage knows the address of its allocations should override this method
+    /// using [`MemoryManagement::reserve_aligned`](crate::memory_management::MemoryManagement::reserve_aligned).
+    /// The default implementation can't align the memory and returns an error.
+    #[allow(unused_variables)]
+    fn initialize_memory_aligned(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        size: u64,
+        alignment: u64,
+        stream_id: StreamId,
+    ) -> Result<(), IoError> {
+        Err(IoError::UnsupportedAlignment {
+            alignment: alignment as usize,
+            backtrace: BackTrace::capture(),
+        })
+    }
+
     /// Reserves N [Bytes] of the provided sizes to be used as staging to load data.
     fn staging(
         &mut self,
@@ -379,6 +414,17 @@ where
     /// Wait for the completion of every task in the server.
     fn sync(&mut self, stream_id: StreamId) -> DynFut<Result<(), ServerError>>;
 
+    /// Register a [callback](CompletionCallback) invoked on the host once every task previously
+    /// submitted on the given [stream](StreamId) is completed.
+    ///
+    /// Runtimes that can be notified by the device (host functions, submission callbacks) should
+    /// override this method. The default implementation waits on [sync](Self::sync), blocking
+    /// the server until the work is done.
+    fn on_complete(&mut self, stream_id: StreamId, callback: CompletionCallback) {
+        let result = cubecl_common::future::block_on(self.sync(stream_id));
+        callback(result);
+    }
+
     /// Given a resource handle, returns the storage resource.
     fn get_resource(
         &mut self,
@@ -433,6 +479,110 @@ where
 
     /// Update the memory mode of allocation in the server.
     fn allocation_mode(&mut self, mode: MemoryAllocationMode, stream_id: StreamId);
+
+    /// Total energy consumed by the device in millijoules, counted from an unspecified point in
+    /// time, so only the difference between two readings is meaningful.
+    ///
+    /// Runtimes that can read the power sensors of the device (NVML, rocm-smi) should override
+    /// this method. The default implementation returns `None`.
+    fn energy_consumption(&mut self) -> Option<u64> {
+        None
+    }
+
+    /// Bind `memory` to the host memory of `data` without a copy, returning the alignment of the
+    /// wrapped memory in bytes.
+    ///
+    /// Runtimes of devices sharing memory with the host, like Apple Silicon and integrated GPUs,
+    /// should override this method and give the data back when it can't be wrapped, e.g. because
+    /// it isn't aligned to a page. The default implementation gives the data back, so it's copied
+    /// instead.
+    #[allow(unused_variables)]
+    fn create_from_host(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        data: Bytes,
+        stream_id: StreamId,
+    ) -> Result<u64, Bytes> {
+        Err(data)
+    }
+
+    /// Import a timeline semaphore created by another API.
+    ///
+    /// Runtimes that can share semaphores with other APIs should override this method, along with
+    /// [`export_semaphore`](Self::export_semaphore), [`signal_semaphore`](Self::signal_semaphore),
+    /// [`wait_semaphore`](Self::wait_semaphore) and [`release_semaphore`](Self::release_semaphore).
+    /// The default implementation returns an error.
+    #[allow(unused_variables)]
+    fn import_semaphore(
+        &mut self,
+        handle: ExternalSemaphoreHandle,
+    ) -> Result<ExternalSemaphore, ServerError> {
+        Err(ServerError::Generic {
+            reason: "External semaphores aren't supported by this runtime".into(),
+            backtrace: BackTrace::capture(),
+        })
+    }
+
+    /// Create a timeline semaphore starting at `0` and export it, so another API can import it.
+    fn export_semaphore(
+        &mut self,
+    ) -> Result<(ExternalSemaphore, ExternalSemaphoreHandle), ServerError> {
+        Err(ServerError::Generic {
+            reason: "External semaphores aren't supported by this runtime".into(),
+            backtrace: BackTrace::capture(),
+        })
+    }
+
+    /// Set the semaphore to `value` once every task previously submitted on the given
+    /// [stream](StreamId) is completed.
+    #[allow(unused_variables)]
+    fn signal_semaphore(
+        &mut self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream_id: StreamId,
+    ) -> Result<(), ServerError> {
+        Err(ServerError::Generic {
+            reason: "External semaphores aren't supported by this runtime".into(),
+            backtrace: BackTrace::capture(),
+        })
+    }
+
+    /// Delay the tasks submitted next on the given [stream](StreamId) until the semaphore reaches
+    /// `value`.
+    #[allow(unused_variables)]
+    fn wait_semaphore(
+        &mut self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream_id: StreamId,
+    ) -> Result<(), ServerError> {
+        Err(ServerError::Generic {
+            reason: "External semaphores aren't supported by this runtime".into(),
+            backtrace: BackTrace::capture(),
+        })
+    }
+
+    /// Release an imported semaphore, which must not be used by submitted tasks anymore, e.g. after
+    /// a [sync](Self::sync).
+    #[allow(unused_variables)]
+    fn release_semaphore(&mut self, semaphore: ExternalSemaphore) {}
+
+    /// Maximum number of cubes of the kernel resident on a single compute unit, accounting for
+    /// the registers and shared memory of the compiled kernel. The kernel is compiled if needed,
+    /// but isn't launched.
+    ///
+    /// Runtimes that can query the occupancy of a compiled kernel should override this method.
+    /// The default implementation returns `None`, and the client falls back to
+    /// [`Occupancy::estimate`].
+    fn max_cubes_per_unit(
+        &mut self,
+        _kernel: Self::Kernel,
+        _mode: ExecutionMode,
+        _stream_id: StreamId,
+    ) -> Result<Option<u32>, ServerError> {
+        Ok(None)
+    }
 }
 
 /// An ID unique to any unordered combination of devices.
@@ -597,6 +747,10 @@ pub struct MemoryLayoutDescriptor {
     pub shape: Shape,
     /// Size of each element in the tensor (used for conversion of shape to bytes)
     pub elem_size: usize,
+    /// Minimum alignment of the start of the allocation in bytes, the device memory alignment
+    /// if `None`.
+    #[new(default)]
+    pub alignment: Option<usize>,
 }
 
 impl MemoryLayoutDescriptor {
@@ -609,6 +763,14 @@ impl MemoryLayoutDescriptor {
     pub fn contiguous(shape: Shape, elem_size: usize) -> Self {
         MemoryLayoutDescriptor::new(MemoryLayoutStrategy::Contiguous, shape, elem_size)
     }
+
+    /// Align the start of the allocation to at least `alignment` bytes, e.g. 128 bytes for
+    /// tensors loaded with TMA. The alignment must be a power of two. Alignments larger than the
+    /// device memory alignment get a dedicated allocation, which not every runtime supports.
+    pub fn with_alignment(mut self, alignment: usize) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
 }
 
 /// An allocation with associated strides. Strides depend on tensor layout.
@@ -753,6 +915,16 @@ pub enum IoError {
         backtrace: BackTrace,
     },
 
+    /// The requested alignment can't be guaranteed by the device
+    #[error("can't align an allocation to {alignment} bytes\n{backtrace}")]
+    UnsupportedAlignment {
+        /// The requested alignment in bytes.
+        alignment: usize,
+        /// The backtrace.
+        #[cfg_attr(std_io, serde(skip))]
+        backtrace: BackTrace,
+    },
+
     /// Unknown error happened during execution
     #[error("Unknown error happened during execution\n{backtrace}")]
     Unknown {
@@ -792,6 +964,9 @@ pub struct KernelArguments {
     pub info: MetadataBindingInfo,
     /// Tensor map bindings
     pub tensor_maps: Vec<TensorMapBinding>,
+    /// Texture bindings
+    pub textures: Vec<TextureBinding>,
+    pub(crate) access_policy_window: Option<AccessPolicyWindow>,
 }
 
 impl core::fmt::Display for KernelArguments {
@@ -819,7 +994,12 @@ impl KernelArguments {
 
     /// Extend the buffers with `bindings`
     pub fn with_buffers(mut self, bindings: Vec<Binding>) -> Self {
-        self.buffers.extend(bindings);
+        // Reuse the allocation of `bindings` instead of growing an empty vector.
+        if self.buffers.is_empty() {
+            self.buffers = bindings;
+        } else {
+            self.buffers.extend(bindings);
+        }
         self
     }
 
@@ -834,6 +1014,35 @@ impl KernelArguments {
         self.tensor_maps.extend(bindings);
         self
     }
+
+    /// Extend the textures with `bindings`
+    pub fn with_textures(mut self, bindings: Vec<TextureBinding>) -> Self {
+        self.textures.extend(bindings);
+        self
+    }
+
+    /// Set the [access policy window](AccessPolicyWindow) of the launch
+    pub fn with_access_policy_window(mut self, window: AccessPolicyWindow) -> Self {
+        self.access_policy_window = Some(window);
+        self
+    }
+
+    /// The buffer that should persist in the L2 cache during the launch, if any
+    pub fn access_policy_window(&self) -> Option<&AccessPolicyWindow> {
+        self.access_policy_window.as_ref()
+    }
+}
+
+/// A buffer that is re-read many times by a launch, like the weights of a matrix-vector product,
+/// and should persist in the L2 cache instead of being evicted by streaming data.
+///
+/// This is only a hint, runtimes without control over the L2 cache ignore it.
+#[derive(new, Debug, Clone)]
+pub struct AccessPolicyWindow {
+    /// The buffer to keep in the L2 cache.
+    pub binding: Binding,
+    /// The fraction of the accesses to the buffer that should persist, between 0 and 1.
+    pub hit_ratio: f32,
 }
 
 /// Binding of a set of scalars of the same type to execute a kernel.
@@ -877,6 +1086,15 @@ pub struct TensorMapBinding {
     pub map: TensorMapMeta,
 }
 
+/// A read-only texture sampled by a kernel
+#[derive(new, Debug)]
+pub struct TextureBinding {
+    /// The binding for the backing buffer
+    pub binding: Binding,
+    /// The texture metadata
+    pub meta: TextureMeta,
+}
+
 /// `TensorMap` metadata for the opaque proxy used in TMA copies
 #[derive(Debug, Clone)]
 pub struct TensorMapMeta {
@@ -954,6 +1172,73 @@ impl From<CubeCountSelection> for CubeCount {
     }
 }
 
+/// How many cubes of a kernel can be resident on the device at the same time.
+///
+/// Persistent kernels (stream-K, scans) should launch at most
+/// [`max_resident_cubes`](Self::max_resident_cubes) cubes and loop over their work, so that
+/// every cube runs concurrently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occupancy {
+    /// Number of compute units of the device: streaming multiprocessors on CUDA, compute units
+    /// on HIP and cores on CPU.
+    pub compute_units: u32,
+    /// Maximum number of cubes of the kernel resident on a single compute unit.
+    ///
+    /// Can be zero when the kernel requests more resources than a compute unit has.
+    pub cubes_per_unit: u32,
+    /// Whether the cubes per unit were queried from the driver for the compiled kernel, rather
+    /// than estimated from the device properties.
+    pub exact: bool,
+}
+
+impl Occupancy {
+    /// Used when the device doesn't report its number of compute units.
+    const COMPUTE_UNITS_APPROX: u32 = 32;
+    /// Resident units of a compute unit on most GPUs.
+    const UNITS_PER_COMPUTE_UNIT_APPROX: u32 = 2048;
+    /// Resident cubes of a compute unit on most GPUs.
+    const CUBES_PER_COMPUTE_UNIT_APPROX: u32 = 16;
+
+    /// Estimates the occupancy of a kernel with the given [`CubeDim`] from the device
+    /// properties only.
+    ///
+    /// The registers and shared memory used by the kernel are unknown, so the cubes per unit
+    /// are an upper bound on GPUs. On CPU, each core runs one cube at a time.
+    pub fn estimate(properties: &DeviceProperties, cube_dim: CubeDim) -> Self {
+        let hardware = &properties.hardware;
+
+        if let Some(num_cores) = hardware.num_cpu_cores {
+            return Self {
+                compute_units: num_cores,
+                cubes_per_unit: 1,
+                exact: false,
+            };
+        }
+
+        let cubes_per_unit = (Self::UNITS_PER_COMPUTE_UNIT_APPROX / cube_dim.num_elems().max(1))
+            .clamp(1, Self::CUBES_PER_COMPUTE_UNIT_APPROX);
+
+        Self {
+            compute_units: hardware
+                .num_streaming_multiprocessors
+                .unwrap_or(Self::COMPUTE_UNITS_APPROX),
+            cubes_per_unit,
+            exact: false,
+        }
+    }
+
+    /// Maximum number of cubes of the kernel resident on the whole device.
+    pub fn max_resident_cubes(&self) -> u32 {
+        self.compute_units * self.cubes_per_unit
+    }
+
+    /// The [`CubeCount`] of a persistent kernel processing `num_cubes` cubes of work, capped to
+    /// the number of resident cubes.
+    pub fn cube_count(&self, num_cubes: u32) -> CubeCount {
+        CubeCount::new_1d(num_cubes.min(self.max_resident_cubes()).max(1))
+    }
+}
+
 impl CubeCount {
     /// Create a new static cube count with the given x = y = z = 1.
     pub fn new_single() -> Self {
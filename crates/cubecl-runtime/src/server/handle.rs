@@ -18,6 +18,8 @@ pub struct Handle {
     pub stream: StreamId,
     /// Length of the underlying buffer ignoring offsets
     pub(crate) size: u64,
+    /// Alignment of the start of the underlying buffer in bytes, 1 when unknown.
+    pub(crate) base_alignment: u64,
 }
 
 impl core::fmt::Debug for Handle {
@@ -28,6 +30,7 @@ impl core::fmt::Debug for Handle {
             .field("offset_end", &self.offset_end)
             .field("stream", &self.stream)
             .field("size", &self.size)
+            .field("base_alignment", &self.base_alignment)
             .finish()
     }
 }
@@ -40,6 +43,7 @@ impl Clone for Handle {
             offset_end: self.offset_end,
             stream: self.stream,
             size: self.size,
+            base_alignment: self.base_alignment,
         }
     }
 }
@@ -53,6 +57,7 @@ impl Handle {
             offset_end: None,
             stream,
             size,
+            base_alignment: 1,
         }
     }
     /// Creates a new handle of the given size.
@@ -63,6 +68,14 @@ impl Handle {
             offset_end: None,
             stream,
             size,
+            base_alignment: 1,
+        }
+    }
+    /// The alignment of the start of the handle in bytes, accounting for offsets.
+    pub fn alignment(&self) -> u64 {
+        match self.offset_start.unwrap_or(0) {
+            0 => self.base_alignment,
+            offset => Ord::min(self.base_alignment, 1 << offset.trailing_zeros()),
         }
     }
     /// Checks whether the handle can be mutated in-place without affecting other computation.
@@ -126,6 +139,16 @@ impl Handle {
     }
 }
 
+/// A handle created from host memory, see
+/// [`ComputeClient::create_from_host`](crate::client::ComputeClient::create_from_host).
+#[derive(Clone, Debug)]
+pub struct HostHandle {
+    /// The handle of the data on the device.
+    pub handle: Handle,
+    /// Whether the handle wraps the host memory directly, instead of a copy of it.
+    pub zero_copy: bool,
+}
+
 /// A binding represents a [Handle] that is bound to managed memory.
 ///
 /// The memory used is known by the compute server.
@@ -1,5 +1,7 @@
 mod base;
 mod handle;
+mod semaphore;
 
 pub use base::*;
 pub use handle::*;
+pub use semaphore::*;
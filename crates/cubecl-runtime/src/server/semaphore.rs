@@ -0,0 +1,21 @@
+/// An OS handle to a timeline semaphore created by another API, e.g. a Vulkan semaphore exported
+/// with `VK_KHR_external_semaphore_fd` by a renderer or a video pipeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExternalSemaphoreHandle {
+    /// An opaque POSIX file descriptor, owned by the runtime once imported.
+    OpaqueFd(i32),
+    /// An opaque Windows NT handle, which stays owned by the caller.
+    OpaqueWin32(usize),
+}
+
+/// A timeline semaphore shared with another API, imported with
+/// [`ComputeClient::import_semaphore`](crate::client::ComputeClient::import_semaphore) or created
+/// with [`ComputeClient::export_semaphore`](crate::client::ComputeClient::export_semaphore).
+///
+/// The device signals and waits on the semaphore in stream order, so external engines can wait on
+/// work of the compute client, and the other way around, without a round trip through the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ExternalSemaphore {
+    /// The id of the semaphore in the server it was imported in.
+    pub id: u64,
+}
@@ -1,5 +1,6 @@
 use crate::{memory_management::ManagedMemoryBinding, server::IoError, storage_id_type};
 use core::fmt::Debug;
+use cubecl_common::backtrace::BackTrace;
 
 // This ID is used to map a handle to its actual data.
 storage_id_type!(StorageId);
@@ -85,6 +86,38 @@ pub trait ComputeStorage: Send {
     /// Allocates `size` units of memory and returns a handle to it
     fn alloc(&mut self, size: u64) -> Result<StorageHandle, IoError>;
 
+    /// The address of the start of the allocation `handle` points to, when the storage can tell.
+    fn address(&mut self, _handle: &StorageHandle) -> Option<u64> {
+        None
+    }
+
+    /// Allocates `size` units of memory starting at a multiple of `alignment` bytes.
+    ///
+    /// Alignments larger than the [storage alignment](Self::alignment) are met by allocating
+    /// more memory and offsetting the handle, which requires the storage to know the
+    /// [address](Self::address) of its allocations.
+    fn alloc_aligned(&mut self, size: u64, alignment: u64) -> Result<StorageHandle, IoError> {
+        let storage_alignment = self.alignment() as u64;
+        if alignment <= storage_alignment {
+            return self.alloc(size);
+        }
+
+        let handle = self.alloc(size + alignment - storage_alignment)?;
+        let Some(address) = self.address(&handle) else {
+            self.dealloc(handle.id);
+            return Err(IoError::UnsupportedAlignment {
+                alignment: alignment as usize,
+                backtrace: BackTrace::capture(),
+            });
+        };
+
+        let offset = address.next_multiple_of(alignment) - address;
+        Ok(StorageHandle::new(
+            handle.id,
+            StorageUtilization { offset, size },
+        ))
+    }
+
     /// Deallocates the memory pointed by the given storage id.
     ///
     /// These deallocations might need to be flushed with [`Self::flush`].
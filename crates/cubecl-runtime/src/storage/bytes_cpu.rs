@@ -2,7 +2,10 @@ use crate::server::IoError;
 
 use super::{ComputeStorage, StorageHandle, StorageId, StorageUtilization};
 use alloc::alloc::{Layout, alloc_zeroed, dealloc};
-use cubecl_common::backtrace::BackTrace;
+use cubecl_common::{
+    backtrace::BackTrace,
+    bytes::{Bytes, Writer},
+};
 use hashbrown::HashMap;
 
 /// The bytes storage maps ids to pointers of bytes in a contiguous layout.
@@ -32,6 +35,8 @@ pub struct BytesResource {
 struct AllocatedBytes {
     ptr: *mut u8,
     layout: Layout,
+    /// The host memory wrapped without a copy, which owns the allocation instead of the layout.
+    host: Option<Bytes>,
 }
 
 impl BytesResource {
@@ -78,6 +83,31 @@ impl BytesResource {
     }
 }
 
+impl BytesStorage {
+    /// Use host memory as storage without a copy, or give it back if it can't be mutated in place,
+    /// e.g. because it's shared or lazily read from a file.
+    pub fn wrap(&mut self, mut bytes: Bytes) -> Result<StorageHandle, Bytes> {
+        let size = bytes.len() as u64;
+        let ptr = match bytes.write(Writer::new().no_copy()) {
+            Ok(memory) if memory.as_ptr().addr() % self.alignment() == 0 => memory.as_mut_ptr(),
+            _ => return Err(bytes),
+        };
+
+        let id = StorageId::new();
+        let memory = AllocatedBytes {
+            ptr,
+            layout: Layout::new::<()>(),
+            host: Some(bytes),
+        };
+        self.memory.insert(id, memory);
+
+        Ok(StorageHandle::new(
+            id,
+            StorageUtilization { offset: 0, size },
+        ))
+    }
+}
+
 impl ComputeStorage for BytesStorage {
     type Resource = BytesResource;
 
@@ -94,6 +124,11 @@ impl ComputeStorage for BytesStorage {
         }
     }
 
+    fn address(&mut self, handle: &StorageHandle) -> Option<u64> {
+        let allocated_bytes = self.memory.get(&handle.id)?;
+        Some(allocated_bytes.ptr.addr() as u64)
+    }
+
     #[cfg_attr(
         feature = "tracing",
         tracing::instrument(level = "trace", skip(self, size))
@@ -110,6 +145,7 @@ impl ComputeStorage for BytesStorage {
             let memory = AllocatedBytes {
                 ptr: core::ptr::NonNull::dangling().as_ptr(),
                 layout: Layout::new::<()>(),
+                host: None,
             };
             self.memory.insert(id, memory);
         } else {
@@ -125,7 +161,11 @@ impl ComputeStorage for BytesStorage {
                         backtrace: BackTrace::capture(),
                     });
                 }
-                let memory = AllocatedBytes { ptr, layout };
+                let memory = AllocatedBytes {
+                    ptr,
+                    layout,
+                    host: None,
+                };
                 self.memory.insert(id, memory);
             }
         }
@@ -136,6 +176,7 @@ impl ComputeStorage for BytesStorage {
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn dealloc(&mut self, id: StorageId) {
         if let Some(memory) = self.memory.remove(&id)
+            && memory.host.is_none()
             && memory.layout.size() > 0
         {
             unsafe {
@@ -335,15 +335,25 @@ impl<B: EventStreamBackend> MultiStream<B> {
                 let stream = self.streams.get_mut_index(*origin);
                 let event = B::flush(&mut stream.stream);
 
-                events.push(((origin, stream.cursor), event));
+                events.push(((origin, stream.cursor), stream.last_synced.clone(), event));
             }
         }
 
+        let index = stream_index(&stream_id, self.max_streams);
         let stream = self.streams.get_mut(&stream_id);
 
-        for ((stream_origin, cursor_origin), event) in events {
+        for ((stream_origin, cursor_origin), origin_synced, event) in events {
             stream.last_synced.insert(*stream_origin, cursor_origin);
 
+            // The origin waited on those streams before the event, so waiting on the event also
+            // synchronizes with them. Independent branches that join later don't wait twice.
+            for (other, cursor) in origin_synced {
+                if other != index {
+                    let last_synced = stream.last_synced.entry(other).or_default();
+                    *last_synced = (*last_synced).max(cursor);
+                }
+            }
+
             self.logger.log_streaming(
                 |level| !matches!(level, StreamingLogLevel::Disabled),
                 || format!("Waiting on {stream_origin} from {stream_id}",),
@@ -493,6 +503,37 @@ mod tests {
         assert_eq!(stream2.cursor, 1);
     }
 
+    #[test_log::test]
+    fn test_state_transitive() {
+        let logger = Arc::new(ServerLogger::default());
+        let stream_1 = StreamId { value: 1 };
+        let stream_2 = StreamId { value: 2 };
+        let stream_3 = StreamId { value: 3 };
+
+        let binding_1 = handle(stream_1);
+        let binding_2 = handle(stream_2);
+
+        let mut ms = MultiStream::new(logger, TestBackend, MAX_STREAMS);
+        ms.resolve(stream_1, [].into_iter(), false).unwrap();
+        ms.resolve(stream_2, [].into_iter(), false).unwrap();
+        ms.resolve(stream_3, [].into_iter(), false).unwrap();
+
+        ms.resolve(stream_2, [&binding_1].into_iter(), false)
+            .unwrap();
+        ms.resolve(stream_3, [&binding_2].into_iter(), false)
+            .unwrap();
+
+        // Stream 3 waited on stream 2 after stream 2 waited on stream 1.
+        let stream3 = ms.streams.get_mut(&stream_3);
+        let index_1 = stream_index(&stream_1, MAX_STREAMS as usize);
+        let index_2 = stream_index(&stream_2, MAX_STREAMS as usize);
+        assert_eq!(stream3.last_synced.get(&index_1), Some(&1));
+        assert_eq!(stream3.last_synced.get(&index_2), Some(&2));
+
+        let analysis = ms.update_shared_bindings(stream_3, [&binding_1].into_iter());
+        assert_eq!(analysis, SharedBindingAnalysis::default());
+    }
+
     fn handle(stream: StreamId) -> Binding {
         Handle::new(stream, 10).binding()
     }
@@ -0,0 +1,46 @@
+use cubecl_ir::StorageType;
+
+/// Filtering applied when a texture is sampled between texel centers.
+#[derive(Default, Hash, PartialEq, Eq, Clone, Debug, Copy)]
+pub enum TextureFilter {
+    /// Return the nearest texel.
+    #[default]
+    Nearest,
+    /// Bilinear interpolation of the four nearest texels. Only valid for float textures.
+    Linear,
+}
+
+/// How coordinates outside of the texture are resolved.
+#[derive(Default, Hash, PartialEq, Eq, Clone, Debug, Copy)]
+pub enum TextureAddressMode {
+    /// Clamp the coordinates to the edge of the texture.
+    #[default]
+    Clamp,
+    /// Return zero outside of the texture.
+    Border,
+    /// Repeat the texture. Requires normalized coordinates.
+    Wrap,
+    /// Repeat the texture, mirrored every other repetition. Requires normalized coordinates.
+    Mirror,
+}
+
+/// Metadata of a read-only 2D texture backed by a pitched buffer.
+#[derive(Hash, PartialEq, Eq, Clone, Debug)]
+pub struct TextureMeta {
+    /// Width of the texture in texels.
+    pub width: usize,
+    /// Height of the texture in texels.
+    pub height: usize,
+    /// Distance between two rows in bytes. Must respect the pitch alignment of the device.
+    pub row_pitch: usize,
+    /// Number of channels of each texel, either 1, 2 or 4.
+    pub channels: usize,
+    /// Storage type of each channel.
+    pub storage_ty: StorageType,
+    /// Filtering mode.
+    pub filter: TextureFilter,
+    /// Addressing mode, for both dimensions.
+    pub address_mode: TextureAddressMode,
+    /// Whether coordinates are normalized to `[0, 1)` instead of `[0, width)` and `[0, height)`.
+    pub normalized_coords: bool,
+}
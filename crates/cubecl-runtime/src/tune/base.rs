@@ -1,4 +1,5 @@
 use super::{AutotuneError, AutotuneKey, TuneFn, TuneInputs};
+use crate::config::experimental::ExperimentalConfig;
 use alloc::boxed::Box;
 use alloc::string::ToString;
 use alloc::{format, string::String, sync::Arc, vec, vec::Vec};
@@ -10,6 +11,7 @@ use hashbrown::HashMap;
 pub struct Tunable<K, F: TuneInputs, Output> {
     pub(crate) function: TuneFn<F, Output>,
     groups: Vec<(TuneGroup<K>, PriorityFunc<K>)>,
+    experimental: Option<String>,
 }
 
 impl<K, F: TuneInputs, Output: 'static> Tunable<K, F, Output> {
@@ -41,9 +43,25 @@ impl<K, F: TuneInputs, Output: 'static> Tunable<K, F, Output> {
                 }),
             ),
             groups: Vec::new(),
+            experimental: None,
         }
     }
 
+    /// Mark this tunable as experimental, so it's only added to a
+    /// [tunable set](super::TunableSet) when the given feature is
+    /// [enabled](ExperimentalConfig), e.g. with `CUBECL_EXPERIMENTAL=feature`.
+    pub fn experimental(mut self, feature: &str) -> Self {
+        self.experimental = Some(feature.to_string());
+        self
+    }
+
+    /// Whether the tunable is stable or its experimental feature is enabled.
+    pub(crate) fn is_enabled(&self, config: &ExperimentalConfig) -> bool {
+        self.experimental
+            .as_ref()
+            .is_none_or(|feature| config.is_enabled(feature))
+    }
+
     /// Add this tunable to a [`TuneGroup`] with the given intra-group priority.
     ///
     /// Groups are autotuned in order of their priority; within each group, tunables are
@@ -322,6 +340,22 @@ mod tests {
 
     impl AutotuneKey for FakeAutotuneKey {}
 
+    #[test_log::test]
+    fn test_experimental_tunable_requires_feature() {
+        let stable = Tunable::<FakeAutotuneKey, (), ()>::new("fake", fake_kernel);
+        let experimental = Tunable::<FakeAutotuneKey, (), ()>::new("fake", fake_kernel)
+            .experimental("warp_specialized");
+        let config = |enabled: &[&str]| ExperimentalConfig {
+            enabled: enabled.iter().map(|feature| feature.to_string()).collect(),
+        };
+
+        assert!(stable.is_enabled(&config(&[])));
+        assert!(!experimental.is_enabled(&config(&[])));
+        assert!(!experimental.is_enabled(&config(&["other"])));
+        assert!(experimental.is_enabled(&config(&["other", "warp_specialized"])));
+        assert!(experimental.is_enabled(&config(&["all"])));
+    }
+
     #[test_log::test]
     fn test_plan_order() {
         let group0 = TuneGroup::<FakeAutotuneKey>::new("group0", |_| 2);
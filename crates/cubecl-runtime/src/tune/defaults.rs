@@ -0,0 +1,60 @@
+/// A compiled-in autotune result for every key matching a pattern, measured ahead of time on a
+/// given architecture.
+///
+/// Tables of defaults are declared as statics and used to [seed](super::Tuner::seed) a tuner:
+///
+/// ```ignore
+/// static DEFAULTS: &[TuneDefault<MatmulKey>] = &[
+///     TuneDefault::new("sm_90", |key| key.m >= 4096 && key.n >= 4096, 2),
+///     TuneDefault::new("sm_90", |key| key.m <= 16, 0),
+/// ];
+///
+/// TUNER.seed(&device_id, "sm_90", DEFAULTS);
+/// ```
+///
+/// Keys matching a default resolve to its candidate without benchmarking, so only the keys that
+/// no pattern covers are autotuned.
+#[derive(Debug)]
+pub struct TuneDefault<K> {
+    architecture: &'static str,
+    pattern: fn(&K) -> bool,
+    fastest_index: usize,
+}
+
+impl<K> Clone for TuneDefault<K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for TuneDefault<K> {}
+
+impl<K> TuneDefault<K> {
+    /// Create a default selecting the candidate at `fastest_index` in the
+    /// [tunable set](super::TunableSet) for every key matching the pattern on the given
+    /// architecture.
+    ///
+    /// The architecture is an arbitrary identifier, e.g. the compute capability or the GPU
+    /// family, that must match the one given when seeding the tuner.
+    pub const fn new(
+        architecture: &'static str,
+        pattern: fn(&K) -> bool,
+        fastest_index: usize,
+    ) -> Self {
+        Self {
+            architecture,
+            pattern,
+            fastest_index,
+        }
+    }
+
+    /// The architecture the default applies to.
+    pub fn architecture(&self) -> &'static str {
+        self.architecture
+    }
+
+    /// The index of the candidate to use when the key matches, if it does.
+    pub fn matches(&self, key: &K) -> Option<usize> {
+        (self.pattern)(key).then_some(self.fastest_index)
+    }
+}
@@ -0,0 +1,115 @@
+use super::{AutotuneKey, Tuner};
+use alloc::{
+    string::String,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+
+/// A result held by the in-memory cache of a tuner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TuneEntry {
+    /// The autotune key, formatted.
+    pub key: String,
+    /// The index of the fastest candidate, or `None` while the key is being tuned.
+    pub fastest_index: Option<usize>,
+}
+
+/// The content of a tuner at the time of the [snapshot](autotune_snapshot).
+#[derive(Debug, Clone)]
+pub struct TunerSnapshot {
+    /// The name of the tuner, usually the module path of its [`LocalTuner`](super::LocalTuner).
+    pub name: String,
+    /// The device the tuner benchmarks on.
+    pub device_id: String,
+    /// The cached results, sorted by key.
+    pub entries: Vec<TuneEntry>,
+}
+
+trait InspectTuner: Send + Sync {
+    fn entries(&self) -> Vec<TuneEntry>;
+    fn clear(&self);
+    fn resume_tuning(&self) -> usize;
+}
+
+impl<K: AutotuneKey> InspectTuner for Tuner<K> {
+    fn entries(&self) -> Vec<TuneEntry> {
+        Tuner::entries(self)
+    }
+
+    fn clear(&self) {
+        Tuner::clear(self)
+    }
+
+    fn resume_tuning(&self) -> usize {
+        Tuner::resume_tuning(self)
+    }
+}
+
+struct RegisteredTuner {
+    name: String,
+    device_id: String,
+    tuner: Weak<dyn InspectTuner>,
+}
+
+static TUNERS: spin::Mutex<Vec<RegisteredTuner>> = spin::Mutex::new(Vec::new());
+
+/// Make the tuner visible to [`autotune_snapshot`], [`clear_autotune_caches`] and
+/// [`resume_tuning`] for as long as it is alive.
+pub(crate) fn register_tuner<K: AutotuneKey>(name: &str, device_id: &str, tuner: &Arc<Tuner<K>>) {
+    let tuner: Arc<dyn InspectTuner> = tuner.clone();
+    let mut tuners = TUNERS.lock();
+
+    tuners.retain(|registered| registered.tuner.strong_count() > 0);
+    tuners.push(RegisteredTuner {
+        name: name.into(),
+        device_id: device_id.into(),
+        tuner: Arc::downgrade(&tuner),
+    });
+}
+
+/// Snapshot the results cached by every live tuner of the process, for debugging and
+/// introspection tools.
+pub fn autotune_snapshot() -> Vec<TunerSnapshot> {
+    TUNERS
+        .lock()
+        .iter()
+        .filter_map(|registered| {
+            let tuner = registered.tuner.upgrade()?;
+
+            Some(TunerSnapshot {
+                name: registered.name.clone(),
+                device_id: registered.device_id.clone(),
+                entries: tuner.entries(),
+            })
+        })
+        .collect()
+}
+
+/// Forget the results cached in memory by every live tuner, so the next executions are tuned
+/// again. Persisted results aren't removed, they are replaced by the results tuned again.
+pub fn clear_autotune_caches() {
+    let tuners = TUNERS
+        .lock()
+        .iter()
+        .filter_map(|registered| registered.tuner.upgrade())
+        .collect::<Vec<_>>();
+
+    for tuner in tuners {
+        tuner.clear();
+    }
+}
+
+/// Continue the searches of every live tuner interrupted by their
+/// [time budget](super::TunableSet::with_time_budget), e.g. when the application is idle.
+///
+/// Each search continues on the next execution of its key, benchmarking its remaining candidates
+/// within the budget again. Returns the number of searches to continue.
+pub fn resume_tuning() -> usize {
+    let tuners = TUNERS
+        .lock()
+        .iter()
+        .filter_map(|registered| registered.tuner.upgrade())
+        .collect::<Vec<_>>();
+
+    tuners.iter().map(|tuner| tuner.resume_tuning()).sum()
+}
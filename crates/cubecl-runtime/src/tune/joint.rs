@@ -0,0 +1,115 @@
+use super::{Tunable, TuneInputs};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+/// The pipeline of a [`JointTunable`], receiving the chosen candidate of every stage.
+type JointPipeline<F, Output> =
+    dyn for<'inp> Fn(&[usize], <F as TuneInputs>::At<'inp>) -> Result<Output, String> + Send + Sync;
+
+/// A pipeline of dependent stages, e.g. a matmul followed by a fused epilogue and a reduce,
+/// whose candidates are tuned jointly.
+///
+/// Tuning each stage with its own [`TunableSet`](super::TunableSet) picks the locally fastest
+/// candidates, which can be slower once composed, e.g. when a stage writes a layout the next one
+/// reads poorly. A joint tunable instead benchmarks the whole pipeline for every combination of
+/// candidates:
+///
+/// ```ignore
+/// let set = TunableSet::new(key_gen, input_gen).with_joint(
+///     JointTunable::new(|choice, (lhs, rhs, out)| {
+///         let tmp = MATMULS[choice[0]](lhs, rhs)?;
+///         REDUCES[choice[1]](tmp, out)
+///     })
+///     .stage("matmul", &["simple", "double_buffering"])
+///     .stage("reduce", &["unit", "plane", "cube"]),
+/// );
+/// ```
+///
+/// The number of combinations grows with the product of the candidates of each stage, so stages
+/// should only list the candidates worth composing. Combinations that can't work together should
+/// return an error, which skips them.
+pub struct JointTunable<F: TuneInputs, Output> {
+    stages: Vec<(String, Vec<String>)>,
+    pipeline: Arc<JointPipeline<F, Output>>,
+}
+
+impl<F: TuneInputs, Output: 'static> JointTunable<F, Output> {
+    /// Create a joint tunable from the pipeline executing every stage.
+    ///
+    /// The pipeline receives the index of the chosen candidate of each stage, in the order the
+    /// stages are added with [`stage`](Self::stage).
+    pub fn new<Func, Err>(pipeline: Func) -> Self
+    where
+        Err: Into<String> + 'static,
+        Func: for<'a> Fn(&[usize], <F as TuneInputs>::At<'a>) -> Result<Output, Err>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            stages: Vec::new(),
+            pipeline: Arc::new(move |choice, inputs| pipeline(choice, inputs).map_err(Into::into)),
+        }
+    }
+
+    /// Add a stage with the names of its candidates.
+    ///
+    /// # Panics
+    ///
+    /// If `candidates` is empty, since the stage would leave no combination to tune.
+    pub fn stage(mut self, name: &str, candidates: &[&str]) -> Self {
+        assert!(
+            !candidates.is_empty(),
+            "The stage {name} of a joint tunable needs at least one candidate"
+        );
+        let candidates = candidates.iter().map(|name| String::from(*name)).collect();
+        self.stages.push((name.into(), candidates));
+        self
+    }
+
+    /// The number of combinations of candidates, which is never zero since stages can't be
+    /// empty.
+    pub fn combinations(&self) -> usize {
+        self.stages
+            .iter()
+            .map(|(_, candidates)| candidates.len())
+            .product()
+    }
+
+    /// The chosen candidate of every stage for the combination at the given index.
+    ///
+    /// Combinations are ordered with the last stage varying the fastest, matching the order of
+    /// [`tunables`](Self::tunables).
+    pub fn choice(&self, mut index: usize) -> Vec<usize> {
+        let mut choice = vec![0; self.stages.len()];
+
+        for (stage, (_, candidates)) in self.stages.iter().enumerate().rev() {
+            choice[stage] = index % candidates.len();
+            index /= candidates.len();
+        }
+
+        choice
+    }
+
+    /// Create a [tunable](Tunable) for every combination of candidates, named after the chosen
+    /// candidate of each stage, e.g. `matmul=simple+reduce=plane`.
+    pub fn tunables<K>(&self) -> Vec<Tunable<K, F, Output>> {
+        (0..self.combinations())
+            .map(|index| {
+                let choice = self.choice(index);
+                let name = self
+                    .stages
+                    .iter()
+                    .zip(&choice)
+                    .map(|((stage, candidates), chosen)| format!("{stage}={}", candidates[*chosen]))
+                    .collect::<Vec<_>>()
+                    .join("+");
+                let pipeline = self.pipeline.clone();
+
+                Tunable::new(&name, move |inputs| pipeline(&choice, inputs))
+            })
+            .collect()
+    }
+}
@@ -1,4 +1,7 @@
-use super::{AutotuneKey, AutotuneOutput, TunableSet, TuneInputs, Tuner};
+use super::inspect::register_tuner;
+use super::{
+    AutotuneKey, AutotuneOutput, TunableSet, TuneDefault, TuneInputs, TunePersistenceFactory, Tuner,
+};
 use crate::{client::ComputeClient, runtime::Runtime, tune::TuneCacheResult};
 use alloc::string::ToString;
 use alloc::sync::Arc;
@@ -16,6 +19,7 @@ pub struct LocalTuner<AK: AutotuneKey, ID> {
     state: Mutex<Option<HashMap<ID, Arc<Tuner<AK>>>>>,
     name: &'static str,
     sets: spin::RwLock<Option<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>,
+    persistence: Option<TunePersistenceFactory>,
 }
 
 /// Create a local tuner with the provided name.
@@ -42,9 +46,22 @@ where
             state: Mutex::new(None),
             name,
             sets: spin::RwLock::new(None),
+            persistence: None,
         }
     }
 
+    /// Persist the tuning results of every device with the [backend](super::TunePersistence)
+    /// created by the given factory.
+    ///
+    /// ```ignore
+    /// static TUNER: LocalTuner<KernelKey, String> = LocalTuner::new("kernel")
+    ///     .with_persistence(|name, device| Box::new(FlashTunePersistence::new(name, device)));
+    /// ```
+    pub const fn with_persistence(mut self, factory: TunePersistenceFactory) -> Self {
+        self.persistence = Some(factory);
+        self
+    }
+
     /// Get or initialize the [`TunableSet`] for this tuner.
     ///
     /// Returns a cached `Arc<TunableSet>` keyed by the `TypeId` of `init_set`. The
@@ -89,6 +106,12 @@ where
         content
     }
 
+    /// Seed the tuner of the given id with the compiled-in [defaults](TuneDefault) of an
+    /// architecture, see [`Tuner::seed`].
+    pub fn seed(&self, id: &ID, architecture: &str, defaults: &[TuneDefault<AK>]) {
+        self.tuner(id).seed(architecture, defaults);
+    }
+
     /// Clear the autotune state.
     pub fn clear(&self) {
         if let Some(s) = self.state.lock().as_mut() {
@@ -112,7 +135,27 @@ where
             let result = op.execute(inputs.clone());
             checks_outputs.push(result);
         }
-        super::check_autotune_outputs(checks_outputs);
+        super::check_autotune_outputs(checks_outputs, operations.tolerance());
+    }
+
+    fn tuner(&self, id: &ID) -> Arc<Tuner<AK>> {
+        let mut state_lock = self.state.lock();
+        let state_map = state_lock.get_or_insert_with(|| HashMap::new());
+        state_map
+            .entry(id.clone())
+            .or_insert_with(move || {
+                let name = self.name.replace("::", "-");
+                let device_id = id.to_string();
+                let tuner = Tuner::new(&name, &device_id);
+
+                let tuner = Arc::new(match self.persistence {
+                    Some(factory) => tuner.with_persistence(factory(&name, &device_id)),
+                    None => tuner,
+                });
+                register_tuner(&name, &device_id, &tuner);
+                tuner
+            })
+            .clone()
     }
 
     /// Execute the fastest operation in a [`TunableSet`], triggering a tuning pass on
@@ -129,18 +172,7 @@ where
         Out: AutotuneOutput,
     {
         let key = operations.generate_key(&inputs);
-
-        let tuner = {
-            let mut state_lock = self.state.lock();
-            let state_map = state_lock.get_or_insert_with(|| HashMap::new());
-            state_map
-                .entry(id.clone())
-                .or_insert_with(move || {
-                    let name = self.name.replace("::", "-");
-                    Arc::new(Tuner::new(&name, &id.to_string()))
-                })
-                .clone()
-        };
+        let tuner = self.tuner(id);
 
         // First, check for a cache hit under a read lock.
         if let TuneCacheResult::Hit { fastest_index } = tuner.fastest(&key) {
@@ -187,4 +219,54 @@ where
             }
         }
     }
+
+    /// Execute the fastest operation in a [`TunableSet`], like [`execute`](Self::execute),
+    /// but waits for the tuning result instead of falling back to a default operation.
+    ///
+    /// On wasm, tuning results resolve on the browser event loop, so [`execute`](Self::execute)
+    /// runs the first viable operation while the tuning job is in flight. The returned future
+    /// only resolves once the actual fastest operation has run.
+    pub async fn execute_async<'a, R: Runtime, I: TuneInputs, Out>(
+        &self,
+        id: &ID,
+        client: &ComputeClient<R>,
+        operations: Arc<TunableSet<AK, I, Out>>,
+        inputs: <I as TuneInputs>::At<'a>,
+    ) -> Out
+    where
+        <I as TuneInputs>::At<'a>: Clone + Send,
+        Out: AutotuneOutput,
+    {
+        let key = operations.generate_key(&inputs);
+        let tuner = self.tuner(id);
+
+        let mut fastest = tuner.fastest(&key);
+        if !matches!(fastest, TuneCacheResult::Hit { .. }) {
+            fastest = tuner.check_tune::<R, I, Out>(
+                &key,
+                &inputs,
+                &operations,
+                || operations.compute_checksum(),
+                client,
+            );
+        }
+        if let TuneCacheResult::Pending = fastest {
+            fastest = tuner.wait_tuned(&key).await;
+        }
+
+        match fastest {
+            TuneCacheResult::Hit { fastest_index } => {
+                #[cfg(feature = "autotune-checks")]
+                self.checks::<I, Out>(&operations, &inputs);
+
+                operations
+                    .fastest(fastest_index)
+                    .execute(inputs)
+                    .expect("Should run when selected by autotune.")
+            }
+            TuneCacheResult::Unchecked | TuneCacheResult::Miss | TuneCacheResult::Pending => {
+                panic!("The tuning job for {key} completed without committing a result.")
+            }
+        }
+    }
 }
@@ -27,10 +27,16 @@
 //! HRTB bound is spelled out directly (closure inference).
 
 mod base;
+mod defaults;
 mod input_generator;
+mod inspect;
+mod joint;
 mod key_generator;
 mod local;
 mod operation;
+mod persistence;
+mod scheduler;
+mod tolerance;
 mod tune_benchmark;
 mod tune_cache;
 mod tune_inputs;
@@ -38,10 +44,17 @@ mod tuner;
 mod util;
 
 pub use base::*;
+pub use defaults::*;
 pub use input_generator::*;
+pub use inspect::{
+    TuneEntry, TunerSnapshot, autotune_snapshot, clear_autotune_caches, resume_tuning,
+};
+pub use joint::*;
 pub use key_generator::*;
 pub use local::*;
 pub use operation::*;
+pub use persistence::*;
+pub use tolerance::*;
 pub use tune_benchmark::*;
 pub use tune_cache::*;
 pub use tune_inputs::*;
@@ -4,14 +4,16 @@ use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::fmt::{Debug, Display};
 use core::hash::Hash;
+use core::time::Duration;
 
 use alloc::format;
 
 use super::{
-    AutotuneError, input_generator::InputGenerator, key_generator::KeyGenerator,
+    AutotuneError, AutotuneTolerance, input_generator::InputGenerator, key_generator::KeyGenerator,
     tune_inputs::TuneInputs,
 };
-use super::{Tunable, TunePlan};
+use super::{JointTunable, Tunable, TunePlan};
+use crate::config::{CubeClRuntimeConfig, RuntimeConfig};
 
 /// A type-erased delegate for a tunable function.
 ///
@@ -29,6 +31,11 @@ pub struct TuneFn<I: TuneInputs, Out> {
 }
 
 impl<I: TuneInputs, Out: 'static> TuneFn<I, Out> {
+    /// The name of the tunable.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Run the wrapped function on the given inputs.
     pub fn execute<'a>(&self, inputs: <I as TuneInputs>::At<'a>) -> Result<Out, AutotuneError> {
         (self.func)(inputs)
@@ -41,6 +48,8 @@ pub struct TunableSet<K: AutotuneKey, F: TuneInputs, Output: 'static> {
     tunables: Vec<Tunable<K, F, Output>>,
     key_gen: Arc<dyn KeyGenerator<K, F> + Send + Sync>,
     input_gen: Arc<dyn InputGenerator<K, F> + Send + Sync>,
+    tolerance: Option<AutotuneTolerance>,
+    time_budget: Option<Duration>,
 }
 
 impl<K: AutotuneKey, F: TuneInputs, Output: 'static> TunableSet<K, F, Output> {
@@ -60,6 +69,8 @@ impl<K: AutotuneKey, F: TuneInputs, Output: 'static> TunableSet<K, F, Output> {
             tunables: Default::default(),
             input_gen: Arc::new(input_gen),
             key_gen: Arc::new(key_gen),
+            tolerance: None,
+            time_budget: None,
         }
     }
 
@@ -70,11 +81,69 @@ impl<K: AutotuneKey, F: TuneInputs, Output: 'static> TunableSet<K, F, Output> {
     }
 
     /// Register a tunable with this tunable set.
+    ///
+    /// [Experimental](Tunable::experimental) tunables are skipped unless their feature is enabled
+    /// in the [configuration](crate::config::experimental::ExperimentalConfig).
     pub fn with(mut self, tunable: Tunable<K, F, Output>) -> Self {
-        self.tunables.push(tunable);
+        let config = CubeClRuntimeConfig::get();
+
+        if tunable.is_enabled(&config.experimental) {
+            self.tunables.push(tunable);
+        } else {
+            log::debug!(
+                "Skipping the disabled experimental tunable {}",
+                tunable.function.name
+            );
+        }
+        self
+    }
+
+    /// Register every combination of candidates of a [`JointTunable`] with this tunable set, so
+    /// its stages are tuned jointly.
+    pub fn with_joint(mut self, joint: JointTunable<F, Output>) -> Self {
+        self.tunables.extend(joint.tunables());
+        self
+    }
+
+    /// Set the tolerance used by the `autotune-checks` feature when comparing the outputs of
+    /// the tunables in this set.
+    ///
+    /// Without it, [outputs](super::AutotuneOutput) fall back to the defaults of their element
+    /// type.
+    pub fn with_tolerance(mut self, tolerance: AutotuneTolerance) -> Self {
+        self.tolerance = Some(tolerance);
         self
     }
 
+    /// The tolerance configured with [`with_tolerance`](Self::with_tolerance), if any.
+    pub fn tolerance(&self) -> Option<&AutotuneTolerance> {
+        self.tolerance.as_ref()
+    }
+
+    /// Limit the wall-clock time spent benchmarking the candidates of a key, including their
+    /// launches, the synchronizations and the candidates that fail.
+    ///
+    /// Once the budget is exceeded, the fastest candidate so far is used and the remaining ones
+    /// are kept aside, until the search is continued with
+    /// [`resume_tuning`](super::resume_tuning).
+    ///
+    /// On wasm the benchmarks are only resolved once the search is over, so the
+    /// [budget](Self::time_budget) is always `None` and every candidate is benchmarked.
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// The budget configured with [`with_time_budget`](Self::with_time_budget), if any. Always
+    /// `None` on wasm.
+    pub fn time_budget(&self) -> Option<Duration> {
+        if cfg!(target_family = "wasm") {
+            None
+        } else {
+            self.time_budget
+        }
+    }
+
     /// All candidate operations in this set, in registration order.
     pub fn autotunables(&self) -> impl Iterator<Item = &TuneFn<F, Output>> {
         self.tunables.iter().map(|tunable| &tunable.function)
@@ -0,0 +1,138 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use hashbrown::HashMap;
+
+/// Storage for tuning results that outlives the process.
+///
+/// Keys are the [`Display`](core::fmt::Display) representation of the
+/// [autotune keys](super::AutotuneKey), so backends only deal with strings and work on `no_std`
+/// targets, e.g. to persist results to flash through the platform's own storage interface.
+///
+/// Each result comes with the checksum of the [tunable set](super::TunableSet) it was measured
+/// with. A stored result must only be returned for the same checksum, since the candidate indices
+/// are meaningless once the candidates change.
+///
+/// Backends are attached to a [`LocalTuner`](super::LocalTuner) with
+/// [`with_persistence`](super::LocalTuner::with_persistence). They complement the built-in
+/// persistent cache enabled with `std`, which is looked up first.
+pub trait TunePersistence: Send + core::fmt::Debug {
+    /// Load the index of the fastest candidate stored for the given key and checksum, if any.
+    fn load(&mut self, key: &str, checksum: &str) -> Option<usize>;
+
+    /// Store the index of the fastest candidate for the given key and checksum.
+    fn store(&mut self, key: &str, checksum: &str, fastest_index: usize);
+}
+
+/// Create the [persistence backend](TunePersistence) of a tuner from its name and device id.
+pub type TunePersistenceFactory = fn(name: &str, device_id: &str) -> Box<dyn TunePersistence>;
+
+/// A tuning result held by an [`InMemoryTunePersistence`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TuneRecord {
+    /// The autotune key, formatted.
+    pub key: String,
+    /// Checksum of the tunable set the result was measured with.
+    pub checksum: String,
+    /// The index of the fastest candidate.
+    pub fastest_index: usize,
+}
+
+/// A [persistence backend](TunePersistence) keeping results in memory.
+///
+/// Clones share the same records, so a handle can be kept to export the results, e.g. to write
+/// them to storage at shutdown, or to import results saved by a previous run.
+#[derive(Default, Clone, Debug)]
+pub struct InMemoryTunePersistence {
+    records: Arc<spin::Mutex<HashMap<String, TuneRecord>>>,
+}
+
+impl InMemoryTunePersistence {
+    /// Create an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a backend holding the given records.
+    pub fn from_records(records: impl IntoIterator<Item = TuneRecord>) -> Self {
+        let records = records
+            .into_iter()
+            .map(|record| (record.key.clone(), record))
+            .collect();
+
+        Self {
+            records: Arc::new(spin::Mutex::new(records)),
+        }
+    }
+
+    /// All records currently stored.
+    pub fn records(&self) -> Vec<TuneRecord> {
+        self.records.lock().values().cloned().collect()
+    }
+}
+
+impl TunePersistence for InMemoryTunePersistence {
+    fn load(&mut self, key: &str, checksum: &str) -> Option<usize> {
+        self.records
+            .lock()
+            .get(key)
+            .filter(|record| record.checksum == checksum)
+            .map(|record| record.fastest_index)
+    }
+
+    fn store(&mut self, key: &str, checksum: &str, fastest_index: usize) {
+        let record = TuneRecord {
+            key: key.to_string(),
+            checksum: checksum.to_string(),
+            fastest_index,
+        };
+        self.records.lock().insert(record.key.clone(), record);
+    }
+}
+
+#[cfg(std_io)]
+pub use file::FileTunePersistence;
+
+#[cfg(std_io)]
+mod file {
+    use super::TunePersistence;
+    use alloc::string::{String, ToString};
+    use cubecl_common::cache::{Cache, CacheOption};
+    use std::path::PathBuf;
+
+    /// A [persistence backend](TunePersistence) storing results in a file under the given root
+    /// directory.
+    #[derive(Debug)]
+    pub struct FileTunePersistence {
+        cache: Cache<(String, String), usize>,
+    }
+
+    impl FileTunePersistence {
+        /// Create a backend storing the results at the given path under `root`.
+        pub fn new(root: impl Into<PathBuf>, path: &str) -> Self {
+            let options = CacheOption::default().root(root).name("autotune");
+
+            Self {
+                cache: Cache::new(path, options),
+            }
+        }
+    }
+
+    impl TunePersistence for FileTunePersistence {
+        fn load(&mut self, key: &str, checksum: &str) -> Option<usize> {
+            self.cache
+                .get(&(key.to_string(), checksum.to_string()))
+                .copied()
+        }
+
+        fn store(&mut self, key: &str, checksum: &str, fastest_index: usize) {
+            let key = (key.to_string(), checksum.to_string());
+
+            // A key is tuned again after its results are cleared, the new result wins.
+            self.cache.update(key, fastest_index);
+        }
+    }
+}
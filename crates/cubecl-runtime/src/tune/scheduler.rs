@@ -0,0 +1,196 @@
+use cubecl_common::device::DeviceId;
+
+/// Hold the turn of the current tuner on a device, see [`acquire_tune_slot`].
+///
+/// The turn is passed to the next tuner waiting on the device when dropped.
+pub(crate) struct TuneSlot {
+    #[cfg(multi_threading)]
+    queue: alloc::sync::Arc<imp::DeviceQueue>,
+}
+
+/// Wait for the turn of the current thread to benchmark a candidate on the given device.
+///
+/// Tuners sharing a device, e.g. matmul, reduce and convolution tuners starting at once from
+/// different threads, are served in the order they ask for a slot. Since a slot covers a single
+/// candidate, their benchmarks are interleaved: no tuner starves while another tunes all its
+/// candidates, and each tuner waits at most one benchmark per other tuner between its own.
+///
+/// A thread already holding a slot for the device re-enters it, so candidates tuning nested
+/// operations don't deadlock.
+pub(crate) fn acquire_tune_slot(
+    #[cfg_attr(not(multi_threading), allow(unused_variables))] device_id: DeviceId,
+) -> TuneSlot {
+    #[cfg(multi_threading)]
+    {
+        imp::acquire(device_id)
+    }
+
+    #[cfg(not(multi_threading))]
+    {
+        // Single-threaded targets never contend for the device.
+        TuneSlot {}
+    }
+}
+
+#[cfg(multi_threading)]
+impl Drop for TuneSlot {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+#[cfg(multi_threading)]
+mod imp {
+    use super::TuneSlot;
+    use alloc::sync::Arc;
+    use core::time::Duration;
+    use cubecl_common::device::DeviceId;
+    use hashbrown::HashMap;
+    use std::thread::ThreadId;
+
+    /// Number of `thread::yield_now` calls before waiting tuners start sleeping.
+    const YIELD_BUDGET: u32 = 64;
+    /// Sleep duration once the yield budget is exhausted, short compared to a benchmark.
+    const SLEEP_STEP: Duration = Duration::from_micros(100);
+
+    static QUEUES: spin::Mutex<Option<HashMap<DeviceId, Arc<DeviceQueue>>>> =
+        spin::Mutex::new(None);
+
+    /// A ticket queue serving the tuners of a device in order.
+    #[derive(Default)]
+    pub(super) struct DeviceQueue {
+        state: spin::Mutex<QueueState>,
+    }
+
+    #[derive(Default)]
+    struct QueueState {
+        next_ticket: u64,
+        serving: u64,
+        owner: Option<ThreadId>,
+        depth: usize,
+    }
+
+    pub(super) fn acquire(device_id: DeviceId) -> TuneSlot {
+        let queue = QUEUES
+            .lock()
+            .get_or_insert_with(HashMap::new)
+            .entry(device_id)
+            .or_default()
+            .clone();
+        let thread = std::thread::current().id();
+
+        let ticket = {
+            let mut state = queue.state.lock();
+            if state.owner == Some(thread) {
+                state.depth += 1;
+                None
+            } else {
+                state.next_ticket += 1;
+                Some(state.next_ticket - 1)
+            }
+        };
+        let Some(ticket) = ticket else {
+            return TuneSlot { queue };
+        };
+
+        let mut idle_count: u32 = 0;
+        loop {
+            {
+                let mut state = queue.state.lock();
+                if state.serving == ticket {
+                    state.owner = Some(thread);
+                    state.depth = 1;
+                    break;
+                }
+            }
+
+            if idle_count < YIELD_BUDGET {
+                std::thread::yield_now();
+                idle_count += 1;
+            } else {
+                std::thread::sleep(SLEEP_STEP);
+            }
+        }
+
+        TuneSlot { queue }
+    }
+
+    /// The number of tuners waiting for their turn on the given device.
+    #[cfg(test)]
+    pub(super) fn waiting(device_id: DeviceId) -> u64 {
+        let queues = QUEUES.lock();
+        let Some(queue) = queues.as_ref().and_then(|queues| queues.get(&device_id)) else {
+            return 0;
+        };
+        let state = queue.state.lock();
+
+        state.next_ticket - state.serving - state.owner.is_some() as u64
+    }
+
+    impl DeviceQueue {
+        pub(super) fn release(&self) {
+            let mut state = self.state.lock();
+            state.depth -= 1;
+
+            if state.depth == 0 {
+                state.owner = None;
+                state.serving += 1;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, multi_threading))]
+mod tests {
+    use super::*;
+    use alloc::{sync::Arc, vec::Vec};
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    #[test_log::test]
+    fn tuners_sharing_a_device_take_turns() {
+        // Not used by any other test, so only the two tuners below queue on it.
+        let device_id = DeviceId {
+            type_id: u16::MAX,
+            index_id: 0,
+        };
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // Each tuner keeps its turn until the other one waits for the next, so the order doesn't
+        // depend on how the threads are scheduled.
+        let tune = |name: char, done: Arc<AtomicBool>, other_done: Arc<AtomicBool>| {
+            let order = order.clone();
+            move || {
+                for _ in 0..3 {
+                    let _slot = acquire_tune_slot(device_id);
+                    order.lock().unwrap().push(name);
+                    while imp::waiting(device_id) == 0 && !other_done.load(Ordering::Acquire) {
+                        std::thread::yield_now();
+                    }
+                }
+                done.store(true, Ordering::Release);
+            }
+        };
+
+        let (a_done, b_done) = (
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+        );
+        let first = acquire_tune_slot(device_id);
+        let b = std::thread::spawn(tune('b', b_done.clone(), a_done.clone()));
+        // The second tuner queues while the first one holds the device.
+        while imp::waiting(device_id) == 0 {
+            std::thread::yield_now();
+        }
+        let a = std::thread::spawn(tune('a', a_done, b_done));
+        while imp::waiting(device_id) < 2 {
+            std::thread::yield_now();
+        }
+        core::mem::drop(first);
+
+        a.join().unwrap();
+        b.join().unwrap();
+
+        assert_eq!(*order.lock().unwrap(), ['b', 'a', 'b', 'a', 'b', 'a']);
+    }
+}
@@ -0,0 +1,197 @@
+use cubecl_ir::{ElemType, FloatKind};
+
+/// Tolerance used by the `autotune-checks` feature when comparing the outputs of candidate
+/// kernels against each other.
+///
+/// Two values `a` and `b` are considered equivalent when
+/// `|a - b| <= max(absolute, relative * max(|a|, |b|), ulps * epsilon * max(|a|, |b|))`,
+/// where `epsilon` is the machine epsilon of the element type the outputs are stored in, or of
+/// `f64` when it isn't known.
+///
+/// Candidates often use different accumulation orders, so low-precision outputs (bf16, fp8)
+/// legitimately differ by a few ULPs; [`for_elem`](Self::for_elem) provides defaults that
+/// account for this.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutotuneTolerance {
+    /// Maximum absolute difference, mostly relevant for values close to zero.
+    pub absolute: f64,
+    /// Maximum difference relative to the largest magnitude of both values.
+    pub relative: f64,
+    /// Maximum difference in units in the last place of the element type.
+    pub ulps: u32,
+    /// The machine epsilon of the element type, used to express `ulps`.
+    pub epsilon: f64,
+}
+
+impl AutotuneTolerance {
+    /// A tolerance that only accepts bitwise identical values, ignoring the sign of zero and
+    /// treating `NaN` as equal to itself.
+    ///
+    /// ULPs added with [`with_ulps`](Self::with_ulps) are the ones of `f64`, unless the epsilon
+    /// of the element type is set with [`with_epsilon`](Self::with_epsilon).
+    pub const fn exact() -> Self {
+        Self {
+            absolute: 0.0,
+            relative: 0.0,
+            ulps: 0,
+            epsilon: f64::EPSILON,
+        }
+    }
+
+    /// The default tolerance for outputs stored in the given element type.
+    pub fn for_elem(elem: ElemType) -> Self {
+        let kind = match elem {
+            ElemType::Float(kind) => kind,
+            ElemType::Int(_) | ElemType::UInt(_) | ElemType::Bool => return Self::exact(),
+        };
+        let epsilon = epsilon(kind);
+        let ulps = match kind {
+            FloatKind::F64 | FloatKind::F32 | FloatKind::TF32 => 8,
+            FloatKind::F16 | FloatKind::BF16 | FloatKind::Flex32 => 4,
+            FloatKind::E2M1
+            | FloatKind::E2M3
+            | FloatKind::E3M2
+            | FloatKind::E4M3
+            | FloatKind::E5M2
+            | FloatKind::UE8M0 => 2,
+        };
+
+        Self {
+            absolute: epsilon,
+            relative: 0.0,
+            ulps,
+            epsilon,
+        }
+    }
+
+    /// Set the maximum absolute difference.
+    pub fn with_absolute(mut self, absolute: f64) -> Self {
+        self.absolute = absolute;
+        self
+    }
+
+    /// Set the maximum relative difference.
+    pub fn with_relative(mut self, relative: f64) -> Self {
+        self.relative = relative;
+        self
+    }
+
+    /// Set the maximum difference in ULPs.
+    pub fn with_ulps(mut self, ulps: u32) -> Self {
+        self.ulps = ulps;
+        self
+    }
+
+    /// Set the machine epsilon of the element type, which defines the size of one ULP.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Whether both values are equivalent under this tolerance.
+    pub fn is_close(&self, a: f64, b: f64) -> bool {
+        if a == b || (a.is_nan() && b.is_nan()) {
+            return true;
+        }
+        if !a.is_finite() || !b.is_finite() {
+            return false;
+        }
+
+        let diff = (a - b).abs();
+        let magnitude = f64::max(a.abs(), b.abs());
+        let allowed = f64::max(
+            self.absolute,
+            f64::max(
+                self.relative * magnitude,
+                self.ulps as f64 * self.epsilon * magnitude,
+            ),
+        );
+
+        diff <= allowed
+    }
+
+    /// Returns the index of the first pair of values that aren't equivalent, if any. Slices of
+    /// different lengths mismatch at the end of the shortest one.
+    pub fn first_mismatch<T: Into<f64> + Copy>(
+        &self,
+        reference: &[T],
+        other: &[T],
+    ) -> Option<usize> {
+        let mismatch = reference
+            .iter()
+            .zip(other)
+            .position(|(a, b)| !self.is_close((*a).into(), (*b).into()));
+
+        match mismatch {
+            Some(index) => Some(index),
+            None if reference.len() != other.len() => {
+                Some(usize::min(reference.len(), other.len()))
+            }
+            None => None,
+        }
+    }
+}
+
+fn epsilon(kind: FloatKind) -> f64 {
+    let mantissa_bits = match kind {
+        FloatKind::UE8M0 => 0,
+        FloatKind::E2M1 => 1,
+        FloatKind::E3M2 | FloatKind::E5M2 => 2,
+        FloatKind::E2M3 | FloatKind::E4M3 => 3,
+        FloatKind::BF16 => 7,
+        FloatKind::F16 | FloatKind::Flex32 | FloatKind::TF32 => 10,
+        FloatKind::F32 => 23,
+        FloatKind::F64 => 52,
+    };
+
+    1.0 / (1u64 << mantissa_bits) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_only_accepts_identical_values() {
+        let tolerance = AutotuneTolerance::exact();
+
+        assert!(tolerance.is_close(1.0, 1.0));
+        assert!(tolerance.is_close(f64::NAN, f64::NAN));
+        assert!(!tolerance.is_close(1.0, 1.0 + f64::EPSILON));
+    }
+
+    #[test]
+    fn low_precision_defaults_allow_a_few_ulps() {
+        let bf16 = AutotuneTolerance::for_elem(ElemType::Float(FloatKind::BF16));
+        let f32 = AutotuneTolerance::for_elem(ElemType::Float(FloatKind::F32));
+
+        // Two bf16 ULPs at a magnitude of 100.
+        let (a, b) = (100.0, 100.0 + 2.0 * 100.0 / 128.0);
+        assert!(bf16.is_close(a, b));
+        assert!(!f32.is_close(a, b));
+    }
+
+    #[test]
+    fn exact_with_ulps_allows_a_few_ulps() {
+        let tolerance = AutotuneTolerance::exact().with_ulps(2);
+
+        assert!(tolerance.is_close(1.0, 1.0 + 2.0 * f64::EPSILON));
+        assert!(!tolerance.is_close(1.0, 1.0 + 4.0 * f64::EPSILON));
+
+        // Two bf16 ULPs at a magnitude of 100.
+        let bf16 = tolerance.with_epsilon(1.0 / 128.0);
+        assert!(bf16.is_close(100.0, 100.0 + 2.0 * 100.0 / 128.0));
+    }
+
+    #[test]
+    fn first_mismatch_reports_index() {
+        let tolerance = AutotuneTolerance::exact().with_absolute(0.5);
+
+        assert_eq!(tolerance.first_mismatch(&[1.0f32, 2.0], &[1.2, 2.4]), None);
+        assert_eq!(
+            tolerance.first_mismatch(&[1.0f32, 2.0], &[1.2, 3.0]),
+            Some(1)
+        );
+        assert_eq!(tolerance.first_mismatch(&[1.0f32, 2.0], &[1.0]), Some(1));
+    }
+}
@@ -1,5 +1,7 @@
-use super::{AutotuneError, TuneFn, TuneInputs};
+use super::{AutotuneError, TuneFn, TuneInputs, scheduler::acquire_tune_slot};
+use crate::server::{ProfileError, ServerError};
 use crate::{client::ComputeClient, runtime::Runtime};
+use alloc::format;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use cubecl_common::profile::ProfileDuration;
@@ -10,6 +12,24 @@ pub trait AutotuneOutput: Send + 'static {
     /// Checks if the output of an autotune operation is the same as another one on the same
     /// problem.
     fn check_equivalence(&self, other: Self);
+
+    #[cfg(feature = "autotune-checks")]
+    /// Same as [`check_equivalence`](Self::check_equivalence), with the tolerance configured on
+    /// the [`TunableSet`](super::TunableSet), if any.
+    ///
+    /// Without a configured tolerance, implementations should use the defaults of their element
+    /// type, see [`AutotuneTolerance::for_elem`](super::AutotuneTolerance::for_elem). The default
+    /// implementation ignores the tolerance.
+    fn check_equivalence_with_tolerance(
+        &self,
+        other: Self,
+        tolerance: Option<&super::AutotuneTolerance>,
+    ) where
+        Self: Sized,
+    {
+        let _ = tolerance;
+        self.check_equivalence(other);
+    }
 }
 
 impl AutotuneOutput for () {
@@ -19,19 +39,61 @@ impl AutotuneOutput for () {
     }
 }
 
+/// The samples collected while benchmarking a tunable.
+pub struct TuneSamples {
+    /// The profiled duration of each successful sample.
+    pub profiles: Vec<ProfileDuration>,
+    /// The average energy consumed by one sample in millijoules, when the runtime can read the
+    /// power sensors of the device.
+    pub energy: Option<u64>,
+}
+
 /// Benchmark how long this operation takes for a number of samples.
 ///
-/// Returns at least one duration, otherwise an error is returned.
+/// Returns at least one duration, otherwise an error is returned. See [`tune_samples`] to also
+/// measure the energy of the samples.
 pub fn tune_benchmark<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
     operation: &TuneFn<F, Out>,
     inputs: <F as TuneInputs>::At<'a>,
     client: ComputeClient<R>,
 ) -> Result<Vec<ProfileDuration>, AutotuneError> {
+    tune_samples(operation, inputs, client).map(|samples| samples.profiles)
+}
+
+/// Benchmark the duration and energy of this operation for a number of samples.
+///
+/// Returns at least one duration, otherwise an error is returned.
+///
+/// When profiling is enabled, the executions are recorded as ranges named `autotune/{name}`, so
+/// the tuning overhead is told apart from the steady-state kernels in traces.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "trace", skip(operation, inputs, client), fields(name = %operation.name))
+)]
+pub fn tune_samples<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
+    operation: &TuneFn<F, Out>,
+    inputs: <F as TuneInputs>::At<'a>,
+    client: ComputeClient<R>,
+) -> Result<TuneSamples, AutotuneError> {
+    let range = format!("autotune/{}", operation.name);
+
+    #[cfg(feature = "profile-tracy")]
+    let _span = tracy_client::Client::running().unwrap().span_alloc(
+        Some(&range),
+        "tune_benchmark",
+        file!(),
+        line!(),
+        0,
+    );
+
+    // Take turns with the other tuners of the device, one candidate at a time.
+    let _slot = acquire_tune_slot(client.device_id());
+
     // `scoped` holds exclusive device access for the whole benchmark loop and
     // accepts non-`'static` closures.
     client
         .clone()
-        .exclusive(move || profile_exclusive(operation, inputs, client))
+        .exclusive(move || profile_exclusive(operation, &range, inputs, client))
         .map_err(|err| AutotuneError::Unknown {
             name: operation.name.to_string(),
             err: err.to_string(),
@@ -40,13 +102,15 @@ pub fn tune_benchmark<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
 
 fn profile_exclusive<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
     operation: &TuneFn<F, Out>,
+    range: &str,
     inputs: <F as TuneInputs>::At<'a>,
     client: ComputeClient<R>,
-) -> Result<Vec<ProfileDuration>, AutotuneError> {
-    warmup(operation, inputs.clone(), client.clone())?;
+) -> Result<TuneSamples, AutotuneError> {
+    warmup(operation, range, inputs.clone(), client.clone())?;
 
     let num_samples = 10;
     let mut durations = Vec::new();
+    let energy_start = client.energy_consumption();
 
     for _ in 0..num_samples {
         let result: Result<
@@ -61,7 +125,7 @@ fn profile_exclusive<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
                     // might optimize away code that needs to be profiled.
                     operation.execute(inputs)
                 },
-                &operation.name,
+                range,
             )
         };
 
@@ -85,16 +149,27 @@ fn profile_exclusive<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
     }
 
     if durations.is_empty() {
-        Err(AutotuneError::InvalidSamples {
+        return Err(AutotuneError::InvalidSamples {
             name: operation.name.to_string(),
-        })
-    } else {
-        Ok(durations)
+        });
     }
+
+    // The counter is only read when the runtime supports it, since it waits for the samples to
+    // complete.
+    let energy = energy_start.and_then(|start| {
+        let end = client.energy_consumption()?;
+        Some(end.saturating_sub(start) / durations.len() as u64)
+    });
+
+    Ok(TuneSamples {
+        profiles: durations,
+        energy,
+    })
 }
 
 fn warmup<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
     operation: &TuneFn<F, Out>,
+    range: &str,
     inputs: <F as TuneInputs>::At<'a>,
     client: ComputeClient<R>,
 ) -> Result<(), AutotuneError> {
@@ -106,7 +181,7 @@ fn warmup<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
 
     for _ in 0..num_warmup {
         let inputs = inputs.clone();
-        let profiled = client.profile(move || operation.execute(inputs), &operation.name);
+        let profiled = client.profile(move || operation.execute(inputs), range);
 
         match profiled {
             Ok(_) => {}
@@ -115,12 +190,22 @@ fn warmup<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
     }
 
     if errors.len() < num_warmup {
-        Ok(())
-    } else {
-        let msg = alloc::format!("{:?}", errors.remove(num_warmup - 1));
-        Err(AutotuneError::Unknown {
+        return Ok(());
+    }
+
+    // Launch errors are kept as is, so the ones that happen on every run are recognized.
+    match errors.remove(num_warmup - 1) {
+        ProfileError::Launch(err) => Err(AutotuneError::Launch(err)),
+        ProfileError::Server(err) => match *err {
+            ServerError::Launch(err) => Err(AutotuneError::Launch(err)),
+            err => Err(AutotuneError::Unknown {
+                name: operation.name.to_string(),
+                err: format!("{err:?}"),
+            }),
+        },
+        err => Err(AutotuneError::Unknown {
             name: operation.name.to_string(),
-            err: msg,
-        })
+            err: format!("{err:?}"),
+        }),
     }
 }
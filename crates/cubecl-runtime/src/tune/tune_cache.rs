@@ -1,6 +1,3 @@
-#[cfg(std_io)]
-use std::vec::Vec;
-
 #[cfg(std_io)]
 use cubecl_common::cache::Cache;
 #[cfg(std_io)]
@@ -8,9 +5,12 @@ use cubecl_common::cache::CacheError;
 #[cfg(std_io)]
 use serde::{Deserialize, Serialize};
 
-use super::{AutotuneError, AutotuneKey, AutotuneOutcome};
-use alloc::string::String;
-use hashbrown::HashMap;
+use super::{AutotuneError, AutotuneKey, AutotuneOutcome, TuneDefault, TuneEntry, TunePersistence};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use async_channel::{Receiver, Sender};
+use hashbrown::{HashMap, HashSet};
 
 #[derive(Debug)]
 pub(crate) enum CacheEntry {
@@ -37,6 +37,17 @@ pub(crate) struct PersistentCacheKey<K> {
     checksum: String,
 }
 
+/// A tunable that failed to compile or launch for a key on a device.
+#[cfg_attr(std_io, derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub(crate) struct FailureKey<K> {
+    fingerprint: String,
+    key: K,
+    name: String,
+    /// The checksum of the tunable set, so failures are forgotten when the tunables change.
+    checksum: String,
+}
+
 /// Persistent cache entry
 #[cfg(std_io)]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -78,12 +89,36 @@ impl PartialEq for AutotuneResult {
     }
 }
 
+/// A search interrupted by the time budget of its [tunable set](super::TunableSet).
+#[derive(Debug)]
+pub(crate) struct ResumableSearch {
+    /// The candidates that weren't benchmarked yet.
+    pub(crate) remaining: Vec<usize>,
+    /// The results of the candidates benchmarked so far, fastest first.
+    pub(crate) results: Vec<AutotuneResult>,
+    /// Whether the search continues on the next execution of the key.
+    resume: bool,
+}
+
 /// Use to find and reuse the best kernel for some input
 #[derive(Debug)]
 pub(crate) struct TuneCache<K> {
     in_memory_cache: HashMap<K, CacheEntry>,
+    /// Searches that ran out of time, with the best result so far committed to the cache.
+    resumable: HashMap<K, ResumableSearch>,
+    /// Callers waiting on a pending key, notified when its result is committed.
+    waiters: HashMap<K, Vec<Sender<()>>>,
+    /// Compiled-in results used for keys that were never tuned.
+    defaults: Vec<TuneDefault<K>>,
+    /// User-provided storage for tuning results.
+    persistence: Option<Box<dyn TunePersistence>>,
+    /// Tunables that can't run on a device, never benchmarked again.
+    failures: HashSet<FailureKey<K>>,
     #[cfg(std_io)]
     persistent_cache: Cache<PersistentCacheKey<K>, PersistentCacheValue>,
+    /// The deterministic failures, with their error message.
+    #[cfg(std_io)]
+    persistent_failures: Cache<FailureKey<K>, String>,
 }
 
 /// Result of the cache try
@@ -112,33 +147,59 @@ impl<K: AutotuneKey> TuneCache<K> {
         #[cfg(std_io)]
         {
             use crate::config::RuntimeConfig;
-            use std::format;
 
             let root = crate::config::CubeClRuntimeConfig::get()
                 .autotune
                 .cache
                 .root();
-            let options = cubecl_common::cache::CacheOption::default();
-            let mut cache = TuneCache {
-                in_memory_cache: HashMap::new(),
-                persistent_cache: Cache::new(
-                    format!("{device_id}/{name}"),
-                    options.root(root).name("autotune"),
-                ),
-            };
-            cache.load();
-            cache
+            Self::new_in(name, device_id, root)
         }
 
         #[cfg(not(std_io))]
         {
             TuneCache {
                 in_memory_cache: HashMap::new(),
+                resumable: HashMap::new(),
+                waiters: HashMap::new(),
+                defaults: Vec::new(),
+                persistence: None,
+                failures: HashSet::new(),
             }
         }
     }
 
-    pub fn fastest(&self, key: &K) -> TuneCacheResult {
+    /// Create a cache persisted in the given root directory instead of the configured one.
+    #[cfg(std_io)]
+    pub(crate) fn new_in(name: &str, device_id: &str, root: std::path::PathBuf) -> Self {
+        use std::format;
+
+        let options = cubecl_common::cache::CacheOption::default();
+        let mut cache = TuneCache {
+            in_memory_cache: HashMap::new(),
+            resumable: HashMap::new(),
+            waiters: HashMap::new(),
+            defaults: Vec::new(),
+            persistence: None,
+            failures: HashSet::new(),
+            persistent_cache: Cache::new(
+                format!("{device_id}/{name}"),
+                options.clone().root(root.clone()).name("autotune"),
+            ),
+            persistent_failures: Cache::new(
+                format!("{device_id}/{name}"),
+                options.root(root).name("autotune-failures"),
+            ),
+        };
+        cache.load();
+        cache
+    }
+
+    pub fn fastest(&mut self, key: &K) -> TuneCacheResult {
+        // Resumed searches are tuned again, starting from their best result so far.
+        if self.resumable.get(key).is_some_and(|search| search.resume) {
+            return TuneCacheResult::Miss;
+        }
+
         let Some(val) = self.in_memory_cache.get(key) else {
             return TuneCacheResult::Miss;
         };
@@ -194,6 +255,41 @@ impl<K: AutotuneKey> TuneCache<K> {
         self.fastest(key)
     }
 
+    /// Add defaults used for keys that are neither cached nor tuned yet. Earlier defaults take
+    /// precedence when several patterns match the same key.
+    pub(crate) fn seed(&mut self, defaults: impl IntoIterator<Item = TuneDefault<K>>) {
+        self.defaults.extend(defaults);
+    }
+
+    /// Resolve a key that is neither cached nor tuned yet with the first matching default,
+    /// committing it to the in-memory cache.
+    ///
+    /// Defaults pointing past the `num_tunables` candidates of the tunable set, e.g. from a table
+    /// measured on an older version of the set, are skipped so the key falls back to tuning.
+    pub(crate) fn load_default(&mut self, key: &K, num_tunables: usize) -> Option<usize> {
+        if self.in_memory_cache.contains_key(key) {
+            return None;
+        }
+
+        let fastest_index = self
+            .defaults
+            .iter()
+            .filter_map(|default| default.matches(key))
+            .find(|fastest_index| {
+                let valid = *fastest_index < num_tunables;
+                if !valid {
+                    log::warn!(
+                        "Ignoring the autotune default {fastest_index} for {key}, the tunable set \
+                         only has {num_tunables} candidates"
+                    );
+                }
+                valid
+            })?;
+        self.cache_insert(key.clone(), fastest_index);
+
+        Some(fastest_index)
+    }
+
     /// Mark a key as being tuned. Used by [`Tuner::tune`] under the cache mutex so that
     /// concurrent callers see [`TuneCacheResult::Pending`] and wait on the same job instead of
     /// starting a second one. Returns `(Sender, Receiver)`:
@@ -201,7 +297,171 @@ impl<K: AutotuneKey> TuneCache<K> {
         self.in_memory_cache.insert(key, CacheEntry::Pending);
     }
 
+    /// Subscribe to the result of a pending key. The returned receiver wakes (with
+    /// `Err(RecvError)`) once the result is committed. Returns `None` when the key isn't
+    /// pending.
+    pub(crate) fn subscribe(&mut self, key: &K) -> Option<Receiver<()>> {
+        if !matches!(self.in_memory_cache.get(key), Some(CacheEntry::Pending)) {
+            return None;
+        }
+
+        let (sender, receiver) = async_channel::bounded(1);
+        self.waiters.entry(key.clone()).or_default().push(sender);
+        Some(receiver)
+    }
+
+    pub(crate) fn set_persistence(&mut self, persistence: Box<dyn TunePersistence>) {
+        self.persistence = Some(persistence);
+    }
+
+    /// Load the result stored by the persistence backend for the given key, committing it to the
+    /// in-memory cache.
+    pub(crate) fn load_persisted(
+        &mut self,
+        key: &K,
+        checksum: impl FnOnce() -> String,
+    ) -> Option<usize> {
+        let persistence = self.persistence.as_mut()?;
+        let fastest_index = persistence.load(&key.to_string(), &checksum())?;
+        self.cache_insert(key.clone(), fastest_index);
+
+        Some(fastest_index)
+    }
+
+    pub(crate) fn store_persisted(&mut self, key: &K, checksum: &str, fastest_index: usize) {
+        if let Some(persistence) = self.persistence.as_mut() {
+            persistence.store(&key.to_string(), checksum, fastest_index);
+        }
+    }
+
+    /// Whether the tunable with the given name previously failed for the key on the device with
+    /// the given fingerprint, with the same tunable set checksum.
+    pub(crate) fn has_failed(
+        &self,
+        fingerprint: &str,
+        key: &K,
+        name: &str,
+        checksum: &str,
+    ) -> bool {
+        self.failures.contains(&FailureKey {
+            fingerprint: fingerprint.to_string(),
+            key: key.clone(),
+            name: name.to_string(),
+            checksum: checksum.to_string(),
+        })
+    }
+
+    /// Record that the tunable with the given name can't run for the key on the device with the
+    /// given fingerprint, so it's skipped by the next tuning jobs.
+    ///
+    /// Only [deterministic](AutotuneError::is_deterministic) failures are persisted, the others
+    /// are tried again by the next processes.
+    pub(crate) fn insert_failure(
+        &mut self,
+        fingerprint: &str,
+        key: &K,
+        name: &str,
+        checksum: &str,
+        error: &AutotuneError,
+    ) {
+        let failure = FailureKey {
+            fingerprint: fingerprint.to_string(),
+            key: key.clone(),
+            name: name.to_string(),
+            checksum: checksum.to_string(),
+        };
+
+        #[cfg(std_io)]
+        if error.is_deterministic()
+            && let Err(CacheError::DuplicatedKey { key, .. }) = self
+                .persistent_failures
+                .insert(failure.clone(), error.to_string())
+        {
+            log::debug!("Autotune failure already recorded for {key:?}");
+        }
+        #[cfg(not(std_io))]
+        let _ = error;
+
+        self.failures.insert(failure);
+    }
+
+    /// Forget the recorded failures, including the persisted ones, so the failing tunables are
+    /// benchmarked again, e.g. after a driver update.
+    pub(crate) fn clear_failures(&mut self) {
+        self.failures.clear();
+        #[cfg(std_io)]
+        self.persistent_failures.clear();
+    }
+
+    /// The results currently held in memory, sorted by key.
+    pub(crate) fn entries(&self) -> Vec<TuneEntry> {
+        let mut entries = self
+            .in_memory_cache
+            .iter()
+            .filter_map(|(key, entry)| {
+                let fastest_index = match entry {
+                    CacheEntry::Done {
+                        checksum: ChecksumState::NoMatch,
+                        ..
+                    } => return None,
+                    CacheEntry::Done { fastest_index, .. } => Some(*fastest_index),
+                    CacheEntry::Pending => None,
+                };
+
+                Some(TuneEntry {
+                    key: key.to_string(),
+                    fastest_index,
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        entries
+    }
+
+    /// Forget every result held in memory, keeping the keys being tuned.
+    pub(crate) fn clear(&mut self) {
+        self.in_memory_cache
+            .retain(|_, entry| matches!(entry, CacheEntry::Pending));
+        self.resumable.clear();
+    }
+
+    /// Record the candidates of a key left untested when its time budget ran out.
+    pub(crate) fn insert_resumable(
+        &mut self,
+        key: K,
+        remaining: Vec<usize>,
+        results: Vec<AutotuneResult>,
+    ) {
+        self.resumable.insert(
+            key,
+            ResumableSearch {
+                remaining,
+                results,
+                resume: false,
+            },
+        );
+    }
+
+    /// Continue every interrupted search on the next execution of its key. Returns the number
+    /// of searches to continue.
+    pub(crate) fn resume_all(&mut self) -> usize {
+        for search in self.resumable.values_mut() {
+            search.resume = true;
+        }
+        self.resumable.len()
+    }
+
+    /// Take the interrupted search of a key, if it should be continued.
+    pub(crate) fn take_resumed(&mut self, key: &K) -> Option<ResumableSearch> {
+        if !self.resumable.get(key)?.resume {
+            return None;
+        }
+        self.resumable.remove(key)
+    }
+
     pub(crate) fn cache_insert(&mut self, key: K, fastest_index: usize) {
+        // Dropping the senders wakes every subscriber.
+        self.waiters.remove(&key);
         self.in_memory_cache.insert(
             key,
             CacheEntry::Done {
@@ -234,9 +494,12 @@ impl<K: AutotuneKey> TuneCache<K> {
                     value_previous,
                     value_updated,
                 } => {
-                    log::warn!(
+                    // The key is tuned again after its results were cleared, the new result
+                    // replaces the stale one so it's also the one loaded by the next process.
+                    log::info!(
                         "Autotune the same function multiple times for key {key:?} => old {value_previous:?}, new {value_updated:?}"
                     );
+                    self.persistent_cache.update(key, value_updated);
                 }
                 CacheError::KeyOutOfSync { .. } => {
                     // This is OK.
@@ -261,5 +524,50 @@ impl<K: AutotuneKey> TuneCache<K> {
             );
         });
         log::info!("Loaded {loaded} autotune cached entries");
+
+        let failures = &mut self.failures;
+        self.persistent_failures.for_each(|failure, _error| {
+            failures.insert(failure.clone());
+        });
+    }
+}
+
+#[cfg(all(test, std_io))]
+mod tests {
+    use super::*;
+    use crate::compiler::CompilationError;
+    use crate::server::LaunchError;
+    use cubecl_common::backtrace::BackTrace;
+
+    #[test_log::test]
+    fn only_deterministic_failures_are_persisted() {
+        let root = tempfile::tempdir().unwrap();
+        let cache = || TuneCache::<String>::new_in("failures", "device", root.path().into());
+        let key = "key".to_string();
+        let compilation =
+            AutotuneError::Launch(LaunchError::CompilationError(CompilationError::Generic {
+                reason: "unsupported".into(),
+                backtrace: BackTrace::capture(),
+            }));
+        let unknown = AutotuneError::Unknown {
+            name: "flaky".into(),
+            err: "out of memory".into(),
+        };
+
+        let mut recorded = cache();
+        recorded.insert_failure("gpu", &key, "broken", "v1", &compilation);
+        recorded.insert_failure("gpu", &key, "flaky", "v1", &unknown);
+        assert!(recorded.has_failed("gpu", &key, "flaky", "v1"));
+
+        let reloaded = cache();
+        assert!(reloaded.has_failed("gpu", &key, "broken", "v1"));
+        assert!(!reloaded.has_failed("gpu", &key, "flaky", "v1"));
+        // Changing the tunables or the device invalidates the failure.
+        assert!(!reloaded.has_failed("gpu", &key, "broken", "v2"));
+        assert!(!reloaded.has_failed("other-gpu", &key, "broken", "v1"));
+
+        recorded.clear_failures();
+        assert!(!recorded.has_failed("gpu", &key, "flaky", "v1"));
+        assert!(!cache().has_failed("gpu", &key, "broken", "v1"));
     }
 }
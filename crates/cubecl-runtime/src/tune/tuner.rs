@@ -1,3 +1,4 @@
+use alloc::boxed::Box;
 use alloc::format;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
@@ -11,10 +12,13 @@ use cubecl_common::benchmark::{BenchmarkComputations, BenchmarkDurations};
 
 use crate::config::{Logger, autotune::AutotuneLogLevel};
 use crate::server::LaunchError;
-use crate::tune::{AutotuneResult, TuneCache, tune_benchmark};
+use crate::tune::{AutotuneResult, TuneCache, tune_samples};
 use crate::{client::ComputeClient, runtime::Runtime};
 
-use super::{AutotuneKey, AutotuneOutput, TunableSet, TuneCacheResult, TuneInputs};
+use super::{
+    AutotuneKey, AutotuneOutput, TunableSet, TuneCacheResult, TuneDefault, TuneEntry, TuneInputs,
+    TunePersistence,
+};
 
 #[derive(Debug)]
 /// Runs autotune benchmarks for a single device and caches the results.
@@ -34,6 +38,9 @@ pub struct AutotuneOutcome {
     name: String,
     index: usize,
     computation: BenchmarkComputations,
+    /// Average energy of one run in millijoules, if the runtime can measure it.
+    #[cfg_attr(std_io, serde(default))]
+    energy: Option<u64>,
 }
 
 impl core::fmt::Display for AutotuneOutcome {
@@ -42,7 +49,13 @@ impl core::fmt::Display for AutotuneOutcome {
             f,
             "Autotune[{}] name {} => {:?}",
             self.index, self.name, self.computation
-        )
+        )?;
+
+        if let Some(energy) = self.energy {
+            write!(f, ", energy {energy} mJ")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -91,6 +104,20 @@ impl core::fmt::Debug for AutotuneError {
     }
 }
 
+impl AutotuneError {
+    /// Whether the error happens on every run on the same device, like a kernel that can't be
+    /// compiled or requests more resources than available, instead of depending on the state of
+    /// the device.
+    pub fn is_deterministic(&self) -> bool {
+        matches!(
+            self,
+            AutotuneError::Launch(
+                LaunchError::CompilationError(_) | LaunchError::TooManyResources(_)
+            )
+        )
+    }
+}
+
 impl From<LaunchError> for AutotuneError {
     fn from(value: LaunchError) -> Self {
         Self::Launch(value)
@@ -102,6 +129,7 @@ struct PendingBench {
     index: usize,
     name: String,
     profiles: Vec<ProfileDuration>,
+    energy: Option<u64>,
 }
 
 /// A queued tuning job: all data needed to resolve samples and commit the result.
@@ -109,10 +137,11 @@ struct PendingBench {
 struct TuneRequest<K: AutotuneKey> {
     key: K,
     results: Vec<AutotuneResult>,
-    #[cfg(std_io)]
     checksum: String,
     context_logs: Option<String>,
     pending: Vec<PendingBench>,
+    /// The candidates left untested when the time budget ran out.
+    remaining: Vec<usize>,
 }
 
 #[allow(clippy::new_without_default)]
@@ -126,11 +155,91 @@ impl<K: AutotuneKey> Tuner<K> {
         }
     }
 
+    /// Create a tuner persisting its results in the given directory, instead of the
+    /// [configured](crate::config::CubeClRuntimeConfig) cache location.
+    #[cfg(std_io)]
+    pub fn new_in(name: &str, device_id: &str, root: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            cache: Arc::new(spin::Mutex::new(TuneCache::new_in(
+                name,
+                device_id,
+                root.into(),
+            ))),
+            logger: Arc::new(spin::Mutex::new(Logger::new())),
+        }
+    }
+
+    /// Seed the tuner with the compiled-in [defaults](TuneDefault) of the given architecture.
+    ///
+    /// Keys matching a default resolve to its candidate without being tuned, while results already
+    /// in the cache, e.g. loaded from the persistent cache, take precedence over the defaults.
+    /// Defaults whose index is out of range for the tunable set are ignored and the key is tuned.
+    pub fn seed(&self, architecture: &str, defaults: &[TuneDefault<K>]) {
+        let defaults = defaults
+            .iter()
+            .filter(|default| default.architecture() == architecture)
+            .copied();
+
+        self.cache.lock().seed(defaults);
+    }
+
+    /// Persist the tuning results with the given [backend](TunePersistence), in addition to the
+    /// built-in persistent cache when `std_io` is enabled.
+    pub fn with_persistence(self, persistence: Box<dyn TunePersistence>) -> Self {
+        self.cache.lock().set_persistence(persistence);
+        self
+    }
+
+    /// The results currently cached in memory, sorted by key.
+    pub fn entries(&self) -> Vec<TuneEntry> {
+        self.cache.lock().entries()
+    }
+
+    /// Forget the results cached in memory, so the next executions are tuned again.
+    pub fn clear(&self) {
+        self.cache.lock().clear();
+    }
+
+    /// Forget the tunables recorded as failing, including the ones persisted on disk, so they are
+    /// benchmarked again by the next tuning jobs.
+    ///
+    /// Failures are already forgotten when the tunable set changes, this is needed when the
+    /// device changes in a way that isn't seen by the runtime, like a driver update.
+    pub fn clear_failures(&self) {
+        self.cache.lock().clear_failures();
+    }
+
+    /// Continue the searches interrupted by their [time budget](TunableSet::with_time_budget)
+    /// on the next execution of their key, starting from their best result so far.
+    ///
+    /// Returns the number of searches to continue.
+    pub fn resume_tuning(&self) -> usize {
+        self.cache.lock().resume_all()
+    }
+
     /// Fetch the fastest autotune operation index for an autotune key.
     pub fn fastest(&self, key: &K) -> TuneCacheResult {
         self.cache.lock().fastest(key)
     }
 
+    /// Wait until the tuning job in flight for the given key, if any, commits its result.
+    ///
+    /// Returns the cache state once it is no longer [pending](TuneCacheResult::Pending).
+    pub async fn wait_tuned(&self, key: &K) -> TuneCacheResult {
+        loop {
+            let receiver = {
+                let mut cache = self.cache.lock();
+                match cache.subscribe(key) {
+                    Some(receiver) => receiver,
+                    None => return cache.fastest(key),
+                }
+            };
+
+            // The sender is dropped without sending once the result is committed.
+            let _ = receiver.recv().await;
+        }
+    }
+
     /// Check the cache, validate checksums if needed, and kick off a tuning job if the
     /// key is a miss. Returns the resolved cache state.
     pub fn check_tune<'a, R: Runtime, F: TuneInputs, Out: AutotuneOutput>(
@@ -144,9 +253,14 @@ impl<K: AutotuneKey> Tuner<K> {
     where
         <F as TuneInputs>::At<'a>: Clone + Send,
     {
-        {
+        let resumed = {
             let mut cache = self.cache.lock();
-            let cur = cache.fastest(key);
+            let resumed = cache.take_resumed(key);
+            let cur = match resumed {
+                // The best result so far stays in the cache while the search continues.
+                Some(_) => TuneCacheResult::Pending,
+                None => cache.fastest(key),
+            };
 
             #[cfg(std_io)]
             let cur = if matches!(cur, TuneCacheResult::Unchecked) {
@@ -161,16 +275,37 @@ impl<K: AutotuneKey> Tuner<K> {
             };
 
             match cur {
+                TuneCacheResult::Pending if resumed.is_some() => {}
                 TuneCacheResult::Hit { .. } | TuneCacheResult::Pending => return cur,
                 TuneCacheResult::Miss | TuneCacheResult::Unchecked => {
+                    if let Some(fastest_index) = cache
+                        .load_persisted(key, || tunables.compute_checksum())
+                        .or_else(|| cache.load_default(key, tunables.len()))
+                    {
+                        return TuneCacheResult::Hit { fastest_index };
+                    }
                     cache.mark_pending(key.clone())
                 }
             }
             // Scope the guard: the rest of this function re-locks `self.cache` (fast
             // path insert, `process_request`), and `spin::Mutex` is non-reentrant.
+            resumed
+        };
+
+        match resumed {
+            Some(_) => log::info!("Resuming the tuning of {key}"),
+            None => log::info!("Tuning {key}"),
         }
 
-        log::info!("Tuning {key}");
+        // Group the candidate benchmarks under one range in the profiler timeline.
+        #[cfg(feature = "profile-tracy")]
+        let _span = tracy_client::Client::running().unwrap().span_alloc(
+            Some(&format!("autotune {key}")),
+            "check_tune",
+            file!(),
+            line!(),
+            0,
+        );
 
         let autotunables = tunables.autotunables().collect::<Vec<_>>();
         let mut results: Vec<AutotuneResult> = autotunables
@@ -182,8 +317,17 @@ impl<K: AutotuneKey> Tuner<K> {
             })
             .collect();
 
-        #[cfg(std_io)]
         let checksum = tunables.compute_checksum();
+        let resumed = resumed.map(|search| {
+            for result in search.results {
+                if let Ok(outcome) = &result.outcome {
+                    let index = outcome.index;
+                    results[index] = result;
+                }
+            }
+            search.remaining
+        });
+        let resuming = resumed.is_some();
 
         // Fast path: single tunable, no benchmarking needed.
         if results.len() == 1 {
@@ -191,6 +335,7 @@ impl<K: AutotuneKey> Tuner<K> {
             return TuneCacheResult::Hit { fastest_index: 0 };
         }
 
+        let fingerprint = client.device_fingerprint();
         let test_inputs = tunables.generate_inputs(key, inputs);
         let mut plan = tunables.plan(key);
         let mut context_logs = match self.logger.lock().log_level_autotune() {
@@ -201,10 +346,19 @@ impl<K: AutotuneKey> Tuner<K> {
         // Walk the plan batch by batch, launching each benchmark synchronously. A
         // successful launch queues a `PendingBench` for the async resolver below;
         // launch errors go straight into `results`. Retry the next batch if a whole
-        // batch failed to queue anything.
+        // batch failed to queue anything. A resumed search only tries its remaining candidates.
+        // The budget counts the wall-clock time of the search, the benchmarks are resolved one
+        // by one so their execution is included.
+        let budget = tunables.time_budget();
+        let start = web_time::Instant::now();
+        let mut resumed = resumed;
         let mut pending = Vec::<PendingBench>::new();
+        let mut remaining = Vec::new();
         loop {
-            let tunable_indices = plan.next(context_logs.as_mut());
+            let tunable_indices = match resumed.take() {
+                Some(indices) => indices,
+                None => plan.next(context_logs.as_mut()),
+            };
 
             if tunable_indices.is_empty() {
                 panic!(
@@ -215,19 +369,52 @@ impl<K: AutotuneKey> Tuner<K> {
             for index in tunable_indices {
                 let op = autotunables[index];
 
-                match tune_benchmark(op, test_inputs.clone(), client.clone()) {
-                    Ok(profiles) => pending.push(PendingBench {
-                        index,
-                        name: op.name.clone(),
-                        profiles,
-                    }),
+                // At least one candidate is benchmarked before the budget is checked.
+                if !pending.is_empty() && budget.is_some_and(|budget| start.elapsed() >= budget) {
+                    remaining.push(index);
+                    continue;
+                }
+
+                if self
+                    .cache
+                    .lock()
+                    .has_failed(&fingerprint, key, &op.name, &checksum)
+                {
+                    log::info!(
+                        "Skipping {} for {key}, it previously failed on this device",
+                        op.name
+                    );
+                    continue;
+                }
+
+                match tune_samples(op, test_inputs.clone(), client.clone()) {
+                    Ok(samples) => {
+                        let profiles = match budget {
+                            Some(_) => resolve_profiles(samples.profiles),
+                            None => samples.profiles,
+                        };
+                        pending.push(PendingBench {
+                            index,
+                            name: op.name.clone(),
+                            profiles,
+                            energy: samples.energy,
+                        })
+                    }
                     Err(err) => {
+                        self.cache.lock().insert_failure(
+                            &fingerprint,
+                            key,
+                            &op.name,
+                            &checksum,
+                            &err,
+                        );
                         results[index] = AutotuneResult::error(err);
                     }
                 }
             }
 
-            if !pending.is_empty() {
+            // A resumed search falls back to its best result so far.
+            if !pending.is_empty() || resuming {
                 break;
             }
         }
@@ -235,10 +422,10 @@ impl<K: AutotuneKey> Tuner<K> {
         let request = TuneRequest {
             key: key.clone(),
             results,
-            #[cfg(std_io)]
             checksum,
             context_logs,
             pending,
+            remaining,
         };
 
         // Resolve samples and commit the result. On wasm this runs on the browser
@@ -259,6 +446,18 @@ impl<K: AutotuneKey> Tuner<K> {
     }
 }
 
+/// Resolve the profiles of a benchmark right away, so its execution counts in the time budget.
+fn resolve_profiles(profiles: Vec<ProfileDuration>) -> Vec<ProfileDuration> {
+    profiles
+        .into_iter()
+        .map(|profile| {
+            let method = profile.timing_method();
+            let ticks = cubecl_common::future::block_on(profile.resolve());
+            ProfileDuration::new(Box::pin(async move { ticks }), method)
+        })
+        .collect()
+}
+
 /// Await every profile sample, pick the fastest tunable, commit to the cache.
 async fn process_request<K: AutotuneKey>(
     request: TuneRequest<K>,
@@ -268,10 +467,10 @@ async fn process_request<K: AutotuneKey>(
     let TuneRequest {
         key,
         mut results,
-        #[cfg(std_io)]
         checksum,
         context_logs,
         pending,
+        remaining,
     } = request;
 
     for bench in pending {
@@ -279,6 +478,7 @@ async fn process_request<K: AutotuneKey>(
             index,
             name,
             profiles,
+            energy,
         } = bench;
 
         if profiles.is_empty() {
@@ -302,6 +502,7 @@ async fn process_request<K: AutotuneKey>(
                 timing_method,
                 durations,
             )),
+            energy,
         ));
     }
 
@@ -329,11 +530,22 @@ async fn process_request<K: AutotuneKey>(
 
     {
         log_result(&mut logger.lock(), &key, &results, context_logs.as_deref());
-        cache.lock().cache_insert(key.clone(), fastest_index);
-        #[cfg(std_io)]
-        cache
-            .lock()
-            .persistent_cache_insert(key, checksum, fastest_index, results);
+        let mut cache = cache.lock();
+        cache.cache_insert(key.clone(), fastest_index);
+
+        // Only complete searches are persisted, an interrupted one is tuned again by the next
+        // process.
+        if remaining.is_empty() {
+            cache.store_persisted(&key, &checksum, fastest_index);
+            #[cfg(std_io)]
+            cache.persistent_cache_insert(key, checksum, fastest_index, results);
+        } else {
+            log::info!(
+                "Tuning budget of {key} exceeded, {} candidates left",
+                remaining.len()
+            );
+            cache.insert_resumable(key, remaining, results);
+        }
     }
 
     TuneCacheResult::Hit { fastest_index }
@@ -406,12 +618,13 @@ fn log_result<K: AutotuneKey>(
 #[cfg(feature = "autotune-checks")]
 pub(crate) fn check_autotune_outputs<O: AutotuneOutput>(
     mut checks_outputs: Vec<Result<O, AutotuneError>>,
+    tolerance: Option<&super::AutotuneTolerance>,
 ) {
     let reference = checks_outputs.remove(checks_outputs.len() - 1);
 
     if let Ok(reference) = reference {
         for other in checks_outputs.into_iter().flatten() {
-            reference.check_equivalence(other);
+            reference.check_equivalence_with_tolerance(other, tolerance);
         }
     }
 }
@@ -15,7 +15,10 @@ use cubecl_runtime::{
 };
 use cubecl_zspace::Shape;
 use cubecl_zspace::Strides;
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
 
 /// The dummy device.
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Default)]
@@ -97,6 +100,12 @@ impl Compiler for DummyCompiler {
 #[derive(Debug, Clone)]
 pub struct DummyRuntime;
 
+/// The number of times the load width of the dummy device was measured.
+pub static LOAD_WIDTH_MEASUREMENTS: AtomicUsize = AtomicUsize::new(0);
+
+/// The load width measured on the dummy device, narrower than the one of its properties.
+pub const MEASURED_LOAD_WIDTH: u32 = 64;
+
 impl Runtime for DummyRuntime {
     type Compiler = DummyCompiler;
 
@@ -124,6 +133,11 @@ impl Runtime for DummyRuntime {
         unimplemented!()
     }
 
+    fn measure_load_width(_client: &ComputeClient<Self>) -> Option<u32> {
+        LOAD_WIDTH_MEASUREMENTS.fetch_add(1, Ordering::Relaxed);
+        Some(MEASURED_LOAD_WIDTH)
+    }
+
     fn enumerate_devices(
         _: u16,
         _: &<Self::Server as ComputeServer>::Info,
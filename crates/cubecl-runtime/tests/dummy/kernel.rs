@@ -9,6 +9,11 @@ pub trait DummyKernel: Sync + Send + 'static + core::fmt::Debug {
     fn name(&self) -> &'static str {
         core::any::type_name::<Self>()
     }
+
+    /// Whether the dummy compiler accepts the kernel.
+    fn compiles(&self) -> bool {
+        true
+    }
 }
 
 /// Contains the algorithm for element-wise addition
@@ -34,3 +39,41 @@ impl DummyKernel for DummyElementwiseAddition {
         KernelId::new::<Self>()
     }
 }
+
+/// Adds the scalar stored in the first info word to every element.
+#[derive(Debug)]
+pub struct DummyAddScalar;
+
+impl DummyKernel for DummyAddScalar {
+    fn compute(&self, inputs: &mut [&mut BytesResource]) {
+        let input = &inputs[0].read();
+        let scalar = inputs[2].read()[0];
+        let out = &mut inputs[1].write();
+
+        for i in 0..input.len() {
+            out[i] = input[i] + scalar;
+        }
+    }
+
+    fn id(&self) -> KernelId {
+        KernelId::new::<Self>()
+    }
+}
+
+/// A kernel the dummy compiler rejects.
+#[derive(Debug)]
+pub struct DummyUncompilable;
+
+impl DummyKernel for DummyUncompilable {
+    fn compute(&self, _inputs: &mut [&mut BytesResource]) {
+        unreachable!("The kernel can't be compiled")
+    }
+
+    fn id(&self) -> KernelId {
+        KernelId::new::<Self>()
+    }
+
+    fn compiles(&self) -> bool {
+        false
+    }
+}
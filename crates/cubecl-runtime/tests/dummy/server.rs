@@ -1,6 +1,9 @@
 use super::DummyKernel;
 use crate::dummy::DummyCompiler;
-use cubecl_common::{bytes::Bytes, future::DynFut, profile::ProfileDuration, stream_id::StreamId};
+use cubecl_common::{
+    backtrace::BackTrace, bytes::Bytes, future::DynFut, profile::ProfileDuration,
+    stream_id::StreamId,
+};
 use cubecl_ir::{
     DeviceProperties, ElemType, HardwareProperties, MemoryDeviceProperties, StorageType, UIntKind,
     VectorSize, features::Features,
@@ -13,15 +16,21 @@ use cubecl_runtime::{
     logging::ServerLogger,
     memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryManagement, MemoryUsage},
     server::{
-        Binding, ComputeServer, CopyDescriptor, CubeCount, CubeDim, ExecutionMode, Handle,
-        KernelArguments, ProfileError, ProfilingToken, ServerCommunication, ServerError,
-        ServerUtilities,
+        Binding, ComputeServer, CopyDescriptor, CubeCount, CubeDim, ExecutionMode, Handle, IoError,
+        KernelArguments, LaunchError, ProfileError, ProfilingToken, ServerCommunication,
+        ServerError, ServerUtilities,
     },
     storage::{BytesResource, BytesStorage, ComputeStorage, ManagedResource},
     timestamp_profiler::TimestampProfiler,
 };
 use cubecl_zspace::{Shape, Strides};
-use std::sync::Arc;
+use std::{
+    cell::Cell,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 /// The dummy server is used to test the cubecl-runtime infrastructure.
 /// It uses simple memory management with a bytes storage on CPU, without asynchronous tasks.
@@ -30,6 +39,28 @@ pub struct DummyServer {
     memory_management: MemoryManagement<BytesStorage>,
     timestamps: TimestampProfiler,
     utilities: Arc<ServerUtilities<Self>>,
+    /// The energy counter, in millijoules.
+    energy: u64,
+    /// The errors recorded by launches, returned by the next flush.
+    errors: Vec<ServerError>,
+}
+
+/// The energy consumed by every launch, in millijoules.
+pub const ENERGY_PER_LAUNCH: u64 = 3;
+
+/// The number of staging buffers reserved on the dummy backend.
+pub static STAGING_RESERVED: AtomicUsize = AtomicUsize::new(0);
+
+std::thread_local! {
+    static EXECUTING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether the current thread is executing a kernel on the dummy backend, so that tests measuring
+/// the runtime can leave out the work of the backend itself.
+#[allow(dead_code)]
+pub fn executing_kernel() -> bool {
+    // The thread local may already be destroyed when a thread exits.
+    EXECUTING.try_with(Cell::get).unwrap_or(false)
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +96,13 @@ impl CubeTask<DummyCompiler> for KernelTask {
         _mode: ExecutionMode,
         _addr_type: StorageType,
     ) -> Result<cubecl_runtime::kernel::CompiledKernel<DummyCompiler>, CompilationError> {
+        if !self.kernel.compiles() {
+            return Err(CompilationError::Generic {
+                reason: format!("{} isn't supported", self.kernel.name()),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
         Ok(CompiledKernel {
             entrypoint_name: self.kernel.name().to_string(),
             debug_name: None,
@@ -113,6 +151,52 @@ impl ComputeServer for DummyServer {
             .unwrap();
     }
 
+    fn initialize_memory_aligned(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        size: u64,
+        alignment: u64,
+        _stream_id: StreamId,
+    ) -> Result<(), IoError> {
+        let reserved = self.memory_management.reserve_aligned(size, alignment)?;
+        self.memory_management.bind(reserved, memory, 0)
+    }
+
+    fn create_from_host(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        data: Bytes,
+        _stream_id: StreamId,
+    ) -> Result<u64, Bytes> {
+        let storage = self.memory_management.storage().wrap(data)?;
+        let (ptr, _) = self
+            .memory_management
+            .storage()
+            .get(&storage)
+            .get_write_ptr_and_length();
+        let alignment = Ord::min(
+            1 << ptr.addr().trailing_zeros(),
+            self.utilities.properties.memory.alignment,
+        );
+
+        let reserved = self.memory_management.reserve_storage(storage);
+        self.memory_management.bind(reserved, memory, 0).unwrap();
+
+        Ok(alignment)
+    }
+
+    fn staging(
+        &mut self,
+        sizes: &[usize],
+        _stream_id: StreamId,
+    ) -> Result<Vec<Bytes>, ServerError> {
+        STAGING_RESERVED.fetch_add(sizes.len(), Ordering::Relaxed);
+        Ok(sizes
+            .iter()
+            .map(|size| Bytes::from_bytes_vec(vec![0; *size]))
+            .collect())
+    }
+
     fn read(
         &mut self,
         descriptors: Vec<CopyDescriptor>,
@@ -183,6 +267,16 @@ impl ComputeServer for DummyServer {
         mode: ExecutionMode,
         stream_id: StreamId,
     ) {
+        EXECUTING.set(true);
+        let kernel = match kernel.compile(&mut DummyCompiler, &(), mode, kernel.address_type()) {
+            Ok(kernel) => kernel,
+            Err(err) => {
+                self.errors.push(LaunchError::from(err).into());
+                EXECUTING.set(false);
+                return;
+            }
+        };
+
         let mut resources: Vec<_> = bindings
             .buffers
             .into_iter()
@@ -207,15 +301,20 @@ impl ComputeServer for DummyServer {
         });
 
         let mut resources: Vec<_> = resources.iter_mut().collect();
-        let kernel = kernel
-            .compile(&mut DummyCompiler, &(), mode, kernel.address_type())
-            .unwrap();
         kernel.repr.unwrap().compute(resources.as_mut_slice());
+        self.energy += ENERGY_PER_LAUNCH;
+        EXECUTING.set(false);
     }
 
     fn flush(&mut self, _stream_id: StreamId) -> Result<(), ServerError> {
-        // Nothing to do with dummy backend.
-        Ok(())
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(ServerError::ServerUnhealthy {
+            errors: core::mem::take(&mut self.errors),
+            backtrace: BackTrace::capture(),
+        })
     }
 
     fn memory_usage(&mut self, _stream_id: StreamId) -> Result<MemoryUsage, ServerError> {
@@ -241,6 +340,10 @@ impl ComputeServer for DummyServer {
     fn allocation_mode(&mut self, mode: MemoryAllocationMode, _stream_id: StreamId) {
         self.memory_management.mode(mode)
     }
+
+    fn energy_consumption(&mut self) -> Option<u64> {
+        Some(self.energy)
+    }
 }
 
 impl DummyServer {
@@ -280,6 +383,8 @@ impl DummyServer {
             memory_management,
             utilities,
             timestamps: TimestampProfiler::default(),
+            energy: 0,
+            errors: Vec::new(),
         }
     }
 
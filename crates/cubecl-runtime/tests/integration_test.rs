@@ -2,9 +2,25 @@ mod dummy;
 
 use crate::dummy::{DummyDevice, DummyElementwiseAddition, test_client};
 
+use cubecl_common::bytes::Bytes;
+use cubecl_common::future::block_on;
+use cubecl_runtime::client::ComputeClient;
+use cubecl_runtime::dry_run::{DryRunDevice, DryRunEvent, DryRunRuntime, DryRunServer};
+use cubecl_runtime::ffi::CubeclStatus;
+use cubecl_runtime::memory_management::MemoryConfiguration;
+use cubecl_runtime::registry::{KernelRegistry, KernelRegistryError};
 use cubecl_runtime::server::CubeCount;
+use cubecl_runtime::server::ExecutionMode;
 use cubecl_runtime::server::KernelArguments;
-use cubecl_runtime::{local_tuner, tune::LocalTuner};
+use cubecl_runtime::server::MemoryLayoutDescriptor;
+use cubecl_runtime::server::{ExternalSemaphore, ExternalSemaphoreHandle};
+use cubecl_runtime::{
+    local_tuner,
+    tune::{
+        InMemoryTunePersistence, LocalTuner, TuneDefault, TuneEntry, TunePersistence,
+        autotune_snapshot, clear_autotune_caches, tune_samples,
+    },
+};
 use dummy::*;
 
 #[test_log::test]
@@ -50,6 +66,448 @@ fn execute_elementwise_addition() {
     assert_eq!(obtained_resource, Vec::from([4, 5, 6]))
 }
 
+#[test_log::test]
+fn watchpoint_records_changing_launches() {
+    let client = test_client(&DummyDevice);
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.create_from_slice(&[4, 0, 6]);
+
+    let out_watch = client.watch(&out, 1, 0..3, Some("DummyElementwiseAddition"));
+    let lhs_watch = client.watch(&lhs, 1, 1..3, None);
+
+    client.launch(
+        Box::new(KernelTask::new(DummyElementwiseAddition)),
+        CubeCount::Static(1, 1, 1),
+        KernelArguments::new().with_buffers(vec![
+            lhs.binding(),
+            rhs.binding(),
+            out.clone().binding(),
+        ]),
+    );
+
+    let events = client.unwatch(out_watch);
+    assert_eq!(events.len(), 1);
+    assert!(events[0].kernel.contains("DummyElementwiseAddition"));
+    assert_eq!(events[0].elements, [1]);
+    assert!(client.unwatch(lhs_watch).is_empty());
+    assert!(client.unwatch(out_watch).is_empty());
+}
+
+#[test_log::test]
+fn registry_launches_kernel_by_name() {
+    let registry = KernelRegistry::<DummyRuntime>::new();
+    registry
+        .register("add", || {
+            Box::new(KernelTask::new(DummyElementwiseAddition))
+        })
+        .unwrap();
+
+    let client = test_client(&DummyDevice);
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+    let bindings = || {
+        KernelArguments::new().with_buffers(vec![
+            lhs.clone().binding(),
+            rhs.clone().binding(),
+            out.clone().binding(),
+        ])
+    };
+
+    registry
+        .launch(&client, "add", CubeCount::Static(1, 1, 1), bindings())
+        .unwrap();
+
+    assert_eq!(client.read_one(out.clone()).unwrap().to_vec(), [4, 5, 6]);
+    assert_eq!(
+        registry.launch(&client, "mul", CubeCount::Static(1, 1, 1), bindings()),
+        Err(KernelRegistryError::NotFound { name: "mul".into() })
+    );
+    assert!(
+        registry
+            .register("add", || Box::new(KernelTask::new(
+                DummyElementwiseAddition
+            )))
+            .is_err()
+    );
+    assert_eq!(registry.names(), ["add"]);
+}
+
+static FFI_KERNELS: KernelRegistry<DummyRuntime> = KernelRegistry::new();
+
+cubecl_runtime::export_c_api!(DummyRuntime, FFI_KERNELS);
+
+#[test_log::test]
+fn c_api_launches_registered_kernel() {
+    FFI_KERNELS
+        .register("add", || {
+            Box::new(KernelTask::new(DummyElementwiseAddition))
+        })
+        .unwrap();
+    FFI_KERNELS
+        .register("panics", || panic!("Can't create the kernel"))
+        .unwrap();
+    FFI_KERNELS
+        .register("add_scalar", || Box::new(KernelTask::new(DummyAddScalar)))
+        .unwrap();
+    FFI_KERNELS
+        .register("uncompilable", || {
+            Box::new(KernelTask::new(DummyUncompilable))
+        })
+        .unwrap();
+
+    let client = cubecl_client_create(0, 0);
+    let mut out = [0u8; 3];
+    let mut shifted = [0u8; 3];
+
+    // SAFETY: Every pointer comes from the C API or points to live buffers of the right size.
+    unsafe {
+        let lhs = cubecl_upload(client, [0, 1, 2].as_ptr(), 3);
+        let rhs = cubecl_upload(client, [4, 4, 4].as_ptr(), 3);
+        let output = cubecl_empty(client, 3);
+        let handles = [lhs as *const _, rhs as *const _, output as *const _];
+
+        assert_eq!(
+            cubecl_launch(
+                client,
+                c"add".as_ptr(),
+                [1, 1, 1].as_ptr(),
+                handles.as_ptr(),
+                3,
+                core::ptr::null(),
+                0
+            ),
+            CubeclStatus::Ok
+        );
+        assert_eq!(
+            cubecl_launch(
+                client,
+                c"mul".as_ptr(),
+                [1, 1, 1].as_ptr(),
+                handles.as_ptr(),
+                3,
+                core::ptr::null(),
+                0
+            ),
+            CubeclStatus::KernelNotFound
+        );
+        assert_eq!(
+            cubecl_launch(
+                client,
+                c"panics".as_ptr(),
+                [1, 1, 1].as_ptr(),
+                handles.as_ptr(),
+                3,
+                core::ptr::null(),
+                0
+            ),
+            CubeclStatus::Panic
+        );
+        // Compilation errors are reported by the launch itself, not by the next read.
+        assert_eq!(
+            cubecl_launch(
+                client,
+                c"uncompilable".as_ptr(),
+                [1, 1, 1].as_ptr(),
+                handles.as_ptr(),
+                3,
+                core::ptr::null(),
+                0
+            ),
+            CubeclStatus::CompilationError
+        );
+        assert_eq!(
+            cubecl_read(client, output, out.as_mut_ptr(), 2),
+            CubeclStatus::BufferTooSmall
+        );
+        assert_eq!(
+            cubecl_read(client, output, out.as_mut_ptr(), out.len()),
+            CubeclStatus::Ok
+        );
+
+        let scalar = [3u64];
+        let shifted_handle = cubecl_empty(client, 3);
+        let scalar_handles = [lhs as *const _, shifted_handle as *const _];
+        assert_eq!(
+            cubecl_launch(
+                client,
+                c"add_scalar".as_ptr(),
+                [1, 1, 1].as_ptr(),
+                scalar_handles.as_ptr(),
+                2,
+                scalar.as_ptr(),
+                scalar.len()
+            ),
+            CubeclStatus::Ok
+        );
+        assert_eq!(
+            cubecl_read(client, shifted_handle, shifted.as_mut_ptr(), shifted.len()),
+            CubeclStatus::Ok
+        );
+
+        for handle in [lhs, rhs, output, shifted_handle] {
+            cubecl_handle_destroy(handle);
+        }
+        cubecl_client_destroy(client);
+    }
+
+    assert_eq!(out, [4, 5, 6]);
+    assert_eq!(shifted, [3, 4, 5]);
+}
+
+#[test_log::test]
+fn c_api_maps_server_errors_to_statuses() {
+    use cubecl_common::backtrace::BackTrace;
+    use cubecl_runtime::server::{IoError, LaunchError, ServerError};
+
+    let validation = ServerError::Validation {
+        message: "invalid binding".into(),
+        backtrace: BackTrace::capture(),
+    };
+    let out_of_memory = ServerError::Launch(LaunchError::OutOfMemory {
+        reason: "no memory left".into(),
+        backtrace: BackTrace::capture(),
+    });
+    let io = ServerError::Io(IoError::NotFound {
+        backtrace: BackTrace::capture(),
+        reason: "no buffer".into(),
+    });
+    let nested = ServerError::Io(IoError::Execution(Box::new(ServerError::Validation {
+        message: "invalid binding".into(),
+        backtrace: BackTrace::capture(),
+    })));
+    let generic = ServerError::Generic {
+        reason: "lost device".into(),
+        backtrace: BackTrace::capture(),
+    };
+
+    assert_eq!(
+        CubeclStatus::from(&validation),
+        CubeclStatus::ValidationError
+    );
+    assert_eq!(
+        CubeclStatus::from(&out_of_memory),
+        CubeclStatus::OutOfMemory
+    );
+    assert_eq!(CubeclStatus::from(&io), CubeclStatus::IoError);
+    assert_eq!(CubeclStatus::from(&nested), CubeclStatus::ValidationError);
+    assert_eq!(CubeclStatus::from(&generic), CubeclStatus::ServerError);
+
+    let unhealthy = ServerError::ServerUnhealthy {
+        errors: vec![out_of_memory, generic],
+        backtrace: BackTrace::capture(),
+    };
+    assert_eq!(CubeclStatus::from(&unhealthy), CubeclStatus::OutOfMemory);
+}
+
+#[test_log::test]
+fn staged_uploader_uploads_every_push() {
+    use std::sync::atomic::Ordering;
+
+    let client = test_client(&DummyDevice);
+    let mut uploader = client.staged_uploader();
+
+    let batches = [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]];
+    let reserved = STAGING_RESERVED.load(Ordering::Relaxed);
+    let handles = batches
+        .iter()
+        .map(|batch| uploader.push(batch))
+        .collect::<Vec<_>>();
+
+    // One buffer for each push ahead of time, one for the first push and one for the push whose
+    // size differs from the ready buffer.
+    assert_eq!(
+        STAGING_RESERVED.load(Ordering::Relaxed) - reserved,
+        batches.len() + 2
+    );
+    assert!(uploader.is_staged());
+    for (batch, handle) in batches.iter().zip(handles) {
+        assert_eq!(&client.read_one(handle).unwrap().to_vec(), batch);
+    }
+}
+
+#[test_log::test]
+fn occupancy_falls_back_to_estimate() {
+    let client = test_client(&DummyDevice);
+    let occupancy = client
+        .occupancy(
+            Box::new(KernelTask::new(DummyElementwiseAddition)),
+            ExecutionMode::Checked,
+        )
+        .unwrap();
+
+    assert!(!occupancy.exact);
+    assert!(occupancy.cubes_per_unit >= 1);
+    let max = occupancy.max_resident_cubes();
+    assert!(matches!(occupancy.cube_count(u32::MAX), CubeCount::Static(x, 1, 1) if x == max));
+    assert!(matches!(
+        occupancy.cube_count(3),
+        CubeCount::Static(3, 1, 1)
+    ));
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn on_complete_runs_callback_after_submitted_work() {
+    let client = test_client(&DummyDevice);
+    let handle = client.create_from_slice(&[1, 2, 3]);
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    for batch in 0..2 {
+        let sender = sender.clone();
+        client.on_complete(batch, move |batch, result| {
+            sender.send((batch, result)).unwrap()
+        });
+    }
+
+    let (first, result) = receiver.recv().unwrap();
+    assert_eq!(first, 0);
+    assert!(result.is_ok());
+    let (second, result) = receiver.recv().unwrap();
+    assert_eq!(second, 1);
+    assert!(result.is_ok());
+    assert_eq!(client.read_one(handle).unwrap().to_vec(), [1, 2, 3]);
+}
+
+#[test_log::test]
+fn external_semaphores_unsupported() {
+    let client = test_client(&DummyDevice);
+
+    assert!(
+        client
+            .import_semaphore(ExternalSemaphoreHandle::OpaqueFd(-1))
+            .is_err()
+    );
+    assert!(client.export_semaphore().is_err());
+
+    let unknown = ExternalSemaphore { id: 0 };
+    assert!(client.signal_semaphore(unknown, 1).is_err());
+    assert!(client.wait_semaphore(unknown, 1).is_err());
+    client.release_semaphore(unknown);
+}
+
+#[test_log::test]
+fn empty_tensors_respect_requested_alignment() {
+    let client = test_client(&DummyDevice);
+    let layouts = client.empty_tensors(vec![
+        MemoryLayoutDescriptor::contiguous([3].into(), 1),
+        MemoryLayoutDescriptor::contiguous([5].into(), 1).with_alignment(32),
+    ]);
+
+    assert_eq!(layouts[1].memory.offset_start, Some(32));
+    assert_eq!(layouts[1].memory.alignment(), 32);
+    assert!(client.empty_aligned(4, 32).unwrap().alignment() >= 32);
+    assert!(client.empty_aligned(4, 24).is_err());
+}
+
+#[test_log::test]
+fn empty_tensors_respect_alignment_above_device_alignment() {
+    let client = test_client(&DummyDevice);
+    let address = |handle| {
+        let resource = client.get_resource(handle).unwrap();
+        resource.resource().read().as_ptr().addr()
+    };
+
+    let layouts = client.empty_tensors(vec![
+        MemoryLayoutDescriptor::contiguous([3].into(), 1),
+        MemoryLayoutDescriptor::contiguous([5].into(), 1).with_alignment(4096),
+    ]);
+    assert_eq!(layouts[1].memory.alignment(), 4096);
+    assert_eq!(address(layouts[1].memory.clone()) % 4096, 0);
+
+    let handle = client.empty_aligned(100, 256).unwrap();
+    assert_eq!(handle.alignment(), 256);
+    assert_eq!(address(handle) % 256, 0);
+}
+
+#[test_log::test]
+fn create_from_host_wraps_owned_bytes() {
+    let client = test_client(&DummyDevice);
+    let address = |handle| {
+        let resource = client.get_resource(handle).unwrap();
+        resource.resource().read().as_ptr().addr()
+    };
+    let data = Bytes::from_bytes_vec(vec![0, 1, 2, 3, 4, 5, 6, 7]);
+    let ptr = data.as_ptr().addr();
+
+    let host = client.create_from_host(data);
+
+    assert!(host.zero_copy);
+    assert_eq!(address(host.handle.clone()), ptr);
+    assert_eq!(
+        host.handle.alignment(),
+        Ord::min(
+            1 << ptr.trailing_zeros(),
+            client.properties().memory.alignment
+        )
+    );
+    assert_eq!(
+        client.read_one(host.handle).unwrap().to_vec(),
+        [0, 1, 2, 3, 4, 5, 6, 7]
+    );
+}
+
+#[test_log::test]
+fn create_from_host_copies_shared_bytes() {
+    let client = test_client(&DummyDevice);
+    let data = Bytes::from_bytes_vec(vec![0, 1, 2, 3, 4, 5, 6, 7]).shared();
+
+    let host = client.create_from_host(data);
+
+    assert!(!host.zero_copy);
+    assert_eq!(
+        client.read_one(host.handle).unwrap().to_vec(),
+        [0, 1, 2, 3, 4, 5, 6, 7]
+    );
+}
+
+#[test_log::test]
+fn dry_run_records_peak_memory() {
+    let server = DryRunServer::new(
+        DryRunServer::default_properties(),
+        MemoryConfiguration::ExclusivePages,
+    );
+    let client = ComputeClient::<DryRunRuntime>::init(&DryRunDevice::new(1), server);
+
+    let lhs = client.empty(1024);
+    let rhs = client.empty(2048);
+    block_on(client.sync()).unwrap();
+    core::mem::drop((lhs, rhs));
+    block_on(client.sync()).unwrap();
+
+    let report = client.info().report();
+    assert!(report.peak_bytes_in_use >= 3072);
+    assert!(report.peak_bytes_reserved >= report.peak_bytes_in_use);
+    assert!(
+        report
+            .events
+            .iter()
+            .any(|event| matches!(event, DryRunEvent::Allocate { .. }))
+    );
+    assert_eq!(client.read_one(client.empty(16)).unwrap().to_vec(), [0; 16]);
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_samples_measure_energy() {
+    let client = test_client(&DummyDevice);
+
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+    let handles = vec![lhs, rhs, out];
+
+    let shapes = vec![vec![1, 3], vec![1, 3], vec![1, 3]];
+    let set = dummy::addition_set(client.clone(), shapes);
+    let operation = set.autotunables().next().unwrap();
+    let samples = tune_samples(operation, handles, client).unwrap();
+
+    // Every sample launches a single kernel.
+    assert!(!samples.profiles.is_empty());
+    assert_eq!(samples.energy, Some(ENERGY_PER_LAUNCH));
+}
+
 #[test_log::test]
 #[cfg(feature = "std")]
 fn autotune_basic_addition_execution() {
@@ -101,6 +559,480 @@ fn autotune_basic_multiplication_execution() {
     assert_eq!(obtained_resource, Vec::from([0, 4, 8]));
 }
 
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_seeded_defaults_skip_tuning() {
+    static TUNER: LocalTuner<String, String> = local_tuner!("autotune_seeded_defaults_skip_tuning");
+    static DEFAULTS: &[TuneDefault<String>] = &[
+        TuneDefault::new("other", |_| true, 0),
+        TuneDefault::new("dummy", |key| key.starts_with("add"), 1),
+    ];
+
+    let client = test_client(&DummyDevice);
+
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+    let handles = vec![lhs, rhs, out.clone()];
+
+    let test_set = TUNER.init(|| {
+        let client = test_client(&DummyDevice);
+        let shapes = vec![vec![1, 3], vec![1, 3], vec![1, 3]];
+        dummy::addition_set(client, shapes)
+    });
+    TUNER.seed(&"test".to_string(), "dummy", DEFAULTS);
+    TUNER.execute(&"test".to_string(), &client, test_set, handles);
+
+    let obtained_resource = client.read_one(out).unwrap().to_vec();
+
+    // The seeded slow kernel is used without benchmarking the candidates.
+    assert_eq!(obtained_resource, Vec::from([0, 1, 2]));
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_out_of_range_defaults_fall_back_to_tuning() {
+    static TUNER: LocalTuner<String, String> =
+        local_tuner!("autotune_out_of_range_defaults_fall_back_to_tuning");
+    static DEFAULTS: &[TuneDefault<String>] = &[TuneDefault::new("dummy", |_| true, 5)];
+
+    let client = test_client(&DummyDevice);
+
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+    let handles = vec![lhs, rhs, out.clone()];
+
+    let test_set = TUNER.init(|| {
+        let client = test_client(&DummyDevice);
+        let shapes = vec![vec![1, 3], vec![1, 3], vec![1, 3]];
+        dummy::addition_set(client, shapes)
+    });
+    TUNER.seed(&"test".to_string(), "dummy", DEFAULTS);
+    TUNER.execute(&"test".to_string(), &client, test_set, handles);
+
+    let obtained_resource = client.read_one(out).unwrap().to_vec();
+
+    // The set only has two candidates, so the key is tuned and the fast kernel wins.
+    assert_eq!(obtained_resource, Vec::from([4, 5, 6]));
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_loads_persisted_results() {
+    static PERSISTENCE: std::sync::LazyLock<InMemoryTunePersistence> =
+        std::sync::LazyLock::new(InMemoryTunePersistence::new);
+    static TUNER: LocalTuner<String, String> = LocalTuner::new("autotune_loads_persisted_results")
+        .with_persistence(|_name, _device| Box::new(PERSISTENCE.clone()));
+
+    let client = test_client(&DummyDevice);
+
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+    let handles = vec![lhs, rhs, out.clone()];
+
+    let test_set = TUNER.init(|| {
+        let client = test_client(&DummyDevice);
+        let shapes = vec![vec![1, 3], vec![1, 3], vec![1, 3]];
+        dummy::addition_set(client, shapes)
+    });
+    PERSISTENCE
+        .clone()
+        .store("add-1,4,", &test_set.compute_checksum(), 1);
+    TUNER.execute(&"test".to_string(), &client, test_set, handles);
+
+    let obtained_resource = client.read_one(out).unwrap().to_vec();
+
+    // The persisted slow kernel is used without benchmarking the candidates.
+    assert_eq!(obtained_resource, Vec::from([0, 1, 2]));
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn file_persistence_overwrites_stored_results() {
+    use cubecl_runtime::tune::FileTunePersistence;
+
+    let root = tempfile::tempdir().unwrap();
+    let mut persistence = FileTunePersistence::new(root.path(), "overwrite");
+    persistence.store("key", "checksum", 0);
+    persistence.store("key", "checksum", 2);
+    assert_eq!(persistence.load("key", "checksum"), Some(2));
+
+    let mut reloaded = FileTunePersistence::new(root.path(), "overwrite");
+    assert_eq!(reloaded.load("key", "checksum"), Some(2));
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_snapshot_lists_cached_results() {
+    static TUNER: LocalTuner<String, String> =
+        local_tuner!("autotune_snapshot_lists_cached_results");
+
+    let client = test_client(&DummyDevice);
+
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+    let handles = vec![lhs, rhs, out.clone()];
+
+    let test_set = TUNER.init(|| {
+        let client = test_client(&DummyDevice);
+        let shapes = vec![vec![1, 3], vec![1, 3], vec![1, 3]];
+        dummy::multiplication_set(client, shapes)
+    });
+    TUNER.execute(&"snapshot".to_string(), &client, test_set, handles);
+
+    let entries = || {
+        autotune_snapshot()
+            .into_iter()
+            .find(|tuner| {
+                tuner
+                    .name
+                    .ends_with("autotune_snapshot_lists_cached_results")
+            })
+            .map(|tuner| tuner.entries)
+            .unwrap()
+    };
+
+    assert_eq!(
+        entries(),
+        [TuneEntry {
+            key: "mul-1,4,".into(),
+            fastest_index: Some(1),
+        }]
+    );
+
+    clear_autotune_caches();
+    assert_eq!(entries(), []);
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_skips_failed_candidates() {
+    use cubecl_runtime::server::Handle;
+    use cubecl_runtime::tune::{CloneInputGenerator, Tunable, TunableSet, TuneCacheResult, Tuner};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static BROKEN_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    let client = test_client(&DummyDevice);
+    let handles = vec![
+        client.create_from_slice(&[0, 1, 2]),
+        client.create_from_slice(&[4, 4, 4]),
+        client.empty(3),
+    ];
+
+    let op_add =
+        OneKernelAutotuneOperation::new(KernelTask::new(DummyElementwiseAddition), client.clone());
+    let test_set = TunableSet::<String, Vec<Handle>, ()>::new(
+        |_input: &Vec<Handle>| "broken-add".to_string(),
+        CloneInputGenerator,
+    )
+    .with(Tunable::new("add", move |inputs| op_add.run(inputs)))
+    .with(Tunable::new("add_broken", |_inputs| {
+        BROKEN_CALLS.fetch_add(1, Ordering::Relaxed);
+        Err("can't compile".to_string())
+    }));
+
+    let root = tempfile::tempdir().unwrap();
+    let tuner = Tuner::new_in("autotune_skips_failed_candidates", "test", root.path());
+    let key = "broken-add".to_string();
+    let tune = || {
+        tuner.check_tune(
+            &key,
+            &handles,
+            &test_set,
+            || test_set.compute_checksum(),
+            &client,
+        )
+    };
+
+    assert!(matches!(tune(), TuneCacheResult::Hit { fastest_index: 0 }));
+    let calls = BROKEN_CALLS.load(Ordering::Relaxed);
+    assert!(calls > 0);
+
+    tuner.clear();
+    assert!(matches!(tune(), TuneCacheResult::Hit { fastest_index: 0 }));
+    assert_eq!(BROKEN_CALLS.load(Ordering::Relaxed), calls);
+
+    // Once the failures are forgotten, the candidate is benchmarked again.
+    tuner.clear();
+    tuner.clear_failures();
+    assert!(matches!(tune(), TuneCacheResult::Hit { fastest_index: 0 }));
+    assert!(BROKEN_CALLS.load(Ordering::Relaxed) > calls);
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_persists_results_tuned_again() {
+    use cubecl_runtime::server::Handle;
+    use cubecl_runtime::tune::{CloneInputGenerator, Tunable, TunableSet, TuneCacheResult, Tuner};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static SECOND_WORKS: AtomicBool = AtomicBool::new(false);
+
+    let client = test_client(&DummyDevice);
+    let handles = vec![
+        client.create_from_slice(&[0, 1, 2]),
+        client.create_from_slice(&[4, 4, 4]),
+        client.empty(3),
+    ];
+
+    let op_first =
+        OneKernelAutotuneOperation::new(KernelTask::new(DummyElementwiseAddition), client.clone());
+    let op_second = op_first.clone();
+    let test_set = TunableSet::<String, Vec<Handle>, ()>::new(
+        |_input: &Vec<Handle>| "retuned-add".to_string(),
+        CloneInputGenerator,
+    )
+    .with(Tunable::new("first", move |inputs| {
+        match SECOND_WORKS.load(Ordering::Relaxed) {
+            true => Err("broken".to_string()),
+            false => op_first.run(inputs),
+        }
+    }))
+    .with(Tunable::new("second", move |inputs| {
+        match SECOND_WORKS.load(Ordering::Relaxed) {
+            true => op_second.run(inputs),
+            false => Err("broken".to_string()),
+        }
+    }));
+
+    let root = tempfile::tempdir().unwrap();
+    let key = "retuned-add".to_string();
+    let tune = |tuner: &Tuner<String>| {
+        tuner.check_tune(
+            &key,
+            &handles,
+            &test_set,
+            || test_set.compute_checksum(),
+            &client,
+        )
+    };
+
+    let tuner = Tuner::new_in("autotune_persists_results_tuned_again", "test", root.path());
+    assert!(matches!(
+        tune(&tuner),
+        TuneCacheResult::Hit { fastest_index: 0 }
+    ));
+
+    SECOND_WORKS.store(true, Ordering::Relaxed);
+    tuner.clear();
+    tuner.clear_failures();
+    assert!(matches!(
+        tune(&tuner),
+        TuneCacheResult::Hit { fastest_index: 1 }
+    ));
+
+    // The next process loads the result tuned again, not the stale one.
+    let reloaded = Tuner::new_in("autotune_persists_results_tuned_again", "test", root.path());
+    assert!(matches!(
+        tune(&reloaded),
+        TuneCacheResult::Hit { fastest_index: 1 }
+    ));
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_tunes_joint_stages_together() {
+    use cubecl_runtime::server::Handle;
+    use cubecl_runtime::tune::{
+        CloneInputGenerator, JointTunable, TunableSet, TuneCacheResult, Tuner,
+    };
+
+    let client = test_client(&DummyDevice);
+    let handles = vec![
+        client.create_from_slice(&[0, 1, 2]),
+        client.create_from_slice(&[4, 4, 4]),
+        client.empty(3),
+    ];
+
+    let op_add =
+        OneKernelAutotuneOperation::new(KernelTask::new(DummyElementwiseAddition), client.clone());
+    let joint = JointTunable::new(move |choice: &[usize], inputs| match choice {
+        // Only this combination of stages works together.
+        [1, 0] => op_add.run(inputs),
+        _ => Err(format!("incompatible stages {choice:?}")),
+    })
+    .stage("first", &["a", "b"])
+    .stage("second", &["c", "d"]);
+
+    assert_eq!(joint.combinations(), 4);
+    assert_eq!(joint.choice(2), vec![1, 0]);
+
+    let test_set = TunableSet::<String, Vec<Handle>, ()>::new(
+        |_input: &Vec<Handle>| "joint-add".to_string(),
+        CloneInputGenerator,
+    )
+    .with_joint(joint);
+    let names = test_set
+        .autotunables()
+        .map(|tunable| tunable.name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "first=a+second=c",
+            "first=a+second=d",
+            "first=b+second=c",
+            "first=b+second=d"
+        ]
+    );
+
+    let tuner = Tuner::new("autotune_tunes_joint_stages_together", "test");
+    let fastest = tuner.check_tune(
+        &"joint-add".to_string(),
+        &handles,
+        &test_set,
+        || test_set.compute_checksum(),
+        &client,
+    );
+    assert!(matches!(fastest, TuneCacheResult::Hit { fastest_index: 2 }));
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_resumes_search_exceeding_time_budget() {
+    use cubecl_runtime::server::Handle;
+    use cubecl_runtime::tune::{CloneInputGenerator, Tunable, TunableSet, TuneCacheResult, Tuner};
+    use std::{sync::Mutex, time::Duration};
+
+    static EXECUTIONS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    let client = test_client(&DummyDevice);
+    let mut set = TunableSet::<String, Vec<Handle>, ()>::new(
+        |_input: &Vec<Handle>| String::new(),
+        CloneInputGenerator,
+    )
+    .with_time_budget(Duration::ZERO);
+    for (name, millis) in [("a", 4), ("b", 0), ("c", 2)] {
+        set = set.with(Tunable::new(name, move |_inputs| {
+            EXECUTIONS.lock().unwrap().push(name);
+            std::thread::sleep(Duration::from_millis(millis));
+            Ok::<(), String>(())
+        }));
+    }
+
+    let root = tempfile::tempdir().unwrap();
+    let tuner = Tuner::new_in(
+        "autotune_resumes_search_exceeding_time_budget",
+        "test",
+        root.path(),
+    );
+    let key = "budget".to_string();
+    let tune = || tuner.check_tune(&key, &vec![], &set, || set.compute_checksum(), &client);
+    let executed = || {
+        let mut order = EXECUTIONS.lock().unwrap().clone();
+        order.dedup();
+        order
+    };
+
+    // The budget is exceeded after the first candidate, which is used in the meantime.
+    assert!(matches!(tune(), TuneCacheResult::Hit { fastest_index: 0 }));
+    assert_eq!(executed(), ["a"]);
+    assert!(matches!(
+        tuner.fastest(&key),
+        TuneCacheResult::Hit { fastest_index: 0 }
+    ));
+
+    // Each resumption benchmarks the next candidate within the budget.
+    assert_eq!(tuner.resume_tuning(), 1);
+    assert!(matches!(tuner.fastest(&key), TuneCacheResult::Miss));
+    assert!(matches!(tune(), TuneCacheResult::Hit { fastest_index: 1 }));
+    assert_eq!(executed(), ["a", "b"]);
+
+    assert_eq!(tuner.resume_tuning(), 1);
+    assert!(matches!(tune(), TuneCacheResult::Hit { fastest_index: 1 }));
+    assert_eq!(executed(), ["a", "b", "c"]);
+    assert_eq!(tuner.resume_tuning(), 0);
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_time_budget_counts_wall_clock() {
+    use cubecl_runtime::server::Handle;
+    use cubecl_runtime::tune::{CloneInputGenerator, Tunable, TunableSet, TuneCacheResult, Tuner};
+    use std::{sync::Mutex, time::Duration};
+
+    static EXECUTIONS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    let client = test_client(&DummyDevice);
+    let mut set = TunableSet::<String, Vec<Handle>, ()>::new(
+        |_input: &Vec<Handle>| String::new(),
+        CloneInputGenerator,
+    )
+    .with_time_budget(Duration::from_millis(10));
+    // The failing candidate has no profiled duration, but its time still counts.
+    set = set.with(Tunable::new("failing", |_inputs| {
+        EXECUTIONS.lock().unwrap().push("failing");
+        std::thread::sleep(Duration::from_millis(30));
+        Err::<(), String>("can't launch".to_string())
+    }));
+    for name in ["b", "c"] {
+        set = set.with(Tunable::new(name, move |_inputs| {
+            EXECUTIONS.lock().unwrap().push(name);
+            Ok::<(), String>(())
+        }));
+    }
+
+    let root = tempfile::tempdir().unwrap();
+    let tuner = Tuner::new_in(
+        "autotune_time_budget_counts_wall_clock",
+        "test",
+        root.path(),
+    );
+    let key = "wall-clock".to_string();
+    let tune = tuner.check_tune(&key, &vec![], &set, || set.compute_checksum(), &client);
+
+    assert!(matches!(tune, TuneCacheResult::Hit { fastest_index: 1 }));
+    let mut executed = EXECUTIONS.lock().unwrap().clone();
+    executed.dedup();
+    assert_eq!(executed, ["failing", "b"]);
+    assert_eq!(tuner.resume_tuning(), 1);
+}
+
+#[test_log::test]
+#[should_panic(expected = "at least one candidate")]
+fn joint_tunable_rejects_empty_stages() {
+    use cubecl_runtime::server::Handle;
+    use cubecl_runtime::tune::JointTunable;
+
+    let _ = JointTunable::<Vec<Handle>, ()>::new(|_choice: &[usize], _inputs| Ok::<_, String>(()))
+        .stage("first", &["a"])
+        .stage("second", &[]);
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_execute_async_runs_fastest() {
+    static TUNER: LocalTuner<String, String> = local_tuner!("autotune_execute_async_runs_fastest");
+
+    let client = test_client(&DummyDevice);
+
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+    let handles = vec![lhs, rhs, out.clone()];
+
+    let test_set = TUNER.init(|| {
+        let client = test_client(&DummyDevice);
+        let shapes = vec![vec![1, 3], vec![1, 3], vec![1, 3]];
+        dummy::multiplication_set(client, shapes)
+    });
+    cubecl_common::future::block_on(TUNER.execute_async(
+        &"test".to_string(),
+        &client,
+        test_set,
+        handles,
+    ));
+
+    let obtained_resource = client.read_one(out).unwrap().to_vec();
+
+    // If slow kernel was selected it would output [0, 1, 2]
+    assert_eq!(obtained_resource, Vec::from([0, 4, 8]));
+}
+
 /// 2-I1 — A panic inside a profiled closure surfaces at the `ComputeClient` caller as
 /// the *original* panic (the issue's symptom), instead of an opaque `CallError`.
 #[test_log::test]
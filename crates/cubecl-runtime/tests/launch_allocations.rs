@@ -0,0 +1,89 @@
+//! Counts the heap allocations made while launching a kernel, on any thread.
+//!
+//! Launching in steady state shouldn't allocate, which matters for CPU-bound workloads that
+//! launch many small kernels. The kernel and its arguments are created by the caller, so they
+//! aren't counted.
+//!
+//! The counter is global, so the allocations of a server running on its own thread are counted
+//! too, except those of the dummy backend executing the kernel. This file holds a single test, so
+//! that no other test allocates while it runs.
+
+#[allow(dead_code)]
+mod dummy;
+
+use cubecl_runtime::server::{CubeCount, KernelArguments};
+use dummy::{DummyDevice, DummyElementwiseAddition, KernelTask, executing_kernel, test_client};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+const WARMUP: usize = 100;
+const LAUNCHES: usize = 10_000;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+fn count() {
+    if !executing_kernel() {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: Every call is forwarded to the system allocator.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn launching_a_kernel_does_not_allocate() {
+    let client = test_client(&DummyDevice);
+    let lhs = client.create_from_slice(&[0, 1, 2]);
+    let rhs = client.create_from_slice(&[4, 4, 4]);
+    let out = client.empty(3);
+
+    let launch = |count| {
+        let launches = (0..count)
+            .map(|_| {
+                let kernel = Box::new(KernelTask::new(DummyElementwiseAddition));
+                let bindings = KernelArguments::new().with_buffers(vec![
+                    lhs.clone().binding(),
+                    rhs.clone().binding(),
+                    out.clone().binding(),
+                ]);
+                (kernel, bindings)
+            })
+            .collect::<Vec<_>>();
+
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        for (kernel, bindings) in launches {
+            client.launch(kernel, CubeCount::Static(1, 1, 1), bindings);
+        }
+        // Wait for the server, which handles the launches on its own thread.
+        client.flush().unwrap();
+        ALLOCATIONS.load(Ordering::Relaxed) - before
+    };
+
+    launch(WARMUP);
+    // Leave out the allocations of the flush itself, measured with nothing to launch.
+    let flush = launch(0);
+    let allocations = launch(LAUNCHES) - flush;
+
+    assert_eq!(allocations, 0, "Launching a kernel shouldn't allocate");
+}
@@ -0,0 +1,47 @@
+//! The load width is calibrated explicitly, once per device, and cached with the autotune
+//! results.
+//!
+//! This file holds a single test, since it sets the global configuration to keep the cache out
+//! of the real cache directory.
+
+#[allow(dead_code)]
+mod dummy;
+
+use cubecl_runtime::config::{CubeClRuntimeConfig, RuntimeConfig, cache::CacheConfig};
+use dummy::{DummyDevice, LOAD_WIDTH_MEASUREMENTS, MEASURED_LOAD_WIDTH, test_client};
+use std::sync::atomic::Ordering;
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn load_width_is_measured_once_and_cached() {
+    let root = tempfile::tempdir().unwrap();
+    let mut config = CubeClRuntimeConfig::default();
+    config.autotune.cache = CacheConfig::File(root.path().to_path_buf());
+    CubeClRuntimeConfig::set(config);
+
+    let client = test_client(&DummyDevice);
+    assert_eq!(LOAD_WIDTH_MEASUREMENTS.load(Ordering::Relaxed), 0);
+
+    // Concurrent calibrations wait for a single measurement.
+    let widths = std::thread::scope(|scope| {
+        let threads = (0..4)
+            .map(|_| scope.spawn(|| test_client(&DummyDevice).calibrate_load_width()))
+            .collect::<Vec<_>>();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(widths, [MEASURED_LOAD_WIDTH; 4]);
+    assert_eq!(LOAD_WIDTH_MEASUREMENTS.load(Ordering::Relaxed), 1);
+
+    // 64 bits hold two u32.
+    let vector_sizes = client.io_optimized_vector_sizes(size_of::<u32>());
+    assert_eq!(vector_sizes.collect::<Vec<_>>(), [2, 1]);
+    assert_eq!(client.io_load_width(), MEASURED_LOAD_WIDTH);
+    assert_eq!(client.calibrate_load_width(), MEASURED_LOAD_WIDTH);
+    assert_eq!(LOAD_WIDTH_MEASUREMENTS.load(Ordering::Relaxed), 1);
+
+    // Persisted for the next processes on the device.
+    assert!(root.path().join("vectorization").exists());
+}
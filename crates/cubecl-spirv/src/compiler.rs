@@ -166,6 +166,13 @@ impl<T: SpirvTarget> Compiler for SpirvCompiler<T> {
             });
         }
 
+        if !value.textures.is_empty() {
+            return Err(CompilationError::UnsupportedInstruction {
+                reason: "Textures aren't supported in SPIR-V".to_string(),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
         let bindings = value.buffers.clone();
         let mut ext_meta_pos = Vec::new();
         let mut num_ext = 0;
@@ -233,6 +240,11 @@ impl<T: SpirvTarget> Compiler for SpirvCompiler<T> {
     fn extension(&self) -> &'static str {
         "spv"
     }
+
+    fn compiles_source(&self) -> bool {
+        // The source is a disassembly, the assembled module is what gets compiled.
+        false
+    }
 }
 
 impl<Target: SpirvTarget> Debug for SpirvCompiler<Target> {
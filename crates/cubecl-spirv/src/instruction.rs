@@ -79,6 +79,9 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             Operation::NonSemantic(debug) => self.compile_debug(debug),
             Operation::Barrier(_) => panic!("Barrier not supported in SPIR-V"),
             Operation::Tma(_) => panic!("TMA not supported in SPIR-V"),
+            Operation::Texture(_) => {
+                unreachable!("Texture kernels are rejected before compilation")
+            }
             Operation::Marker(_) => {}
             Operation::ConstructAggregate(..) | Operation::ExtractAggregateField(..) => {
                 unreachable!("Should be disaggregated at this point")
@@ -399,6 +402,19 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                 let out = self.compile_value(out);
                 self.write(&out, value);
             }
+            Operator::ReadScalarArray(op) => {
+                let index = self.compile_value(op.index);
+                let index_ty = Item::builtin_u32();
+                let index = self.read_as(&index, &index_ty);
+                let index_ty_id = index_ty.id(self);
+                let offset = self.const_u32(op.id);
+                let index = self.i_add(index_ty_id, None, offset, index).unwrap();
+                self.mark_uniformity(index, uniform);
+
+                let value = self.global_scalar_array(index, out.storage_type());
+                let out = self.compile_value(out);
+                self.write(&out, value);
+            }
         }
     }
 
@@ -439,6 +439,9 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                     panic!("Barrier not supported in SPIR-V")
                 }
                 core::OpaqueType::TensorMap => panic!("Tensor map not supported in SPIR-V"),
+                core::OpaqueType::Texture => {
+                    unreachable!("Texture kernels are rejected before compilation")
+                }
             },
             core::Type::Aggregate(_) => {
                 unreachable!("Should be disaggregated at this point")
@@ -348,4 +348,26 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             .unwrap()
         })
     }
+
+    /// Load the scalar at the dynamic `index` of the scalars of type `ty`.
+    pub fn global_scalar_array(&mut self, index: Word, ty: ir::StorageType) -> Word {
+        let field_id = self.const_u32(self.state.scalar_bindings[&ty]);
+        let item = self.compile_type(ir::Type::new(ty));
+        let align = item.size();
+        let ty_id = item.id(self);
+        let storage_class = T::info_storage_class(self);
+        let ptr_ty = Item::Pointer(storage_class, Box::new(item)).id(self);
+        let info = self.state.info.unwrap().id;
+        let access = self
+            .in_bounds_access_chain(ptr_ty, None, info, [field_id, index])
+            .unwrap();
+        self.load(
+            ty_id,
+            None,
+            access,
+            Some(MemoryAccess::ALIGNED),
+            [align.into()],
+        )
+        .unwrap()
+    }
 }
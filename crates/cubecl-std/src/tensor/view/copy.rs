@@ -0,0 +1,90 @@
+use cubecl::prelude::*;
+use cubecl_core::{
+    self as cubecl,
+    ir::{DeviceProperties, features::Tma},
+    prelude::barrier::{Barrier, copy_async},
+};
+
+use crate::tensor::{View, layout::Coords2d};
+
+/// How a [cooperative copy](cooperative_copy) is lowered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CopyStrategy {
+    /// A single TMA load of the whole tile, issued by the first unit. The source view must be
+    /// backed by a tensor map with a box matching the tile.
+    TensorMap,
+    /// A `copy_async` per vector, committed to the barrier.
+    CopyAsync,
+    /// A regular load and store per vector.
+    Vectorized,
+}
+
+impl CopyStrategy {
+    /// Select the fastest strategy supported by the device for vectors of `vector_bytes`, using TMA
+    /// only when the source is backed by a tensor map.
+    pub fn select(properties: &DeviceProperties, vector_bytes: usize, tensor_map: bool) -> Self {
+        let features = &properties.features;
+
+        if tensor_map && features.tma.contains(Tma::Base) {
+            CopyStrategy::TensorMap
+        } else if features.copy_async && matches!(vector_bytes, 4 | 8 | 16) {
+            CopyStrategy::CopyAsync
+        } else {
+            CopyStrategy::Vectorized
+        }
+    }
+}
+
+/// Copy a tile of `rows` by `cols` vectors of the `source` view to the `destination` shared
+/// memory in row-major order, cooperatively with every unit of the cube. Elements out of bounds of
+/// the source are zeroed, or filled according to the tensor map with [`CopyStrategy::TensorMap`].
+///
+/// The copy completes on the `barrier` for every strategy, so each unit must arrive on it and wait
+/// before reading `destination`, e.g. with [`Barrier::arrive_and_wait`]. The barrier must be a
+/// shared barrier expecting an arrival from every unit of the cube.
+#[cube]
+pub fn cooperative_copy<E: Numeric, N: Size>(
+    source: &View<Vector<E, N>, Coords2d>,
+    destination: &mut [Vector<E, N>],
+    barrier: &Barrier,
+    #[comptime] rows: u32,
+    #[comptime] cols: u32,
+    #[comptime] strategy: CopyStrategy,
+) {
+    let num_vectors = comptime![rows * cols];
+
+    match comptime!(strategy) {
+        CopyStrategy::TensorMap => {
+            if UNIT_POS == 0 {
+                let bytes = comptime![num_vectors * Vector::<E, N>::type_size() as u32];
+                barrier.expect_tx(bytes);
+                let origin = 0u32.runtime();
+                source.tensor_map_load(barrier, destination, (origin, origin));
+            }
+        }
+        CopyStrategy::CopyAsync => {
+            let vector_size = comptime![N::value() as u32];
+            let one = 1u32.runtime();
+
+            for index in range_stepped(UNIT_POS, num_vectors, CUBE_DIM) {
+                let pos = (index / cols, index % cols);
+                let index = index as usize;
+
+                if source.is_in_bounds(pos) {
+                    let vector = source.slice(pos, (one, one)).as_linear_slice();
+                    copy_async(vector, &mut destination[index..index + 1], vector_size);
+                } else {
+                    destination[index] = Vector::new(E::from_int(0));
+                }
+            }
+
+            barrier.commit_copy_async();
+        }
+        CopyStrategy::Vectorized => {
+            for index in range_stepped(UNIT_POS, num_vectors, CUBE_DIM) {
+                let pos = (index / cols, index % cols);
+                destination[index as usize] = source.read_checked(pos);
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
 mod as_view;
 mod base;
+mod copy;
 pub mod launch;
 mod operations;
 
 pub use as_view::*;
 pub use base::*;
+pub use copy::*;
 pub use operations::*;
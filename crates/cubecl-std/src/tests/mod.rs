@@ -17,6 +17,7 @@ macro_rules! testgen {
             cubecl_std::testgen_reinterpret_slice!();
             cubecl_std::testgen_trigonometry!();
             cubecl_std::testgen_event!();
+            cubecl_std::testgen_cooperative_copy!();
         }
     };
 }
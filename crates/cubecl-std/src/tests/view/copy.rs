@@ -0,0 +1,144 @@
+use cubecl::prelude::*;
+use cubecl_core::{
+    self as cubecl,
+    ir::{BarrierLevel, OpaqueType},
+    prelude::barrier::Barrier,
+};
+
+use crate::tensor::{
+    AsView, AsViewExpand, CopyStrategy, cooperative_copy,
+    layout::{Coords1d, Coords2d, Layout, LayoutExpand},
+};
+
+#[derive(CubeType)]
+struct TestRowMajorLayout {
+    rows: u32,
+    cols: u32,
+}
+
+#[cube]
+impl Layout for TestRowMajorLayout {
+    type Coordinates = Coords2d;
+    type SourceCoordinates = Coords1d;
+
+    fn to_source_pos(&self, pos: Self::Coordinates) -> Self::SourceCoordinates {
+        let (row, col) = pos;
+        (row * self.cols + col) as usize
+    }
+
+    fn to_source_pos_checked(&self, pos: Self::Coordinates) -> (Self::SourceCoordinates, bool) {
+        (self.to_source_pos(pos), self.is_in_bounds(pos))
+    }
+
+    fn is_in_bounds(&self, pos: Self::Coordinates) -> bool {
+        let (row, col) = pos;
+        row < self.rows && col < self.cols
+    }
+
+    fn shape(&self) -> Self::Coordinates {
+        (self.rows, self.cols)
+    }
+}
+
+#[cube(launch)]
+fn kernel_cooperative_copy<F: Float, N: Size>(
+    input: &[Vector<F, N>],
+    output: &mut [Vector<F, N>],
+    rows: u32,
+    cols: u32,
+    row: u32,
+    col: u32,
+    #[comptime] tile_rows: u32,
+    #[comptime] tile_cols: u32,
+    #[comptime] strategy: CopyStrategy,
+) {
+    let num_elems = comptime![(tile_rows * tile_cols) as usize];
+    let mut smem = Shared::<[Vector<F, N>]>::new_slice(num_elems);
+    let barrier = Barrier::shared(CUBE_DIM, UNIT_POS == 0);
+    sync_cube();
+
+    let size = (tile_rows.runtime(), tile_cols.runtime());
+    let source = input
+        .view(TestRowMajorLayout { rows, cols })
+        .slice((row, col), size);
+    cooperative_copy::<F, N>(
+        &source,
+        smem.as_mut_slice(),
+        &barrier,
+        tile_rows,
+        tile_cols,
+        strategy,
+    );
+    barrier.arrive_and_wait();
+
+    for index in range_stepped(UNIT_POS as usize, num_elems, CUBE_DIM as usize) {
+        output[index] = smem[index];
+    }
+}
+
+pub fn test_cooperative_copy<R: Runtime, F: Float + CubeElement>(
+    client: ComputeClient<R>,
+    strategy: CopyStrategy,
+) {
+    if !client
+        .properties()
+        .supports_type(OpaqueType::Barrier(BarrierLevel::Cube))
+    {
+        // We can't execute the test, skip.
+        return;
+    }
+
+    // A 2x4 tile at (2, 2) of a 3x5 matrix, with the last row and column out of bounds.
+    let input = (0..15).map(|it| F::new(it as f32)).collect::<Vec<_>>();
+    let input = client.create_from_slice(F::as_bytes(&input));
+    let output = client.empty(8 * size_of::<F>());
+
+    kernel_cooperative_copy::launch::<F, R>(
+        &client,
+        CubeCount::new_single(),
+        CubeDim::new_1d(4),
+        1,
+        unsafe { BufferArg::from_raw_parts(input, 15) },
+        unsafe { BufferArg::from_raw_parts(output.clone(), 8) },
+        3,
+        5,
+        2,
+        2,
+        2,
+        4,
+        strategy,
+    );
+
+    let actual = client.read_one_unchecked(output);
+    let actual = F::from_bytes(&actual);
+    let expected = [12.0, 13.0, 14.0, 0.0, 0.0, 0.0, 0.0, 0.0].map(F::new);
+
+    assert_eq!(actual, expected);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_cooperative_copy {
+    () => {
+        use super::*;
+        use cubecl_std::tensor::CopyStrategy;
+
+        #[$crate::tests::test_log::test]
+        fn test_cooperative_copy_vectorized() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_std::tests::view::copy::test_cooperative_copy::<TestRuntime, f32>(
+                client,
+                CopyStrategy::Vectorized,
+            );
+        }
+
+        #[$crate::tests::test_log::test]
+        fn test_cooperative_copy_selected() {
+            let client = TestRuntime::client(&Default::default());
+            let strategy = CopyStrategy::select(&client.properties(), size_of::<f32>(), false);
+            cubecl_std::tests::view::copy::test_cooperative_copy::<TestRuntime, f32>(
+                client, strategy,
+            );
+        }
+    };
+}
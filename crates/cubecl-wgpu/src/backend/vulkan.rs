@@ -37,6 +37,9 @@ use wgpu::{
 use crate::{WgpuCompiler, WgpuServer};
 
 mod features;
+mod semaphore;
+
+pub use semaphore::ExternalSemaphores;
 
 pub type VkSpirvCompiler = SpirvCompiler<GLCompute>;
 
@@ -255,6 +258,102 @@ pub(crate) fn create_storage_buffer(
     Ok((buffer, device_address))
 }
 
+/// Import host memory as a storage buffer without a copy, with `VK_EXT_external_memory_host`.
+///
+/// Only integrated GPUs import host memory, since a discrete GPU would read it over the bus on
+/// every access. The memory must be aligned to the import alignment of the device and stay alive
+/// until the device stops using the buffer. Returns `None` when the memory can't be imported.
+pub(crate) fn import_host_buffer(
+    wgpu_device: &wgpu::Device,
+    ptr: *mut u8,
+    desc: &wgpu::BufferDescriptor,
+) -> Option<(wgpu::Buffer, u64)> {
+    let device: &vulkan::Device = unsafe { &wgpu_device.as_hal::<hal::api::Vulkan>()? };
+    if !device
+        .enabled_device_extensions()
+        .contains(&vk::EXT_EXTERNAL_MEMORY_HOST_NAME)
+    {
+        return None;
+    }
+    let instance = device.shared_instance().raw_instance();
+    let phys_device = device.raw_physical_device();
+    let raw = device.raw_device();
+
+    let mut host_props = vk::PhysicalDeviceExternalMemoryHostPropertiesEXT::default();
+    let mut props = vk::PhysicalDeviceProperties2::default().push_next(&mut host_props);
+    unsafe { instance.get_physical_device_properties2(phys_device, &mut props) };
+    if props.properties.device_type != vk::PhysicalDeviceType::INTEGRATED_GPU {
+        return None;
+    }
+    let alignment = host_props.min_imported_host_pointer_alignment;
+    if ptr.addr() as u64 % alignment != 0 || desc.size % alignment != 0 {
+        return None;
+    }
+
+    let handle_type = vk::ExternalMemoryHandleTypeFlags::HOST_ALLOCATION_EXT;
+    let loader = ash::ext::external_memory_host::Device::new(instance, raw);
+    let mut pointer_props = vk::MemoryHostPointerPropertiesEXT::default();
+    // SAFETY: The pointer is valid for `desc.size` bytes and aligned to the import alignment.
+    unsafe {
+        loader
+            .get_memory_host_pointer_properties(handle_type, ptr.cast(), &mut pointer_props)
+            .ok()?
+    };
+
+    let uses = map_buffer_usage(desc.usage);
+    let usage_flags = wgpu::hal::vulkan::conv::map_buffer_usage(uses);
+    let mut external = vk::ExternalMemoryBufferCreateInfo::default().handle_types(handle_type);
+    let vk_info = ash::vk::BufferCreateInfo::default()
+        .size(desc.size)
+        .usage(usage_flags | BufferUsageFlags::SHADER_DEVICE_ADDRESS)
+        .sharing_mode(SharingMode::EXCLUSIVE)
+        .push_next(&mut external);
+    let buffer = unsafe { raw.create_buffer(&vk_info, None).ok()? };
+
+    let requirements = unsafe { raw.get_buffer_memory_requirements(buffer) };
+    let memory_type_bits = requirements.memory_type_bits & pointer_props.memory_type_bits;
+    if memory_type_bits == 0 {
+        unsafe { raw.destroy_buffer(buffer, None) };
+        return None;
+    }
+
+    let mut alloc_flags =
+        MemoryAllocateFlagsInfo::default().flags(MemoryAllocateFlags::DEVICE_ADDRESS);
+    let mut import = vk::ImportMemoryHostPointerInfoEXT::default()
+        .handle_type(handle_type)
+        .host_pointer(ptr.cast());
+    let alloc_info = MemoryAllocateInfo::default()
+        .allocation_size(desc.size)
+        .memory_type_index(memory_type_bits.trailing_zeros())
+        .push_next(&mut alloc_flags)
+        .push_next(&mut import);
+
+    // SAFETY: The buffer was just created and isn't used yet, so it can be destroyed on failure.
+    let memory = match unsafe { raw.allocate_memory(&alloc_info, None) } {
+        Ok(memory) => memory,
+        Err(_) => {
+            unsafe { raw.destroy_buffer(buffer, None) };
+            return None;
+        }
+    };
+    if unsafe { raw.bind_buffer_memory(buffer, memory, 0) }.is_err() {
+        unsafe {
+            raw.destroy_buffer(buffer, None);
+            raw.free_memory(memory, None);
+        };
+        return None;
+    }
+
+    let addr_info = BufferDeviceAddressInfo::default().buffer(buffer);
+    let device_address = unsafe { raw.get_buffer_device_address(&addr_info) };
+
+    let buffer =
+        unsafe { wgpu::hal::vulkan::Buffer::from_raw_managed(buffer, memory, 0, desc.size) };
+    let buffer = unsafe { wgpu_device.create_buffer_from_hal::<hal::api::Vulkan>(buffer, desc) };
+
+    Some((buffer, device_address))
+}
+
 fn as_io_error(result: vk::Result, size: u64) -> IoError {
     match result {
         vk::Result::ERROR_OUT_OF_HOST_MEMORY | vk::Result::ERROR_OUT_OF_DEVICE_MEMORY => {
@@ -142,6 +142,15 @@ impl<'a> ExtendedFeatures<'a> {
             // Properties
             EXT_SHADER_LONG_VECTOR_NAME => long_vector_properties,
         );
+
+        // Lets other APIs share timeline semaphores with the device, no feature to enable.
+        if phys_caps.supports_extension(KHR_EXTERNAL_SEMAPHORE_FD_NAME) {
+            self.extensions.push(KHR_EXTERNAL_SEMAPHORE_FD_NAME);
+        }
+        // Lets integrated GPUs use host memory without a copy, no feature to enable.
+        if phys_caps.supports_extension(EXT_EXTERNAL_MEMORY_HOST_NAME) {
+            self.extensions.push(EXT_EXTERNAL_MEMORY_HOST_NAME);
+        }
     }
 
     pub fn add_to_device_create(
@@ -0,0 +1,355 @@
+use ash::vk;
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::server::{ExternalSemaphore, ExternalSemaphoreHandle, ServerError};
+use hashbrown::HashMap;
+use wgpu::hal;
+
+/// Timeline semaphores shared with other APIs, only available with the Vulkan backend.
+///
+/// Signals are attached to a queue submission. wgpu has no way to make its submissions wait on a
+/// semaphore, so waits are submitted to the queue directly, with a full pipeline barrier that
+/// orders every later submission of the queue after the wait.
+#[derive(Debug)]
+pub struct ExternalSemaphores {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    semaphores: HashMap<u64, vk::Semaphore>,
+    barrier: Option<Barrier>,
+    next_id: u64,
+}
+
+/// A command buffer recorded once with a full pipeline barrier, submitted with every wait.
+#[derive(Debug)]
+struct Barrier {
+    pool: vk::CommandPool,
+    commands: vk::CommandBuffer,
+}
+
+impl ExternalSemaphores {
+    /// Manage the semaphores imported in the device, signaled through the queue.
+    pub fn new(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        Self {
+            device,
+            queue,
+            semaphores: HashMap::new(),
+            barrier: None,
+            next_id: 0,
+        }
+    }
+
+    /// Import a timeline semaphore exported as an opaque file descriptor.
+    pub fn import(
+        &mut self,
+        handle: ExternalSemaphoreHandle,
+    ) -> Result<ExternalSemaphore, ServerError> {
+        let ExternalSemaphoreHandle::OpaqueFd(fd) = handle else {
+            return Err(error("Only file descriptors can be imported as semaphores"));
+        };
+        // SAFETY: The raw device is only used to create and import the semaphore.
+        let Some(device) = (unsafe { self.device.as_hal::<hal::api::Vulkan>() }) else {
+            return Err(unsupported());
+        };
+        let instance = device.shared_instance().raw_instance();
+        let raw = device.raw_device();
+
+        let mut timeline =
+            vk::SemaphoreTypeCreateInfo::default().semaphore_type(vk::SemaphoreType::TIMELINE);
+        let info = vk::SemaphoreCreateInfo::default().push_next(&mut timeline);
+        // SAFETY: The create info is valid and the semaphore is destroyed by this struct.
+        let semaphore = unsafe { raw.create_semaphore(&info, None) }
+            .map_err(|err| error(format!("Can't create the semaphore: {err}")))?;
+
+        let import = vk::ImportSemaphoreFdInfoKHR::default()
+            .semaphore(semaphore)
+            .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD)
+            .fd(fd);
+        let loader = ash::khr::external_semaphore_fd::Device::new(instance, raw);
+        // SAFETY: The semaphore was just created, the driver takes ownership of the file
+        // descriptor on success.
+        if let Err(err) = unsafe { loader.import_semaphore_fd(&import) } {
+            // SAFETY: The semaphore isn't used by any submission yet.
+            unsafe { raw.destroy_semaphore(semaphore, None) };
+            return Err(error(format!("Can't import the semaphore: {err}")));
+        }
+
+        Ok(self.insert(semaphore))
+    }
+
+    /// Create a timeline semaphore starting at `0` and export it as an opaque file descriptor, so
+    /// another API can import it. The caller owns the returned file descriptor.
+    pub fn export(&mut self) -> Result<(ExternalSemaphore, ExternalSemaphoreHandle), ServerError> {
+        // SAFETY: The raw device is only used to create and export the semaphore.
+        let Some(device) = (unsafe { self.device.as_hal::<hal::api::Vulkan>() }) else {
+            return Err(unsupported());
+        };
+        let instance = device.shared_instance().raw_instance();
+        let raw = device.raw_device();
+
+        let mut timeline = vk::SemaphoreTypeCreateInfo::default()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(0);
+        let mut export = vk::ExportSemaphoreCreateInfo::default()
+            .handle_types(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD);
+        let info = vk::SemaphoreCreateInfo::default()
+            .push_next(&mut timeline)
+            .push_next(&mut export);
+        // SAFETY: The create info is valid and the semaphore is destroyed by this struct.
+        let semaphore = unsafe { raw.create_semaphore(&info, None) }
+            .map_err(|err| error(format!("Can't create the semaphore: {err}")))?;
+
+        let get = vk::SemaphoreGetFdInfoKHR::default()
+            .semaphore(semaphore)
+            .handle_type(vk::ExternalSemaphoreHandleTypeFlags::OPAQUE_FD);
+        let loader = ash::khr::external_semaphore_fd::Device::new(instance, raw);
+        // SAFETY: The semaphore was created exportable as an opaque file descriptor.
+        let fd = match unsafe { loader.get_semaphore_fd(&get) } {
+            Ok(fd) => fd,
+            Err(err) => {
+                // SAFETY: The semaphore isn't used by any submission yet.
+                unsafe { raw.destroy_semaphore(semaphore, None) };
+                return Err(error(format!("Can't export the semaphore: {err}")));
+            }
+        };
+
+        Ok((
+            self.insert(semaphore),
+            ExternalSemaphoreHandle::OpaqueFd(fd),
+        ))
+    }
+
+    /// Signal `value` once the work submitted to the queue is completed.
+    pub fn signal(&self, semaphore: ExternalSemaphore, value: u64) -> Result<(), ServerError> {
+        let semaphore = self.get(semaphore)?;
+        {
+            // SAFETY: The signal is only added to the next submission of the queue.
+            let Some(queue) = (unsafe { self.queue.as_hal::<hal::api::Vulkan>() }) else {
+                return Err(unsupported());
+            };
+            queue.add_signal_semaphore(semaphore, Some(value));
+        }
+        // The signal is attached to the next submission, which has nothing else to do.
+        self.queue.submit([]);
+
+        Ok(())
+    }
+
+    /// Delay the work submitted next to the queue until the semaphore reaches `value`, without
+    /// blocking the host.
+    pub fn wait(&mut self, semaphore: ExternalSemaphore, value: u64) -> Result<(), ServerError> {
+        let semaphore = self.get(semaphore)?;
+        let commands = self.barrier()?;
+        // SAFETY: The raw queue is only used for a submission of the server thread, which owns
+        // the queue, and the barrier outlives it.
+        let Some(queue) = (unsafe { self.queue.as_hal::<hal::api::Vulkan>() }) else {
+            return Err(unsupported());
+        };
+        // SAFETY: The raw device is only used to submit to its own queue.
+        let Some(device) = (unsafe { self.device.as_hal::<hal::api::Vulkan>() }) else {
+            return Err(unsupported());
+        };
+
+        let semaphores = [semaphore];
+        let values = [value];
+        let stages = [vk::PipelineStageFlags::ALL_COMMANDS];
+        let command_buffers = [commands];
+        let mut timeline =
+            vk::TimelineSemaphoreSubmitInfo::default().wait_semaphore_values(&values);
+        let submit = vk::SubmitInfo::default()
+            .wait_semaphores(&semaphores)
+            .wait_dst_stage_mask(&stages)
+            .command_buffers(&command_buffers)
+            .push_next(&mut timeline);
+        // SAFETY: The semaphore is a valid timeline semaphore of the device and the barrier was
+        // recorded for simultaneous use.
+        unsafe {
+            device
+                .raw_device()
+                .queue_submit(queue.as_raw(), &[submit], vk::Fence::null())
+        }
+        .map_err(|err| error(format!("Can't wait on the semaphore: {err}")))
+    }
+
+    /// Destroy the semaphore, which must not be used by pending submissions anymore.
+    pub fn release(&mut self, semaphore: ExternalSemaphore) {
+        if let Some(semaphore) = self.semaphores.remove(&semaphore.id) {
+            self.destroy(semaphore);
+        }
+    }
+
+    /// The barrier submitted with the waits, recorded on the first wait.
+    fn barrier(&mut self) -> Result<vk::CommandBuffer, ServerError> {
+        if let Some(barrier) = &self.barrier {
+            return Ok(barrier.commands);
+        }
+        // SAFETY: The raw device is only used to record the barrier, destroyed by this struct.
+        let Some(device) = (unsafe { self.device.as_hal::<hal::api::Vulkan>() }) else {
+            return Err(unsupported());
+        };
+        let raw = device.raw_device();
+
+        let info =
+            vk::CommandPoolCreateInfo::default().queue_family_index(device.queue_family_index());
+        // SAFETY: The pool is created for the queue the barrier is submitted to.
+        let pool = unsafe { raw.create_command_pool(&info, None) }
+            .map_err(|err| error(format!("Can't create the command pool: {err}")))?;
+        let record = || {
+            let info = vk::CommandBufferAllocateInfo::default()
+                .command_pool(pool)
+                .level(vk::CommandBufferLevel::PRIMARY)
+                .command_buffer_count(1);
+            // SAFETY: The pool was just created, the buffer is freed with it.
+            let commands = unsafe { raw.allocate_command_buffers(&info) }?[0];
+            let begin = vk::CommandBufferBeginInfo::default()
+                .flags(vk::CommandBufferUsageFlags::SIMULTANEOUS_USE);
+            // Every access before the wait is made available to every access after, so the
+            // memory written by the other API is visible to the next submissions.
+            let memory = [vk::MemoryBarrier::default()
+                .src_access_mask(vk::AccessFlags::MEMORY_WRITE)
+                .dst_access_mask(vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE)];
+            // SAFETY: The buffer is recorded once, before any submission.
+            unsafe {
+                raw.begin_command_buffer(commands, &begin)?;
+                raw.cmd_pipeline_barrier(
+                    commands,
+                    vk::PipelineStageFlags::ALL_COMMANDS,
+                    vk::PipelineStageFlags::ALL_COMMANDS,
+                    vk::DependencyFlags::empty(),
+                    &memory,
+                    &[],
+                    &[],
+                );
+                raw.end_command_buffer(commands)?;
+            }
+            Ok::<_, vk::Result>(commands)
+        };
+        let commands = match record() {
+            Ok(commands) => commands,
+            Err(err) => {
+                // SAFETY: The pool isn't used by any submission yet.
+                unsafe { raw.destroy_command_pool(pool, None) };
+                return Err(error(format!("Can't record the semaphore barrier: {err}")));
+            }
+        };
+
+        self.barrier = Some(Barrier { pool, commands });
+        Ok(commands)
+    }
+
+    fn insert(&mut self, semaphore: vk::Semaphore) -> ExternalSemaphore {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.semaphores.insert(id, semaphore);
+
+        ExternalSemaphore { id }
+    }
+
+    fn destroy(&self, semaphore: vk::Semaphore) {
+        // SAFETY: The semaphore was created by this struct and removed from the map.
+        unsafe {
+            if let Some(device) = self.device.as_hal::<hal::api::Vulkan>() {
+                device.raw_device().destroy_semaphore(semaphore, None);
+            }
+        }
+    }
+
+    fn get(&self, semaphore: ExternalSemaphore) -> Result<vk::Semaphore, ServerError> {
+        self.semaphores
+            .get(&semaphore.id)
+            .copied()
+            .ok_or_else(|| error(format!("Unknown external semaphore {}", semaphore.id)))
+    }
+}
+
+impl Drop for ExternalSemaphores {
+    fn drop(&mut self) {
+        // SAFETY: The device is idle once the wait returns, so neither the semaphores nor the
+        // barrier are used by pending submissions anymore.
+        unsafe {
+            if let Some(device) = self.device.as_hal::<hal::api::Vulkan>() {
+                let raw = device.raw_device();
+                let _ = raw.device_wait_idle();
+                if let Some(barrier) = self.barrier.take() {
+                    raw.destroy_command_pool(barrier.pool, None);
+                }
+            }
+        }
+        let semaphores = core::mem::take(&mut self.semaphores);
+        for semaphore in semaphores.into_values() {
+            self.destroy(semaphore);
+        }
+    }
+}
+
+fn unsupported() -> ServerError {
+    error("External semaphores are only supported with the Vulkan backend")
+}
+
+fn error(reason: impl Into<String>) -> ServerError {
+    ServerError::Generic {
+        reason: reason.into(),
+        backtrace: BackTrace::capture(),
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{WgpuDevice, runtime::create_setup_for_device};
+    use cubecl_common::future;
+
+    /// The value of the semaphore, read from the host.
+    fn counter(device: &wgpu::Device, semaphore: vk::Semaphore) -> u64 {
+        unsafe {
+            let device = device.as_hal::<hal::api::Vulkan>().unwrap();
+            device
+                .raw_device()
+                .get_semaphore_counter_value(semaphore)
+                .unwrap()
+        }
+    }
+
+    #[test_log::test]
+    fn waits_are_ordered_on_the_queue() {
+        let setup = future::block_on(create_setup_for_device(
+            &WgpuDevice::DefaultDevice,
+            wgpu::Backend::Vulkan,
+        ));
+        if unsafe { setup.device.as_hal::<hal::api::Vulkan>() }.is_none() {
+            return;
+        }
+        let mut semaphores = ExternalSemaphores::new(setup.device.clone(), setup.queue);
+        let Ok((exported, handle)) = semaphores.export() else {
+            // The device can't export semaphores as file descriptors.
+            return;
+        };
+        let imported = semaphores.import(handle).unwrap();
+        let raw = semaphores.get(exported).unwrap();
+
+        // The wait doesn't block the host, but the signal submitted after it is held back.
+        semaphores.wait(imported, 1).unwrap();
+        semaphores.signal(exported, 2).unwrap();
+        std::thread::sleep(core::time::Duration::from_millis(50));
+        assert_eq!(counter(&setup.device, raw), 0);
+
+        // Reaching the waited value from another API releases the queue.
+        let waited = [raw];
+        let values = [2];
+        unsafe {
+            let device = setup.device.as_hal::<hal::api::Vulkan>().unwrap();
+            let info = vk::SemaphoreSignalInfo::default().semaphore(raw).value(1);
+            device.raw_device().signal_semaphore(&info).unwrap();
+            let info = vk::SemaphoreWaitInfo::default()
+                .semaphores(&waited)
+                .values(&values);
+            device
+                .raw_device()
+                .wait_semaphores(&info, 5_000_000_000)
+                .unwrap();
+        }
+        assert_eq!(counter(&setup.device, raw), 2);
+
+        semaphores.release(imported);
+        semaphores.release(exported);
+        assert!(semaphores.wait(exported, 3).is_err());
+    }
+}
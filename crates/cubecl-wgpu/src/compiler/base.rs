@@ -172,6 +172,14 @@ impl Compiler for AutoCompiler {
             AutoCompiler::Msl(_) => "msl",
         }
     }
+
+    fn compiles_source(&self) -> bool {
+        match self {
+            #[cfg(feature = "spirv")]
+            AutoCompiler::SpirV(compiler) => compiler.compiles_source(),
+            _ => true,
+        }
+    }
 }
 
 impl WgpuCompiler for AutoCompiler {
@@ -103,6 +103,14 @@ impl WgslCompiler {
             });
         }
 
+        // Textures sample buffers, which would need to be copied to real texture resources.
+        if !value.textures.is_empty() {
+            return Err(CompilationError::UnsupportedInstruction {
+                reason: "Textures aren't supported on wgpu".to_string(),
+                backtrace: BackTrace::capture(),
+            });
+        }
+
         self.strategy = mode;
         self.kernel_name = value.options.kernel_name.clone();
 
@@ -128,6 +136,13 @@ impl WgslCompiler {
         self.buffer_vis = optimize_scope(&value.body).into();
         self.buffer_vis
             .resize(value.num_global_buffers(), Visibility::Read);
+        for binding in value.buffers.iter() {
+            // Buffers are only bound as read-only storage with exclusive memory, and atomics are
+            // only allowed in read-write storage. Pointers must match the access of the binding.
+            if !cfg!(exclusive_memory_only) || binding.value.ty.is_atomic() {
+                self.buffer_vis[binding.id as usize] = Visibility::ReadWrite;
+            }
+        }
 
         let address_type = self.compile_storage_type(address_type);
         let instructions = self.compile_scope(&value.body);
@@ -368,6 +383,9 @@ impl WgslCompiler {
                 panic!("Barrier isn't supported on wgpu.")
             }
             cube::Operation::Tma(_) => panic!("TMA isn't supported on wgpu."),
+            cube::Operation::Texture(_) => {
+                unreachable!("Texture kernels are rejected before compilation")
+            }
             cube::Operation::TensorIndexing(_) => panic!("TMA isn't supported on wgpu."),
             cube::Operation::Marker(_) => {}
             cube::Operation::ConstructAggregate(..)
@@ -1117,6 +1135,13 @@ impl WgslCompiler {
                 id,
                 out: self.compile_value(out),
             }),
+            cube::Operator::ReadScalarArray(op) => {
+                instructions.push(wgsl::Instruction::ReadScalarArray {
+                    id: op.id,
+                    index: self.compile_value(op.index),
+                    out: self.compile_value(out),
+                })
+            }
         }
     }
 
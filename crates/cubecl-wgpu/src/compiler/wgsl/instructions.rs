@@ -91,6 +91,11 @@ pub enum Instruction {
         id: Id,
         out: Value,
     },
+    ReadScalarArray {
+        id: Id,
+        index: Value,
+        out: Value,
+    },
     ModFloor {
         lhs: Value,
         rhs: Value,
@@ -1143,6 +1148,14 @@ for (*{i} = {start}; *{i} {cmp} {end}; {increment}) {{
                 let elem = out.elem();
                 writeln!(f, "{} = info.scalars_{elem}[{id}];", out.fmt_left())
             }
+            Instruction::ReadScalarArray { id, index, out } => {
+                let elem = out.elem();
+                writeln!(
+                    f,
+                    "{} = info.scalars_{elem}[{id}u + {index}];",
+                    out.fmt_left()
+                )
+            }
         }
     }
 }
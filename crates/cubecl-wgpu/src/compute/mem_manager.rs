@@ -1,5 +1,5 @@
 use crate::{WgpuResource, WgpuStorage};
-use cubecl_common::stub::Arc;
+use cubecl_common::{bytes::Bytes, stub::Arc};
 use cubecl_core::{
     MemoryConfiguration,
     server::{Binding, IoError},
@@ -11,7 +11,7 @@ use cubecl_runtime::{
         ManagedMemoryBinding, ManagedMemoryHandle, MemoryAllocationMode, MemoryHandle,
         MemoryManagement, MemoryManagementOptions,
     },
-    storage::ComputeStorage,
+    storage::{ComputeStorage, StorageId},
 };
 use wgpu::BufferUsages;
 
@@ -92,6 +92,12 @@ impl WgpuMemManager {
         self.memory_pool.bind(old, new, 0).unwrap();
     }
 
+    /// Manage host memory imported without a copy, see [`WgpuStorage::wrap`].
+    pub(crate) fn wrap(&mut self, data: Bytes) -> Result<ManagedMemoryHandle, Bytes> {
+        let storage = self.memory_pool.storage().wrap(data)?;
+        Ok(self.memory_pool.reserve_storage(storage))
+    }
+
     pub(crate) fn reserve(&mut self, size: u64) -> Result<ManagedMemoryHandle, IoError> {
         match self.memory_pool.reserve(size) {
             Ok(handle) => Ok(handle),
@@ -144,6 +150,16 @@ impl WgpuMemManager {
         self.memory_pool.mode(mode);
     }
 
+    /// The storages deallocated since the last call, whose buffers can't be bound anymore.
+    pub(crate) fn take_deallocated(&mut self) -> Vec<StorageId> {
+        // Staging buffers are never bound, but are drained so they don't accumulate.
+        self.memory_pool_staging.storage().take_deallocated();
+
+        let mut deallocated = self.memory_pool.storage().take_deallocated();
+        deallocated.extend(self.memory_uniforms.storage().take_deallocated());
+        deallocated
+    }
+
     pub(crate) fn release_uniforms(&mut self) {
         self.uniforms.clear();
     }
@@ -1,5 +1,5 @@
 use crate::{
-    CompilerInfo, ParamsTransfer, WgpuResource, stream::WgpuStream,
+    CompilerInfo, FlushPolicy, ParamsTransfer, WgpuResource, stream::WgpuStream,
     timings::TimestampQuerySetBudget,
 };
 use alloc::sync::Arc;
@@ -79,6 +79,7 @@ pub struct WgpuStreamFactory {
     /// Per-device budget of live timestamp query sets, shared by every stream it creates.
     timing_budget: Arc<TimestampQuerySetBudget>,
     tasks_max: usize,
+    flush_policy: FlushPolicy,
     logger: Arc<ServerLogger>,
     count: u64,
     use_vulkan_compiler: bool,
@@ -98,6 +99,7 @@ impl StreamFactory for WgpuStreamFactory {
             self.timing_method,
             self.timing_budget.clone(),
             self.tasks_max,
+            self.flush_policy,
             self.logger.clone(),
             self.use_vulkan_compiler,
         )
@@ -115,6 +117,7 @@ impl ScheduledWgpuBackend {
         timing_method: TimingMethod,
         backend: wgpu::Backend,
         tasks_max: usize,
+        flush_policy: FlushPolicy,
         logger: Arc<ServerLogger>,
         use_vulkan_compiler: bool,
     ) -> Self {
@@ -133,6 +136,7 @@ impl ScheduledWgpuBackend {
                 timing_method,
                 timing_budget,
                 tasks_max,
+                flush_policy,
                 logger,
                 count: 0,
                 use_vulkan_compiler,
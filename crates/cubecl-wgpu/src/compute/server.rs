@@ -1,8 +1,8 @@
 use std::marker::PhantomData;
 
 use super::storage::{WgpuResource, WgpuStorage};
-use crate::WgpuCompiler;
 use crate::schedule::{BindingsResource, ScheduleTask, ScheduledWgpuBackend};
+use crate::{FlushPolicy, WgpuCompiler};
 use alloc::sync::Arc;
 use cubecl_common::{
     backtrace::BackTrace,
@@ -17,8 +17,9 @@ use cubecl_core::{
     future::DynFut,
     prelude::*,
     server::{
-        CopyDescriptor, IoError, KernelArguments, LaunchError, ProfileError, ProfilingToken,
-        ServerCommunication, ServerError, ServerUtilities,
+        CompletionCallback, CopyDescriptor, ExternalSemaphore, ExternalSemaphoreHandle, IoError,
+        KernelArguments, LaunchError, ProfileError, ProfilingToken, ServerCommunication,
+        ServerError, ServerUtilities,
     },
     zspace::{Strides, strides},
 };
@@ -69,6 +70,8 @@ pub struct WgpuServer<C: WgpuCompiler> {
     pub compilation_options: WgpuCompilationOptions,
     pub(crate) backend: wgpu::Backend,
     pub(crate) utilities: Arc<ServerUtilities<Self>>,
+    #[cfg(feature = "spirv")]
+    semaphores: crate::vulkan::ExternalSemaphores,
     _compiler: PhantomData<C>,
 }
 
@@ -86,12 +89,15 @@ impl<C: WgpuCompiler> WgpuServer<C> {
         device: wgpu::Device,
         queue: wgpu::Queue,
         tasks_max: usize,
+        flush_policy: FlushPolicy,
         backend: wgpu::Backend,
         timing_method: TimingMethod,
         utilities: ServerUtilities<Self>,
     ) -> Self {
         #[cfg(feature = "spirv")]
         let adapter_info = device.adapter_info();
+        #[cfg(feature = "spirv")]
+        let semaphores = crate::vulkan::ExternalSemaphores::new(device.clone(), queue.clone());
         let backend_scheduler = ScheduledWgpuBackend::new(
             device.clone(),
             queue.clone(),
@@ -100,6 +106,7 @@ impl<C: WgpuCompiler> WgpuServer<C> {
             timing_method,
             backend,
             tasks_max,
+            flush_policy,
             utilities.logger.clone(),
             compilation_options.supports_vulkan_compiler,
         );
@@ -136,6 +143,8 @@ impl<C: WgpuCompiler> WgpuServer<C> {
             },
             backend,
             utilities: Arc::new(utilities),
+            #[cfg(feature = "spirv")]
+            semaphores,
             _compiler: PhantomData,
         }
     }
@@ -287,6 +296,24 @@ impl<C: WgpuCompiler> ComputeServer for WgpuServer<C> {
         stream.mem_manage.bind(reserved, memory);
     }
 
+    fn create_from_host(
+        &mut self,
+        memory: ManagedMemoryHandle,
+        data: Bytes,
+        stream_id: StreamId,
+    ) -> Result<u64, Bytes> {
+        // Host memory is only as aligned as its allocation, which may be less than the pools.
+        let alignment = Ord::min(
+            1 << data.as_ptr().addr().trailing_zeros(),
+            self.utilities.properties.memory.alignment,
+        );
+        let stream = self.scheduler.stream(&stream_id);
+        let reserved = stream.mem_manage.wrap(data)?;
+        stream.mem_manage.bind(reserved, memory);
+
+        Ok(alignment)
+    }
+
     fn read(
         &mut self,
         descriptors: Vec<CopyDescriptor>,
@@ -424,6 +451,57 @@ impl<C: WgpuCompiler> ComputeServer for WgpuServer<C> {
         stream.sync()
     }
 
+    #[cfg(feature = "spirv")]
+    fn import_semaphore(
+        &mut self,
+        handle: ExternalSemaphoreHandle,
+    ) -> Result<ExternalSemaphore, ServerError> {
+        self.semaphores.import(handle)
+    }
+
+    #[cfg(feature = "spirv")]
+    fn export_semaphore(
+        &mut self,
+    ) -> Result<(ExternalSemaphore, ExternalSemaphoreHandle), ServerError> {
+        self.semaphores.export()
+    }
+
+    #[cfg(feature = "spirv")]
+    fn signal_semaphore(
+        &mut self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream_id: StreamId,
+    ) -> Result<(), ServerError> {
+        self.flush(stream_id)?;
+        self.semaphores.signal(semaphore, value)
+    }
+
+    #[cfg(feature = "spirv")]
+    fn wait_semaphore(
+        &mut self,
+        semaphore: ExternalSemaphore,
+        value: u64,
+        stream_id: StreamId,
+    ) -> Result<(), ServerError> {
+        // The wait is submitted to the queue directly, so the tasks queued so far are submitted
+        // first to stay ahead of it.
+        self.flush(stream_id)?;
+        self.semaphores.wait(semaphore, value)
+    }
+
+    #[cfg(feature = "spirv")]
+    fn release_semaphore(&mut self, semaphore: ExternalSemaphore) {
+        self.semaphores.release(semaphore);
+    }
+
+    fn on_complete(&mut self, stream_id: StreamId, callback: CompletionCallback) {
+        self.scheduler.execute_streams(vec![stream_id]);
+        let stream = self.scheduler.stream(&stream_id);
+
+        stream.on_complete(callback)
+    }
+
     fn start_profile(&mut self, stream_id: StreamId) -> Result<ProfilingToken, ServerError> {
         self.scheduler.execute_streams(vec![stream_id]);
         let stream = self.scheduler.stream(&stream_id);
@@ -1,3 +1,4 @@
+use cubecl_common::bytes::{Bytes, Writer};
 use cubecl_core::server::IoError;
 use cubecl_runtime::storage::{ComputeStorage, StorageHandle, StorageId, StorageUtilization};
 use hashbrown::HashMap;
@@ -12,6 +13,12 @@ const MIN_BUFFER_SIZE: u64 = 32;
 /// Buffer storage for wgpu.
 pub struct WgpuStorage {
     memory: HashMap<StorageId, WgpuMemory>,
+    /// Storages deallocated since the last [take](WgpuStorage::take_deallocated).
+    deallocated: Vec<StorageId>,
+    /// Host memory imported without a copy, kept alive as long as its buffer.
+    host: HashMap<StorageId, Bytes>,
+    /// Host memory of deallocated buffers, which the device may still read until it's idle.
+    released: Vec<Bytes>,
     device: wgpu::Device,
     buffer_usages: BufferUsages,
     mem_alignment: usize,
@@ -28,6 +35,8 @@ impl core::fmt::Debug for WgpuStorage {
 /// The memory resource that can be allocated for wgpu.
 #[derive(new, Debug)]
 pub struct WgpuResource {
+    /// The storage of the buffer.
+    pub storage: StorageId,
     /// The wgpu buffer.
     pub buffer: wgpu::Buffer,
     /// The buffer device address, if supported
@@ -85,12 +94,41 @@ impl WgpuStorage {
     ) -> Self {
         Self {
             memory: HashMap::new(),
+            deallocated: Vec::new(),
+            host: HashMap::new(),
+            released: Vec::new(),
             device,
             buffer_usages: usages,
             mem_alignment,
             vk_storage,
         }
     }
+
+    /// The storages deallocated since the last call, whose resources must not be used anymore.
+    pub(crate) fn take_deallocated(&mut self) -> Vec<StorageId> {
+        core::mem::take(&mut self.deallocated)
+    }
+
+    /// Use host memory as storage without a copy, or give it back when the device can't import
+    /// it. Only integrated GPUs using the Vulkan backend import host memory.
+    pub fn wrap(&mut self, mut bytes: Bytes) -> Result<StorageHandle, Bytes> {
+        let size = bytes.len() as u64;
+        let ptr = match bytes.write(Writer::new().no_copy()) {
+            Ok(memory) => memory.as_mut_ptr(),
+            Err(_) => return Err(bytes),
+        };
+        let Some(memory) = self.import_buffer(ptr, size) else {
+            return Err(bytes);
+        };
+
+        let id = StorageId::new();
+        self.memory.insert(id, memory);
+        self.host.insert(id, bytes);
+        Ok(StorageHandle::new(
+            id,
+            StorageUtilization { offset: 0, size },
+        ))
+    }
 }
 
 impl ComputeStorage for WgpuStorage {
@@ -103,6 +141,7 @@ impl ComputeStorage for WgpuStorage {
     fn get(&mut self, handle: &StorageHandle) -> Self::Resource {
         let memory = self.memory.get(&handle.id).unwrap();
         WgpuResource::new(
+            handle.id,
             memory.buffer.clone(),
             memory.address,
             handle.offset(),
@@ -135,11 +174,27 @@ impl ComputeStorage for WgpuStorage {
 
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn dealloc(&mut self, id: StorageId) {
-        self.memory.remove(&id);
+        if self.memory.remove(&id).is_some() {
+            self.deallocated.push(id);
+        }
+        if let Some(bytes) = self.host.remove(&id) {
+            self.released.push(bytes);
+        }
     }
 
     fn flush(&mut self) {
-        // We don't wait for dealloc
+        // We don't wait for dealloc, except to free host memory the device may still read.
+        if self.released.is_empty() {
+            return;
+        }
+        #[cfg(not(target_family = "wasm"))]
+        if let Err(e) = self.device.poll(wgpu::PollType::Wait {
+            submission_index: None,
+            timeout: None,
+        }) {
+            log::warn!("wgpu: poll before freeing host memory failed ({e})");
+        }
+        self.released.clear();
     }
 }
 
@@ -163,4 +218,24 @@ impl WgpuStorage {
     fn create_buffer(&self, desc: &wgpu::BufferDescriptor<'_>) -> Result<WgpuMemory, IoError> {
         Ok(WgpuMemory::new(self.device.create_buffer(desc), None))
     }
+
+    #[cfg(feature = "spirv")]
+    fn import_buffer(&self, ptr: *mut u8, size: u64) -> Option<WgpuMemory> {
+        if !self.vk_storage {
+            return None;
+        }
+        let desc = wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: self.buffer_usages,
+            mapped_at_creation: false,
+        };
+        let (buffer, addr) = crate::backend::vulkan::import_host_buffer(&self.device, ptr, &desc)?;
+        Some(WgpuMemory::new(buffer, NonZeroU64::new(addr)))
+    }
+
+    #[cfg(not(feature = "spirv"))]
+    fn import_buffer(&self, _ptr: *mut u8, _size: u64) -> Option<WgpuMemory> {
+        None
+    }
 }
@@ -4,7 +4,7 @@ use super::{
     timings::{QueryProfiler, TimestampQuerySetBudget},
 };
 use crate::{
-    WgpuResource,
+    FlushPolicy, WgpuResource,
     controller::WgpuAllocController,
     schedule::{Addresses, ScheduleTask},
 };
@@ -21,14 +21,17 @@ use cubecl_core::stub::Mutex;
 use cubecl_core::{
     CubeCount, MemoryConfiguration,
     future::{self, DynFut},
-    server::{IoError, ProfileError, ProfilingToken, ServerError, StreamErrorMode},
-    zspace::Shape,
+    server::{
+        CompletionCallback, IoError, ProfileError, ProfilingToken, ServerError, StreamErrorMode,
+    },
+    zspace::{Shape, SmallVec},
 };
 use cubecl_ir::MemoryDeviceProperties;
 use cubecl_runtime::{
-    logging::ServerLogger, memory_management::ManagedMemoryHandle,
+    logging::ServerLogger, memory_management::ManagedMemoryHandle, storage::StorageId,
     timestamp_profiler::TimestampProfiler,
 };
+use hashbrown::HashMap;
 #[cfg(renderdoc)]
 use renderdoc::{RenderDoc, V100};
 use std::{future::Future, num::NonZero, pin::Pin, sync::Arc};
@@ -47,15 +50,95 @@ enum Timings {
     System(TimestampProfiler),
 }
 
+/// The pipeline address and the bound storage ranges of a bind group.
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct BindGroupKey {
+    pipeline: usize,
+    buffers: SmallVec<[(StorageId, u64, u64); 8]>,
+}
+
+/// Bind groups reused across launches, since creating one per launch dominates the CPU time of
+/// small kernels.
+///
+/// Bind groups are keyed on the storages they bind rather than on the buffers, which they keep
+/// alive. They are evicted when one of their storages is deallocated, so a freed buffer is
+/// released and never bound again.
+#[derive(Debug, Default)]
+pub(crate) struct BindGroupCache {
+    // The pipeline is kept alive so its address in the key stays unique.
+    entries: HashMap<BindGroupKey, (Arc<ComputePipeline>, wgpu::BindGroup)>,
+}
+
+impl BindGroupCache {
+    const MAX_ENTRIES: usize = 1024;
+
+    fn get_or_create(
+        &mut self,
+        device: &wgpu::Device,
+        pipeline: &Arc<ComputePipeline>,
+        resources: &[WgpuResource],
+    ) -> wgpu::BindGroup {
+        let key = BindGroupKey {
+            pipeline: Arc::as_ptr(pipeline) as usize,
+            buffers: resources
+                .iter()
+                .map(|r| (r.storage, r.offset, r.size))
+                .collect(),
+        };
+
+        if let Some((_, bind_group)) = self.entries.get(&key) {
+            return bind_group.clone();
+        }
+
+        let entries = resources
+            .iter()
+            .enumerate()
+            .map(|(i, r)| wgpu::BindGroupEntry {
+                binding: i as u32,
+                resource: r.as_wgpu_bind_resource(),
+            })
+            .collect::<Vec<_>>();
+        let group_layout = pipeline.get_bind_group_layout(0);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &group_layout,
+            entries: &entries,
+        });
+
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.entries.clear();
+        }
+        self.entries
+            .insert(key, (pipeline.clone(), bind_group.clone()));
+
+        bind_group
+    }
+
+    /// Drops the bind groups of the deallocated storages.
+    fn evict(&mut self, deallocated: &[StorageId]) {
+        if deallocated.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|key, _| {
+            !key.buffers
+                .iter()
+                .any(|(storage, _, _)| deallocated.contains(storage))
+        });
+    }
+}
+
 #[derive(Debug)]
 pub struct WgpuStream {
     pub mem_manage: WgpuMemManager,
     pub device: wgpu::Device,
     pub errors: Vec<ServerError>,
+    bind_groups: BindGroupCache,
     compute_pass: Option<wgpu::ComputePass<'static>>,
     timings: Timings,
     tasks_count: usize,
     tasks_max: usize,
+    flush_policy: FlushPolicy,
     queue: wgpu::Queue,
     encoder: wgpu::CommandEncoder,
     poll: WgpuPoll,
@@ -77,6 +160,7 @@ impl WgpuStream {
         timing_method: TimingMethod,
         timing_budget: Arc<TimestampQuerySetBudget>,
         tasks_max: usize,
+        flush_policy: FlushPolicy,
         logger: Arc<ServerLogger>,
         use_vulkan_compiler: bool,
     ) -> Self {
@@ -117,6 +201,7 @@ impl WgpuStream {
 
         Self {
             mem_manage,
+            bind_groups: BindGroupCache::default(),
             compute_pass: None,
             timings,
             errors: Vec::new(),
@@ -129,6 +214,7 @@ impl WgpuStream {
             queue,
             tasks_count: 0,
             tasks_max,
+            flush_policy,
             poll,
             submission_load: SubmissionLoad::default(),
             pending_write_count: 0,
@@ -404,6 +490,24 @@ impl WgpuStream {
         })
     }
 
+    /// Invoke the callback once the work submitted so far on the queue is done, without waiting
+    /// on the host.
+    pub fn on_complete(&mut self, callback: CompletionCallback) {
+        if let Err(err) = self.flush(StreamErrorMode {
+            ignore: false,
+            flush: true,
+        }) {
+            callback(Err(err));
+            return;
+        }
+
+        let poll = self.poll.start_polling();
+        self.queue.on_submitted_work_done(move || {
+            callback(Ok(()));
+            core::mem::drop(poll);
+        });
+    }
+
     /// Allocates a new empty buffer using the main memory pool.
     pub fn empty(&mut self, size: u64) -> Result<ManagedMemoryHandle, IoError> {
         self.mem_manage.reserve(size)
@@ -495,7 +599,10 @@ impl WgpuStream {
         // Flush when there are too many tasks, or when too many handles are locked.
         // Locked handles should only accumulate in rare circumstances (where uniforms
         // are being created but no work is submitted).
-        if self.tasks_count >= self.tasks_max {
+        if self
+            .flush_policy
+            .should_flush(self.tasks_count, self.tasks_max)
+        {
             let _ = self
                 .flush(StreamErrorMode {
                     ignore: true,
@@ -596,15 +703,6 @@ impl WgpuStream {
             return;
         }
 
-        let entries = resources
-            .iter()
-            .enumerate()
-            .map(|(i, r)| wgpu::BindGroupEntry {
-                binding: i as u32,
-                resource: r.as_wgpu_bind_resource(),
-            })
-            .collect::<Vec<_>>();
-
         // Start a new compute pass if needed. The forget_lifetime allows
         // to store this with a 'static lifetime, but the compute pass must
         // be dropped before the encoder. This isn't unsafe - it's still checked at runtime.
@@ -633,12 +731,10 @@ impl WgpuStream {
         pass.set_pipeline(&pipeline);
 
         if !resources.is_empty() {
-            let group_layout = pipeline.get_bind_group_layout(0);
-            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: None,
-                layout: &group_layout,
-                entries: &entries,
-            });
+            self.bind_groups.evict(&self.mem_manage.take_deallocated());
+            let bind_group = self
+                .bind_groups
+                .get_or_create(&self.device, &pipeline, resources);
 
             pass.set_bind_group(0, &bind_group, &[]);
         }
@@ -764,3 +860,72 @@ mod __submission_load_wasm {
 use __submission_load::*;
 #[cfg(target_family = "wasm")]
 use __submission_load_wasm::*;
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use super::*;
+    use crate::{
+        AutoGraphicsApi, GraphicsApi, WgpuDevice, WgpuStorage, runtime::create_setup_for_device,
+    };
+    use cubecl_runtime::storage::ComputeStorage;
+
+    const SHADER: &str = "@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+@compute @workgroup_size(1) fn main() { data[0] = 1u; }";
+
+    #[test_log::test]
+    fn bind_groups_are_evicted_with_their_storage() {
+        let setup = future::block_on(create_setup_for_device(
+            &WgpuDevice::DefaultDevice,
+            AutoGraphicsApi::backend(),
+        ));
+        let device = setup.device;
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = Arc::new(
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: None,
+                layout: None,
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            }),
+        );
+        let mut storage = WgpuStorage::new(4, device.clone(), wgpu::BufferUsages::STORAGE, false);
+        let mut cache = BindGroupCache::default();
+
+        let first = storage.alloc(64).unwrap();
+        let second = storage.alloc(64).unwrap();
+        cache.get_or_create(&device, &pipeline, &[storage.get(&first)]);
+        cache.get_or_create(&device, &pipeline, &[storage.get(&first)]);
+        cache.get_or_create(&device, &pipeline, &[storage.get(&second)]);
+        assert_eq!(cache.entries.len(), 2);
+
+        storage.dealloc(first.id);
+        cache.evict(&storage.take_deallocated());
+        assert_eq!(cache.entries.len(), 1);
+        assert!(storage.take_deallocated().is_empty());
+
+        // A new storage never hits the bind group of the freed one.
+        let third = storage.alloc(64).unwrap();
+        cache.get_or_create(&device, &pipeline, &[storage.get(&third)]);
+        assert_eq!(cache.entries.len(), 2);
+        assert!(
+            cache
+                .entries
+                .keys()
+                .all(|key| key.buffers.iter().all(|(id, _, _)| *id != first.id))
+        );
+    }
+
+    #[test_log::test]
+    fn explicit_flush_policy_is_capped() {
+        assert!(FlushPolicy::TaskThreshold.should_flush(32, 32));
+        assert!(!FlushPolicy::Explicit.should_flush(32, 32));
+        assert!(!FlushPolicy::Explicit.should_flush(FlushPolicy::MAX_EXPLICIT_TASKS - 1, 32));
+        assert!(FlushPolicy::Explicit.should_flush(FlushPolicy::MAX_EXPLICIT_TASKS, 32));
+        assert!(!FlushPolicy::Explicit.should_flush(FlushPolicy::MAX_EXPLICIT_TASKS, 4096));
+    }
+}
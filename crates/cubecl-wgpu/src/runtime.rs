@@ -6,9 +6,9 @@ use crate::{
     contiguous_strides,
 };
 use cubecl_common::device::{Device, DeviceService};
-use cubecl_common::{future, profile::TimingMethod};
+use cubecl_common::{backtrace::BackTrace, future, profile::TimingMethod};
 use cubecl_core::device::{DeviceId, ServerUtilitiesHandle};
-use cubecl_core::server::ServerUtilities;
+use cubecl_core::server::{ServerError, ServerUtilities};
 use cubecl_core::zspace::{Shape, Strides};
 use cubecl_core::{Runtime, ir::TargetProperties};
 use cubecl_ir::{DeviceProperties, HardwareProperties, MemoryDeviceProperties};
@@ -54,6 +54,26 @@ impl<C: WgpuCompiler> Runtime for WgpuRuntime<C> {
         ComputeClient::load(device)
     }
 
+    fn try_client(device: &Self::Device) -> Result<ComputeClient<Self>, ServerError> {
+        // WebGPU adapters can only be requested asynchronously, so they're assumed available.
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let backend = AutoGraphicsApi::backend();
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+                backends: backend.into(),
+                ..wgpu::InstanceDescriptor::new_without_display_handle()
+            });
+            if enumerate_all_adapters(instance, backend).is_empty() {
+                return Err(ServerError::Generic {
+                    reason: format!("No {backend:?} adapter found"),
+                    backtrace: BackTrace::capture(),
+                });
+            }
+        }
+
+        Ok(Self::client(device))
+    }
+
     fn name(client: &ComputeClient<Self>) -> &'static str {
         match client.info() {
             wgpu::Backend::Vulkan => {
@@ -100,6 +120,17 @@ impl<C: WgpuCompiler> Runtime for WgpuRuntime<C> {
         }
     }
 
+    fn measure_load_width(client: &ComputeClient<Self>) -> Option<u32> {
+        // Profiling results can't be awaited synchronously on wasm.
+        #[cfg(target_family = "wasm")]
+        {
+            let _ = client;
+            None
+        }
+        #[cfg(not(target_family = "wasm"))]
+        cubecl_core::compute::measure_load_width(client)
+    }
+
     fn enumerate_devices(type_id: u16, info: &wgpu::Backend) -> Vec<DeviceId> {
         #[cfg(target_family = "wasm")]
         {
@@ -185,12 +216,46 @@ fn enumerate_all_adapters(instance: wgpu::Instance, backend: wgpu::Backend) -> V
     cubecl_common::future::block_on(instance.enumerate_adapters(backend.into()))
 }
 
+/// Controls when a stream submits its batched launches to the queue.
+///
+/// Launches are recorded into a single command encoder, which is always submitted on reads,
+/// syncs, writes and explicit flushes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Also submit once [`tasks_max`](RuntimeOptions::tasks_max) launches are batched, so the
+    /// GPU starts on long sequences of launches early.
+    #[default]
+    TaskThreshold,
+    /// Only submit on reads, syncs, writes and explicit flushes, or once
+    /// [`MAX_EXPLICIT_TASKS`](FlushPolicy::MAX_EXPLICIT_TASKS) launches are batched.
+    ///
+    /// Minimizes the submissions of short loops that sync every iteration, but long sequences
+    /// of launches delay the start of the GPU work and keep their uniforms until submitted.
+    Explicit,
+}
+
+impl FlushPolicy {
+    /// The number of batched launches submitted even with [`FlushPolicy::Explicit`], which
+    /// bounds the uniforms and bind groups kept alive by launches without a sync.
+    pub const MAX_EXPLICIT_TASKS: usize = 1024;
+
+    /// Whether a stream with `tasks` batched launches submits them.
+    pub(crate) fn should_flush(self, tasks: usize, tasks_max: usize) -> bool {
+        match self {
+            FlushPolicy::TaskThreshold => tasks >= tasks_max,
+            FlushPolicy::Explicit => tasks >= Self::MAX_EXPLICIT_TASKS.max(tasks_max),
+        }
+    }
+}
+
 /// The values that control how a WGPU Runtime will perform its calculations.
 pub struct RuntimeOptions {
     /// Control the amount of compute tasks to be aggregated into a single GPU command.
     pub tasks_max: usize,
     /// Configures the memory management.
     pub memory_config: MemoryConfiguration,
+    /// When batched compute tasks are submitted to the queue.
+    pub flush_policy: FlushPolicy,
 }
 
 impl Default for RuntimeOptions {
@@ -207,9 +272,22 @@ impl Default for RuntimeOptions {
             Err(_) => DEFAULT_MAX_TASKS,
         };
 
+        let flush_policy = match std::env::var("CUBECL_WGPU_FLUSH_POLICY").as_deref() {
+            Ok("tasks") | Err(_) => FlushPolicy::TaskThreshold,
+            Ok("explicit") => FlushPolicy::Explicit,
+            Ok(value) => {
+                log::warn!(
+                    "CUBECL_WGPU_FLUSH_POLICY should be either `tasks` or `explicit`, got \
+                     `{value}`, using `tasks`."
+                );
+                FlushPolicy::TaskThreshold
+            }
+        };
+
         Self {
             tasks_max,
             memory_config: MemoryConfiguration::default(),
+            flush_policy,
         }
     }
 }
@@ -393,6 +471,7 @@ pub(crate) fn create_server<C: WgpuCompiler>(
         setup.device.clone(),
         setup.queue,
         options.tasks_max,
+        options.flush_policy,
         setup.backend,
         time_measurement,
         ServerUtilities::new(device_props, logger, setup.backend, allocator),
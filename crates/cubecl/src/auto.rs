@@ -0,0 +1,204 @@
+//! Selection of the runtime to use on the current machine.
+//!
+//! Every runtime enabled with a feature is probed by creating a client on its default device, so
+//! applications can ship with several backends and use the best one available at startup:
+//!
+//! ```ignore
+//! let client = cubecl::auto_runtime().expect("No runtime is available");
+//! println!("Running on {}", client.name());
+//! ```
+
+use core::time::Duration;
+
+use crate::prelude::*;
+
+/// A client of a runtime selected by [`AutoRuntime`].
+#[derive(Clone)]
+pub enum AutoClient {
+    /// A client of the CUDA runtime.
+    #[cfg(feature = "cuda")]
+    Cuda(ComputeClient<crate::cuda::CudaRuntime>),
+    /// A client of the HIP runtime.
+    #[cfg(feature = "hip")]
+    Hip(ComputeClient<crate::hip::HipRuntime>),
+    /// A client of the native Metal runtime.
+    #[cfg(feature = "metal-native")]
+    Metal(ComputeClient<crate::metal::MetalRuntime>),
+    /// A client of the wgpu runtime.
+    #[cfg(feature = "wgpu")]
+    Wgpu(ComputeClient<crate::wgpu::WgpuRuntime>),
+    /// A client of the CPU runtime.
+    #[cfg(feature = "cpu")]
+    Cpu(ComputeClient<crate::cpu::CpuRuntime>),
+}
+
+impl AutoClient {
+    /// The name of the runtime of the client.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "cuda")]
+            AutoClient::Cuda(ref client) => crate::cuda::CudaRuntime::name(client),
+            #[cfg(feature = "hip")]
+            AutoClient::Hip(ref client) => crate::hip::HipRuntime::name(client),
+            #[cfg(feature = "metal-native")]
+            AutoClient::Metal(ref client) => crate::metal::MetalRuntime::name(client),
+            #[cfg(feature = "wgpu")]
+            AutoClient::Wgpu(ref client) => crate::wgpu::WgpuRuntime::name(client),
+            #[cfg(feature = "cpu")]
+            AutoClient::Cpu(ref client) => crate::cpu::CpuRuntime::name(client),
+        }
+    }
+
+    /// Run [`benchmark_runtime`] on the client.
+    ///
+    /// The CPU runtime isn't benchmarked, since it's only worth selecting when no other runtime
+    /// is available.
+    pub async fn benchmark(&self) -> Option<Duration> {
+        match *self {
+            #[cfg(feature = "cuda")]
+            AutoClient::Cuda(ref client) => benchmark_runtime(client).await,
+            #[cfg(feature = "hip")]
+            AutoClient::Hip(ref client) => benchmark_runtime(client).await,
+            #[cfg(feature = "metal-native")]
+            AutoClient::Metal(ref client) => benchmark_runtime(client).await,
+            #[cfg(feature = "wgpu")]
+            AutoClient::Wgpu(ref client) => benchmark_runtime(client).await,
+            #[cfg(feature = "cpu")]
+            AutoClient::Cpu(_) => None,
+        }
+    }
+}
+
+/// Select the runtime to use on the current machine, see [`AutoRuntime`].
+#[derive(Clone, Debug, Default)]
+pub struct AutoRuntime;
+
+/// The first available runtime in order of preference, see [`AutoRuntime::select`].
+pub fn auto_runtime() -> Option<AutoClient> {
+    AutoRuntime::new().select()
+}
+
+impl AutoRuntime {
+    /// Select the first available runtime in order of preference.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The clients of the available runtimes, in order of preference: CUDA, HIP, native Metal,
+    /// wgpu and then the CPU runtime.
+    pub fn probe(&self) -> Vec<AutoClient> {
+        #[allow(unused_mut, reason = "Every runtime is optional")]
+        let mut clients = Vec::new();
+        #[cfg(feature = "cuda")]
+        clients.extend(probe_runtime().map(AutoClient::Cuda));
+        #[cfg(feature = "hip")]
+        clients.extend(probe_runtime().map(AutoClient::Hip));
+        #[cfg(feature = "metal-native")]
+        clients.extend(probe_runtime().map(AutoClient::Metal));
+        #[cfg(feature = "wgpu")]
+        clients.extend(probe_runtime().map(AutoClient::Wgpu));
+        #[cfg(feature = "cpu")]
+        clients.extend(probe_runtime().map(AutoClient::Cpu));
+        clients
+    }
+
+    /// The client of the first available runtime, or `None` if no runtime is available.
+    pub fn select(&self) -> Option<AutoClient> {
+        self.probe().into_iter().next()
+    }
+
+    /// The client of the available runtime that [benchmarks](benchmark_runtime) the fastest,
+    /// falling back to the first available one if none could be profiled, or `None` if no
+    /// runtime is available.
+    ///
+    /// It launches kernels on every device, so it's worth it only when the devices can't be told
+    /// apart otherwise.
+    pub async fn select_fastest(&self) -> Option<AutoClient> {
+        let clients = self.probe();
+        let mut fastest = None;
+        for (index, client) in clients.iter().enumerate() {
+            let duration = client.benchmark().await;
+            log::info!("Runtime {} benchmarked at {duration:?}", client.name());
+            if let Some(duration) = duration
+                && fastest.is_none_or(|(fastest, _)| duration < fastest)
+            {
+                fastest = Some((duration, index));
+            }
+        }
+
+        let index = fastest.map(|(_, index)| index).unwrap_or_default();
+        clients.into_iter().nth(index)
+    }
+}
+
+/// The client of the default device of the runtime, or `None` if the runtime isn't available on
+/// this machine, see [`Runtime::try_client`].
+pub fn probe_runtime<R: Runtime>() -> Option<ComputeClient<R>> {
+    R::try_client(&Default::default())
+        .inspect_err(|err| log::info!("Runtime unavailable: {err}"))
+        .ok()
+}
+
+/// Large enough to take a measurable time on a GPU, while staying well below the memory of any
+/// device.
+const BENCHMARK_LEN: usize = 1 << 20;
+const BENCHMARK_SAMPLES: usize = 5;
+
+#[cube(launch)]
+fn benchmark_kernel(input: &[f32], output: &mut [f32]) {
+    if ABSOLUTE_POS < output.len() {
+        let mut acc = input[ABSOLUTE_POS];
+        #[unroll]
+        for _ in 0..64u32 {
+            acc = acc * 0.999 + 0.5;
+        }
+        output[ABSOLUTE_POS] = acc;
+    }
+}
+
+/// The duration of a canonical kernel on the client, both memory and compute bound, or `None` if
+/// it couldn't be profiled.
+///
+/// The kernel is launched once to warm up, then the fastest of a few samples is returned.
+pub async fn benchmark_runtime<R: Runtime>(client: &ComputeClient<R>) -> Option<Duration> {
+    let cube_dim = CubeDim::new_1d(256);
+    let cube_count = CubeCount::new_1d(BENCHMARK_LEN.div_ceil(256) as u32);
+    let input = client.empty(BENCHMARK_LEN * size_of::<f32>());
+    let output = client.empty(BENCHMARK_LEN * size_of::<f32>());
+
+    let launch = || {
+        benchmark_kernel::launch::<R>(
+            client,
+            cube_count.clone(),
+            cube_dim,
+            unsafe { BufferArg::from_raw_parts(input.clone(), BENCHMARK_LEN) },
+            unsafe { BufferArg::from_raw_parts(output.clone(), BENCHMARK_LEN) },
+        )
+    };
+
+    launch();
+    client.sync().await.ok()?;
+
+    let mut fastest = None;
+    for _ in 0..BENCHMARK_SAMPLES {
+        let Ok((_, duration)) = client.profile(launch, "benchmark_runtime") else {
+            continue;
+        };
+        let duration = duration.resolve().await.duration();
+        fastest = Some(fastest.map_or(duration, |fastest: Duration| fastest.min(duration)));
+    }
+    fastest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dry_run::DryRunRuntime, future::block_on};
+
+    #[test]
+    fn available_runtime_is_probed_and_benchmarked() {
+        let client = probe_runtime::<DryRunRuntime>().expect("The dry-run runtime is available");
+
+        assert!(block_on(benchmark_runtime(&client)).is_some());
+    }
+}
@@ -1,5 +1,10 @@
+extern crate self as cubecl;
+
 pub use cubecl_core::*;
 
+mod auto;
+pub use auto::*;
+
 pub use cubecl_ir::features;
 pub use cubecl_runtime::config;
 pub use cubecl_runtime::memory_management::MemoryAllocationMode;